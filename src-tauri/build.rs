@@ -1,3 +1,59 @@
+//! Build script.
+//!
+//! Declares every command registered in `specta_builder()` (src/lib.rs) in the
+//! app's permission manifest, so a command is only callable from a window whose
+//! capability grants it. The local frontend gets all of them through the
+//! generated `app-commands` set; the remote platforms only get the bridge
+//! commands listed in `capabilities/remote.json`.
+
+use std::fs;
+
+/// Where the generated permission set is written
+const APP_COMMANDS_SET: &str = "permissions/app-commands.toml";
+
+/// The commands listed in `collect_commands![...]`, in order
+fn registered_commands(source: &str) -> Vec<String> {
+    let start = source.find("collect_commands![").expect("collect_commands! not found in src/lib.rs");
+    let list = &source[start..];
+    let end = list.find(']').expect("unterminated collect_commands! in src/lib.rs");
+    list[..end]
+        .lines()
+        .skip(1)
+        .map(|l| l.split("//").next().unwrap_or_default().trim().trim_end_matches(','))
+        .filter(|l| !l.is_empty())
+        .map(|l| l.rsplit("::").next().unwrap_or(l).to_string())
+        .collect()
+}
+
+/// A permission set granting every command
+fn app_commands_set(commands: &[String]) -> String {
+    let permissions: String = commands.iter().map(|c| format!("  \"allow-{}\",\n", c.replace('_', "-"))).collect();
+    format!(
+        "# Generated by build.rs from the commands in `specta_builder()` - do not edit by hand.\n\n\
+         [[set]]\n\
+         identifier = \"app-commands\"\n\
+         description = \"Every command of the app, for the local frontend\"\n\
+         permissions = [\n{}]\n",
+        permissions
+    )
+}
+
 fn main() {
-    tauri_build::build();
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    let source = fs::read_to_string("src/lib.rs").expect("failed to read src/lib.rs");
+    let commands = registered_commands(&source);
+
+    // Only rewritten on change, so the file doesn't retrigger the build
+    let set = app_commands_set(&commands);
+    if fs::read_to_string(APP_COMMANDS_SET).ok().as_deref() != Some(set.as_str()) {
+        fs::create_dir_all("permissions").expect("failed to create permissions/");
+        fs::write(APP_COMMANDS_SET, set).expect("failed to write the app-commands permission set");
+    }
+
+    let commands: &'static [&'static str] =
+        Box::leak(commands.into_iter().map(|c| &*Box::leak(c.into_boxed_str())).collect::<Vec<_>>().into_boxed_slice());
+    tauri_build::try_build(
+        tauri_build::Attributes::new().app_manifest(tauri_build::AppManifest::new().commands(commands)),
+    )
+    .expect("failed to run tauri-build");
 }
@@ -0,0 +1,10 @@
+//! Regenerates the frontend's typed command bindings (`src/bindings.ts`).
+//!
+//! Run with `npm run bindings` (or `cargo run --example export_bindings`) after
+//! adding or changing a command.
+
+fn main() {
+    let out = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/bindings.ts");
+    messenger_desktop::export_bindings(out).expect("failed to export TypeScript bindings");
+    println!("Wrote {}", out);
+}
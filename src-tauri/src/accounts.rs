@@ -3,15 +3,18 @@
 
 use tauri::{AppHandle, Manager, Emitter};
 use serde::{Serialize, Deserialize};
-use std::path::Path;
+use specta::Type;
+use std::path::{Path, PathBuf};
 use std::fs;
 use uuid::Uuid;
 use anyhow::{Context, Result};
 use image::io::Reader as ImageReader;
 use image::imageops::FilterType;
 
+use crate::store;
+
 /// Account information.
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Type)]
 pub struct Account {
     pub id: String,
     pub name: String,
@@ -25,30 +28,26 @@ pub struct Account {
 /// Account manager state.
 pub struct AccountManager {
     accounts: Vec<Account>,
-    app: AppHandle,
+    store_path: PathBuf,
+    /// Where each account's own directory (profile picture) is created
+    accounts_dir: PathBuf,
 }
 
-#[allow(dead_code)]
 impl AccountManager {
-    /// Create a new AccountManager.
-    pub fn new(app: &AppHandle) -> Self {
-        let accounts = app.state::<tauri_plugin_store::Store<tauri::Wry>>()
-            .get("accounts")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-        
+    /// Create a new AccountManager, loading any saved accounts.
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("accounts.json");
         Self {
-            accounts,
-            app: app.clone(),
+            accounts: store::load_or_default(&store_path),
+            store_path,
+            accounts_dir: app_data_dir.join("accounts"),
         }
     }
     
     /// Add a new account.
     pub fn add_account(&mut self, name: String) -> Result<Account> {
         let id = Uuid::new_v4().to_string();
-        let app_data = self.app.path().app_data_dir()
-            .context("Failed to resolve app data directory")?;
-        let data_dir = app_data.join("accounts").join(&id);
+        let data_dir = self.accounts_dir.join(&id);
         
         fs::create_dir_all(&data_dir)
             .context("Failed to create account directory")?;
@@ -68,33 +67,32 @@ impl AccountManager {
         Ok(account)
     }
     
-    /// Remove an account.
-    pub fn remove_account(&mut self, id: &str) -> Result<()> {
-        if let Some(pos) = self.accounts.iter().position(|a| a.id == id) {
-            let data_dir = Path::new(&self.accounts[pos].data_dir);
-            if data_dir.exists() {
-                fs::remove_dir_all(data_dir)
-                    .context("Failed to remove account directory")?;
-            }
-            
-            self.accounts.remove(pos);
-            self.save()?;
+    /// Remove an account. Returns whether it existed.
+    pub fn remove_account(&mut self, id: &str) -> Result<bool> {
+        let Some(pos) = self.accounts.iter().position(|a| a.id == id) else {
+            return Ok(false);
+        };
+        let data_dir = Path::new(&self.accounts[pos].data_dir);
+        if data_dir.exists() {
+            fs::remove_dir_all(data_dir)
+                .context("Failed to remove account directory")?;
         }
-        Ok(())
+        
+        self.accounts.remove(pos);
+        self.save()?;
+        Ok(true)
     }
     
-    /// Switch to an account.
-    pub fn switch_account(&mut self, id: &str) -> Result<()> {
+    /// Switch to an account. Returns its name, or `None` if there is no such account.
+    pub fn switch_account(&mut self, id: &str) -> Result<Option<String>> {
+        let Some(name) = self.accounts.iter().find(|a| a.id == id).map(|a| a.name.clone()) else {
+            return Ok(None);
+        };
         for account in &mut self.accounts {
             account.is_active = account.id == id;
         }
         self.save()?;
-        
-        self.app.emit("switch-account", id)?;
-        if let Some(window) = self.app.get_webview_window("main") {
-            window.set_title(&format!("Messenger - {}", id))?;
-        }
-        Ok(())
+        Ok(Some(name))
     }
     
     /// List all accounts.
@@ -102,6 +100,17 @@ impl AccountManager {
         self.accounts.clone()
     }
     
+    /// Replace all accounts (used when importing a settings backup).
+    /// The previous accounts are kept if saving fails.
+    pub fn replace_accounts(&mut self, accounts: Vec<Account>) -> Result<()> {
+        let previous = std::mem::replace(&mut self.accounts, accounts);
+        if let Err(e) = self.save() {
+            self.accounts = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+    
     /// Set profile picture for an account.
     pub fn set_profile_picture(&mut self, id: &str, path: &str) -> Result<()> {
         if let Some(account) = self.accounts.iter_mut().find(|a| a.id == id) {
@@ -121,6 +130,27 @@ impl AccountManager {
         Ok(())
     }
     
+    /// Fill in the active account's blank name and missing profile picture from
+    /// the identity the platform reports. Returns whether anything changed.
+    pub fn fill_from_identity(&mut self, identity: &crate::identity::Identity) -> Result<bool> {
+        let Some(account) = self.accounts.iter_mut().find(|a| a.is_active) else {
+            return Ok(false);
+        };
+        let mut changed = false;
+        if account.name.trim().is_empty() {
+            account.name = identity.name.clone();
+            changed = true;
+        }
+        if account.profile_picture.is_none() && identity.avatar_url.is_some() {
+            account.profile_picture = identity.avatar_url.clone();
+            changed = true;
+        }
+        if changed {
+            self.save()?;
+        }
+        Ok(changed)
+    }
+    
     /// Set session token for an account.
     pub fn set_session_token(&mut self, id: &str, token: &str) -> Result<()> {
         if let Some(account) = self.accounts.iter_mut().find(|a| a.id == id) {
@@ -144,35 +174,124 @@ impl AccountManager {
         Ok(())
     }
     
-    /// Save accounts to store.
+    /// Save accounts to disk.
     fn save(&self) -> Result<()> {
-        self.app.state::<tauri_plugin_store::Store<tauri::Wry>>()
-            .set("accounts", serde_json::to_value(&self.accounts)?);
-        Ok(())
+        store::save(&self.store_path, &self.accounts).context("Failed to save accounts")
+    }
+}
+
+/// Navigates to the platform last used with the account, or the global one
+fn open_account_platform(app: &AppHandle, id: &str) {
+    let Some(platforms) = app.try_state::<crate::platform_manager::PlatformManager>() else {
+        return;
+    };
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    match platforms.platform_for_account(id) {
+        Ok(Some(platform)) => {
+            if let Err(e) = crate::platform_manager::open_platform(&window, &platforms, platform) {
+                log::warn!("[accounts] failed to open the account's platform: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("[accounts] failed to look up the account's platform: {}", e),
+    }
+}
+
+/// The accounts.
+pub fn managed_accounts(app: &AppHandle) -> Vec<Account> {
+    app.try_state::<std::sync::Mutex<AccountManager>>()
+        .and_then(|m| m.lock().ok().map(|m| m.list_accounts()))
+        .unwrap_or_default()
+}
+
+/// Auto-populates the active account from the logged-in identity, emitting
+/// `account-updated` with the account when that changed it.
+pub fn fill_from_identity(app: &AppHandle, identity: &crate::identity::Identity) {
+    let Some(manager) = app.try_state::<std::sync::Mutex<AccountManager>>() else {
+        return;
+    };
+    let Ok(mut manager) = manager.lock() else {
+        return;
+    };
+    match manager.fill_from_identity(identity) {
+        Ok(true) => {
+            if let Some(account) = manager.accounts.iter().find(|a| a.is_active) {
+                let _ = app.emit("account-updated", account);
+            }
+        }
+        Ok(false) => {}
+        Err(e) => log::warn!("[accounts] failed to fill account from identity: {}", e),
+    }
+}
+
+/// The active account's id, plus its name when there are several accounts to tell apart.
+pub fn notification_identity(accounts: &[Account]) -> Option<(String, Option<String>)> {
+    let active = accounts.iter().find(|a| a.is_active)?;
+    let name = (accounts.len() > 1).then(|| active.name.clone());
+    Some((active.id.clone(), name))
+}
+
+/// Checks an imported account list: ids must be present and unique, and at most
+/// one account may be active.
+pub fn validate_accounts(accounts: &[Account]) -> Result<(), String> {
+    let mut ids = std::collections::HashSet::new();
+    for account in accounts {
+        if account.id.trim().is_empty() {
+            return Err("Account without an id".to_string());
+        }
+        if !ids.insert(account.id.as_str()) {
+            return Err(format!("Duplicate account id: {}", account.id));
+        }
+    }
+    if accounts.iter().filter(|a| a.is_active).count() > 1 {
+        return Err("More than one active account".to_string());
     }
+    Ok(())
 }
 
 /// Tauri command: Add an account.
 #[tauri::command]
+#[specta::specta]
 pub fn add_account(state: tauri::State<'_, std::sync::Mutex<AccountManager>>, name: String) -> Result<Account, String> {
     state.lock().map_err(|e| format!("Lock error: {}", e))?.add_account(name).map_err(|e| e.to_string())
 }
 
 /// Tauri command: Remove an account.
 #[tauri::command]
-pub fn remove_account(state: tauri::State<'_, std::sync::Mutex<AccountManager>>, id: String) -> Result<(), String> {
-    state.lock().map_err(|e| format!("Lock error: {}", e))?.remove_account(&id).map_err(|e| e.to_string())
+#[specta::specta]
+pub fn remove_account(app: AppHandle, state: tauri::State<'_, std::sync::Mutex<AccountManager>>, id: String) -> Result<(), String> {
+    let removed = state.lock().map_err(|e| format!("Lock error: {}", e))?.remove_account(&id).map_err(|e| e.to_string())?;
+    if removed {
+        if let Some(platforms) = app.try_state::<crate::platform_manager::PlatformManager>() {
+            if let Err(e) = platforms.forget_account(&id) {
+                log::warn!("[accounts] failed to forget the account's platform: {}", e);
+            }
+        }
+    }
+    Ok(())
 }
 
-/// Tauri command: Switch to an account.
+/// Tauri command: Switch to an account, restoring the platform last used with it.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn switch_account(state: tauri::State<'_, std::sync::Mutex<AccountManager>>, id: String) -> Result<(), String> {
-    state.lock().map_err(|e| format!("Lock error: {}", e))?.switch_account(&id).map_err(|e| e.to_string())
+#[specta::specta]
+pub fn switch_account(app: AppHandle, state: tauri::State<'_, std::sync::Mutex<AccountManager>>, id: String) -> Result<(), String> {
+    let name = state
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .switch_account(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Unknown account: {}", id))?;
+    app.emit("switch-account", &id).map_err(|e| e.to_string())?;
+    crate::window_manager::set_title_account(&app, Some(name));
+    open_account_platform(&app, &id);
+    Ok(())
 }
 
 /// Tauri command: List all accounts.
 #[tauri::command]
+#[specta::specta]
 pub fn list_accounts(state: tauri::State<'_, std::sync::Mutex<AccountManager>>) -> Vec<Account> {
     match state.lock() {
         Ok(guard) => guard.list_accounts(),
@@ -185,21 +304,21 @@ pub fn list_accounts(state: tauri::State<'_, std::sync::Mutex<AccountManager>>)
 
 /// Tauri command: Set profile picture for an account.
 #[tauri::command]
-#[allow(dead_code)]
+#[specta::specta]
 pub fn set_profile_picture(state: tauri::State<'_, std::sync::Mutex<AccountManager>>, id: String, path: String) -> Result<(), String> {
     state.lock().map_err(|e| format!("Lock error: {}", e))?.set_profile_picture(&id, &path).map_err(|e| e.to_string())
 }
 
 /// Tauri command: Set session token for an account.
 #[tauri::command]
-#[allow(dead_code)]
+#[specta::specta]
 pub fn set_session_token(state: tauri::State<'_, std::sync::Mutex<AccountManager>>, id: String, token: String) -> Result<(), String> {
     state.lock().map_err(|e| format!("Lock error: {}", e))?.set_session_token(&id, &token).map_err(|e| e.to_string())
 }
 
 /// Tauri command: Get session token for an account.
 #[tauri::command]
-#[allow(dead_code)]
+#[specta::specta]
 pub fn get_session_token(state: tauri::State<'_, std::sync::Mutex<AccountManager>>, id: String) -> Option<String> {
     match state.lock() {
         Ok(guard) => guard.get_session_token(&id),
@@ -212,7 +331,7 @@ pub fn get_session_token(state: tauri::State<'_, std::sync::Mutex<AccountManager
 
 /// Tauri command: Update last sync time for an account.
 #[tauri::command]
-#[allow(dead_code)]
+#[specta::specta]
 pub fn update_last_sync(state: tauri::State<'_, std::sync::Mutex<AccountManager>>, id: String) -> Result<(), String> {
     state.lock().map_err(|e| format!("Lock error: {}", e))?.update_last_sync(&id).map_err(|e| e.to_string())
 }
@@ -254,4 +373,86 @@ mod tests {
         assert!(account.profile_picture.is_none());
         assert!(account.session_token.is_none());
     }
+
+    #[test]
+    fn test_accounts_persist() {
+        let tmp = std::env::temp_dir().join("test-accounts");
+        let _ = fs::remove_dir_all(&tmp);
+        let mut manager = AccountManager::new(&tmp);
+        let first = manager.add_account(String::new()).unwrap();
+        let second = manager.add_account("Work".to_string()).unwrap();
+        assert!(first.is_active && !second.is_active);
+        assert_eq!(manager.switch_account(&second.id).unwrap().as_deref(), Some("Work"));
+        assert_eq!(manager.switch_account("missing").unwrap(), None);
+
+        let mut reloaded = AccountManager::new(&tmp);
+        let accounts = reloaded.list_accounts();
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts.iter().find(|a| a.id == second.id).unwrap().is_active);
+
+        assert!(reloaded.remove_account(&first.id).unwrap());
+        assert!(!Path::new(&first.data_dir).exists());
+        assert!(!reloaded.remove_account(&first.id).unwrap());
+        assert_eq!(AccountManager::new(&tmp).list_accounts().len(), 1);
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_fill_from_identity() {
+        let tmp = std::env::temp_dir().join("test-accounts-identity");
+        let _ = fs::remove_dir_all(&tmp);
+        let mut manager = AccountManager::new(&tmp);
+        let identity = crate::identity::Identity {
+            name: "Ada Lovelace".to_string(),
+            handle: Some("ada".to_string()),
+            avatar_url: Some("https://pbs.twimg.com/a.jpg".to_string()),
+        };
+        // Nothing to fill without an active account
+        assert!(!manager.fill_from_identity(&identity).unwrap());
+
+        manager.add_account(String::new()).unwrap();
+        assert!(manager.fill_from_identity(&identity).unwrap());
+        assert!(!manager.fill_from_identity(&identity).unwrap());
+        let account = &AccountManager::new(&tmp).list_accounts()[0];
+        assert_eq!(account.name, "Ada Lovelace");
+        assert_eq!(account.profile_picture.as_deref(), Some("https://pbs.twimg.com/a.jpg"));
+
+        // What the user set is kept
+        let mut manager = AccountManager::new(&tmp);
+        manager.accounts[0].name = "Work".to_string();
+        let other = crate::identity::Identity { name: "Someone".to_string(), handle: None, avatar_url: None };
+        assert!(!manager.fill_from_identity(&other).unwrap());
+        assert_eq!(manager.list_accounts()[0].name, "Work");
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    fn account(id: &str, is_active: bool) -> Account {
+        Account {
+            id: id.to_string(),
+            name: format!("{} name", id),
+            data_dir: "/c".to_string(),
+            is_active,
+            profile_picture: None,
+            last_sync: None,
+            session_token: None,
+        }
+    }
+
+    #[test]
+    fn test_notification_identity() {
+        assert_eq!(notification_identity(&[]), None);
+        assert_eq!(notification_identity(&[account("a", true)]), Some(("a".to_string(), None)));
+        assert_eq!(
+            notification_identity(&[account("a", false), account("b", true)]),
+            Some(("b".to_string(), Some("b name".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_validate_accounts() {
+        assert!(validate_accounts(&[account("a", true), account("b", false)]).is_ok());
+        assert!(validate_accounts(&[account("a", true), account("a", false)]).is_err());
+        assert!(validate_accounts(&[account("a", true), account("b", true)]).is_err());
+        assert!(validate_accounts(&[account("", false)]).is_err());
+    }
 }
@@ -0,0 +1,216 @@
+//! Media autoplay policy.
+//!
+//! Independent of the bandwidth saver, which holds all video back: this only
+//! decides whether `<video>` / `<audio>` may start by themselves, and with sound.
+//! The injected handler hooks the media elements generically (`play()` and the
+//! `play` event), so it works the same on every platform. Playback the user
+//! starts with a click is always allowed. The script is re-injected on every page
+//! load through the injection registry.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::store;
+
+/// Whether media may start playing without the user asking
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoplayPolicy {
+    /// The page decides, as in a browser
+    #[default]
+    Allow,
+    /// Autoplaying media is muted first
+    MutedOnly,
+    /// Media only plays when the user starts it
+    Block,
+}
+
+/// What happens to a play attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayDecision {
+    Play,
+    /// Set `muted` first, then play
+    PlayMuted,
+    Refuse,
+}
+
+/// Decides a play attempt on an element that is `muted` or not, started by a
+/// user gesture or not. The injected handler looks the answer up in a table built
+/// from this.
+pub fn decide(policy: AutoplayPolicy, muted: bool, user_gesture: bool) -> PlayDecision {
+    if user_gesture {
+        return PlayDecision::Play;
+    }
+    match policy {
+        AutoplayPolicy::Allow => PlayDecision::Play,
+        AutoplayPolicy::MutedOnly if muted => PlayDecision::Play,
+        AutoplayPolicy::MutedOnly => PlayDecision::PlayMuted,
+        AutoplayPolicy::Block => PlayDecision::Refuse,
+    }
+}
+
+/// Payload emitted with `autoplay-policy-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct AutoplayPolicyChangedPayload {
+    pub policy: AutoplayPolicy,
+}
+
+/// Manages the autoplay policy and its persistence
+pub struct AutoplayManager {
+    policy: std::sync::Mutex<AutoplayPolicy>,
+    store_path: PathBuf,
+}
+
+impl AutoplayManager {
+    /// Creates a new AutoplayManager, loading any saved policy
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("autoplay.json");
+        let policy = store::load_or_default(&store_path);
+        Self {
+            policy: std::sync::Mutex::new(policy),
+            store_path,
+        }
+    }
+
+    /// Gets the current policy
+    pub fn get(&self) -> Result<AutoplayPolicy, String> {
+        Ok(*self.policy.lock().map_err(|e| e.to_string())?)
+    }
+
+    /// Updates and persists the policy
+    pub fn set(&self, policy: AutoplayPolicy) -> Result<(), String> {
+        *self.policy.lock().map_err(|e| e.to_string())? = policy;
+        store::save(&self.store_path, &policy).map_err(|e| format!("Failed to save autoplay policy: {}", e))
+    }
+}
+
+/// Builds the script applying `policy` in the page. `Allow` only removes a
+/// previously installed handler.
+pub fn autoplay_script(policy: AutoplayPolicy) -> String {
+    if policy == AutoplayPolicy::Allow {
+        return r#"
+        (function() {
+            if (window.__MD_AUTOPLAY__) window.__MD_AUTOPLAY__.disable();
+        })();
+        "#
+        .to_string();
+    }
+
+    // Indexed by muted + 2 * user gesture
+    let decisions = [(false, false), (true, false), (false, true), (true, true)]
+        .map(|(muted, gesture)| decide(policy, muted, gesture));
+    let decisions = serde_json::to_string(&decisions).unwrap_or_else(|_| "[]".to_string());
+
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_AUTOPLAY__) window.__MD_AUTOPLAY__.disable();
+
+            const DECISIONS = {decisions};
+            const originalPlay = HTMLMediaElement.prototype.play;
+
+            function decision(media) {{
+                const gesture = !!(navigator.userActivation && navigator.userActivation.isActive);
+                return DECISIONS[(media.muted ? 1 : 0) + (gesture ? 2 : 0)] || 'play';
+            }}
+
+            HTMLMediaElement.prototype.play = function() {{
+                const d = decision(this);
+                if (d === 'refuse') {{
+                    return Promise.reject(new DOMException('Autoplay is blocked', 'NotAllowedError'));
+                }}
+                if (d === 'play_muted') this.muted = true;
+                return originalPlay.apply(this, arguments);
+            }};
+
+            // Elements with the `autoplay` attribute start without calling play()
+            function onPlay(event) {{
+                const media = event.target;
+                if (!(media instanceof HTMLMediaElement)) return;
+                const d = decision(media);
+                if (d === 'refuse') media.pause();
+                else if (d === 'play_muted') media.muted = true;
+            }}
+
+            document.addEventListener('play', onPlay, true);
+
+            window.__MD_AUTOPLAY__ = {{
+                disable: function() {{
+                    HTMLMediaElement.prototype.play = originalPlay;
+                    document.removeEventListener('play', onPlay, true);
+                    delete window.__MD_AUTOPLAY__;
+                }},
+            }};
+        }})();
+        "#,
+        decisions = decisions,
+    )
+}
+
+/// Script re-injected after every page load; `None` while autoplay is allowed.
+pub fn page_load_script(app: &AppHandle) -> Option<String> {
+    let policy = app.try_state::<AutoplayManager>()?.get().ok()?;
+    (policy != AutoplayPolicy::Allow).then(|| autoplay_script(policy))
+}
+
+/// Tauri command to set whether media may autoplay: `allow`, `muted_only` (muted
+/// first) or `block` (only when the user starts it)
+#[tauri::command]
+#[specta::specta]
+pub fn set_autoplay_policy(
+    policy: AutoplayPolicy,
+    app: AppHandle,
+    manager: tauri::State<'_, AutoplayManager>,
+) -> Result<(), String> {
+    manager.set(policy)?;
+    crate::injection::apply(&app, crate::injection::AUTOPLAY, &autoplay_script(policy))?;
+    log::info!("[autoplay] policy set to {:?}", policy);
+    app.emit("autoplay-policy-changed", AutoplayPolicyChangedPayload { policy })
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command to get the autoplay policy
+#[tauri::command]
+#[specta::specta]
+pub fn get_autoplay_policy(manager: tauri::State<'_, AutoplayManager>) -> Result<AutoplayPolicy, String> {
+    manager.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_decisions() {
+        use AutoplayPolicy::*;
+        use PlayDecision::*;
+
+        for muted in [false, true] {
+            assert_eq!(decide(Allow, muted, false), Play);
+            assert_eq!(decide(Block, muted, false), Refuse);
+        }
+        assert_eq!(decide(MutedOnly, false, false), PlayMuted);
+        assert_eq!(decide(MutedOnly, true, false), Play);
+
+        // The user pressing play always wins
+        for policy in [Allow, MutedOnly, Block] {
+            assert_eq!(decide(policy, false, true), Play);
+        }
+    }
+
+    #[test]
+    fn test_script_and_persistence() {
+        assert!(autoplay_script(AutoplayPolicy::Block).contains(r#"["refuse","refuse","play","play"]"#));
+        assert!(autoplay_script(AutoplayPolicy::MutedOnly).contains(r#"["play_muted","play","play","play"]"#));
+        assert!(autoplay_script(AutoplayPolicy::Allow).contains("disable()"));
+
+        let tmp = std::env::temp_dir().join("test-autoplay");
+        std::fs::create_dir_all(&tmp).unwrap();
+        AutoplayManager::new(&tmp).set(AutoplayPolicy::MutedOnly).unwrap();
+        assert_eq!(AutoplayManager::new(&tmp).get().unwrap(), AutoplayPolicy::MutedOnly);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
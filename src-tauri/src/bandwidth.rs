@@ -0,0 +1,273 @@
+//! Bandwidth-saver mode.
+//!
+//! Stops videos from autoplaying and defers message images/GIFs until clicked, so a
+//! metered connection isn't spent on media the user never looks at. Optionally also
+//! refuses tracker requests from the page. The session stays logged in; only media
+//! payloads are held back. The script is re-injected on every page load through the
+//! injection registry.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::privacy_engine::BLOCKED_DOMAINS;
+use crate::store;
+
+/// Persisted bandwidth-saver configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct BandwidthSaverConfig {
+    pub enabled: bool,
+    /// Also refuse tracker / ad requests while enabled
+    pub skip_trackers: bool,
+}
+
+/// Payload emitted with `bandwidth-saver-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct BandwidthSaverChangedPayload {
+    pub enabled: bool,
+    pub skip_trackers: bool,
+}
+
+/// Manages the bandwidth-saver configuration and its persistence
+pub struct BandwidthSaverManager {
+    config: std::sync::Mutex<BandwidthSaverConfig>,
+    store_path: PathBuf,
+}
+
+impl BandwidthSaverManager {
+    /// Creates a new BandwidthSaverManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("bandwidth_saver.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: std::sync::Mutex::new(config),
+            store_path,
+        }
+    }
+
+    /// Gets the current configuration
+    pub fn get_config(&self) -> Result<BandwidthSaverConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Updates and persists the configuration
+    pub fn set(&self, config: BandwidthSaverConfig) -> Result<(), String> {
+        *self.config.lock().map_err(|e| e.to_string())? = config.clone();
+        store::save(&self.store_path, &config)
+            .map_err(|e| format!("Failed to save bandwidth saver config: {}", e))
+    }
+}
+
+/// Selectors for the heavy images (photos, GIFs, stickers) in a platform's messages.
+/// Without an active platform, a generic selector is used.
+fn media_selectors(platform: Option<&Platform>) -> &'static [&'static str] {
+    match platform {
+        Some(Platform::Messenger) | Some(Platform::Facebook) => &[
+            "[role=\"main\"] img[src*=\"scontent\"]",
+            "[role=\"main\"] img[src*=\".gif\"]",
+        ],
+        Some(Platform::Instagram) => &[
+            "[role=\"main\"] img[src*=\"cdninstagram\"]",
+            "[role=\"main\"] img[src*=\"fbcdn\"]",
+        ],
+        Some(Platform::X) => &[
+            "[data-testid=\"DmActivityViewport\"] img[src*=\"pbs.twimg.com/dm_\"]",
+            "[data-testid=\"DmActivityViewport\"] img[src*=\"pbs.twimg.com/media\"]",
+        ],
+        None => &["[role=\"main\"] img"],
+    }
+}
+
+/// Builds the script that turns the bandwidth saver on or off in the page.
+pub fn bandwidth_script(config: &BandwidthSaverConfig, platform: Option<&Platform>) -> String {
+    if !config.enabled {
+        return r#"
+        (function() {
+            if (window.__MD_BANDWIDTH_SAVER__) window.__MD_BANDWIDTH_SAVER__.disable();
+        })();
+        "#
+        .to_string();
+    }
+
+    let selectors = serde_json::to_string(&media_selectors(platform).join(", ")).unwrap_or_default();
+    let blocked: &[&str] = if config.skip_trackers { BLOCKED_DOMAINS } else { &[] };
+    let blocked = serde_json::to_string(blocked).unwrap_or_else(|_| "[]".to_string());
+
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_BANDWIDTH_SAVER__) window.__MD_BANDWIDTH_SAVER__.disable();
+
+            const SELECTOR = {selectors};
+            const BLOCKED = {blocked};
+            const MIN_SIZE = 64; // smaller images are avatars / icons
+
+            function holdVideo(video) {{
+                video.autoplay = false;
+                video.preload = 'none';
+                if (!video.paused) video.pause();
+            }}
+
+            function deferImage(img) {{
+                if (img.dataset.mdSrc || (img.width && img.width < MIN_SIZE)) return;
+                img.dataset.mdSrc = img.currentSrc || img.src;
+                if (img.srcset) {{ img.dataset.mdSrcset = img.srcset; img.removeAttribute('srcset'); }}
+                img.removeAttribute('src');
+                img.style.minWidth = MIN_SIZE + 'px';
+                img.style.minHeight = MIN_SIZE + 'px';
+                img.style.outline = '1px dashed currentColor';
+                img.style.cursor = 'pointer';
+                img.title = 'Click to load';
+                img.addEventListener('click', loadImage, {{ once: true, capture: true }});
+            }}
+
+            function loadImage(event) {{
+                const img = event.currentTarget;
+                event.preventDefault();
+                event.stopPropagation();
+                restore(img);
+            }}
+
+            function restore(img) {{
+                if (img.dataset.mdSrcset) img.srcset = img.dataset.mdSrcset;
+                if (img.dataset.mdSrc) img.src = img.dataset.mdSrc;
+                delete img.dataset.mdSrc;
+                delete img.dataset.mdSrcset;
+                img.style.outline = img.style.cursor = img.style.minWidth = img.style.minHeight = '';
+                img.title = '';
+            }}
+
+            function scan(root) {{
+                root.querySelectorAll('video').forEach(holdVideo);
+                root.querySelectorAll(SELECTOR).forEach(deferImage);
+            }}
+
+            const observer = new MutationObserver(function() {{ scan(document); }});
+            observer.observe(document.documentElement, {{ childList: true, subtree: true }});
+            scan(document);
+
+            const isBlocked = function(url) {{
+                return BLOCKED.some(function(d) {{ return String(url).indexOf(d) !== -1; }});
+            }};
+            const originalFetch = window.fetch;
+            const originalOpen = XMLHttpRequest.prototype.open;
+            if (BLOCKED.length) {{
+                window.fetch = function(input, init) {{
+                    const url = (input && input.url) || input;
+                    if (isBlocked(url)) return Promise.reject(new TypeError('Blocked by bandwidth saver'));
+                    return originalFetch.call(this, input, init);
+                }};
+                XMLHttpRequest.prototype.open = function(method, url) {{
+                    if (isBlocked(url)) url = 'about:blank';
+                    return originalOpen.apply(this, [method, url].concat([].slice.call(arguments, 2)));
+                }};
+            }}
+
+            window.__MD_BANDWIDTH_SAVER__ = {{
+                disable: function() {{
+                    observer.disconnect();
+                    window.fetch = originalFetch;
+                    XMLHttpRequest.prototype.open = originalOpen;
+                    document.querySelectorAll('img[data-md-src]').forEach(restore);
+                    delete window.__MD_BANDWIDTH_SAVER__;
+                }},
+            }};
+        }})();
+        "#,
+        selectors = selectors,
+        blocked = blocked,
+    )
+}
+
+/// The bandwidth-saver script for the current config and platform
+fn current_script(app: &AppHandle, config: &BandwidthSaverConfig) -> String {
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    bandwidth_script(config, platform.as_ref())
+}
+
+/// Script re-injected after every page load; `None` while the saver is off.
+pub fn page_load_script(app: &AppHandle) -> Option<String> {
+    let config = app.try_state::<BandwidthSaverManager>()?.get_config().ok()?;
+    config.enabled.then(|| current_script(app, &config))
+}
+
+/// Tauri command to turn the bandwidth saver on or off
+#[tauri::command]
+#[specta::specta]
+pub fn set_bandwidth_saver(
+    enabled: bool,
+    skip_trackers: bool,
+    app: AppHandle,
+    manager: tauri::State<'_, BandwidthSaverManager>,
+) -> Result<(), String> {
+    manager.set(BandwidthSaverConfig {
+        enabled,
+        skip_trackers,
+    })?;
+    let config = manager.get_config()?;
+    crate::injection::apply(&app, crate::injection::BANDWIDTH_SAVER, &current_script(&app, &config))?;
+    log::info!("[bandwidth] bandwidth saver {}", if enabled { "enabled" } else { "disabled" });
+    app.emit(
+        "bandwidth-saver-changed",
+        BandwidthSaverChangedPayload {
+            enabled,
+            skip_trackers,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command to get the bandwidth saver configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_bandwidth_saver(
+    manager: tauri::State<'_, BandwidthSaverManager>,
+) -> Result<BandwidthSaverConfig, String> {
+    manager.get_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(skip_trackers: bool) -> BandwidthSaverConfig {
+        BandwidthSaverConfig {
+            enabled: true,
+            skip_trackers,
+        }
+    }
+
+    #[test]
+    fn test_script_is_platform_aware() {
+        let messenger = bandwidth_script(&enabled(false), Some(&Platform::Messenger));
+        let x = bandwidth_script(&enabled(false), Some(&Platform::X));
+        assert!(messenger.contains("scontent"));
+        assert!(!messenger.contains("twimg"));
+        assert!(x.contains("pbs.twimg.com"));
+    }
+
+    #[test]
+    fn test_trackers_only_blocked_when_requested() {
+        let plain = bandwidth_script(&enabled(false), None);
+        let strict = bandwidth_script(&enabled(true), None);
+        assert!(plain.contains("const BLOCKED = [];"));
+        assert!(strict.contains("doubleclick.net"));
+
+        let off = bandwidth_script(&BandwidthSaverConfig::default(), Some(&Platform::X));
+        assert!(off.contains("disable()"));
+        assert!(!off.contains("MutationObserver"));
+    }
+
+    #[test]
+    fn test_config_persistence() {
+        let tmp = std::env::temp_dir().join("test-bandwidth-saver");
+        std::fs::create_dir_all(&tmp).unwrap();
+        BandwidthSaverManager::new(&tmp).set(enabled(true)).unwrap();
+        assert_eq!(BandwidthSaverManager::new(&tmp).get_config().unwrap(), enabled(true));
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,476 @@
+//! Call detection and Do Not Disturb during calls.
+//!
+//! A probe injected into the page reports whether a call is going on: the
+//! platform's call controls are on screen (selectors differ per platform), or the
+//! page holds a live camera, microphone or screen-capture stream (`getUserMedia` /
+//! `getDisplayMedia` are wrapped to see them). Screen sharing from other apps is
+//! not visible to the webview, so only sharing started from the page counts.
+//! `get_call_state` sums this up as a `CallKind`, and `call-state-changed` fires
+//! whenever that changes.
+//!
+//! With `set_dnd_during_calls` on, a call turns DND on and pauses sounds, and its
+//! end turns them back off. DND the user set themselves is never touched: a call
+//! that starts while DND is already on leaves it alone, and changing DND by hand
+//! during a call hands it back to the user.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::notifications::{DndChangedPayload, NotificationService};
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::store;
+
+/// How often the injected probe looks for a call
+const PROBE_INTERVAL_MS: u32 = 2_000;
+
+/// What the probe sees of a call. Fields the probe leaves out read as `false`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct CallState {
+    /// The platform's call controls are on screen
+    pub call_ui: bool,
+    /// The call controls include a camera toggle or video
+    pub call_video: bool,
+    pub camera: bool,
+    pub microphone: bool,
+    pub screen_sharing: bool,
+}
+
+/// The kind of call in progress
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum CallKind {
+    #[default]
+    None,
+    Voice,
+    Video,
+    ScreenShare,
+}
+
+impl CallState {
+    /// The call this state amounts to. Screen sharing wins over video, video over
+    /// voice; call controls without any media count only when the platform's UI
+    /// says which kind of call it is.
+    pub fn kind(&self) -> CallKind {
+        if self.screen_sharing {
+            CallKind::ScreenShare
+        } else if self.camera || (self.call_ui && self.call_video) {
+            CallKind::Video
+        } else if self.microphone || self.call_ui {
+            CallKind::Voice
+        } else {
+            CallKind::None
+        }
+    }
+
+    /// Whether any sign of a call is present
+    pub fn active(&self) -> bool {
+        self.kind() != CallKind::None
+    }
+}
+
+/// Persisted call configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct CallConfig {
+    /// Turn DND on and pause sounds while in a call
+    pub dnd_during_calls: bool,
+}
+
+/// Payload emitted with `call-state-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct CallStateChangedPayload {
+    pub kind: CallKind,
+    pub platform: Option<String>,
+}
+
+/// Payload emitted with `call-dnd-engaged` / `call-dnd-disengaged`
+#[derive(Serialize, Clone, Debug)]
+pub struct CallDndPayload {
+    pub state: CallState,
+}
+
+/// What to do to DND after a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AutoDndAction {
+    Engage,
+    Disengage,
+    Nothing,
+}
+
+/// Tracks whether DND is on because of a call, so only DND this turned on is
+/// ever turned off
+#[derive(Debug, Default)]
+struct AutoDnd {
+    engaged: bool,
+}
+
+impl AutoDnd {
+    /// Decides what to do given the setting, whether a call is active and whether
+    /// DND is currently on
+    fn update(&mut self, enabled: bool, in_call: bool, dnd_on: bool) -> AutoDndAction {
+        match (self.engaged, enabled && in_call) {
+            (false, true) if !dnd_on => {
+                self.engaged = true;
+                AutoDndAction::Engage
+            }
+            (true, false) => {
+                self.engaged = false;
+                AutoDndAction::Disengage
+            }
+            _ => AutoDndAction::Nothing,
+        }
+    }
+
+    /// The user changed DND by hand; it is theirs from now on
+    fn release(&mut self) -> bool {
+        std::mem::take(&mut self.engaged)
+    }
+}
+
+/// Manages the call configuration, the last reported call state and auto DND
+pub struct CallManager {
+    config: Mutex<CallConfig>,
+    store_path: PathBuf,
+    state: Mutex<CallState>,
+    auto: Mutex<AutoDnd>,
+}
+
+impl CallManager {
+    /// Creates a new CallManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("calls.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: Mutex::new(config),
+            store_path,
+            state: Mutex::new(CallState::default()),
+            auto: Mutex::new(AutoDnd::default()),
+        }
+    }
+
+    /// Gets the current configuration
+    pub fn get_config(&self) -> Result<CallConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Updates and persists the configuration
+    pub fn set(&self, config: CallConfig) -> Result<(), String> {
+        *self.config.lock().map_err(|e| e.to_string())? = config.clone();
+        store::save(&self.store_path, &config)
+            .map_err(|e| format!("Failed to save call config: {}", e))
+    }
+
+    /// The last reported call state
+    pub fn state(&self) -> Result<CallState, String> {
+        Ok(self.state.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Records a report; returns the new kind of call if it changed
+    fn record(&self, state: CallState) -> Result<Option<CallKind>, String> {
+        let mut current = self.state.lock().map_err(|e| e.to_string())?;
+        let kind = state.kind();
+        let previous = std::mem::replace(&mut *current, state).kind();
+        Ok((previous != kind).then_some(kind))
+    }
+
+    fn update_auto(&self, dnd_on: bool) -> Result<AutoDndAction, String> {
+        let enabled = self.get_config()?.dnd_during_calls;
+        let in_call = self.state()?.active();
+        Ok(self.auto.lock().map_err(|e| e.to_string())?.update(enabled, in_call, dnd_on))
+    }
+}
+
+/// Selectors for a platform's call controls, and for the parts of them that only
+/// a video call has. Without an active platform, generic ones are used.
+fn call_selectors(platform: Option<&Platform>) -> (&'static str, &'static str) {
+    match platform {
+        Some(Platform::Messenger) | Some(Platform::Facebook) => (
+            "[aria-label=\"End call\" i], [aria-label=\"Leave call\" i]",
+            "[aria-label*=\"camera\" i][role=\"button\"], [aria-label*=\"video\" i][role=\"button\"]",
+        ),
+        Some(Platform::Instagram) => (
+            "[aria-label*=\"End call\" i], [aria-label*=\"Leave call\" i]",
+            "[aria-label*=\"camera\" i][role=\"button\"]",
+        ),
+        Some(Platform::X) => (
+            "[data-testid=\"callControls\"], [aria-label*=\"Hang up\" i], [aria-label*=\"End call\" i]",
+            "[data-testid=\"callControls\"] [aria-label*=\"camera\" i]",
+        ),
+        None => (
+            "[aria-label*=\"End call\" i], [aria-label*=\"Leave call\" i], [aria-label*=\"Hang up\" i]",
+            "[aria-label*=\"camera\" i][role=\"button\"]",
+        ),
+    }
+}
+
+/// Builds the probe for `platform`: wraps the media APIs and reports the call state
+/// whenever it changes. Anything the probe can't read reports as no call.
+pub fn probe_script(platform: Option<&Platform>) -> String {
+    let (call_ui, call_video) = call_selectors(platform);
+    let call_ui = serde_json::to_string(call_ui).unwrap_or_default();
+    let call_video = serde_json::to_string(call_video).unwrap_or_default();
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_CALL_PROBE__) clearInterval(window.__MD_CALL_PROBE__);
+            const core = window.__TAURI__ && window.__TAURI__.core;
+            const media = navigator.mediaDevices;
+            if (!core || !media) return;
+
+            const CALL_UI = {call_ui};
+            const CALL_VIDEO = {call_video};
+
+            if (!window.__MD_CALL_STREAMS__) {{
+                const streams = window.__MD_CALL_STREAMS__ = {{ user: [], display: [] }};
+                function wrap(name, list) {{
+                    if (!media[name]) return;
+                    const original = media[name].bind(media);
+                    media[name] = function() {{
+                        return original.apply(null, arguments).then(function(stream) {{
+                            list.push(stream);
+                            return stream;
+                        }});
+                    }};
+                }}
+                wrap('getUserMedia', streams.user);
+                wrap('getDisplayMedia', streams.display);
+            }}
+
+            function live(list, kind) {{
+                return list.some(function(stream) {{
+                    return stream.getTracks().some(function(t) {{
+                        return t.readyState === 'live' && (!kind || t.kind === kind);
+                    }});
+                }});
+            }}
+
+            function present(selector) {{
+                try {{
+                    return !!document.querySelector(selector);
+                }} catch (e) {{
+                    return false;
+                }}
+            }}
+
+            function read() {{
+                const streams = window.__MD_CALL_STREAMS__;
+                ['user', 'display'].forEach(function(k) {{
+                    streams[k] = streams[k].filter(function(s) {{ return live([s]); }});
+                }});
+                const callUi = present(CALL_UI);
+                return {{
+                    call_ui: callUi,
+                    call_video: callUi && present(CALL_VIDEO),
+                    camera: live(streams.user, 'video'),
+                    microphone: live(streams.user, 'audio'),
+                    screen_sharing: live(streams.display),
+                }};
+            }}
+
+            let last = null;
+            function probe() {{
+                let state;
+                try {{
+                    state = read();
+                }} catch (e) {{
+                    state = {{}};
+                }}
+                const key = JSON.stringify(state);
+                if (key === last) return;
+                last = key;
+                core.invoke('report_call_state', {{ state: state }}).catch(function() {{}});
+            }}
+
+            probe();
+            window.__MD_CALL_PROBE__ = setInterval(probe, {interval});
+        }})();
+        "#,
+        call_ui = call_ui,
+        call_video = call_video,
+        interval = PROBE_INTERVAL_MS,
+    )
+}
+
+/// Script re-injected after every page load, for the active platform
+pub fn page_load_script(app: &AppHandle) -> Option<String> {
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    Some(probe_script(platform.as_ref()))
+}
+
+/// Applies `action` to DND and sounds and emits the matching events
+async fn apply(app: &AppHandle, action: AutoDndAction, state: CallState) -> Result<(), String> {
+    let service = app.state::<NotificationService>();
+    let enabled = match action {
+        AutoDndAction::Engage => true,
+        AutoDndAction::Disengage => false,
+        AutoDndAction::Nothing => return Ok(()),
+    };
+    service.set_dnd(enabled).await.map_err(|e| e.to_string())?;
+    service.set_sounds_paused(enabled).await;
+    log::info!("[calls] DND {} for a call", if enabled { "engaged" } else { "disengaged" });
+
+    app.emit("dnd-changed", DndChangedPayload { enabled, until: None })
+        .map_err(|e| e.to_string())?;
+    let event = if enabled { "call-dnd-engaged" } else { "call-dnd-disengaged" };
+    app.emit(event, CallDndPayload { state }).map_err(|e| e.to_string())?;
+    if !enabled {
+        crate::notifications::show_dnd_summary(app).await?;
+    }
+    Ok(())
+}
+
+/// Re-evaluates auto DND against the current DND setting
+async fn reevaluate(app: &AppHandle) -> Result<(), String> {
+    let Some(manager) = app.try_state::<CallManager>() else {
+        return Ok(());
+    };
+    let dnd_on = app.state::<NotificationService>().get_settings().await.do_not_disturb;
+    let action = manager.update_auto(dnd_on)?;
+    apply(app, action, manager.state()?).await
+}
+
+/// Called after the user changes DND by hand: a call no longer owns it
+pub async fn on_manual_dnd(app: &AppHandle) {
+    let released = app
+        .try_state::<CallManager>()
+        .and_then(|m| m.auto.lock().ok().map(|mut auto| auto.release()))
+        .unwrap_or(false);
+    if released {
+        app.state::<NotificationService>().set_sounds_paused(false).await;
+    }
+}
+
+/// Tauri command called by the injected probe when the call state changes
+#[tauri::command]
+#[specta::specta]
+pub async fn report_call_state(
+    state: CallState,
+    app: AppHandle,
+    manager: tauri::State<'_, CallManager>,
+) -> Result<(), String> {
+    if let Some(kind) = manager.record(state)? {
+        log::info!("[calls] call state {:?}", kind);
+        let platform = app
+            .try_state::<PlatformManager>()
+            .and_then(|m| m.get_current().ok().flatten())
+            .map(|p| p.name().to_string());
+        app.emit("call-state-changed", CallStateChangedPayload { kind, platform })
+            .map_err(|e| e.to_string())?;
+    }
+    reevaluate(&app).await
+}
+
+/// Tauri command to get the kind of call in progress on the current platform
+#[tauri::command]
+#[specta::specta]
+pub fn get_call_state(manager: tauri::State<'_, CallManager>) -> Result<CallKind, String> {
+    Ok(manager.state()?.kind())
+}
+
+/// Tauri command to turn DND (and sounds off) automatically during calls
+#[tauri::command]
+#[specta::specta]
+pub async fn set_dnd_during_calls(
+    enabled: bool,
+    app: AppHandle,
+    manager: tauri::State<'_, CallManager>,
+) -> Result<(), String> {
+    manager.set(CallConfig { dnd_during_calls: enabled })?;
+    log::info!("[calls] DND during calls {}", if enabled { "enabled" } else { "disabled" });
+    reevaluate(&app).await
+}
+
+/// Tauri command to get the call configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_call_config(manager: tauri::State<'_, CallManager>) -> Result<CallConfig, String> {
+    manager.get_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_engages_and_restores_dnd() {
+        let mut auto = AutoDnd::default();
+        assert_eq!(auto.update(true, true, false), AutoDndAction::Engage);
+        // Further reports during the call change nothing
+        assert_eq!(auto.update(true, true, true), AutoDndAction::Nothing);
+        assert_eq!(auto.update(true, false, true), AutoDndAction::Disengage);
+        assert_eq!(auto.update(true, false, false), AutoDndAction::Nothing);
+
+        // Turning the setting off mid-call restores DND as well
+        assert_eq!(auto.update(true, true, false), AutoDndAction::Engage);
+        assert_eq!(auto.update(false, true, true), AutoDndAction::Disengage);
+        assert_eq!(auto.update(false, true, false), AutoDndAction::Nothing);
+    }
+
+    #[test]
+    fn test_manual_dnd_is_not_clobbered() {
+        // DND already on when the call starts: left on when it ends
+        let mut auto = AutoDnd::default();
+        assert_eq!(auto.update(true, true, true), AutoDndAction::Nothing);
+        assert_eq!(auto.update(true, false, true), AutoDndAction::Nothing);
+
+        // DND changed by hand during the call: the call's end leaves it alone
+        assert_eq!(auto.update(true, true, false), AutoDndAction::Engage);
+        assert!(auto.release());
+        assert_eq!(auto.update(true, true, true), AutoDndAction::Nothing);
+        assert_eq!(auto.update(true, false, true), AutoDndAction::Nothing);
+        assert!(!auto.release());
+
+        // Setting off: calls never engage
+        let mut auto = AutoDnd::default();
+        assert_eq!(auto.update(false, true, false), AutoDndAction::Nothing);
+    }
+
+    #[test]
+    fn test_probe_results_map_to_call_kind() {
+        let state = |call_ui, call_video, camera, microphone, screen_sharing| CallState {
+            call_ui,
+            call_video,
+            camera,
+            microphone,
+            screen_sharing,
+        };
+        assert_eq!(CallState::default().kind(), CallKind::None);
+        assert_eq!(state(false, false, false, true, false).kind(), CallKind::Voice);
+        assert_eq!(state(true, false, false, false, false).kind(), CallKind::Voice);
+        assert_eq!(state(false, false, true, true, false).kind(), CallKind::Video);
+        assert_eq!(state(true, true, false, false, false).kind(), CallKind::Video);
+        assert_eq!(state(true, true, true, true, true).kind(), CallKind::ScreenShare);
+        // A camera toggle with no call controls is not a call
+        assert_eq!(state(false, true, false, false, false).kind(), CallKind::None);
+
+        // What a failed probe reports, and unknown fields from a newer probe
+        let failed: CallState = serde_json::from_str("{}").unwrap();
+        assert_eq!(failed.kind(), CallKind::None);
+        let newer: CallState = serde_json::from_str(r#"{"microphone":true,"ringing":true}"#).unwrap();
+        assert_eq!(newer.kind(), CallKind::Voice);
+    }
+
+    #[test]
+    fn test_call_state_and_config() {
+        let x = probe_script(Some(&Platform::X));
+        assert!(x.contains("callControls"));
+        assert!(!probe_script(Some(&Platform::Messenger)).contains("callControls"));
+
+        let tmp = std::env::temp_dir().join("test-call-config");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = CallManager::new(&tmp);
+        assert!(!manager.get_config().unwrap().dnd_during_calls);
+        manager.set(CallConfig { dnd_during_calls: true }).unwrap();
+        assert!(CallManager::new(&tmp).get_config().unwrap().dnd_during_calls);
+        let voice = CallState { microphone: true, ..CallState::default() };
+        assert_eq!(manager.record(voice.clone()).unwrap(), Some(CallKind::Voice));
+        assert_eq!(manager.record(voice).unwrap(), None);
+        // Unchanged kind, even though the raw state moved
+        assert_eq!(manager.record(CallState { call_ui: true, ..CallState::default() }).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
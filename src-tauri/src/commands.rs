@@ -1,17 +1,51 @@
+//! Miscellaneous webview commands: clipboard access, printing and restarting.
+
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// How long the frontend gets to finish up after `before-restart`
+const RESTART_GRACE: Duration = Duration::from_millis(500);
+
 /// Read text from the clipboard.
 #[tauri::command]
+#[specta::specta]
 pub fn read_clipboard_text(clipboard: tauri::State<'_, tauri_plugin_clipboard_manager::Clipboard<tauri::Wry>>) -> Result<String, String> {
-    clipboard.read_text().map_err(|e: arboard::Error| e.to_string())
+    clipboard.read_text().map_err(|e| e.to_string())
 }
 
 /// Write text to the clipboard.
 #[tauri::command]
+#[specta::specta]
 pub fn write_clipboard_text(clipboard: tauri::State<'_, tauri_plugin_clipboard_manager::Clipboard<tauri::Wry>>, text: String) -> Result<(), String> {
-    clipboard.write_text(text).map_err(|e: arboard::Error| e.to_string())
+    clipboard.write_text(text).map_err(|e| e.to_string())
 }
 
 /// Print the current page.
 #[tauri::command]
+#[specta::specta]
 pub async fn print_page(window: tauri::WebviewWindow) -> Result<(), String> {
     window.print().map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+/// Saves state that is only held in memory until the app closes
+async fn flush_pending(app: &tauri::AppHandle) -> Result<(), String> {
+    let window_manager = app.state::<crate::window_manager::WindowManager>();
+    match app.get_webview_window("main") {
+        Some(main) => crate::window_manager::capture_window(&main, &window_manager).await,
+        None => window_manager.save_current_state().await.map_err(|e| e.to_string()),
+    }
+}
+
+/// Restart the app, e.g. after a setting that only applies at startup changed.
+/// Emits `before-restart` and saves the window state first. `restart` exits
+/// without closing the window, so the quit cleanup (which clears sessions) doesn't
+/// run and sessions are kept.
+#[tauri::command]
+#[specta::specta]
+pub async fn restart_app(app: tauri::AppHandle) -> Result<(), String> {
+    app.emit("before-restart", ()).map_err(|e| e.to_string())?;
+    tokio::time::sleep(RESTART_GRACE).await;
+    flush_pending(&app).await?;
+    log::info!("[commands] restarting");
+    app.restart()
+}
@@ -0,0 +1,232 @@
+//! Send-on-Enter preference for the platforms' message composers.
+//!
+//! With `send_on_enter` on (the default, and what every platform does on its own),
+//! Enter sends and Shift+Enter starts a new line. With it off the two are swapped:
+//! Enter inserts a line break and Shift/Ctrl/Cmd+Enter sends. The handler only acts
+//! on key presses in the conversation composer, so search boxes and other inputs
+//! keep their own Enter behavior. The script is re-injected on every page load
+//! through the injection registry.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::store;
+
+/// Persisted composer configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ComposerConfig {
+    /// Enter sends (Shift+Enter for a new line); off swaps the two
+    pub send_on_enter: bool,
+}
+
+impl Default for ComposerConfig {
+    fn default() -> Self {
+        Self { send_on_enter: true }
+    }
+}
+
+/// Payload emitted with `send-on-enter-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct SendOnEnterChangedPayload {
+    pub enabled: bool,
+}
+
+/// Manages the composer configuration and its persistence
+pub struct ComposerManager {
+    config: std::sync::Mutex<ComposerConfig>,
+    store_path: PathBuf,
+}
+
+impl ComposerManager {
+    /// Creates a new ComposerManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("composer.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: std::sync::Mutex::new(config),
+            store_path,
+        }
+    }
+
+    /// Gets the current configuration
+    pub fn get_config(&self) -> Result<ComposerConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Updates and persists the configuration
+    pub fn set(&self, config: ComposerConfig) -> Result<(), String> {
+        *self.config.lock().map_err(|e| e.to_string())? = config.clone();
+        store::save(&self.store_path, &config)
+            .map_err(|e| format!("Failed to save composer config: {}", e))
+    }
+}
+
+/// Selectors for a platform's conversation composer and its send button. Without
+/// an active platform, generic selectors are used.
+pub(crate) fn composer_selectors(platform: Option<&Platform>) -> (&'static str, &'static str) {
+    match platform {
+        Some(Platform::Messenger) | Some(Platform::Facebook) => (
+            "[role=\"main\"] [role=\"textbox\"][contenteditable=\"true\"]",
+            "[role=\"main\"] [aria-label=\"Press enter to send\"], [role=\"main\"] [aria-label=\"Send\"]",
+        ),
+        Some(Platform::Instagram) => (
+            "[role=\"main\"] [role=\"textbox\"][contenteditable=\"true\"], [role=\"main\"] textarea",
+            "[role=\"main\"] [role=\"button\"]",
+        ),
+        Some(Platform::X) => (
+            "[data-testid=\"dmComposerTextInput\"]",
+            "[data-testid=\"dmComposerSendButton\"]",
+        ),
+        None => (
+            "[role=\"main\"] [role=\"textbox\"][contenteditable=\"true\"]",
+            "[role=\"main\"] [aria-label=\"Send\"]",
+        ),
+    }
+}
+
+/// Builds the script that applies `config` in the page. With send-on-Enter on, the
+/// platform's own behavior is left alone and the script only removes a previously
+/// installed handler.
+pub fn send_on_enter_script(config: &ComposerConfig, platform: Option<&Platform>) -> String {
+    if config.send_on_enter {
+        return r#"
+        (function() {
+            if (window.__MD_COMPOSER__) window.__MD_COMPOSER__.disable();
+        })();
+        "#
+        .to_string();
+    }
+
+    let (composer, send) = composer_selectors(platform);
+    let composer = serde_json::to_string(composer).unwrap_or_default();
+    let send = serde_json::to_string(send).unwrap_or_default();
+
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_COMPOSER__) window.__MD_COMPOSER__.disable();
+
+            const COMPOSER = {composer};
+            const SEND_BUTTON = {send};
+
+            function newline(composer) {{
+                if (composer.value !== undefined) {{
+                    composer.setRangeText('\n', composer.selectionStart, composer.selectionEnd, 'end');
+                    composer.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                }} else if (!document.execCommand('insertLineBreak')) {{
+                    document.execCommand('insertText', false, '\n');
+                }}
+            }}
+
+            function send() {{
+                // Some platforms' send button is only recognizable by its label or text
+                const button = Array.from(document.querySelectorAll(SEND_BUTTON)).find(function(b) {{
+                    return /send/i.test(b.getAttribute('aria-label') || '') || /^send$/i.test((b.textContent || '').trim());
+                }});
+                if (button) button.click();
+            }}
+
+            function onKeyDown(event) {{
+                if (event.key !== 'Enter' || event.isComposing || event.altKey) return;
+                const composer = event.target.closest && event.target.closest(COMPOSER);
+                if (!composer) return;
+
+                event.preventDefault();
+                event.stopImmediatePropagation();
+                if (event.shiftKey || event.ctrlKey || event.metaKey) {{
+                    send();
+                }} else {{
+                    newline(composer);
+                }}
+            }}
+
+            document.addEventListener('keydown', onKeyDown, true);
+
+            window.__MD_COMPOSER__ = {{
+                disable: function() {{
+                    document.removeEventListener('keydown', onKeyDown, true);
+                    delete window.__MD_COMPOSER__;
+                }},
+            }};
+        }})();
+        "#,
+        composer = composer,
+        send = send,
+    )
+}
+
+/// The composer script for the current config and platform
+fn current_script(app: &AppHandle, config: &ComposerConfig) -> String {
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    send_on_enter_script(config, platform.as_ref())
+}
+
+/// Script re-injected after every page load; `None` while Enter sends (the default).
+pub fn page_load_script(app: &AppHandle) -> Option<String> {
+    let config = app.try_state::<ComposerManager>()?.get_config().ok()?;
+    (!config.send_on_enter).then(|| current_script(app, &config))
+}
+
+/// Tauri command to choose whether Enter sends (Shift+Enter for a new line) or
+/// inserts a new line (Shift/Ctrl/Cmd+Enter to send)
+#[tauri::command]
+#[specta::specta]
+pub fn set_send_on_enter(
+    enabled: bool,
+    app: AppHandle,
+    manager: tauri::State<'_, ComposerManager>,
+) -> Result<(), String> {
+    manager.set(ComposerConfig { send_on_enter: enabled })?;
+    let config = manager.get_config()?;
+    crate::injection::apply(&app, crate::injection::COMPOSER, &current_script(&app, &config))?;
+    log::info!("[composer] send on Enter {}", if enabled { "enabled" } else { "disabled" });
+    app.emit("send-on-enter-changed", SendOnEnterChangedPayload { enabled })
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command to get the composer configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_composer_config(
+    manager: tauri::State<'_, ComposerManager>,
+) -> Result<ComposerConfig, String> {
+    manager.get_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn newline_on_enter() -> ComposerConfig {
+        ComposerConfig { send_on_enter: false }
+    }
+
+    #[test]
+    fn test_script_is_platform_aware() {
+        let x = send_on_enter_script(&newline_on_enter(), Some(&Platform::X));
+        let messenger = send_on_enter_script(&newline_on_enter(), Some(&Platform::Messenger));
+        assert!(x.contains("dmComposerTextInput"));
+        assert!(!messenger.contains("dmComposerTextInput"));
+        assert!(messenger.contains("Press enter to send"));
+
+        // The default leaves the platform alone and only removes our handler
+        let default = send_on_enter_script(&ComposerConfig::default(), Some(&Platform::X));
+        assert!(default.contains("disable()"));
+        assert!(!default.contains("keydown"));
+    }
+
+    #[test]
+    fn test_config_persistence() {
+        let tmp = std::env::temp_dir().join("test-composer");
+        std::fs::create_dir_all(&tmp).unwrap();
+        assert!(ComposerManager::new(&tmp).get_config().unwrap().send_on_enter);
+        ComposerManager::new(&tmp).set(newline_on_enter()).unwrap();
+        assert_eq!(ComposerManager::new(&tmp).get_config().unwrap(), newline_on_enter());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
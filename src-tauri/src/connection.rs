@@ -0,0 +1,235 @@
+//! Connection status of the active platform.
+//!
+//! An injected probe checks whether the browser thinks it is online, whether the
+//! platform's origin answers, whether the page is a webview error page and whether
+//! the platform's logged-in markers are in the DOM, and reports to
+//! `report_connection_status`. Changes are emitted as `connection-status-changed`
+//! so the frontend can show an offline banner.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::platform_manager::{Platform, PlatformManager};
+
+/// How often the injected probe reports
+const POLL_INTERVAL_MS: u32 = 10_000;
+
+/// How long the reachability check may take before the platform counts as unreachable
+const REACH_TIMEOUT_MS: u32 = 8_000;
+
+/// What the probe found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionStatus {
+    /// The platform is loaded and logged in
+    Connected,
+    /// The platform is loaded but showing its login page
+    LoggedOut,
+    /// No network, or the platform can't be reached
+    Offline,
+    /// The webview is showing an error page
+    LoadError,
+}
+
+/// Payload emitted with `connection-status-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct ConnectionStatusChangedPayload {
+    pub platform: Option<String>,
+    pub status: ConnectionStatus,
+    pub previous: Option<ConnectionStatus>,
+}
+
+/// Works out the status from a probe report
+pub fn classify(online: bool, load_error: bool, logged_in: bool) -> ConnectionStatus {
+    if !online {
+        ConnectionStatus::Offline
+    } else if load_error {
+        ConnectionStatus::LoadError
+    } else if logged_in {
+        ConnectionStatus::Connected
+    } else {
+        ConnectionStatus::LoggedOut
+    }
+}
+
+/// Holds the last reported status
+pub struct ConnectionMonitor {
+    status: Mutex<Option<ConnectionStatus>>,
+}
+
+impl ConnectionMonitor {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(None),
+        }
+    }
+
+    /// The last reported status; `None` before the first report
+    pub fn status(&self) -> Result<Option<ConnectionStatus>, String> {
+        Ok(*self.status.lock().map_err(|e| e.to_string())?)
+    }
+
+    /// Records a status. Returns the previous one if it changed.
+    fn record(&self, status: ConnectionStatus) -> Result<Option<Option<ConnectionStatus>>, String> {
+        let mut current = self.status.lock().map_err(|e| e.to_string())?;
+        if *current == Some(status) {
+            return Ok(None);
+        }
+        Ok(Some(current.replace(status)))
+    }
+}
+
+impl Default for ConnectionMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Selector that only matches once the user is logged in to the platform
+fn logged_in_selector(platform: Option<&Platform>) -> Option<&'static str> {
+    match platform {
+        Some(Platform::Messenger) => Some("a[href*=\"/t/\"], [aria-label=\"Chats\" i][role=\"navigation\"]"),
+        Some(Platform::Facebook) => Some("a[href*=\"/messages/t/\"], [aria-label=\"Chats\" i]"),
+        Some(Platform::Instagram) => Some("a[href^=\"/direct/t/\"], svg[aria-label=\"New message\" i]"),
+        Some(Platform::X) => Some("[data-testid=\"AppTabBar_DirectMessage_Link\"], [data-testid=\"DM_Timeline\"]"),
+        None => None,
+    }
+}
+
+/// Builds the probe script for the active platform
+pub fn probe_script(platform: Option<&Platform>) -> String {
+    let selector = serde_json::to_string(&logged_in_selector(platform)).unwrap_or_else(|_| "null".to_string());
+    let url = serde_json::to_string(&platform.map(Platform::url)).unwrap_or_else(|_| "null".to_string());
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_CONNECTION_PROBE__) clearInterval(window.__MD_CONNECTION_PROBE__);
+            const SELECTOR = {selector};
+            const URL = {url};
+
+            function reachable() {{
+                const target = /^https?:$/.test(location.protocol) ? location.origin + '/' : URL;
+                if (!target) return Promise.resolve(true);
+                const timeout = new Promise(function(resolve) {{ setTimeout(resolve, {timeout}, false); }});
+                const ping = fetch(target, {{ method: 'HEAD', mode: 'no-cors', cache: 'no-store', credentials: 'include' }})
+                    .then(function() {{ return true; }}, function() {{ return false; }});
+                return Promise.race([ping, timeout]);
+            }}
+
+            function probe() {{
+                const core = window.__TAURI__ && window.__TAURI__.core;
+                if (!core) return;
+                const loadError = !/^https?:$/.test(location.protocol);
+                const loggedIn = !SELECTOR || !!document.querySelector(SELECTOR);
+                (navigator.onLine ? reachable() : Promise.resolve(false)).then(function(online) {{
+                    core.invoke('report_connection_status', {{ online: online, loadError: loadError, loggedIn: loggedIn }})
+                        .catch(function(e) {{ console.warn('[messenger-desktop] connection report failed:', e); }});
+                }});
+            }}
+
+            if (!window.__MD_CONNECTION_EVENTS__) {{
+                window.__MD_CONNECTION_EVENTS__ = true;
+                window.addEventListener('online', function() {{ window.__MD_CONNECTION_PROBE_NOW__(); }});
+                window.addEventListener('offline', function() {{ window.__MD_CONNECTION_PROBE_NOW__(); }});
+            }}
+            window.__MD_CONNECTION_PROBE_NOW__ = probe;
+            probe();
+            window.__MD_CONNECTION_PROBE__ = setInterval(probe, {interval});
+        }})();
+        "#,
+        selector = selector,
+        url = url,
+        timeout = REACH_TIMEOUT_MS,
+        interval = POLL_INTERVAL_MS,
+    )
+}
+
+fn current_platform(app: &AppHandle) -> Option<Platform> {
+    app.try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten())
+}
+
+/// Page-load hook: (re)installs the probe for the active platform
+pub fn on_page_load(window: &WebviewWindow) {
+    let platform = current_platform(window.app_handle());
+    if let Err(e) = window.eval(&probe_script(platform.as_ref())) {
+        log::warn!("[connection] failed to install connection probe: {}", e);
+    }
+}
+
+/// Tauri command called by the injected probe with what it found
+#[tauri::command]
+#[specta::specta]
+pub fn report_connection_status(
+    online: bool,
+    load_error: bool,
+    logged_in: bool,
+    app: AppHandle,
+    monitor: tauri::State<'_, ConnectionMonitor>,
+) -> Result<(), String> {
+    let status = classify(online, load_error, logged_in);
+    let Some(previous) = monitor.record(status)? else {
+        return Ok(());
+    };
+    log::info!("[connection] {:?} -> {:?}", previous, status);
+    app.emit(
+        "connection-status-changed",
+        ConnectionStatusChangedPayload {
+            platform: current_platform(&app).map(|p| p.name().to_string()),
+            status,
+            previous,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command to get the active platform's connection status. Also re-runs the
+/// probe, so a change shows up as `connection-status-changed` shortly after.
+/// `None` until the first probe has reported.
+#[tauri::command]
+#[specta::specta]
+pub fn get_connection_status(
+    app: AppHandle,
+    monitor: tauri::State<'_, ConnectionMonitor>,
+) -> Result<Option<ConnectionStatus>, String> {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.eval("window.__MD_CONNECTION_PROBE_NOW__ && window.__MD_CONNECTION_PROBE_NOW__();");
+    }
+    monitor.status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(true, false, true), ConnectionStatus::Connected);
+        assert_eq!(classify(true, false, false), ConnectionStatus::LoggedOut);
+        assert_eq!(classify(true, true, false), ConnectionStatus::LoadError);
+        // Being offline explains an error page
+        assert_eq!(classify(false, true, false), ConnectionStatus::Offline);
+    }
+
+    #[test]
+    fn test_monitor_reports_changes_only() {
+        let monitor = ConnectionMonitor::new();
+        assert_eq!(monitor.status().unwrap(), None);
+        assert_eq!(monitor.record(ConnectionStatus::Connected).unwrap(), Some(None));
+        assert_eq!(monitor.record(ConnectionStatus::Connected).unwrap(), None);
+        assert_eq!(
+            monitor.record(ConnectionStatus::Offline).unwrap(),
+            Some(Some(ConnectionStatus::Connected))
+        );
+        assert_eq!(monitor.status().unwrap(), Some(ConnectionStatus::Offline));
+    }
+
+    #[test]
+    fn test_probe_markers_are_platform_specific() {
+        assert!(probe_script(Some(&Platform::X)).contains("AppTabBar_DirectMessage_Link"));
+        assert!(probe_script(Some(&Platform::Instagram)).contains("/direct/t/"));
+        assert!(probe_script(None).contains("const SELECTOR = null"));
+    }
+}
@@ -0,0 +1,273 @@
+//! Popped-out conversations.
+//!
+//! `open_conversation_window` opens one conversation in a window of its own. The
+//! window uses the default webview profile like the main window, so it is already
+//! logged in, and it stays on its platform whichever platform the main window
+//! shows. Closing it only closes that window.
+//!
+//! Notifications keep coming from the main window's page. One for a popped-out
+//! conversation is attributed to its window (which takes the sender's name as its
+//! title if it was opened without one) and suppressed while that window has focus,
+//! as the platforms do for the conversation that is open.
+
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::platform_manager::Platform;
+use crate::privacy_engine::PrivacyEngine;
+
+/// Window label prefix for conversation windows
+const WINDOW_PREFIX: &str = "conversation-";
+
+/// A popped-out conversation
+#[derive(Serialize, Clone, Debug, PartialEq, Type)]
+pub struct ConversationWindow {
+    pub label: String,
+    pub platform: String,
+    pub conversation_id: String,
+    pub title: String,
+}
+
+/// Payload emitted with `conversation-window-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct ConversationWindowChangedPayload {
+    pub platform: String,
+    pub conversation_id: String,
+    pub open: bool,
+}
+
+/// The open conversation windows, by label
+#[derive(Default)]
+pub struct ConversationWindows {
+    windows: Mutex<HashMap<String, ConversationWindow>>,
+}
+
+impl ConversationWindows {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&self, window: ConversationWindow) {
+        if let Ok(mut windows) = self.windows.lock() {
+            windows.insert(window.label.clone(), window);
+        }
+    }
+
+    fn remove(&self, label: &str) -> Option<ConversationWindow> {
+        self.windows.lock().ok()?.remove(label)
+    }
+
+    /// The open conversation windows
+    pub fn list(&self) -> Vec<ConversationWindow> {
+        let mut list: Vec<ConversationWindow> = self
+            .windows
+            .lock()
+            .map(|w| w.values().cloned().collect())
+            .unwrap_or_default();
+        list.sort_by(|a, b| a.label.cmp(&b.label));
+        list
+    }
+
+    /// The window showing `conversation_id`, if it is popped out
+    pub fn find(&self, conversation_id: &str) -> Option<ConversationWindow> {
+        let windows = self.windows.lock().ok()?;
+        windows.values().find(|w| w.conversation_id == conversation_id).cloned()
+    }
+
+    fn set_title(&self, label: &str, title: &str) {
+        if let Ok(mut windows) = self.windows.lock() {
+            if let Some(window) = windows.get_mut(label) {
+                window.title = title.to_string();
+            }
+        }
+    }
+}
+
+/// Window label for a conversation; characters labels don't allow become `_`
+fn window_label(platform: &Platform, conversation_id: &str) -> String {
+    let id: String = conversation_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}{}-{}", WINDOW_PREFIX, platform.name().to_lowercase(), id)
+}
+
+fn window_title(platform: &Platform, name: Option<&str>) -> String {
+    match name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => format!("{} - {}", name, platform.name()),
+        None => format!("{} conversation", platform.name()),
+    }
+}
+
+/// Everything a conversation window is built from
+#[derive(Debug, Clone, PartialEq)]
+struct WindowSpec {
+    label: String,
+    url: Url,
+    title: String,
+}
+
+fn window_spec(platform: &Platform, conversation_id: &str, name: Option<&str>) -> Result<WindowSpec, String> {
+    Ok(WindowSpec {
+        label: window_label(platform, conversation_id),
+        url: platform.conversation_url(conversation_id)?,
+        title: window_title(platform, name),
+    })
+}
+
+fn emit_changed(app: &AppHandle, window: &ConversationWindow, open: bool) {
+    let _ = app.emit(
+        "conversation-window-changed",
+        ConversationWindowChangedPayload {
+            platform: window.platform.clone(),
+            conversation_id: window.conversation_id.clone(),
+            open,
+        },
+    );
+}
+
+/// Attributes a notification to its popped-out conversation, titling an untitled
+/// window with the sender. Returns `true` if the notification should be suppressed
+/// because that window has focus.
+pub fn on_notification(app: &AppHandle, conversation_id: Option<&str>, sender: &str) -> bool {
+    let (Some(id), Some(windows)) = (conversation_id, app.try_state::<ConversationWindows>()) else {
+        return false;
+    };
+    let Some(entry) = windows.find(id) else {
+        return false;
+    };
+    let Some(window) = app.get_webview_window(&entry.label) else {
+        return false;
+    };
+
+    let platform = Platform::from_str(&entry.platform);
+    if let Some(platform) = platform.filter(|p| entry.title == window_title(p, None)) {
+        let title = window_title(&platform, Some(sender));
+        if window.set_title(&title).is_ok() {
+            windows.set_title(&entry.label, &title);
+        }
+    }
+    window.is_focused().unwrap_or(false)
+}
+
+/// Tauri command to open a conversation in its own window (or focus it if it is
+/// already open). `title` is the conversation / sender name for the window title.
+/// Returns the window label. Async: a sync command runs on the main thread, and
+/// building a window there deadlocks on Windows (WebView2).
+#[tauri::command]
+#[specta::specta]
+pub async fn open_conversation_window(
+    platform: String,
+    conversation_id: String,
+    title: Option<String>,
+    app: AppHandle,
+    windows: tauri::State<'_, ConversationWindows>,
+) -> Result<String, String> {
+    let platform = Platform::from_str(&platform).ok_or_else(|| format!("Unknown platform: {}", platform))?;
+    let spec = window_spec(&platform, &conversation_id, title.as_deref())?;
+
+    if let Some(window) = app.get_webview_window(&spec.label) {
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(spec.label);
+    }
+
+    let nav_handle = app.clone();
+    let nav_platform = platform.clone();
+    let window = WebviewWindowBuilder::new(&app, &spec.label, WebviewUrl::External(spec.url))
+        .title(&spec.title)
+        .inner_size(480.0, 720.0)
+        // Logged in like the main window
+        .data_directory(app.state::<PrivacyEngine>().profile_dir())
+        .on_navigation(move |url| crate::navigation::on_platform_navigation(&nav_handle, url, &nav_platform))
+        .build()
+        .map_err(|e| format!("Failed to open conversation window: {}", e))?;
+
+    let entry = ConversationWindow {
+        label: spec.label.clone(),
+        platform: platform.name().to_string(),
+        conversation_id,
+        title: spec.title,
+    };
+    windows.insert(entry.clone());
+
+    let handle = app.clone();
+    let label = spec.label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            if let Some(entry) = handle.state::<ConversationWindows>().remove(&label) {
+                emit_changed(&handle, &entry, false);
+            }
+        }
+    });
+
+    log::info!("[conversation_window] opened {} conversation {}", entry.platform, entry.conversation_id);
+    emit_changed(&app, &entry, true);
+    Ok(spec.label)
+}
+
+/// Tauri command to close a popped-out conversation window
+#[tauri::command]
+#[specta::specta]
+pub fn close_conversation_window(label: String, app: AppHandle) -> Result<(), String> {
+    if !label.starts_with(WINDOW_PREFIX) {
+        return Err(format!("Not a conversation window: {}", label));
+    }
+    match app.get_webview_window(&label) {
+        // The window's Destroyed handler forgets it and emits the event
+        Some(window) => window.destroy().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Tauri command to list the popped-out conversations
+#[tauri::command]
+#[specta::specta]
+pub fn list_conversation_windows(windows: tauri::State<'_, ConversationWindows>) -> Vec<ConversationWindow> {
+    windows.list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_spec_points_at_the_conversation() {
+        let spec = window_spec(&Platform::Messenger, "100012345", Some("Alice")).unwrap();
+        assert_eq!(spec.url.as_str(), "https://www.messenger.com/t/100012345");
+        assert_eq!(spec.label, "conversation-messenger-100012345");
+        assert_eq!(spec.title, "Alice - Messenger");
+
+        let spec = window_spec(&Platform::Instagram, "340282366841710300949128", None).unwrap();
+        assert_eq!(spec.url.as_str(), "https://www.instagram.com/direct/t/340282366841710300949128");
+        assert_eq!(spec.title, "Instagram conversation");
+
+        // Ids can't escape the conversation path or the label's character set
+        let spec = window_spec(&Platform::X, "123-456/../settings?x", None).unwrap();
+        assert!(spec.url.as_str().starts_with("https://x.com/messages/123-456"));
+        assert_eq!(spec.url.path_segments().unwrap().count(), 2);
+        assert_eq!(spec.label, "conversation-x-123-456____settings_x");
+
+        assert!(window_spec(&Platform::X, " ", None).is_err());
+    }
+
+    #[test]
+    fn test_registry_finds_by_conversation() {
+        let windows = ConversationWindows::new();
+        windows.insert(ConversationWindow {
+            label: "conversation-x-1".to_string(),
+            platform: "X".to_string(),
+            conversation_id: "1".to_string(),
+            title: "X conversation".to_string(),
+        });
+        assert_eq!(windows.find("1").unwrap().label, "conversation-x-1");
+        assert!(windows.find("2").is_none());
+        windows.set_title("conversation-x-1", "Bob - X");
+        assert_eq!(windows.list()[0].title, "Bob - X");
+        assert!(windows.remove("conversation-x-1").is_some());
+        assert!(windows.list().is_empty());
+    }
+}
@@ -11,19 +11,85 @@
 ///   const open: boolean = await invoke('is_devtools_open');
 ///
 /// Keyboard shortcut: press F12 or trigger `toggle_devtools` from the frontend.
+/// F12 is bound through the shortcut manager (`ACTION`) and, like quick search, only
+/// held while the main window is focused.
+///
+/// DevTools can't be opened while the app is locked (see `lock`).
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use tracing::{debug, warn};
+
+use crate::shortcuts::ShortcutManager;
+
+/// Shortcut action name for toggling DevTools
+pub const ACTION: &str = "devtools";
+
+/// Default DevTools shortcut
+pub const DEFAULT_SHORTCUT: &str = "F12";
+
+/// Opens DevTools unless the app is locked
+fn open(window: &WebviewWindow) {
+    if crate::lock::is_locked(window.app_handle()) {
+        warn!("[devtools] not opening DevTools while the app is locked");
+        return;
+    }
+    window.open_devtools();
+}
+
+fn toggle(window: &WebviewWindow) {
+    if window.is_devtools_open() {
+        debug!("[devtools] toggle → closing");
+        window.close_devtools();
+    } else {
+        debug!("[devtools] toggle → opening");
+        open(window);
+    }
+}
 
-use tauri::WebviewWindow;
-use tracing::debug;
+/// Registers `keys` as the DevTools shortcut
+pub fn register_shortcut(app: &AppHandle, keys: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(keys, |app, _shortcut, event| {
+            if event.state != ShortcutState::Pressed {
+                return;
+            }
+            if let Some(window) = app.get_webview_window("main") {
+                toggle(&window);
+            }
+        })
+        .map_err(|e| format!("Failed to register devtools shortcut {}: {}", keys, e))
+}
+
+/// Holds the DevTools shortcut only while the main window has focus
+pub fn on_focus_changed(app: &AppHandle, focused: bool) {
+    let Some(keys) = app
+        .try_state::<Mutex<ShortcutManager>>()
+        .and_then(|m| m.lock().ok().and_then(|m| m.bindings().get(ACTION).cloned()))
+    else {
+        return;
+    };
+    if !focused {
+        let _ = app.global_shortcut().unregister(keys.as_str());
+    } else if !app.global_shortcut().is_registered(keys.as_str()) {
+        if let Err(e) = register_shortcut(app, &keys) {
+            warn!("[devtools] {}", e);
+        }
+    }
+}
 
 /// Open the webview DevTools inspector on the given window.
 #[tauri::command]
+#[specta::specta]
 pub fn open_devtools(window: WebviewWindow) {
     debug!("[devtools] open_devtools → window '{}'", window.label());
-    window.open_devtools();
+    open(&window);
 }
 
 /// Close the webview DevTools inspector on the given window.
 #[tauri::command]
+#[specta::specta]
 pub fn close_devtools(window: WebviewWindow) {
     debug!("[devtools] close_devtools → window '{}'", window.label());
     window.close_devtools();
@@ -31,18 +97,14 @@ pub fn close_devtools(window: WebviewWindow) {
 
 /// Toggle DevTools — opens if closed, closes if open.
 #[tauri::command]
+#[specta::specta]
 pub fn toggle_devtools(window: WebviewWindow) {
-    if window.is_devtools_open() {
-        debug!("[devtools] toggle → closing");
-        window.close_devtools();
-    } else {
-        debug!("[devtools] toggle → opening");
-        window.open_devtools();
-    }
+    toggle(&window);
 }
 
 /// Returns `true` if DevTools are currently open on the given window.
 #[tauri::command]
+#[specta::specta]
 pub fn is_devtools_open(window: WebviewWindow) -> bool {
     let open = window.is_devtools_open();
     debug!("[devtools] is_devtools_open = {}", open);
@@ -0,0 +1,126 @@
+//! `messenger-desktop://` deep links.
+//!
+//! Links open a platform or a conversation in the running app:
+//!
+//! - `messenger-desktop://platform/Instagram` selects a platform
+//! - `messenger-desktop://conversation/<id>` emits `navigate` with `conversation/<id>`
+//!
+//! The deep-link plugin delivers links opened while the app runs; a link that starts
+//! a second instance is forwarded to this one by the single-instance guard. Links that
+//! don't parse are logged and ignored.
+
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::platform_manager::{self, Platform, PlatformManager};
+
+/// URL scheme registered with the OS
+pub const SCHEME: &str = "messenger-desktop";
+
+/// A parsed deep link
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    /// Switch to a platform
+    Platform(Platform),
+    /// Open a conversation by id
+    Conversation(String),
+}
+
+/// Parses a `messenger-desktop://` link
+pub fn parse(link: &str) -> Result<DeepLink, String> {
+    let url = Url::parse(link).map_err(|e| format!("Invalid deep link {:?}: {}", link, e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("Unsupported deep link scheme: {}", url.scheme()));
+    }
+    let target = url.path().trim_matches('/');
+    if target.is_empty() || target.contains('/') {
+        return Err(format!("Malformed deep link: {}", link));
+    }
+
+    match url.host_str() {
+        Some("platform") => Platform::from_str(target)
+            .map(DeepLink::Platform)
+            .ok_or_else(|| format!("Unknown platform in deep link: {}", target)),
+        Some("conversation") => Ok(DeepLink::Conversation(target.to_string())),
+        _ => Err(format!("Unknown deep link target: {}", link)),
+    }
+}
+
+/// Parses and acts on a deep link, focusing the main window
+pub fn handle(app: &AppHandle, link: &str) {
+    let deep_link = match parse(link) {
+        Ok(deep_link) => deep_link,
+        Err(e) => {
+            log::warn!("[deep_link] {}", e);
+            return;
+        }
+    };
+    log::info!("[deep_link] opening {:?}", deep_link);
+
+    crate::startup::focus_main_window(app);
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let result = match deep_link {
+        DeepLink::Platform(platform) => {
+            let manager = app.state::<PlatformManager>();
+            platform_manager::open_platform(&window, &manager, platform)
+        }
+        DeepLink::Conversation(id) => window
+            .emit("navigate", format!("conversation/{}", id))
+            .map_err(|e| e.to_string()),
+    };
+    if let Err(e) = result {
+        log::warn!("[deep_link] failed to open {}: {}", link, e);
+    }
+}
+
+/// Registers the scheme and starts handling links, including the one (if any) the
+/// app was launched with
+pub fn init(app: &AppHandle) {
+    // Installed builds register the scheme at bundle time; this covers dev builds and AppImages
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!("[deep_link] failed to register {}:// scheme: {}", SCHEME, e);
+    }
+
+    let app_handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle(&app_handle, url.as_str());
+        }
+    });
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            handle(app, url.as_str());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_links() {
+        assert_eq!(
+            parse("messenger-desktop://platform/Instagram"),
+            Ok(DeepLink::Platform(Platform::Instagram))
+        );
+        assert_eq!(
+            parse("messenger-desktop://conversation/12345/"),
+            Ok(DeepLink::Conversation("12345".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_malformed_deep_links_are_rejected() {
+        assert!(parse("not a url").is_err());
+        assert!(parse("https://platform/Instagram").is_err());
+        assert!(parse("messenger-desktop://platform/MySpace").is_err());
+        assert!(parse("messenger-desktop://platform/").is_err());
+        assert!(parse("messenger-desktop://conversation/a/b").is_err());
+        assert!(parse("messenger-desktop://settings/general").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Message density: how tightly the conversation view packs messages.
+//!
+//! Comfortable is each platform's own layout and injects nothing. Cozy and Compact
+//! trim the padding and line height of message rows with a small stylesheet; the
+//! selectors differ per platform because each site marks up messages its own way.
+//! The mode is persisted and re-applied on every page load through the injection
+//! registry.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::store;
+
+/// Id of the style element the density CSS is injected as
+const DENSITY_STYLE_ID: &str = "__md_density__";
+
+/// How tightly messages are packed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum MessageDensity {
+    /// The platform's own spacing
+    #[default]
+    Comfortable,
+    Cozy,
+    Compact,
+}
+
+/// Payload emitted with `message-density-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct MessageDensityPayload {
+    pub mode: MessageDensity,
+}
+
+/// Manages the message density and its persistence
+pub struct DensityManager {
+    mode: std::sync::Mutex<MessageDensity>,
+    store_path: PathBuf,
+}
+
+impl DensityManager {
+    /// Creates a new DensityManager, loading any saved mode
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("density.json");
+        let mode = store::load_or_default(&store_path);
+        Self {
+            mode: std::sync::Mutex::new(mode),
+            store_path,
+        }
+    }
+
+    /// Gets the current mode
+    pub fn get(&self) -> Result<MessageDensity, String> {
+        Ok(*self.mode.lock().map_err(|e| e.to_string())?)
+    }
+
+    /// Updates and persists the mode
+    pub fn set(&self, mode: MessageDensity) -> Result<(), String> {
+        *self.mode.lock().map_err(|e| e.to_string())? = mode;
+        store::save(&self.store_path, &mode)
+            .map_err(|e| format!("Failed to save message density: {}", e))
+    }
+}
+
+/// A platform's message markup: the row around each message, the bubble and its text
+struct MessageSelectors {
+    row: &'static str,
+    bubble: &'static str,
+    text: &'static str,
+}
+
+fn message_selectors(platform: Option<&Platform>) -> MessageSelectors {
+    match platform {
+        Some(Platform::Instagram) => MessageSelectors {
+            row: "[role=\"main\"] [role=\"row\"]",
+            bubble: "[role=\"main\"] [role=\"row\"] [role=\"button\"] > div[dir=\"auto\"]",
+            text: "[role=\"main\"] [role=\"row\"] div[dir=\"auto\"]",
+        },
+        Some(Platform::X) => MessageSelectors {
+            row: "[data-testid=\"messageEntry\"]",
+            bubble: "[data-testid=\"messageEntry\"] [data-testid=\"tweetText\"]",
+            text: "[data-testid=\"messageEntry\"] span",
+        },
+        Some(Platform::Messenger) | Some(Platform::Facebook) | None => MessageSelectors {
+            row: "[role=\"main\"] [role=\"row\"]",
+            bubble: "[role=\"main\"] [role=\"row\"] [role=\"presentation\"] > div[dir=\"auto\"]",
+            text: "[role=\"main\"] [role=\"row\"] div[dir=\"auto\"]",
+        },
+    }
+}
+
+/// CSS for `mode` on `platform`; empty for Comfortable
+pub fn density_css(mode: MessageDensity, platform: Option<&Platform>) -> String {
+    // (row padding, bubble padding, line height)
+    let (row, bubble, line_height) = match mode {
+        MessageDensity::Comfortable => return String::new(),
+        MessageDensity::Cozy => ("2px", "6px 10px", "1.3"),
+        MessageDensity::Compact => ("0", "3px 8px", "1.2"),
+    };
+    let selectors = message_selectors(platform);
+    format!(
+        "{row_sel}{{padding-top:{row}!important;padding-bottom:{row}!important;margin-top:0!important;margin-bottom:0!important;}}\
+         {bubble_sel}{{padding:{bubble}!important;}}\
+         {text_sel}{{line-height:{line_height}!important;}}",
+        row_sel = selectors.row,
+        bubble_sel = selectors.bubble,
+        text_sel = selectors.text,
+        row = row,
+        bubble = bubble,
+        line_height = line_height,
+    )
+}
+
+/// Builds the script that puts the density CSS on the page (or removes it)
+pub fn density_script(mode: MessageDensity, platform: Option<&Platform>) -> String {
+    crate::injection::style_script(DENSITY_STYLE_ID, &density_css(mode, platform))
+}
+
+/// The density script for `mode` on the active platform
+fn current_script(app: &AppHandle, mode: MessageDensity) -> String {
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    density_script(mode, platform.as_ref())
+}
+
+/// Script re-injected after every page load; `None` while Comfortable (the default).
+pub fn page_load_script(app: &AppHandle) -> Option<String> {
+    let mode = app.try_state::<DensityManager>()?.get().ok()?;
+    (mode != MessageDensity::Comfortable).then(|| current_script(app, mode))
+}
+
+/// Persists `mode`, applies it to the page and tells the frontend
+pub fn apply(app: &AppHandle, mode: MessageDensity) -> Result<(), String> {
+    app.state::<DensityManager>().set(mode)?;
+    crate::injection::apply(app, crate::injection::DENSITY, &current_script(app, mode))?;
+    log::info!("[density] message density {:?}", mode);
+    app.emit("message-density-changed", MessageDensityPayload { mode })
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command to choose how tightly messages are packed
+#[tauri::command]
+#[specta::specta]
+pub fn set_message_density(mode: MessageDensity, app: AppHandle) -> Result<(), String> {
+    apply(&app, mode)
+}
+
+/// Tauri command to get the message density
+#[tauri::command]
+#[specta::specta]
+pub fn get_message_density(manager: tauri::State<'_, DensityManager>) -> Result<MessageDensity, String> {
+    manager.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_density_css() {
+        for platform in Platform::all() {
+            assert!(!density_css(MessageDensity::Compact, Some(&platform)).is_empty());
+            assert!(density_css(MessageDensity::Comfortable, Some(&platform)).is_empty());
+        }
+        assert!(density_css(MessageDensity::Compact, Some(&Platform::X)).contains("messageEntry"));
+        assert_ne!(
+            density_css(MessageDensity::Compact, None),
+            density_css(MessageDensity::Cozy, None)
+        );
+    }
+
+    #[test]
+    fn test_density_persistence() {
+        let tmp = std::env::temp_dir().join("test-message-density");
+        std::fs::create_dir_all(&tmp).unwrap();
+        assert_eq!(DensityManager::new(&tmp).get().unwrap(), MessageDensity::Comfortable);
+        DensityManager::new(&tmp).set(MessageDensity::Compact).unwrap();
+        assert_eq!(DensityManager::new(&tmp).get().unwrap(), MessageDensity::Compact);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,157 @@
+//! Build and runtime diagnostics for bug reports.
+//!
+//! `get_diagnostics` gathers versions, the OS, the active platform and the data
+//! directory in one place, together with how each optional feature came up at
+//! startup. Features that fall back instead of failing the launch (spellcheck,
+//! notification permission, the tray) record their state in `Features`, so a
+//! degraded feature shows up here and not only in the logs.
+
+use serde::Serialize;
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::gpu::GpuManager;
+use crate::platform_manager::PlatformManager;
+
+/// How an optional feature came up
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Type)]
+pub enum FeatureState {
+    Ok,
+    /// Running without it, or with less of it
+    Degraded,
+}
+
+/// An optional feature's startup outcome
+#[derive(Serialize, Clone, Debug, PartialEq, Type)]
+pub struct FeatureStatus {
+    pub name: String,
+    pub state: FeatureState,
+    /// Why it is degraded
+    pub detail: Option<String>,
+}
+
+/// Startup outcomes of the optional features, in the order they were recorded
+#[derive(Default)]
+pub struct Features {
+    statuses: Mutex<Vec<FeatureStatus>>,
+}
+
+impl Features {
+    fn record(&self, name: &str, state: FeatureState, detail: Option<String>) {
+        if let Ok(mut statuses) = self.statuses.lock() {
+            statuses.retain(|s| s.name != name);
+            statuses.push(FeatureStatus {
+                name: name.to_string(),
+                state,
+                detail,
+            });
+        }
+    }
+
+    /// Records that `name` initialized normally
+    pub fn ok(&self, name: &str) {
+        self.record(name, FeatureState::Ok, None);
+    }
+
+    /// Records that `name` is running degraded, and why
+    pub fn degraded(&self, name: &str, detail: impl Into<String>) {
+        self.record(name, FeatureState::Degraded, Some(detail.into()));
+    }
+
+    /// The recorded outcomes
+    pub fn list(&self) -> Vec<FeatureStatus> {
+        self.statuses.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+}
+
+/// Environment details for bug triage
+#[derive(Serialize, Clone, Debug, Type)]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub tauri_version: String,
+    /// WebView2 / WebKitGTK / WKWebView version, if it could be read
+    pub webview_version: Option<String>,
+    pub os: String,
+    pub arch: String,
+    pub platform: Option<String>,
+    pub hardware_acceleration: bool,
+    pub data_dir: String,
+    pub features: Vec<FeatureStatus>,
+}
+
+impl Diagnostics {
+    /// Fills in the build-time details around what was gathered at runtime
+    fn new(
+        webview_version: Option<String>,
+        platform: Option<String>,
+        hardware_acceleration: bool,
+        data_dir: String,
+        features: Vec<FeatureStatus>,
+    ) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            tauri_version: tauri::VERSION.to_string(),
+            webview_version,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            platform,
+            hardware_acceleration,
+            data_dir,
+            features,
+        }
+    }
+}
+
+/// Tauri command to get build and runtime diagnostics
+#[tauri::command]
+#[specta::specta]
+pub fn get_diagnostics(app: AppHandle) -> Result<Diagnostics, String> {
+    let webview_version = tauri::webview_version()
+        .map_err(|e| log::warn!("[diagnostics] failed to read webview version: {}", e))
+        .ok();
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten())
+        .map(|p| p.name().to_string());
+    let hardware_acceleration = app.try_state::<GpuManager>().is_none_or(|m| m.is_enabled());
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .into_owned();
+    let features = app.try_state::<Features>().map(|f| f.list()).unwrap_or_default();
+    Ok(Diagnostics::new(
+        webview_version,
+        platform,
+        hardware_acceleration,
+        data_dir,
+        features,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_serialize_with_version() {
+        let features = Features::default();
+        features.ok("notifications");
+        features.degraded("spellcheck", "init failed");
+        features.degraded("notifications", "permission denied");
+
+        let diagnostics = Diagnostics::new(None, Some("X".into()), true, "/tmp/data".into(), features.list());
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(json["app_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["os"], std::env::consts::OS);
+        assert_eq!(json["platform"], "X");
+
+        // A later outcome replaces the earlier one for the same feature
+        assert_eq!(json["features"].as_array().unwrap().len(), 2);
+        assert_eq!(json["features"][1]["name"], "notifications");
+        assert_eq!(json["features"][1]["state"], "Degraded");
+        assert_eq!(json["features"][1]["detail"], "permission denied");
+    }
+}
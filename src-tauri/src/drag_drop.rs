@@ -1,14 +1,17 @@
 //! Drag and drop handler for Tauri app.
 //! Handles file drops, injects files into messenger.com's file input, and validates file types.
+//! Dropped text and links (which some platforms report in place of paths) are typed
+//! into the composer instead and emitted as `text-drop`.
 
-use tauri::{WebviewWindow, Manager, Emitter};
+use tauri::{WebviewWindow, Manager, Emitter, Url};
 use std::path::{Path, PathBuf};
 use serde::{Serialize, Deserialize};
+use specta::Type;
 use mime_guess::from_path;
 use log::{info, error};
 
 /// File drop event payload.
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Clone, Debug, Type)]
 pub struct FileDropPayload {
     pub files: Vec<FileDropInfo>,
     pub status: String,
@@ -16,7 +19,7 @@ pub struct FileDropPayload {
 }
 
 /// File drop information.
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Type)]
 pub struct FileDropInfo {
     pub name: String,
     pub path: String,
@@ -28,6 +31,82 @@ pub struct FileDropInfo {
     pub is_document: bool,
 }
 
+/// Text drop event payload.
+#[derive(Serialize, Clone, Debug, Type)]
+pub struct TextDropPayload {
+    pub text: String,
+    pub is_url: bool,
+}
+
+/// What a drop carried
+#[derive(Debug, Clone, PartialEq)]
+pub enum DroppedContent {
+    Files(Vec<PathBuf>),
+    Url(String),
+    Text(String),
+}
+
+/// Sorts a drop into files, a link or text. A drop with any entry on the filesystem
+/// (a plain path or a `file://` URI, folders included) counts as files, of those
+/// entries; only a drop with none is the text the platform handed over in place of
+/// paths.
+pub fn classify_drop(paths: &[PathBuf]) -> DroppedContent {
+    let files: Vec<PathBuf> = paths.iter().filter_map(|p| as_existing_path(p)).collect();
+    if !files.is_empty() {
+        return DroppedContent::Files(files);
+    }
+
+    let text = paths
+        .iter()
+        .map(|p| p.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let trimmed = text.trim();
+    if is_url(trimmed) {
+        DroppedContent::Url(trimmed.to_string())
+    } else {
+        DroppedContent::Text(text)
+    }
+}
+
+/// The filesystem entry a dropped entry refers to, if it exists
+fn as_existing_path(path: &Path) -> Option<PathBuf> {
+    let path = match path.to_str().and_then(|s| Url::parse(s).ok()) {
+        Some(url) if url.scheme() == "file" => url.to_file_path().ok()?,
+        _ => path.to_path_buf(),
+    };
+    path.exists().then_some(path)
+}
+
+/// Whether `text` is a single web or mail link
+fn is_url(text: &str) -> bool {
+    !text.contains(char::is_whitespace)
+        && Url::parse(text).is_ok_and(|url| matches!(url.scheme(), "http" | "https" | "mailto"))
+}
+
+/// Handles any drop: files go to the file input, text and links into the composer
+/// (emitting `text-drop`, and returning a payload with status "text").
+pub fn handle_any_drop(window: &WebviewWindow, paths: Vec<PathBuf>) -> FileDropPayload {
+    let (text, is_url) = match classify_drop(&paths) {
+        DroppedContent::Files(files) => return handle_drop(window, files),
+        DroppedContent::Url(url) => (url, true),
+        DroppedContent::Text(text) => (text, false),
+    };
+
+    let error = inject_text_to_messenger(window, &text, is_url).err();
+    if let Some(e) = &error {
+        error!("Failed to inject dropped text: {}", e);
+    }
+    if let Err(e) = window.emit("text-drop", TextDropPayload { text, is_url }) {
+        error!("Failed to emit text-drop event: {}", e);
+    }
+    FileDropPayload {
+        files: Vec::new(),
+        status: if error.is_none() { "text" } else { "error" }.to_string(),
+        error,
+    }
+}
+
 /// Handle file drop event.
 /// - `window`: WebviewWindow where the drop occurred.
 /// - `paths`: List of dropped file paths.
@@ -62,6 +141,9 @@ pub fn handle_drop(window: &WebviewWindow, paths: Vec<PathBuf>) -> FileDropPaylo
 /// - `path`: Path to the file.
 fn process_file(path: &Path) -> Result<FileDropInfo, String> {
     let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
+    if metadata.is_dir() {
+        return Err("Folders can't be attached".to_string());
+    }
     let mime_type = from_path(path).first_or_octet_stream();
     let name = path.file_name()
         .ok_or("Invalid file name")?
@@ -109,6 +191,46 @@ fn inject_file_to_messenger(window: &WebviewWindow, file_info: &FileDropInfo) ->
     window.eval(&js).map_err(|e| e.to_string())
 }
 
+/// Types `text` into the focused conversation's composer. Links are inserted as-is so
+/// the platform can unfurl them, unless link previews are blocked, in which case the
+/// preview card is dismissed.
+fn inject_text_to_messenger(window: &WebviewWindow, text: &str, is_url: bool) -> Result<(), String> {
+    let block_previews = is_url
+        && window
+            .try_state::<std::sync::Mutex<crate::privacy::PrivacyManager>>()
+            .and_then(|state| state.lock().ok().map(|m| m.config().block_link_previews))
+            .unwrap_or(false);
+    let text = serde_json::to_string(text).map_err(|e| e.to_string())?;
+    let js = format!(
+        r#"
+        (() => {{
+            const composer = document.querySelector('[role="textbox"][contenteditable="true"], [data-testid="dmComposerTextInput"], textarea[placeholder]');
+            if (!composer) {{
+                console.error('Composer not found');
+                return;
+            }}
+            composer.focus();
+            if (composer.value !== undefined) {{
+                composer.setRangeText({text}, composer.selectionStart, composer.selectionEnd, 'end');
+                composer.dispatchEvent(new Event('input', {{ bubbles: true }}));
+            }} else {{
+                document.execCommand('insertText', false, {text});
+            }}
+            if ({block_previews}) {{
+                setTimeout(() => {{
+                    const close = document.querySelector('[aria-label*="Remove" i][aria-label*="preview" i]');
+                    if (close) close.click();
+                }}, 1000);
+            }}
+        }})();
+        "#,
+        text = text,
+        block_previews = block_previews,
+    );
+
+    window.eval(&js).map_err(|e| e.to_string())
+}
+
 /// Listen for drag-drop events in the window.
 #[allow(dead_code)]
 pub fn setup_drag_drop_handler(window: &WebviewWindow) {
@@ -117,9 +239,11 @@ pub fn setup_drag_drop_handler(window: &WebviewWindow) {
         if let tauri::WindowEvent::DragDrop(event) = event {
             match event {
                 tauri::DragDropEvent::Drop { paths, .. } => {
-                    let payload = handle_drop(&window_clone, paths.to_vec());
-                    if let Err(e) = window_clone.emit("file-drop", payload) {
-                        error!("Failed to emit file-drop event: {}", e);
+                    let payload = handle_any_drop(&window_clone, paths.to_vec());
+                    if payload.status != "text" {
+                        if let Err(e) = window_clone.emit("file-drop", payload) {
+                            error!("Failed to emit file-drop event: {}", e);
+                        }
                     }
                 }
                 tauri::DragDropEvent::Enter { paths, .. } => {
@@ -136,10 +260,11 @@ pub fn setup_drag_drop_handler(window: &WebviewWindow) {
 
 /// Tauri command: Handle file drop.
 #[tauri::command]
-pub fn handle_file_drop(state: tauri::State<tauri::AppHandle>, paths: Vec<String>) -> FileDropPayload {
-    if let Some(window) = state.get_webview_window("main") {
+#[specta::specta]
+pub fn handle_file_drop(app: tauri::AppHandle, paths: Vec<String>) -> FileDropPayload {
+    if let Some(window) = app.get_webview_window("main") {
         let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
-        handle_drop(&window, paths)
+        handle_any_drop(&window, paths)
     } else {
         FileDropPayload {
             files: Vec::new(),
@@ -151,7 +276,7 @@ pub fn handle_file_drop(state: tauri::State<tauri::AppHandle>, paths: Vec<String
 
 /// Tauri command: Validate dropped files.
 #[tauri::command]
-#[allow(dead_code)]
+#[specta::specta]
 pub fn validate_files(paths: Vec<String>) -> Vec<FileDropInfo> {
     paths
         .into_iter()
@@ -227,4 +352,38 @@ mod tests {
         };
         assert!(info.is_document);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_classify_drop() {
+        let file = std::env::temp_dir().join("test-classify-drop.txt");
+        std::fs::write(&file, "content").unwrap();
+        let file_uri = Url::from_file_path(&file).unwrap().to_string();
+
+        assert_eq!(classify_drop(&[file.clone()]), DroppedContent::Files(vec![file.clone()]));
+        assert_eq!(
+            classify_drop(&[PathBuf::from(&file_uri)]),
+            DroppedContent::Files(vec![file.clone()])
+        );
+        assert_eq!(
+            classify_drop(&[PathBuf::from("https://example.com/a?b=c")]),
+            DroppedContent::Url("https://example.com/a?b=c".to_string())
+        );
+        assert_eq!(
+            classify_drop(&[PathBuf::from("see https://example.com")]),
+            DroppedContent::Text("see https://example.com".to_string())
+        );
+        // A missing file is text, but a file among text is still a file drop
+        assert!(matches!(classify_drop(&[PathBuf::from("/no/such/file.txt")]), DroppedContent::Text(_)));
+        assert_eq!(
+            classify_drop(&[file.clone(), PathBuf::from("hello")]),
+            DroppedContent::Files(vec![file.clone()])
+        );
+
+        // A folder is a file drop too, which can't be attached
+        let dir = std::env::temp_dir();
+        assert_eq!(classify_drop(&[dir.clone()]), DroppedContent::Files(vec![dir.clone()]));
+        assert!(process_file(&dir).is_err());
+
+        let _ = std::fs::remove_file(&file);
+    }
+}
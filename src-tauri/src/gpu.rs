@@ -0,0 +1,149 @@
+//! Hardware acceleration preference.
+//!
+//! Some GPUs and drivers render the webview with glitches; turning acceleration off
+//! falls back to software rendering. No platform can switch a live webview between
+//! the two, so the preference is applied while the main window is built and a change
+//! takes effect on the next launch (`hardware-acceleration-changed` carries
+//! `restart_required`):
+//!
+//! - Linux (WebKitGTK): compositing and the DMA-BUF renderer are disabled through
+//!   environment variables, which must be set before the webview is created
+//! - Windows (WebView2): `--disable-gpu` is passed to the browser process
+//! - macOS (WKWebView): not supported; the preference is stored but has no effect
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::store;
+
+/// WebView2's default browser arguments, which `additional_browser_args` replaces
+#[cfg(target_os = "windows")]
+const WEBVIEW2_DEFAULT_ARGS: &str = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+/// Payload emitted with `hardware-acceleration-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct HardwareAccelerationChangedPayload {
+    pub enabled: bool,
+    pub restart_required: bool,
+}
+
+/// Persisted GPU configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct GpuConfig {
+    pub hardware_acceleration: bool,
+}
+
+impl Default for GpuConfig {
+    fn default() -> Self {
+        Self {
+            hardware_acceleration: true,
+        }
+    }
+}
+
+/// Manages the GPU configuration and its persistence
+pub struct GpuManager {
+    config: std::sync::Mutex<GpuConfig>,
+    store_path: PathBuf,
+}
+
+impl GpuManager {
+    /// Creates a new GpuManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("gpu.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: std::sync::Mutex::new(config),
+            store_path,
+        }
+    }
+
+    /// Gets the current configuration
+    pub fn get_config(&self) -> Result<GpuConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Whether hardware acceleration is enabled (the default)
+    pub fn is_enabled(&self) -> bool {
+        self.get_config().map_or(true, |c| c.hardware_acceleration)
+    }
+
+    /// Updates and persists the preference
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), String> {
+        let config = GpuConfig {
+            hardware_acceleration: enabled,
+        };
+        *self.config.lock().map_err(|e| e.to_string())? = config.clone();
+        store::save(&self.store_path, &config).map_err(|e| format!("Failed to save GPU config: {}", e))
+    }
+
+    /// Sets the WebKitGTK environment for software rendering. Call before the webview is built.
+    #[cfg(target_os = "linux")]
+    pub fn apply_environment(&self) {
+        if self.is_enabled() {
+            return;
+        }
+        log::info!("[gpu] hardware acceleration disabled; using software rendering");
+        std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
+        std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+    }
+
+    /// WebView2 browser arguments for the saved preference, if they differ from the defaults
+    #[cfg(target_os = "windows")]
+    pub fn browser_args(&self) -> Option<String> {
+        if self.is_enabled() {
+            return None;
+        }
+        log::info!("[gpu] hardware acceleration disabled; using software rendering");
+        Some(format!("{} --disable-gpu", WEBVIEW2_DEFAULT_ARGS))
+    }
+}
+
+/// Tauri command to turn hardware acceleration on or off (applies after restart)
+#[tauri::command]
+#[specta::specta]
+pub fn set_hardware_acceleration(
+    enabled: bool,
+    app: AppHandle,
+    manager: tauri::State<'_, GpuManager>,
+) -> Result<(), String> {
+    manager.set_enabled(enabled)?;
+    log::info!(
+        "[gpu] hardware acceleration {} (after restart)",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    app.emit(
+        "hardware-acceleration-changed",
+        HardwareAccelerationChangedPayload {
+            enabled,
+            restart_required: true,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command to get the GPU configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_hardware_acceleration(manager: tauri::State<'_, GpuManager>) -> Result<GpuConfig, String> {
+    manager.get_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acceleration_defaults_on_and_persists() {
+        let tmp = std::env::temp_dir().join("test-gpu");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = GpuManager::new(&tmp);
+        assert!(manager.is_enabled());
+
+        manager.set_enabled(false).unwrap();
+        assert!(!GpuManager::new(&tmp).is_enabled());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,248 @@
+//! Back/forward navigation within the active platform.
+//!
+//! An injected script reports whether the page can go back or forward to
+//! `report_history_state`, and changes are emitted as `history-changed` so the
+//! frontend can enable its buttons. Only entries on the page's own origin count:
+//! going back never leaves the platform (anything that would is also caught by the
+//! navigation guard). Where the Navigation API is missing (WebKit), back is offered
+//! whenever there is history and forward is never offered.
+//!
+//! Alt+Left / Alt+Right are bound through the shortcut manager and, like quick
+//! search, only held while the main window is focused.
+
+use serde::Serialize;
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::shortcuts::ShortcutManager;
+
+/// Shortcut action names
+pub const ACTION_BACK: &str = "back";
+pub const ACTION_FORWARD: &str = "forward";
+
+/// Default shortcuts
+pub const DEFAULT_BACK_SHORTCUT: &str = "Alt+Left";
+pub const DEFAULT_FORWARD_SHORTCUT: &str = "Alt+Right";
+
+/// Reports the history state whenever the page navigates
+const HISTORY_HOOK_JS: &str = r#"
+(function() {
+    if (window.__MD_HISTORY_HOOK__) return window.__MD_HISTORY_HOOK__.report();
+    const nav = window.navigation;
+
+    function origin(entry) {
+        try { return new URL(entry.url).origin; } catch (e) { return null; }
+    }
+
+    function state() {
+        if (nav && nav.currentEntry && nav.entries) {
+            const entries = nav.entries();
+            const i = nav.currentEntry.index;
+            return {
+                canGoBack: i > 0 && origin(entries[i - 1]) === location.origin,
+                canGoForward: i >= 0 && i < entries.length - 1 && origin(entries[i + 1]) === location.origin,
+            };
+        }
+        return { canGoBack: history.length > 1, canGoForward: false };
+    }
+
+    function report() {
+        const core = window.__TAURI__ && window.__TAURI__.core;
+        if (core) core.invoke('report_history_state', state()).catch(function() {});
+    }
+
+    function go(delta) {
+        const s = state();
+        if (delta < 0 ? s.canGoBack : s.canGoForward) history.go(delta);
+    }
+
+    ['pushState', 'replaceState'].forEach(function(name) {
+        const original = history[name];
+        history[name] = function() {
+            const result = original.apply(this, arguments);
+            setTimeout(report, 0);
+            return result;
+        };
+    });
+    window.addEventListener('popstate', function() { setTimeout(report, 0); });
+    if (nav) nav.addEventListener('navigatesuccess', report);
+
+    window.__MD_HISTORY_HOOK__ = { report: report, go: go };
+    report();
+})();
+"#;
+
+/// Whether the page can go back or forward
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Type)]
+pub struct HistoryState {
+    pub can_go_back: bool,
+    pub can_go_forward: bool,
+}
+
+/// Holds the last reported history state
+pub struct HistoryTracker {
+    state: Mutex<HistoryState>,
+}
+
+impl HistoryTracker {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(HistoryState::default()),
+        }
+    }
+
+    /// The last reported state
+    pub fn state(&self) -> Result<HistoryState, String> {
+        Ok(*self.state.lock().map_err(|e| e.to_string())?)
+    }
+
+    /// Records a state. Returns whether it changed.
+    fn record(&self, state: HistoryState) -> Result<bool, String> {
+        let mut current = self.state.lock().map_err(|e| e.to_string())?;
+        let changed = *current != state;
+        *current = state;
+        Ok(changed)
+    }
+}
+
+impl Default for HistoryTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Script that moves `delta` entries through the page's history, if allowed
+fn go_script(delta: i32) -> String {
+    format!(
+        "window.__MD_HISTORY_HOOK__ ? window.__MD_HISTORY_HOOK__.go({}) : null;",
+        delta
+    )
+}
+
+/// Goes back (`-1`) or forward (`1`) in `window`
+fn go(window: &WebviewWindow, delta: i32) -> Result<(), String> {
+    window.eval(&go_script(delta)).map_err(|e| e.to_string())
+}
+
+/// Page-load hook: installs the history hook
+pub fn on_page_load(window: &WebviewWindow) {
+    if let Err(e) = window.eval(HISTORY_HOOK_JS) {
+        log::warn!("[history] failed to install history hook: {}", e);
+    }
+}
+
+/// Whether `action` is one of the history shortcuts
+pub fn is_action(action: &str) -> bool {
+    action == ACTION_BACK || action == ACTION_FORWARD
+}
+
+/// Registers `keys` as the shortcut for a history action
+pub fn register_shortcut(app: &AppHandle, action: &str, keys: &str) -> Result<(), String> {
+    let delta = if action == ACTION_BACK { -1 } else { 1 };
+    app.global_shortcut()
+        .on_shortcut(keys, move |app, _shortcut, event| {
+            if event.state != ShortcutState::Pressed {
+                return;
+            }
+            if let Some(window) = app.get_webview_window("main") {
+                if let Err(e) = go(&window, delta) {
+                    log::warn!("[history] {}", e);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to register {} shortcut {}: {}", action, keys, e))
+}
+
+/// Holds the history shortcuts only while the main window has focus
+pub fn on_focus_changed(app: &AppHandle, focused: bool) {
+    let Some(bindings) = app
+        .try_state::<Mutex<ShortcutManager>>()
+        .and_then(|m| m.lock().ok().map(|m| m.bindings()))
+    else {
+        return;
+    };
+    for action in [ACTION_BACK, ACTION_FORWARD] {
+        let Some(keys) = bindings.get(action) else {
+            continue;
+        };
+        if !focused {
+            let _ = app.global_shortcut().unregister(keys.as_str());
+        } else if !app.global_shortcut().is_registered(keys.as_str()) {
+            if let Err(e) = register_shortcut(app, action, keys) {
+                log::warn!("[history] {}", e);
+            }
+        }
+    }
+}
+
+/// Tauri command called by the injected hook with the page's history state
+#[tauri::command]
+#[specta::specta]
+pub fn report_history_state(
+    can_go_back: bool,
+    can_go_forward: bool,
+    app: AppHandle,
+    tracker: tauri::State<'_, HistoryTracker>,
+) -> Result<(), String> {
+    let state = HistoryState {
+        can_go_back,
+        can_go_forward,
+    };
+    if tracker.record(state)? {
+        app.emit("history-changed", state).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Tauri command to go back within the platform
+#[tauri::command]
+#[specta::specta]
+pub fn webview_back(window: WebviewWindow) -> Result<(), String> {
+    go(&window, -1)
+}
+
+/// Tauri command to go forward within the platform
+#[tauri::command]
+#[specta::specta]
+pub fn webview_forward(window: WebviewWindow) -> Result<(), String> {
+    go(&window, 1)
+}
+
+/// Tauri command to check whether back is available
+#[tauri::command]
+#[specta::specta]
+pub fn can_go_back(tracker: tauri::State<'_, HistoryTracker>) -> Result<bool, String> {
+    Ok(tracker.state()?.can_go_back)
+}
+
+/// Tauri command to check whether forward is available
+#[tauri::command]
+#[specta::specta]
+pub fn can_go_forward(tracker: tauri::State<'_, HistoryTracker>) -> Result<bool, String> {
+    Ok(tracker.state()?.can_go_forward)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_reports_changes() {
+        let tracker = HistoryTracker::new();
+        let back = HistoryState {
+            can_go_back: true,
+            can_go_forward: false,
+        };
+        assert!(tracker.record(back).unwrap());
+        assert!(!tracker.record(back).unwrap());
+        assert_eq!(tracker.state().unwrap(), back);
+    }
+
+    #[test]
+    fn test_history_stays_on_origin() {
+        assert!(HISTORY_HOOK_JS.contains("=== location.origin"));
+        assert_eq!(go_script(-1), "window.__MD_HISTORY_HOOK__ ? window.__MD_HISTORY_HOOK__.go(-1) : null;");
+    }
+}
@@ -0,0 +1,284 @@
+//! Notification icon downloads.
+//!
+//! All downloads go through one pooled `reqwest::Client` with a per-request
+//! timeout. Concurrent requests for the same URL share a single download, and
+//! finished icons are kept in `notification_icons/` under a hash of their URL, so a
+//! sender who messages again reuses the file instead of downloading it again.
+//!
+//! Icons of notifications the rate limiter holds back are fetched ahead of time
+//! (`prefetch`); when a newer notification takes the held one's place, its
+//! download is aborted unless the newer one needs the same icon.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+/// Longest a single icon download may take
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Idle connections kept per host
+const POOL_MAX_IDLE_PER_HOST: usize = 4;
+
+/// Icons larger than this (bytes) are refused
+const MAX_ICON_BYTES: usize = 5 * 1024 * 1024;
+
+/// One download, shared by everyone waiting for the same URL
+type InFlight = Arc<OnceCell<Option<PathBuf>>>;
+
+/// Downloads and caches notification icons
+pub struct IconCache {
+    client: reqwest::Client,
+    dir: PathBuf,
+    in_flight: Mutex<HashMap<String, InFlight>>,
+}
+
+/// FNV-1a: a stable hash for cache file names (std's hasher may change between releases)
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Whether `url` has to be downloaded (anything else is handed to the OS as-is)
+fn is_remote(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+impl IconCache {
+    /// Creates a cache keeping its files in `dir`
+    pub fn new(dir: PathBuf) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DOWNLOAD_TIMEOUT)
+            .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+            .build()
+            .unwrap_or_else(|e| {
+                log::warn!("[icon_cache] failed to build HTTP client, using defaults: {}", e);
+                reqwest::Client::new()
+            });
+        Self {
+            client,
+            dir,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The shared HTTP client, for other downloads that should reuse its pool
+    pub fn client(&self) -> reqwest::Client {
+        self.client.clone()
+    }
+
+    /// Where the icon for `url` is cached
+    fn path_for(&self, url: &str) -> PathBuf {
+        let ext = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| Path::new(path).extension())
+            .and_then(|ext| ext.to_str())
+            .filter(|ext| matches!(ext.to_ascii_lowercase().as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp"))
+            .unwrap_or("img");
+        self.dir.join(format!("{:016x}.{}", fnv1a(url.as_bytes()), ext))
+    }
+
+    /// The local path of the icon at `url`, downloading it unless it is cached or
+    /// already being downloaded. `None` if the download failed.
+    pub async fn fetch(&self, url: &str) -> Option<String> {
+        if !is_remote(url) {
+            return Some(url.to_string());
+        }
+        let client = self.client.clone();
+        let owned = url.to_string();
+        let path = self.fetch_with(url, move || download(client, owned)).await?;
+        Some(path.to_string_lossy().into_owned())
+    }
+
+    /// Starts fetching `url` in the background; abort the handle to cancel
+    pub fn prefetch(self: &Arc<Self>, url: &str) -> tauri::async_runtime::JoinHandle<()> {
+        let cache = self.clone();
+        let url = url.to_string();
+        tauri::async_runtime::spawn(async move {
+            cache.fetch(&url).await;
+        })
+    }
+
+    /// `fetch` with the download itself supplied by the caller
+    async fn fetch_with<F, Fut>(&self, url: &str, download: F) -> Option<PathBuf>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<u8>, String>>,
+    {
+        let path = self.path_for(url);
+        if path.exists() {
+            return Some(path);
+        }
+
+        let cell = {
+            let mut in_flight = self.in_flight.lock().ok()?;
+            in_flight.entry(url.to_string()).or_default().clone()
+        };
+        // Only the first caller runs `download`; the rest wait for its result. If
+        // that caller is cancelled, the next waiter starts over.
+        let result = cell
+            .get_or_init(|| async {
+                let saved = download().await.and_then(|bytes| self.save(&path, &bytes));
+                match saved {
+                    Ok(()) => Some(path.clone()),
+                    Err(e) => {
+                        log::warn!("[icon_cache] failed to fetch {}: {}", url, e);
+                        None
+                    }
+                }
+            })
+            .await
+            .clone();
+
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            if in_flight.get(url).is_some_and(|c| Arc::ptr_eq(c, &cell)) {
+                in_flight.remove(url);
+            }
+        }
+        result
+    }
+
+    /// Writes an icon to the cache, via a temporary file so a partial write is never used
+    fn save(&self, path: &Path, bytes: &[u8]) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("part");
+        std::fs::write(&tmp, bytes).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+    }
+}
+
+/// Downloads `url` with the shared client. Icons over `MAX_ICON_BYTES` are refused
+/// by their Content-Length before any of the body is read, and while reading when
+/// the header is missing or wrong.
+async fn download(client: reqwest::Client, url: String) -> Result<Vec<u8>, String> {
+    let mut response = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    if let Some(len) = response.content_length().filter(|len| *len > MAX_ICON_BYTES as u64) {
+        return Err(format!("icon is {} bytes", len));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        append_limited(&mut body, &chunk)?;
+    }
+    Ok(body)
+}
+
+/// Appends `chunk` to the icon read so far, unless that takes it past `MAX_ICON_BYTES`
+fn append_limited(body: &mut Vec<u8>, chunk: &[u8]) -> Result<(), String> {
+    if body.len() + chunk.len() > MAX_ICON_BYTES {
+        return Err(format!("icon is over {} bytes", MAX_ICON_BYTES));
+    }
+    body.extend_from_slice(chunk);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counting_download(
+        calls: &Arc<AtomicUsize>,
+    ) -> impl FnOnce() -> std::pin::Pin<Box<dyn Future<Output = Result<Vec<u8>, String>> + Send>> {
+        let calls = calls.clone();
+        move || {
+            Box::pin(async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(b"icon".to_vec())
+            })
+        }
+    }
+
+    #[test]
+    fn test_concurrent_fetches_share_one_download() {
+        let tmp = std::env::temp_dir().join("test-icon-cache-dedup");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let cache = Arc::new(IconCache::new(tmp.clone()));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let url = "https://example.com/a.png";
+
+        tauri::async_runtime::block_on(async {
+            let tasks: Vec<_> = (0..3)
+                .map(|_| {
+                    let cache = cache.clone();
+                    let download = counting_download(&calls);
+                    tauri::async_runtime::spawn(async move { cache.fetch_with(url, download).await })
+                })
+                .collect();
+            for task in tasks {
+                assert_eq!(task.await.unwrap(), Some(cache.path_for(url)));
+            }
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(cache.in_flight.lock().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_size_limit_holds_while_reading() {
+        let mut body = Vec::new();
+        append_limited(&mut body, &vec![0; MAX_ICON_BYTES - 1]).unwrap();
+        append_limited(&mut body, &[0]).unwrap();
+        assert_eq!(body.len(), MAX_ICON_BYTES);
+        assert!(append_limited(&mut body, &[0]).is_err());
+        assert_eq!(body.len(), MAX_ICON_BYTES);
+    }
+
+    #[test]
+    fn test_cached_icons_are_reused() {
+        let tmp = std::env::temp_dir().join("test-icon-cache-hit");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let cache = IconCache::new(tmp.clone());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let url = "https://example.com/avatar.jpg?size=64";
+
+        tauri::async_runtime::block_on(async {
+            let first = cache.fetch_with(url, counting_download(&calls)).await.unwrap();
+            assert_eq!(first.extension().unwrap(), "jpg");
+            assert_eq!(std::fs::read(&first).unwrap(), b"icon");
+            assert_eq!(cache.fetch_with(url, counting_download(&calls)).await, Some(first));
+
+            // A failed download isn't cached
+            let other = "https://example.com/b.png";
+            assert_eq!(cache.fetch_with(other, || async { Err("404".to_string()) }).await, None);
+            assert!(cache.fetch_with(other, counting_download(&calls)).await.is_some());
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_ne!(cache.path_for("https://example.com/a.png"), cache.path_for("https://example.com/b.png"));
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_cancelled_prefetch_can_be_retried() {
+        let tmp = std::env::temp_dir().join("test-icon-cache-cancel");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let cache = Arc::new(IconCache::new(tmp.clone()));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let url = "https://example.com/c.png";
+
+        tauri::async_runtime::block_on(async {
+            let task = {
+                let cache = cache.clone();
+                let download = counting_download(&calls);
+                tauri::async_runtime::spawn(async move { cache.fetch_with(url, download).await })
+            };
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            task.abort();
+            assert!(!cache.path_for(url).exists());
+
+            assert!(cache.fetch_with(url, counting_download(&calls)).await.is_some());
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(cache.in_flight.lock().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
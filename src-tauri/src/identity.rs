@@ -0,0 +1,292 @@
+//! Who is logged in to each platform.
+//!
+//! An injected probe reads the account's display name, handle and avatar from the
+//! platform's own page chrome (the account switcher on X, the profile link on
+//! Instagram, the "Your profile" menu on Messenger and Facebook) and reports to
+//! `report_identity`. The last identity per platform is cached; changes, including
+//! logging out, are emitted as `identity-changed`.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::platform_manager::{Platform, PlatformManager};
+
+/// How often the injected probe re-reads the page, to catch account switches
+/// that don't reload it
+const POLL_INTERVAL_MS: u32 = 60_000;
+
+/// Delay before the first read, so the page's chrome has rendered
+const FIRST_READ_DELAY_MS: u32 = 3_000;
+
+/// The account logged in to a platform
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Identity {
+    /// Display name, or the handle where the page only shows that
+    pub name: String,
+    /// Username, without a leading `@`
+    pub handle: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// What the probe read from the page; all empty when logged out
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProbeReport {
+    pub name: Option<String>,
+    pub handle: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// Payload emitted with `identity-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct IdentityChangedPayload {
+    pub platform: String,
+    /// `None` once logged out
+    pub identity: Option<Identity>,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Maps a probe report to an identity. `None` when the page showed no account
+/// (logged out, or still loading).
+pub fn from_report(report: ProbeReport) -> Option<Identity> {
+    let handle = non_empty(report.handle)
+        .map(|h| h.trim_start_matches('@').to_string())
+        .filter(|h| !h.is_empty() && !h.contains(char::is_whitespace));
+    let name = non_empty(report.name).or_else(|| handle.clone())?;
+    // Only real images; the pages sometimes report placeholders and data URIs
+    let avatar_url = non_empty(report.avatar_url).filter(|u| u.starts_with("https://"));
+    Some(Identity { name, handle, avatar_url })
+}
+
+/// Holds the last identity reported per platform
+pub struct IdentityCache {
+    identities: Mutex<HashMap<String, Option<Identity>>>,
+}
+
+impl IdentityCache {
+    pub fn new() -> Self {
+        Self {
+            identities: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The identity last seen on `platform`; `None` if logged out or not probed yet
+    pub fn get(&self, platform: &Platform) -> Result<Option<Identity>, String> {
+        let identities = self.identities.lock().map_err(|e| e.to_string())?;
+        Ok(identities.get(platform.name()).cloned().flatten())
+    }
+
+    /// Records what the probe saw. Returns `true` if it changed.
+    fn record(&self, platform: &Platform, identity: Option<Identity>) -> Result<bool, String> {
+        let mut identities = self.identities.lock().map_err(|e| e.to_string())?;
+        let previous = identities.insert(platform.name().to_string(), identity.clone());
+        Ok(previous != Some(identity))
+    }
+}
+
+impl Default for IdentityCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The platform's read of the logged-in account, as a JS function body returning
+/// `{ name, handle, avatarUrl }`
+fn reader(platform: &Platform) -> &'static str {
+    match platform {
+        // The account switcher button holds the avatar, the name and "@handle"
+        Platform::X => r#"
+            const button = document.querySelector('[data-testid="SideNav_AccountSwitcher_Button"]');
+            if (!button) return null;
+            const lines = (button.innerText || '').split('\n').map(function(l) { return l.trim(); }).filter(Boolean);
+            const img = button.querySelector('img');
+            return {
+                name: lines.find(function(l) { return l[0] !== '@'; }) || null,
+                handle: lines.find(function(l) { return l[0] === '@'; }) || null,
+                avatarUrl: img ? img.src : null,
+            };
+        "#,
+        // The nav's profile link shows the avatar, alt-texted "<username>'s profile picture"
+        Platform::Instagram => r#"
+            const img = document.querySelector('a[role="link"] img[alt$="profile picture" i]');
+            if (!img) return null;
+            const handle = (img.getAttribute('alt') || '').replace(/'s profile picture$/i, '');
+            return { name: null, handle: handle, avatarUrl: img.src };
+        "#,
+        // The "Your profile" menu button carries the avatar; the name is on the profile shortcut
+        Platform::Messenger | Platform::Facebook => r#"
+            const button = document.querySelector('[aria-label="Your profile" i], [aria-label="Settings, help and more" i]');
+            if (!button) return null;
+            const image = button.querySelector('image, img');
+            const avatar = image && (image.getAttribute('xlink:href') || image.getAttribute('href') || image.src);
+            const shortcut = document.querySelector('[role="navigation"] a[href*="/me/"] span, [role="navigation"] a[href*="profile.php"] span');
+            return {
+                name: shortcut ? shortcut.textContent : null,
+                handle: null,
+                avatarUrl: avatar || null,
+            };
+        "#,
+    }
+}
+
+/// Builds the probe script for `platform`. Without one there is nobody to probe.
+pub fn probe_script(platform: Option<&Platform>) -> Option<String> {
+    let platform = platform?;
+    Some(format!(
+        r#"
+        (function() {{
+            if (window.__MD_IDENTITY_PROBE__) clearInterval(window.__MD_IDENTITY_PROBE__);
+
+            function read() {{
+                try {{ {reader} }} catch (e) {{ return null; }}
+            }}
+
+            function probe() {{
+                const core = window.__TAURI__ && window.__TAURI__.core;
+                if (!core) return;
+                const found = read() || {{}};
+                core.invoke('report_identity', {{
+                    name: found.name || null,
+                    handle: found.handle || null,
+                    avatarUrl: found.avatarUrl || null,
+                }}).catch(function(e) {{ console.warn('[messenger-desktop] identity report failed:', e); }});
+            }}
+
+            window.__MD_IDENTITY_PROBE_NOW__ = probe;
+            setTimeout(probe, {delay});
+            window.__MD_IDENTITY_PROBE__ = setInterval(probe, {interval});
+        }})();
+        "#,
+        reader = reader(platform),
+        delay = FIRST_READ_DELAY_MS,
+        interval = POLL_INTERVAL_MS,
+    ))
+}
+
+fn current_platform(app: &AppHandle) -> Option<Platform> {
+    app.try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten())
+}
+
+/// Page-load hook: (re)installs the probe for the active platform, so the
+/// identity is re-read after every navigation
+pub fn on_page_load(window: &WebviewWindow) {
+    let platform = current_platform(window.app_handle());
+    let Some(script) = probe_script(platform.as_ref()) else {
+        return;
+    };
+    if let Err(e) = window.eval(&script) {
+        log::warn!("[identity] failed to install identity probe: {}", e);
+    }
+}
+
+/// Tauri command called by the injected probe with what it read
+#[tauri::command]
+#[specta::specta]
+pub fn report_identity(
+    name: Option<String>,
+    handle: Option<String>,
+    avatar_url: Option<String>,
+    app: AppHandle,
+    cache: tauri::State<'_, IdentityCache>,
+) -> Result<(), String> {
+    let Some(platform) = current_platform(&app) else {
+        return Ok(());
+    };
+    let identity = from_report(ProbeReport { name, handle, avatar_url });
+    if !cache.record(&platform, identity.clone())? {
+        return Ok(());
+    }
+    log::info!(
+        "[identity] {} {}",
+        platform.name(),
+        if identity.is_some() { "logged in" } else { "logged out" }
+    );
+    if let Some(identity) = &identity {
+        crate::accounts::fill_from_identity(&app, identity);
+    }
+    app.emit(
+        "identity-changed",
+        IdentityChangedPayload {
+            platform: platform.name().to_string(),
+            identity,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command to get who is logged in to `platform`: `None` if logged out or
+/// not opened yet. For the active platform it also re-reads the page, so a change
+/// shows up as `identity-changed` shortly after.
+#[tauri::command]
+#[specta::specta]
+pub fn get_logged_in_identity(
+    platform: String,
+    app: AppHandle,
+    cache: tauri::State<'_, IdentityCache>,
+) -> Result<Option<Identity>, String> {
+    let platform = Platform::from_str(&platform).ok_or_else(|| format!("Unknown platform: {}", platform))?;
+    if current_platform(&app).as_ref() == Some(&platform) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.eval("window.__MD_IDENTITY_PROBE_NOW__ && window.__MD_IDENTITY_PROBE_NOW__();");
+        }
+    }
+    cache.get(&platform)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(name: Option<&str>, handle: Option<&str>, avatar_url: Option<&str>) -> ProbeReport {
+        ProbeReport {
+            name: name.map(str::to_string),
+            handle: handle.map(str::to_string),
+            avatar_url: avatar_url.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_probe_output_mapping() {
+        assert_eq!(
+            from_report(report(Some(" Ada Lovelace "), Some("@ada"), Some("https://pbs.twimg.com/a.jpg"))),
+            Some(Identity {
+                name: "Ada Lovelace".to_string(),
+                handle: Some("ada".to_string()),
+                avatar_url: Some("https://pbs.twimg.com/a.jpg".to_string()),
+            })
+        );
+        // Instagram only shows the username
+        let instagram = from_report(report(None, Some("ada.l"), None)).unwrap();
+        assert_eq!(instagram.name, "ada.l");
+        assert_eq!(instagram.handle.as_deref(), Some("ada.l"));
+        // Placeholder avatars and odd handles are dropped, not the identity
+        let odd = from_report(report(Some("Ada"), Some("not a handle"), Some("data:image/png;base64,AA"))).unwrap();
+        assert_eq!((odd.handle, odd.avatar_url), (None, None));
+
+        // Logged out: nothing on the page
+        assert_eq!(from_report(ProbeReport::default()), None);
+        assert_eq!(from_report(report(Some("  "), Some("@"), Some("https://x/a.jpg"))), None);
+    }
+
+    #[test]
+    fn test_cache_reports_changes_only() {
+        let cache = IdentityCache::new();
+        let ada = from_report(report(Some("Ada"), None, None));
+        assert!(cache.record(&Platform::X, ada.clone()).unwrap());
+        assert!(!cache.record(&Platform::X, ada.clone()).unwrap());
+        assert_eq!(cache.get(&Platform::X).unwrap(), ada);
+        assert_eq!(cache.get(&Platform::Instagram).unwrap(), None);
+        // Logging out is a change
+        assert!(cache.record(&Platform::X, None).unwrap());
+        assert_eq!(cache.get(&Platform::X).unwrap(), None);
+
+        assert!(probe_script(Some(&Platform::X)).unwrap().contains("SideNav_AccountSwitcher_Button"));
+        assert!(probe_script(None).is_none());
+    }
+}
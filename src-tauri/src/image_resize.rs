@@ -0,0 +1,337 @@
+//! Downscaling of large pasted / dropped images before upload.
+//!
+//! A hook injected into the page catches images pasted or dropped into the
+//! conversation that are over `OFFER_MIN_BYTES`. With auto-resize on they are
+//! shrunk straight away; otherwise the user is asked first. The hook sends the image
+//! to `resize_image`, which scales it to fit `max_dimension` (keeping the aspect
+//! ratio) and recompresses it: photos become JPEG at the configured quality, images
+//! with transparency stay PNG. The result goes into the platform's file input in
+//! place of the original.
+
+use image::{DynamicImage, GenericImageView, ImageOutputFormat};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::store;
+
+/// Pasted / dropped images smaller than this (bytes) are left alone
+const OFFER_MIN_BYTES: u64 = 1024 * 1024;
+
+/// Smallest `max_dimension` accepted
+const MIN_DIMENSION: u32 = 64;
+
+/// Persisted image-resize configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ImageResizeConfig {
+    /// Resize without asking
+    pub enabled: bool,
+    /// Longest side after resizing, in pixels
+    pub max_dimension: u32,
+    /// JPEG quality (1-100)
+    pub quality: u8,
+}
+
+impl Default for ImageResizeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_dimension: 2048,
+            quality: 85,
+        }
+    }
+}
+
+/// Format a resized image is encoded in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Jpeg,
+    Png,
+}
+
+impl OutputFormat {
+    fn mime_type(self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::Png => "image/png",
+        }
+    }
+}
+
+/// A recompressed image handed back to the page
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ResizedImage {
+    pub data: Vec<u8>,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Size of a `width` x `height` image scaled down (never up) so its longest side
+/// is at most `max_dimension`, keeping the aspect ratio
+pub fn fit_within(width: u32, height: u32, max_dimension: u32) -> (u32, u32) {
+    let longest = width.max(height);
+    if longest <= max_dimension {
+        return (width, height);
+    }
+    let scale = |side: u32| ((side as u64 * max_dimension as u64 + longest as u64 / 2) / longest as u64).max(1) as u32;
+    (scale(width), scale(height))
+}
+
+/// JPEG for photos; PNG when the image uses transparency (JPEG would flatten it)
+pub fn choose_format(has_transparency: bool) -> OutputFormat {
+    if has_transparency {
+        OutputFormat::Png
+    } else {
+        OutputFormat::Jpeg
+    }
+}
+
+/// Whether any pixel of `image` is not fully opaque
+fn has_transparency(image: &DynamicImage) -> bool {
+    image.color().has_alpha() && image.to_rgba8().pixels().any(|p| p.0[3] < u8::MAX)
+}
+
+/// Scales and recompresses an encoded image. Returns `None` when that wouldn't help:
+/// the image already fits and recompressing doesn't make it smaller.
+pub fn resize(data: &[u8], config: &ImageResizeConfig) -> Result<Option<ResizedImage>, String> {
+    let image = image::load_from_memory(data).map_err(|e| format!("Unreadable image: {}", e))?;
+    let (width, height) = image.dimensions();
+    let (new_width, new_height) = fit_within(width, height, config.max_dimension);
+    let image = if (new_width, new_height) == (width, height) {
+        image
+    } else {
+        image.resize_exact(new_width, new_height, image::imageops::FilterType::Lanczos3)
+    };
+
+    let format = choose_format(has_transparency(&image));
+    let mut out = Vec::new();
+    match format {
+        OutputFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_to(&mut Cursor::new(&mut out), ImageOutputFormat::Jpeg(config.quality)),
+        OutputFormat::Png => image.write_to(&mut Cursor::new(&mut out), ImageOutputFormat::Png),
+    }
+    .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    if (new_width, new_height) == (width, height) && out.len() >= data.len() {
+        return Ok(None);
+    }
+    Ok(Some(ResizedImage {
+        data: out,
+        mime_type: format.mime_type().to_string(),
+        width: new_width,
+        height: new_height,
+    }))
+}
+
+/// Manages the image-resize configuration and its persistence
+pub struct ImageResizeManager {
+    config: std::sync::Mutex<ImageResizeConfig>,
+    store_path: PathBuf,
+}
+
+impl ImageResizeManager {
+    /// Creates a new ImageResizeManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("image_resize.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: std::sync::Mutex::new(config),
+            store_path,
+        }
+    }
+
+    /// Gets the current configuration
+    pub fn get_config(&self) -> Result<ImageResizeConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Validates, updates and persists the configuration
+    pub fn set(&self, config: ImageResizeConfig) -> Result<(), String> {
+        if config.max_dimension < MIN_DIMENSION {
+            return Err(format!("Max dimension must be at least {} pixels", MIN_DIMENSION));
+        }
+        if !(1..=100).contains(&config.quality) {
+            return Err("Quality must be between 1 and 100".to_string());
+        }
+        *self.config.lock().map_err(|e| e.to_string())? = config.clone();
+        store::save(&self.store_path, &config)
+            .map_err(|e| format!("Failed to save image resize config: {}", e))
+    }
+}
+
+/// Builds the paste / drop hook. With `auto` off it asks before resizing.
+pub fn resize_hook_script(config: &ImageResizeConfig) -> String {
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_IMAGE_RESIZE__) window.__MD_IMAGE_RESIZE__.disable();
+            const core = window.__TAURI__ && window.__TAURI__.core;
+            if (!core) return;
+
+            const AUTO = {auto};
+            const MIN_BYTES = {min_bytes};
+
+            function isLarge(file) {{
+                return file.type.indexOf('image/') === 0 && file.type !== 'image/gif' && file.size > MIN_BYTES;
+            }}
+
+            async function shrink(file) {{
+                const data = Array.from(new Uint8Array(await file.arrayBuffer()));
+                const out = await core.invoke('resize_image', {{ data: data }});
+                if (!out) return file;
+                const ext = out.mime_type === 'image/png' ? '.png' : '.jpg';
+                const name = (file.name || 'image').replace(/\.[^.]*$/, '') + ext;
+                return new File([new Uint8Array(out.data)], name, {{ type: out.mime_type }});
+            }}
+
+            function onTransfer(event) {{
+                const transfer = event.clipboardData || event.dataTransfer;
+                const files = Array.from((transfer && transfer.files) || []);
+                if (!files.some(isLarge)) return;
+                if (!AUTO && !window.confirm('Resize large images before sending?')) return;
+
+                event.preventDefault();
+                event.stopImmediatePropagation();
+                Promise.all(files.map(function(f) {{
+                    return isLarge(f) ? shrink(f).catch(function() {{ return f; }}) : f;
+                }})).then(function(resized) {{
+                    const input = document.querySelector('input[type="file"]');
+                    if (!input) {{
+                        console.error('File input not found');
+                        return;
+                    }}
+                    const dataTransfer = new DataTransfer();
+                    resized.forEach(function(f) {{ dataTransfer.items.add(f); }});
+                    input.files = dataTransfer.files;
+                    input.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                }});
+            }}
+
+            document.addEventListener('paste', onTransfer, true);
+            document.addEventListener('drop', onTransfer, true);
+
+            window.__MD_IMAGE_RESIZE__ = {{
+                disable: function() {{
+                    document.removeEventListener('paste', onTransfer, true);
+                    document.removeEventListener('drop', onTransfer, true);
+                    delete window.__MD_IMAGE_RESIZE__;
+                }},
+            }};
+        }})();
+        "#,
+        auto = config.enabled,
+        min_bytes = OFFER_MIN_BYTES,
+    )
+}
+
+/// Script re-injected after every page load
+pub fn page_load_script(app: &AppHandle) -> Option<String> {
+    let config = app.try_state::<ImageResizeManager>()?.get_config().ok()?;
+    Some(resize_hook_script(&config))
+}
+
+/// Tauri command to configure image resizing. `enabled` resizes without asking.
+#[tauri::command]
+#[specta::specta]
+pub fn set_auto_resize_images(
+    enabled: bool,
+    max_dimension: u32,
+    quality: u8,
+    app: AppHandle,
+    manager: tauri::State<'_, ImageResizeManager>,
+) -> Result<(), String> {
+    let config = ImageResizeConfig {
+        enabled,
+        max_dimension,
+        quality,
+    };
+    manager.set(config.clone())?;
+    crate::injection::apply(&app, crate::injection::IMAGE_RESIZE, &resize_hook_script(&config))?;
+    log::info!(
+        "[image_resize] auto-resize {} ({}px, quality {})",
+        if enabled { "enabled" } else { "disabled" },
+        max_dimension,
+        quality
+    );
+    Ok(())
+}
+
+/// Tauri command to get the image-resize configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_image_resize_config(
+    manager: tauri::State<'_, ImageResizeManager>,
+) -> Result<ImageResizeConfig, String> {
+    manager.get_config()
+}
+
+/// Tauri command used by the page hook: resizes an image with the current settings.
+/// Returns `None` if the original should be sent as-is.
+#[tauri::command]
+#[specta::specta]
+pub fn resize_image(
+    data: Vec<u8>,
+    manager: tauri::State<'_, ImageResizeManager>,
+) -> Result<Option<ResizedImage>, String> {
+    resize(&data, &manager.get_config()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    fn encode(image: DynamicImage) -> Vec<u8> {
+        let mut out = Vec::new();
+        image.write_to(&mut Cursor::new(&mut out), ImageOutputFormat::Png).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_fit_within_keeps_aspect_ratio() {
+        assert_eq!(fit_within(4000, 3000, 2000), (2000, 1500));
+        assert_eq!(fit_within(1080, 2400, 1200), (540, 1200));
+        // Already small enough: never scaled up
+        assert_eq!(fit_within(800, 600, 2048), (800, 600));
+        // Extreme strips keep at least a pixel
+        assert_eq!(fit_within(10_000, 1, 100), (100, 1));
+    }
+
+    #[test]
+    fn test_format_follows_transparency() {
+        assert_eq!(choose_format(false), OutputFormat::Jpeg);
+        assert_eq!(choose_format(true), OutputFormat::Png);
+
+        let opaque = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+        assert!(!has_transparency(&DynamicImage::ImageRgba8(opaque)));
+        let mut clear = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+        clear.put_pixel(0, 0, Rgba([0, 0, 0, 0]));
+        assert!(has_transparency(&DynamicImage::ImageRgba8(clear)));
+    }
+
+    #[test]
+    fn test_resize_large_images() {
+        let config = ImageResizeConfig {
+            max_dimension: 100,
+            ..ImageResizeConfig::default()
+        };
+
+        let photo = encode(DynamicImage::ImageRgb8(RgbImage::from_fn(400, 200, |x, y| {
+            Rgb([x as u8, y as u8, (x ^ y) as u8])
+        })));
+        let resized = resize(&photo, &config).unwrap().unwrap();
+        assert_eq!((resized.width, resized.height), (100, 50));
+        assert_eq!(resized.mime_type, "image/jpeg");
+
+        let mut graphic = RgbaImage::from_pixel(300, 300, Rgba([255, 0, 0, 255]));
+        graphic.put_pixel(5, 5, Rgba([0, 0, 0, 0]));
+        let resized = resize(&encode(DynamicImage::ImageRgba8(graphic)), &config).unwrap().unwrap();
+        assert_eq!((resized.width, resized.height), (100, 100));
+        assert_eq!(resized.mime_type, "image/png");
+
+        assert!(resize(b"not an image", &config).is_err());
+    }
+}
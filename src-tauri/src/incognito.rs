@@ -0,0 +1,136 @@
+//! Incognito (ephemeral) sessions.
+//!
+//! An incognito session opens a platform in its own window backed by a throwaway
+//! directory under `sessions/incognito/<id>`. The directory is removed when the
+//! window is destroyed or the session is ended explicitly, and any left behind by
+//! a crash are swept on the next startup.
+
+use serde::Serialize;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, Url, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::platform_manager::Platform;
+use crate::privacy_engine::PrivacyEngine;
+
+/// Window label prefix for incognito windows
+const WINDOW_PREFIX: &str = "incognito-";
+
+/// Incognito directories older than this are treated as crash leftovers
+const STALE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Payload emitted with `incognito-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct IncognitoChangedPayload {
+    pub session_id: String,
+    pub platform: String,
+    pub active: bool,
+}
+
+fn window_label(session_id: &str) -> String {
+    format!("{}{}", WINDOW_PREFIX, session_id)
+}
+
+fn window_title(platform: &Platform) -> String {
+    format!("{} (Incognito)", platform.name())
+}
+
+/// Removes incognito directories left behind by a previous crash
+pub fn sweep_stale(engine: &PrivacyEngine) {
+    let removed = engine.sweep_stale_incognito(SystemTime::now(), STALE_AFTER);
+    if removed > 0 {
+        log::info!("[incognito] removed {} stale incognito session(s)", removed);
+    }
+}
+
+fn emit_changed(app: &AppHandle, session_id: &str, platform: &Platform, active: bool) {
+    let _ = app.emit(
+        "incognito-changed",
+        IncognitoChangedPayload {
+            session_id: session_id.to_string(),
+            platform: platform.name().to_string(),
+            active,
+        },
+    );
+}
+
+/// Tauri command to open a platform in a new incognito window. Returns the session id.
+/// Async: a sync command runs on the main thread, and building a window there
+/// deadlocks on Windows (WebView2).
+#[tauri::command]
+#[specta::specta]
+pub async fn start_incognito_session(
+    platform: String,
+    app: AppHandle,
+    engine: tauri::State<'_, PrivacyEngine>,
+) -> Result<String, String> {
+    let platform = Platform::from_str(&platform).ok_or_else(|| format!("Unknown platform: {}", platform))?;
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let dir = engine.incognito_dir(&session_id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create incognito session: {}", e))?;
+
+    let url = Url::parse(platform.url()).map_err(|e| format!("Invalid platform URL: {}", e))?;
+    let window = WebviewWindowBuilder::new(&app, window_label(&session_id), WebviewUrl::External(url))
+        .title(window_title(&platform))
+        .inner_size(1000.0, 750.0)
+        .data_directory(dir)
+        .incognito(true)
+        .build()
+        .map_err(|e| {
+            let _ = engine.clear_incognito(&session_id);
+            format!("Failed to open incognito window: {}", e)
+        })?;
+
+    // Wipe the session however the window goes away
+    let handle = app.clone();
+    let id = session_id.clone();
+    let window_platform = platform.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            if let Err(e) = handle.state::<PrivacyEngine>().clear_incognito(&id) {
+                log::warn!("[incognito] failed to clear session {}: {}", id, e);
+            }
+            emit_changed(&handle, &id, &window_platform, false);
+        }
+    });
+
+    log::info!("[incognito] started {} session {}", platform.name(), session_id);
+    emit_changed(&app, &session_id, &platform, true);
+    Ok(session_id)
+}
+
+/// Tauri command to close an incognito session and delete its data
+#[tauri::command]
+#[specta::specta]
+pub fn end_incognito_session(
+    session_id: String,
+    app: AppHandle,
+    engine: tauri::State<'_, PrivacyEngine>,
+) -> Result<(), String> {
+    match app.get_webview_window(&window_label(&session_id)) {
+        // The window's Destroyed handler clears the directory and emits the event
+        Some(window) => window.destroy().map_err(|e| e.to_string()),
+        None => engine.clear_incognito(&session_id),
+    }
+}
+
+/// Tauri command to list the open incognito session ids
+#[tauri::command]
+#[specta::specta]
+pub fn list_incognito_sessions(app: AppHandle) -> Vec<String> {
+    app.webview_windows()
+        .into_keys()
+        .filter_map(|label| label.strip_prefix(WINDOW_PREFIX).map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incognito_window_naming() {
+        assert_eq!(window_label("abc"), "incognito-abc");
+        assert_eq!(window_label("abc").strip_prefix(WINDOW_PREFIX), Some("abc"));
+        assert!(window_title(&Platform::Instagram).contains("Incognito"));
+    }
+}
@@ -0,0 +1,440 @@
+//! Registry of the scripts injected into platform pages.
+//!
+//! Each injection has a name, a phase and an enabled flag. `OnLoad` injections are
+//! re-run by `reinject_all` after every page load, building their script from the
+//! owning manager's current state, so a setting survives navigations. `OnDemand`
+//! injections only run when their module asks (`apply`). A module that changes a
+//! setting calls `apply` with the new script; that goes through the same enabled
+//! and safe-mode checks.
+//!
+//! Disabling an injection reloads the page, which is the only reliable way to take
+//! an interceptor back out; the remaining injections are re-run on the reload.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+/// Injection names
+pub const PRIVACY: &str = "privacy";
+pub const BANDWIDTH_SAVER: &str = "bandwidth_saver";
+pub const AUTOPLAY: &str = "autoplay";
+pub const THEME: &str = "theme";
+pub const ACCENT: &str = "accent";
+pub const DENSITY: &str = "density";
+pub const FOCUS_MODE: &str = "focus_mode";
+pub const DRAG_REGION: &str = "drag_region";
+pub const WINDOW_EFFECT: &str = "window_effect";
+pub const QUICK_SEARCH: &str = "quick_search";
+pub const IMAGE_RESIZE: &str = "image_resize";
+pub const COMPOSER: &str = "composer";
+pub const SNIPPETS: &str = "snippets";
+pub const CALL_PROBE: &str = "call_probe";
+
+/// When an injection runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// After every page load, and when its module applies a change
+    OnLoad,
+    /// Only when its module applies it
+    OnDemand,
+}
+
+/// Builds an injection's script from current state; `None` = nothing to inject
+type ScriptFn = Box<dyn Fn() -> Option<String> + Send + Sync>;
+
+struct Injection {
+    name: &'static str,
+    phase: Phase,
+    enabled: bool,
+    script: ScriptFn,
+}
+
+/// An injection as reported to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct InjectionInfo {
+    pub name: String,
+    pub phase: Phase,
+    pub enabled: bool,
+}
+
+/// Something scripts can be evaluated in
+pub trait ScriptTarget {
+    fn eval_script(&self, script: &str) -> Result<(), String>;
+}
+
+impl ScriptTarget for WebviewWindow {
+    fn eval_script(&self, script: &str) -> Result<(), String> {
+        self.eval(script).map_err(|e| e.to_string())
+    }
+}
+
+/// Builds a script that sets the CSS of the `<style id="{id}">` element, creating
+/// it if needed. Empty CSS removes the element. Running it twice leaves one element.
+pub fn style_script(id: &str, css: &str) -> String {
+    let id = serde_json::to_string(id).unwrap_or_else(|_| "\"\"".to_string());
+    let css = serde_json::to_string(css).unwrap_or_else(|_| "\"\"".to_string());
+    format!(
+        r#"
+        (function() {{
+            let style = document.getElementById({id});
+            if (!{css}) {{
+                if (style) style.remove();
+                return;
+            }}
+            if (!style) {{
+                style = document.createElement('style');
+                style.id = {id};
+                document.documentElement.appendChild(style);
+            }}
+            style.textContent = {css};
+        }})();
+        "#,
+        id = id,
+        css = css,
+    )
+}
+
+/// The injections known to the app
+pub struct InjectionRegistry {
+    entries: Mutex<Vec<Injection>>,
+}
+
+impl InjectionRegistry {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Adds an enabled injection, replacing any with the same name
+    pub fn register<F>(&self, name: &'static str, phase: Phase, script: F) -> Result<(), String>
+    where
+        F: Fn() -> Option<String> + Send + Sync + 'static,
+    {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        entries.retain(|i| i.name != name);
+        entries.push(Injection {
+            name,
+            phase,
+            enabled: true,
+            script: Box::new(script),
+        });
+        Ok(())
+    }
+
+    /// All injections, in registration order
+    pub fn list(&self) -> Result<Vec<InjectionInfo>, String> {
+        let entries = self.entries.lock().map_err(|e| e.to_string())?;
+        Ok(entries
+            .iter()
+            .map(|i| InjectionInfo {
+                name: i.name.to_string(),
+                phase: i.phase,
+                enabled: i.enabled,
+            })
+            .collect())
+    }
+
+    /// Whether `name` is registered and enabled
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.entries
+            .lock()
+            .is_ok_and(|entries| entries.iter().any(|i| i.name == name && i.enabled))
+    }
+
+    /// Enables or disables an injection. Returns whether it changed.
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> Result<bool, String> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        let injection = entries
+            .iter_mut()
+            .find(|i| i.name == name)
+            .ok_or_else(|| format!("Unknown injection: {}", name))?;
+        let changed = injection.enabled != enabled;
+        injection.enabled = enabled;
+        Ok(changed)
+    }
+
+    /// Runs one injection's current script in `target`, if it is enabled. Returns
+    /// whether anything was injected.
+    pub fn run(&self, target: &impl ScriptTarget, name: &str) -> Result<bool, String> {
+        let entries = self.entries.lock().map_err(|e| e.to_string())?;
+        let Some(injection) = entries.iter().find(|i| i.name == name && i.enabled) else {
+            return Ok(false);
+        };
+        let Some(script) = (injection.script)() else {
+            return Ok(false);
+        };
+        target.eval_script(&script)?;
+        Ok(true)
+    }
+
+    /// Runs every enabled `OnLoad` injection in `target`. Returns the names injected.
+    pub fn run_all(&self, target: &impl ScriptTarget) -> Vec<&'static str> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let mut injected = Vec::new();
+        for injection in entries.iter().filter(|i| i.enabled && i.phase == Phase::OnLoad) {
+            let Some(script) = (injection.script)() else {
+                continue;
+            };
+            match target.eval_script(&script) {
+                Ok(()) => injected.push(injection.name),
+                Err(e) => log::warn!("[injection] failed to inject {}: {}", injection.name, e),
+            }
+        }
+        injected
+    }
+}
+
+impl Default for InjectionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the registry with the app's injections. Each script reads its manager's
+/// state when it runs, so it is always current.
+pub fn register_defaults(app: &AppHandle) -> InjectionRegistry {
+    let registry = InjectionRegistry::new();
+
+    let handle = app.clone();
+    let _ = registry.register(PRIVACY, Phase::OnLoad, move || {
+        let state = handle.try_state::<Mutex<crate::privacy::PrivacyManager>>()?;
+        let config = state.lock().ok()?.config().clone();
+        Some(crate::privacy::privacy_script(&config))
+    });
+
+    let handle = app.clone();
+    let _ = registry.register(BANDWIDTH_SAVER, Phase::OnLoad, move || {
+        crate::bandwidth::page_load_script(&handle)
+    });
+
+    let handle = app.clone();
+    let _ = registry.register(AUTOPLAY, Phase::OnLoad, move || crate::autoplay::page_load_script(&handle));
+
+    let handle = app.clone();
+    let _ = registry.register(THEME, Phase::OnLoad, move || {
+        let state = handle.try_state::<Mutex<crate::theme_manager::ThemeManager>>()?;
+        let css = state.lock().ok()?.current_css();
+        Some(crate::theme_manager::theme_script(&css))
+    });
+
+    // After the theme, so the accent is laid over it
+    let handle = app.clone();
+    let _ = registry.register(ACCENT, Phase::OnLoad, move || {
+        let state = handle.try_state::<Mutex<crate::theme_manager::ThemeManager>>()?;
+        let css = state.lock().ok()?.current_accent_css();
+        (!css.is_empty()).then(|| crate::theme_manager::accent_script(&css))
+    });
+
+    let handle = app.clone();
+    let _ = registry.register(DENSITY, Phase::OnLoad, move || crate::density::page_load_script(&handle));
+
+    let handle = app.clone();
+    let _ = registry.register(FOCUS_MODE, Phase::OnLoad, move || {
+        let manager = handle.try_state::<crate::window_manager::WindowManager>()?;
+        Some(crate::window_manager::focus_mode_script(manager.page_look().focus_mode))
+    });
+
+    let handle = app.clone();
+    let _ = registry.register(DRAG_REGION, Phase::OnLoad, move || {
+        let manager = handle.try_state::<crate::window_manager::WindowManager>()?;
+        let decorations = manager.page_look().decorations;
+        let platform = handle
+            .try_state::<crate::platform_manager::PlatformManager>()
+            .and_then(|m| m.get_current().ok().flatten());
+        // Nothing to undo on a fresh page while decorated
+        (!decorations).then(|| crate::window_manager::drag_region_script(false, platform.as_ref()))
+    });
+
+    // After the theme, whose body background it clears
+    let handle = app.clone();
+    let _ = registry.register(WINDOW_EFFECT, Phase::OnLoad, move || {
+        let manager = handle.try_state::<crate::window_manager::WindowManager>()?;
+        let effect = manager.page_look().window_effect;
+        // Nothing to undo on a fresh page without an effect
+        (effect != crate::window_manager::WindowEffect::None)
+            .then(|| crate::window_manager::window_effect_script(effect))
+    });
+
+    let handle = app.clone();
+    let _ = registry.register(IMAGE_RESIZE, Phase::OnLoad, move || {
+        crate::image_resize::page_load_script(&handle)
+    });
+
+    let handle = app.clone();
+    let _ = registry.register(COMPOSER, Phase::OnLoad, move || {
+        crate::composer::page_load_script(&handle)
+    });
+
+    let handle = app.clone();
+    let _ = registry.register(SNIPPETS, Phase::OnLoad, move || crate::snippets::page_load_script(&handle));
+
+    let handle = app.clone();
+    let _ = registry.register(CALL_PROBE, Phase::OnLoad, move || crate::calls::page_load_script(&handle));
+
+    let handle = app.clone();
+    let _ = registry.register(QUICK_SEARCH, Phase::OnDemand, move || {
+        Some(crate::search::current_script(&handle))
+    });
+
+    registry
+}
+
+/// Page-load hook: re-runs every enabled `OnLoad` injection. Nothing is injected in
+/// safe mode.
+pub fn reinject_all(window: &WebviewWindow) {
+    let app = window.app_handle();
+    if crate::safe_mode::is_active(app) {
+        return;
+    }
+    if let Some(registry) = app.try_state::<InjectionRegistry>() {
+        let injected = registry.run_all(window);
+        log::debug!("[injection] injected {:?}", injected);
+    }
+}
+
+/// Evaluates `script` for injection `name` in the main window, unless the injection
+/// is disabled or the app is in safe mode
+pub fn apply(app: &AppHandle, name: &str, script: &str) -> Result<(), String> {
+    if crate::safe_mode::is_active(app) {
+        return Ok(());
+    }
+    let enabled = match app.try_state::<InjectionRegistry>() {
+        Some(registry) => registry.is_enabled(name),
+        None => true,
+    };
+    match app.get_webview_window("main") {
+        Some(window) if enabled => window.eval_script(script),
+        _ => Ok(()),
+    }
+}
+
+/// Tauri command to list the injections and whether each is enabled
+#[tauri::command]
+#[specta::specta]
+pub fn list_injections(registry: tauri::State<'_, InjectionRegistry>) -> Result<Vec<InjectionInfo>, String> {
+    registry.list()
+}
+
+/// Tauri command to enable or disable an injection. Enabling injects it right away;
+/// disabling reloads the page without it.
+#[tauri::command]
+#[specta::specta]
+pub fn set_injection_enabled(
+    name: String,
+    enabled: bool,
+    app: AppHandle,
+    registry: tauri::State<'_, InjectionRegistry>,
+) -> Result<(), String> {
+    if !registry.set_enabled(&name, enabled)? {
+        return Ok(());
+    }
+    log::info!("[injection] {} {}", name, if enabled { "enabled" } else { "disabled" });
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    if enabled {
+        if !crate::safe_mode::is_active(&app) {
+            registry.run(&window, &name)?;
+        }
+        Ok(())
+    } else {
+        window.eval_script("window.location.reload();")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Records evaluated scripts instead of running them
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl ScriptTarget for Recorder {
+        fn eval_script(&self, script: &str) -> Result<(), String> {
+            self.0.lock().unwrap().push(script.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_all_respects_phase_and_enabled() {
+        let registry = InjectionRegistry::new();
+        registry.register("a", Phase::OnLoad, || Some("a()".to_string())).unwrap();
+        registry.register("b", Phase::OnDemand, || Some("b()".to_string())).unwrap();
+        registry.register("c", Phase::OnLoad, || None).unwrap();
+        registry.register("d", Phase::OnLoad, || Some("d()".to_string())).unwrap();
+        registry.set_enabled("d", false).unwrap();
+
+        let target = Recorder::default();
+        assert_eq!(registry.run_all(&target), vec!["a"]);
+        assert_eq!(*target.0.lock().unwrap(), vec!["a()"]);
+
+        assert!(registry.run(&target, "b").unwrap());
+        assert!(!registry.run(&target, "d").unwrap());
+        assert!(registry.set_enabled("missing", true).is_err());
+    }
+
+    #[test]
+    fn test_scripts_read_current_state() {
+        let registry = InjectionRegistry::new();
+        let state = Arc::new(Mutex::new("one".to_string()));
+        let source = state.clone();
+        registry
+            .register("s", Phase::OnLoad, move || Some(source.lock().unwrap().clone()))
+            .unwrap();
+        // Re-registering replaces rather than duplicates
+        let source = state.clone();
+        registry
+            .register("s", Phase::OnLoad, move || Some(source.lock().unwrap().clone()))
+            .unwrap();
+        assert_eq!(registry.list().unwrap().len(), 1);
+
+        let target = Recorder::default();
+        registry.run_all(&target);
+        *state.lock().unwrap() = "two".to_string();
+        registry.run_all(&target);
+        assert_eq!(*target.0.lock().unwrap(), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_navigation_reapplies_active_settings() {
+        // Stand-ins for the managers: privacy, theme and focus mode are on, the
+        // bandwidth saver is off
+        let registry = InjectionRegistry::new();
+        registry
+            .register(PRIVACY, Phase::OnLoad, || Some("privacy()".to_string()))
+            .unwrap();
+        registry.register(BANDWIDTH_SAVER, Phase::OnLoad, || None).unwrap();
+        registry
+            .register(THEME, Phase::OnLoad, || Some(style_script("__md_theme__", "body{}")))
+            .unwrap();
+        registry
+            .register(FOCUS_MODE, Phase::OnLoad, || Some(style_script("__md_focus_mode__", "nav{}")))
+            .unwrap();
+        registry
+            .register(QUICK_SEARCH, Phase::OnDemand, || Some("search()".to_string()))
+            .unwrap();
+
+        // Every navigation re-runs the same set
+        let target = Recorder::default();
+        for _ in 0..2 {
+            assert_eq!(registry.run_all(&target), vec![PRIVACY, THEME, FOCUS_MODE]);
+        }
+        assert_eq!(target.0.lock().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_style_script_is_idempotent() {
+        let script = style_script("__md_theme__", "body{color:red}");
+        assert!(script.contains("document.getElementById(\"__md_theme__\")"));
+        assert!(script.contains("if (!style)"));
+        assert!(style_script("x", "a\"b").contains(r#""a\"b""#));
+    }
+}
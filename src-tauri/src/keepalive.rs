@@ -0,0 +1,262 @@
+//! Session keepalive for the active platform.
+//!
+//! Periodically pings the active platform's own origin from inside the webview so
+//! an idle session isn't expired overnight. Only runs while the platform is logged
+//! in (as the identity probe last saw it). Keeps pinging while the user is away, as
+//! that is when the session would expire, but pauses while Do Not Disturb is on or
+//! the app is locked, so the pings don't generate activity that marks the user
+//! "online" when they asked not to be reached. Also holds off while the network is
+//! down or a reconnect reload is settling.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::identity::IdentityCache;
+use crate::lock::LockManager;
+use crate::network::NetworkMonitor;
+use crate::notifications::NotificationService;
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::store;
+
+/// How often the background task wakes up to check whether a ping is due.
+const TICK: Duration = Duration::from_secs(60);
+
+/// Allowed keepalive interval range, in minutes.
+const MIN_INTERVAL_MINUTES: u32 = 5;
+const MAX_INTERVAL_MINUTES: u32 = 720;
+
+/// Persisted keepalive configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct KeepaliveConfig {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 30,
+        }
+    }
+}
+
+/// Payload emitted with `session-refreshed`
+#[derive(Serialize, Clone, Debug)]
+pub struct SessionRefreshedPayload {
+    pub platform: String,
+}
+
+/// Manages the keepalive configuration and its persistence
+pub struct KeepaliveManager {
+    config: std::sync::Mutex<KeepaliveConfig>,
+    store_path: PathBuf,
+}
+
+impl KeepaliveManager {
+    /// Creates a new KeepaliveManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("keepalive.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: std::sync::Mutex::new(config),
+            store_path,
+        }
+    }
+
+    /// Gets the current keepalive configuration
+    pub fn get_config(&self) -> Result<KeepaliveConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Updates and persists the keepalive configuration
+    pub fn set(&self, enabled: bool, interval_minutes: u32) -> Result<KeepaliveConfig, String> {
+        if !(MIN_INTERVAL_MINUTES..=MAX_INTERVAL_MINUTES).contains(&interval_minutes) {
+            return Err(format!(
+                "Keepalive interval must be between {} and {} minutes",
+                MIN_INTERVAL_MINUTES, MAX_INTERVAL_MINUTES
+            ));
+        }
+        let config = KeepaliveConfig {
+            enabled,
+            interval_minutes,
+        };
+        self.persist(&config)?;
+        *self.config.lock().map_err(|e| e.to_string())? = config.clone();
+        Ok(config)
+    }
+
+    /// Persists the configuration to disk
+    fn persist(&self, config: &KeepaliveConfig) -> Result<(), String> {
+        store::save(&self.store_path, config).map_err(|e| format!("Failed to save keepalive config: {}", e))
+    }
+}
+
+/// Decides whether a keepalive ping should be sent now.
+pub fn should_ping(
+    config: &KeepaliveConfig,
+    since_last_ping: Duration,
+    platform_selected: bool,
+    logged_in: bool,
+    dnd_active: bool,
+    locked: bool,
+) -> bool {
+    config.enabled
+        && platform_selected
+        && logged_in
+        && !dnd_active
+        && !locked
+        && since_last_ping >= Duration::from_secs(u64::from(config.interval_minutes) * 60)
+}
+
+/// Builds the injected heartbeat: a credentialed HEAD request to the site's own origin.
+fn ping_script() -> &'static str {
+    r#"
+    (function() {
+        fetch(window.location.origin + '/', { method: 'HEAD', credentials: 'include', cache: 'no-store' })
+            .catch(function(e) { console.warn('[messenger-desktop] keepalive ping failed:', e); });
+    })();
+    "#
+}
+
+/// Sends a single keepalive ping for the given platform.
+fn ping(app: &AppHandle, platform: &Platform) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    window.eval(ping_script()).map_err(|e| e.to_string())?;
+    log::debug!("[keepalive] pinged {}", platform.name());
+    app.emit(
+        "session-refreshed",
+        SessionRefreshedPayload {
+            platform: platform.name().to_string(),
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Spawns the background keepalive loop.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last_ping = Instant::now();
+        loop {
+            tokio::time::sleep(TICK).await;
+
+            let Ok(config) = app.state::<KeepaliveManager>().get_config() else {
+                continue;
+            };
+            let platform = app.state::<PlatformManager>().get_current().ok().flatten();
+            let logged_in = platform
+                .as_ref()
+                .is_some_and(|p| app.state::<IdentityCache>().get(p).ok().flatten().is_some());
+            let dnd_active = app
+                .state::<NotificationService>()
+                .get_settings()
+                .await
+                .do_not_disturb;
+            let locked = app.state::<LockManager>().is_locked();
+
+            if !should_ping(
+                &config,
+                last_ping.elapsed(),
+                platform.is_some(),
+                logged_in,
+                dnd_active,
+                locked,
+            ) {
+                continue;
+            }
+            if app.state::<NetworkMonitor>().is_settling() {
+                continue;
+            }
+
+            if let Some(platform) = platform {
+                if let Err(e) = ping(&app, &platform) {
+                    log::warn!("[keepalive] ping failed: {}", e);
+                }
+            }
+            last_ping = Instant::now();
+        }
+    });
+}
+
+/// Tauri command to configure the session keepalive
+#[tauri::command]
+#[specta::specta]
+pub fn set_session_keepalive(
+    enabled: bool,
+    minutes: u32,
+    manager: tauri::State<'_, KeepaliveManager>,
+) -> Result<KeepaliveConfig, String> {
+    manager.set(enabled, minutes)
+}
+
+/// Tauri command to get the session keepalive configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_session_keepalive(manager: tauri::State<'_, KeepaliveManager>) -> Result<KeepaliveConfig, String> {
+    manager.get_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(minutes: u32) -> KeepaliveConfig {
+        KeepaliveConfig {
+            enabled: true,
+            interval_minutes: minutes,
+        }
+    }
+
+    #[test]
+    fn test_should_ping_when_due() {
+        let due = Duration::from_secs(30 * 60);
+        assert!(should_ping(&enabled(30), due, true, true, false, false));
+        assert!(!should_ping(&enabled(30), Duration::from_secs(60), true, true, false, false));
+        assert!(!should_ping(&KeepaliveConfig::default(), due, true, true, false, false));
+    }
+
+    #[test]
+    fn test_should_ping_gating() {
+        let due = Duration::from_secs(30 * 60);
+        assert!(!should_ping(&enabled(30), due, false, true, false, false));
+        // Logged out
+        assert!(!should_ping(&enabled(30), due, true, false, false, false));
+        // Do Not Disturb
+        assert!(!should_ping(&enabled(30), due, true, true, true, false));
+        // Locked
+        assert!(!should_ping(&enabled(30), due, true, true, false, true));
+    }
+
+    #[test]
+    fn test_should_ping_while_away() {
+        // Idle overnight is exactly when the session needs the pings
+        let overnight = Duration::from_secs(8 * 60 * 60);
+        assert!(should_ping(&enabled(720), overnight, true, true, false, false));
+    }
+
+    #[test]
+    fn test_config_persistence() {
+        let tmp = std::env::temp_dir().join("test-keepalive");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = KeepaliveManager::new(&tmp);
+        assert!(manager.set(true, 1).is_err());
+        manager.set(true, 45).unwrap();
+        let reloaded = KeepaliveManager::new(&tmp);
+        assert_eq!(reloaded.get_config().unwrap(), enabled(45));
+        let _ = std::fs::remove_dir_all(&tmp);
+
+        // A failed write leaves the configuration as it was (the store can't
+        // create its file under a regular file)
+        std::fs::write(&tmp, b"").unwrap();
+        let manager = KeepaliveManager::new(&tmp);
+        assert!(manager.set(true, 60).is_err());
+        assert_eq!(manager.get_config().unwrap(), KeepaliveConfig::default());
+        let _ = std::fs::remove_file(&tmp);
+    }
+}
@@ -3,54 +3,149 @@
 
 use tauri::{Manager, WebviewWindowBuilder, WebviewUrl};
 
+use crate::commands::{read_clipboard_text, write_clipboard_text, print_page, restart_app};
 use crate::debug::{open_devtools, close_devtools, toggle_devtools, is_devtools_open};
+use crate::diagnostics::get_diagnostics;
+use crate::logging::{get_log_level, set_log_level, open_log_file, collect_diagnostics};
 
 // Import all the command functions
 use crate::notifications::{
     show_notification, set_dnd, toggle_dnd, is_dnd_enabled, set_notification_sound,
     get_notification_settings, set_notification_enabled, set_notification_sound_enabled,
-    use_default_notification_sound, handle_notification
+    use_default_notification_sound, handle_notification, set_dnd_until, get_dnd_remaining,
+    add_notification_filter, remove_notification_filter, list_notification_filters,
+    get_notification_history, mute_conversation, unmute_conversation,
+    send_test_notification, play_sent_sound, set_sent_sound_enabled, set_sent_sound,
+    set_preview_mode, clear_preview_mode, set_platform_dnd_schedule, clear_platform_dnd_schedule, reset_notification_settings, get_dnd_digest,
+    set_digest_on_dnd_end, set_notification_rate_limit, set_toast_grouping, set_respect_system_dnd, list_notification_actions,
+    register_notification_action, unregister_notification_action, invoke_notification_action,
+    handle_toast_activation, dismiss_notification, dismiss_all_notifications, report_conversation_read, prioritize_conversation, clear_priority, list_prioritized
 };
 use crate::window_manager::{
-    toggle_always_on_top, set_always_on_top, is_always_on_top, set_zoom, get_zoom,
-    zoom_in, zoom_out, reset_zoom, get_zoom_formatted, get_zoom_percentage,
-    toggle_focus_mode, set_focus_mode, is_in_focus_mode, get_window_state,
+    toggle_always_on_top, set_always_on_top, is_always_on_top, set_above_fullscreen, set_sticky_workspace, set_window_effect, set_autosave_interval, set_window_title, reset_window_title, set_decorations, set_zoom, get_zoom,
+    zoom_in, zoom_out, reset_zoom, reset_all_zoom, get_zoom_formatted, get_zoom_percentage,
+    toggle_focus_mode, set_focus_mode, is_in_focus_mode, toggle_mini_mode, is_mini_mode, dock_window, undock_window,
+    list_monitors, move_to_monitor, restore_previous_position, get_position_history, clear_position_history,
+    get_window_state,
     save_window_state, restore_window_state, reset_window_state, toggle_fullscreen,
+    list_windows, focus_window,
     toggle_maximize, set_maximized, is_maximized, minimize_to_tray, restore_from_tray
 };
-use crate::tray::{init_tray, update_unread_count, set_tray_tooltip};
-use crate::shortcuts::{init_shortcuts, register_shortcuts, update_shortcut, unregister_shortcut};
-use crate::theme_manager::{set_theme, get_themes, set_custom_css, current_theme_name};
-use crate::privacy::{set_privacy, get_privacy, set_block_typing, set_block_read_receipts, set_hide_last_active};
-use crate::updater::{check_update, install_update};
-// use crate::spellcheck::{spellcheck, get_suggestions}; // Disabled due to hunspell issues
-use crate::accounts::{list_accounts, add_account, remove_account};
-use crate::media::grant_media_permission;
-use crate::drag_drop::handle_file_drop;
-use crate::platform_manager::{PlatformManager, select_platform, get_current_platform, get_last_platform, list_platforms};
-use crate::privacy_engine::{PrivacyEngine, clear_platform_session, clear_all_sessions, get_csp_for_platform};
+use crate::tray::{init_tray, update_unread_count, set_tray_tooltip, set_tray_icon, set_tray_icon_per_platform, get_tray_icon, is_tray_available};
+use crate::unread::{UnreadWatcher, BadgeManager, report_unread_count, get_unread_by_account, set_badge_mode, get_badge_config, set_background_unread_poll};
+use crate::connection::{ConnectionMonitor, report_connection_status, get_connection_status};
+use crate::identity::{IdentityCache, report_identity, get_logged_in_identity};
+use crate::media_export::{MediaExport, export_conversation_media, report_conversation_media};
+use crate::network::{NetworkMonitor, set_auto_reconnect, get_auto_reconnect};
+use crate::shortcuts::{init_shortcuts, register_shortcuts, update_shortcut, unregister_shortcut, register_custom_shortcut, unregister_custom_shortcut, list_shortcuts};
+use crate::theme_manager::{set_theme, get_themes, set_custom_css, current_theme_name, reset_theme, set_accent_color, clear_accent_color, get_accent_color};
+use crate::privacy::{set_privacy, get_privacy, set_block_typing, set_block_read_receipts, set_hide_last_active, set_block_link_previews, reset_privacy_config, set_conversation_read_receipts, list_blocked_receipt_conversations, clear_blocked_receipt_conversations};
+use crate::updater::{check_update, install_update, get_current_version, get_last_check_time, set_channel, get_channel};
+use crate::spellcheck::{
+    enable_spellcheck, disable_spellcheck, set_spellcheck_language, get_available_languages, is_misspelled,
+    get_suggestions, check_text,
+};
+use crate::accounts::{
+    AccountManager, list_accounts, add_account, remove_account, switch_account, set_profile_picture,
+    set_session_token, get_session_token, update_last_sync,
+};
+use crate::media::{
+    MediaManager, get_media_permissions, grant_media_permission, save_media_file, get_media_file_command,
+    generate_preview_command, delete_media_file_command,
+};
+use crate::drag_drop::{handle_file_drop, validate_files};
+use crate::platform_manager::{PlatformManager, select_platform, get_current_platform, get_last_platform, list_platforms, set_platform_order, set_platform_visibility};
+use crate::privacy_engine::{
+    PrivacyEngine, clear_platform_session, clear_webview_cache, clear_all_sessions, get_csp_for_platform,
+    logout_platform, get_session_usage
+};
+use crate::keepalive::{KeepaliveManager, set_session_keepalive, get_session_keepalive};
+use crate::autoplay::{AutoplayManager, set_autoplay_policy, get_autoplay_policy};
+use crate::bandwidth::{BandwidthSaverManager, set_bandwidth_saver, get_bandwidth_saver};
+use crate::composer::{ComposerManager, set_send_on_enter, get_composer_config};
+use crate::snippets::{SnippetManager, add_snippet, remove_snippet, list_snippets};
+use crate::density::{DensityManager, set_message_density, get_message_density};
+use crate::calls::{CallManager, report_call_state, set_dnd_during_calls, get_call_config, get_call_state};
+use crate::image_resize::{ImageResizeManager, set_auto_resize_images, get_image_resize_config, resize_image};
+use crate::proxy::{ProxyManager, set_proxy, clear_proxy, get_proxy};
+use crate::gpu::{GpuManager, set_hardware_acceleration, get_hardware_acceleration};
+use crate::session_backup::{export_session, import_session};
+use crate::panic_button::panic_wipe;
+use crate::search::focus_search;
+use crate::safe_mode::{enter_safe_mode, exit_safe_mode, is_safe_mode};
+use crate::injection::{list_injections, set_injection_enabled};
+use crate::history::{HistoryTracker, report_history_state, webview_back, webview_forward, can_go_back, can_go_forward};
+use crate::lock::{LockManager, set_app_lock, lock_app_now, unlock_app, report_activity, get_app_lock_status};
+use crate::pins::{PinManager, add_pin, list_pins, remove_pin, open_pin};
+use crate::reminders::{ReminderManager, schedule_reminder, list_reminders, cancel_reminder};
+use crate::incognito::{start_incognito_session, end_incognito_session, list_incognito_sessions};
+use crate::navigation::{InAppDomains, add_inapp_domain, remove_inapp_domain, list_inapp_domains};
+use crate::conversation_window::{ConversationWindows, open_conversation_window, close_conversation_window, list_conversation_windows};
+use crate::startup::{
+    StartupManager, set_autostart, is_autostart_enabled, set_start_hidden, set_always_start_visible,
+    get_startup_config
+};
+use crate::settings::{get_all_settings, apply_settings, export_settings, import_settings};
+use crate::store::get_data_dir;
 
 mod accounts;
+mod autoplay;
+mod bandwidth;
+mod calls;
+mod commands;
+mod composer;
+mod connection;
+mod conversation_window;
 mod debug;
+mod deep_link;
+mod density;
+mod diagnostics;
 mod drag_drop;
+mod gpu;
+mod history;
+mod icon_cache;
+mod identity;
+mod image_resize;
+mod incognito;
+mod injection;
+mod keepalive;
+mod lock;
+mod logging;
 mod media;
+mod media_export;
+mod navigation;
+mod network;
 mod notifications;
 mod platform;
 mod platform_manager;
+mod panic_button;
+mod pins;
 mod privacy;
 mod privacy_engine;
+mod proxy;
+mod reminders;
+mod safe_mode;
+mod session_backup;
+mod search;
+mod settings;
 mod shortcuts;
+mod snippets;
 mod spellcheck;
+mod startup;
+mod store;
 mod theme_manager;
 mod tray;
+mod unread;
 mod updater;
 mod window_manager;
 
+pub use logging::init as init_logging;
+
 // Clipboard commands and print command are defined in their respective modules
 
 // Notification interceptor JS — injected into EVERY navigation including external URLs
-// Uses the official Tauri v2 notification plugin JS API (window.__TAURI__.notification)
-// No custom Rust command roundtrip needed — plugin handles permission + delivery directly.
+// Routes through the `handle_notification` command so filters, conversation mutes and DND
+// apply; falls back to the notification plugin JS API (window.__TAURI__.notification).
 const NOTIFICATION_INTERCEPTOR_JS: &str = r#"
 (function() {
     if (window.__MESSENGER_DESKTOP_PATCHED__) { return; }
@@ -59,6 +154,26 @@ const NOTIFICATION_INTERCEPTOR_JS: &str = r#"
     const OriginalNotification = window.Notification;
 
     async function sendViaTauri(title, options) {
+        const core = window.__TAURI__ && window.__TAURI__.core;
+        if (core) {
+            try {
+                await core.invoke('handle_notification', {
+                    title: String(title),
+                    options: {
+                        body: options.body ? String(options.body) : '',
+                        tag: options.tag ? String(options.tag) : null,
+                        icon: options.icon ? String(options.icon) : null,
+                        silent: !!options.silent,
+                        requireInteraction: !!options.requireInteraction,
+                        timestamp: typeof options.timestamp === 'number' ? options.timestamp : null,
+                    },
+                });
+                return;
+            } catch (e) {
+                console.warn('[messenger-desktop] handle_notification failed:', e);
+            }
+        }
+
         try {
             const notif = window.__TAURI__ && window.__TAURI__.notification;
             if (!notif) {
@@ -105,39 +220,500 @@ const NOTIFICATION_INTERCEPTOR_JS: &str = r#"
 })();
 "#;
 
+/// Builds the typed command set shared by the invoke handler and the TypeScript bindings.
+/// Every command must carry `#[specta::specta]` or this fails to compile.
+pub fn specta_builder() -> tauri_specta::Builder<tauri::Wry> {
+    tauri_specta::Builder::<tauri::Wry>::new().commands(tauri_specta::collect_commands![
+        // Notifications
+        show_notification,
+        set_dnd,
+        toggle_dnd,
+        is_dnd_enabled,
+        set_notification_sound,
+        get_notification_settings,
+        set_notification_enabled,
+        reset_notification_settings,
+        set_notification_sound_enabled,
+        use_default_notification_sound,
+        handle_notification,
+        set_dnd_until,
+        set_dnd_during_calls,
+        get_call_config,
+        report_call_state,
+        get_call_state,
+        get_dnd_remaining,
+        get_dnd_digest,
+        set_digest_on_dnd_end,
+        set_notification_rate_limit,
+        set_toast_grouping,
+        set_respect_system_dnd,
+        list_notification_actions,
+        register_notification_action,
+        unregister_notification_action,
+        invoke_notification_action,
+        handle_toast_activation,
+        dismiss_notification,
+        dismiss_all_notifications,
+        report_conversation_read,
+        add_notification_filter,
+        remove_notification_filter,
+        list_notification_filters,
+        get_notification_history,
+        mute_conversation,
+        unmute_conversation,
+        prioritize_conversation,
+        clear_priority,
+        list_prioritized,
+        send_test_notification,
+        play_sent_sound,
+        set_sent_sound_enabled,
+        set_sent_sound,
+        set_preview_mode,
+        clear_preview_mode,
+        set_platform_dnd_schedule,
+        clear_platform_dnd_schedule,
+
+        // Window management
+        toggle_always_on_top,
+        set_always_on_top,
+        is_always_on_top,
+        set_above_fullscreen,
+        set_sticky_workspace,
+        set_window_effect,
+        set_autosave_interval,
+        set_window_title,
+        reset_window_title,
+        set_decorations,
+        set_zoom,
+        get_zoom,
+        zoom_in,
+        zoom_out,
+        reset_zoom,
+        reset_all_zoom,
+        get_zoom_formatted,
+        get_zoom_percentage,
+        toggle_focus_mode,
+        set_focus_mode,
+        is_in_focus_mode,
+        toggle_mini_mode,
+        is_mini_mode,
+        dock_window,
+        undock_window,
+        list_monitors,
+        move_to_monitor,
+        restore_previous_position,
+        get_position_history,
+        clear_position_history,
+        get_window_state,
+        save_window_state,
+        restore_window_state,
+        reset_window_state,
+        toggle_fullscreen,
+        toggle_maximize,
+        set_maximized,
+        is_maximized,
+        minimize_to_tray,
+        restore_from_tray,
+        list_windows,
+        focus_window,
+
+        // Tray
+        init_tray,
+        update_unread_count,
+        report_unread_count,
+        get_unread_by_account,
+        set_background_unread_poll,
+        set_badge_mode,
+        get_badge_config,
+        set_tray_tooltip,
+        set_tray_icon,
+        set_tray_icon_per_platform,
+        get_tray_icon,
+        is_tray_available,
+
+        // Connection
+        report_connection_status,
+        get_connection_status,
+        set_auto_reconnect,
+        get_auto_reconnect,
+
+        // Media export
+        export_conversation_media,
+        report_conversation_media,
+
+        // Identity
+        report_identity,
+        get_logged_in_identity,
+
+        // History
+        report_history_state,
+        webview_back,
+        webview_forward,
+        can_go_back,
+        can_go_forward,
+
+        // Shortcuts
+        init_shortcuts,
+        register_shortcuts,
+        update_shortcut,
+        unregister_shortcut,
+        register_custom_shortcut,
+        unregister_custom_shortcut,
+        list_shortcuts,
+        panic_wipe,
+        focus_search,
+
+        // Theme
+        set_theme,
+        get_themes,
+        set_custom_css,
+        current_theme_name,
+        reset_theme,
+        set_accent_color,
+        clear_accent_color,
+        get_accent_color,
+        set_message_density,
+        get_message_density,
+
+        // Privacy
+        set_privacy,
+        get_privacy,
+        set_block_typing,
+        set_block_read_receipts,
+        set_conversation_read_receipts,
+        list_blocked_receipt_conversations,
+        clear_blocked_receipt_conversations,
+        set_hide_last_active,
+        set_block_link_previews,
+        reset_privacy_config,
+
+        // Updater
+        check_update,
+        install_update,
+        get_current_version,
+        get_last_check_time,
+        set_channel,
+        get_channel,
+
+        // Spellcheck (disabled due to hunspell issues)
+        // spellcheck,
+        // get_suggestions,
+
+        // Accounts
+        list_accounts,
+        add_account,
+        remove_account,
+        switch_account,
+        set_profile_picture,
+        set_session_token,
+        get_session_token,
+        update_last_sync,
+
+        // Media
+        get_media_permissions,
+        grant_media_permission,
+        save_media_file,
+        get_media_file_command,
+        generate_preview_command,
+        delete_media_file_command,
+
+        // Spellcheck
+        enable_spellcheck,
+        disable_spellcheck,
+        set_spellcheck_language,
+        get_available_languages,
+        is_misspelled,
+        get_suggestions,
+        check_text,
+
+        // Drag & Drop
+        handle_file_drop,
+        validate_files,
+
+        // Platform
+        select_platform,
+        get_current_platform,
+        get_last_platform,
+        list_platforms,
+        set_platform_order,
+        set_platform_visibility,
+
+        // Privacy Engine
+        clear_platform_session,
+        clear_webview_cache,
+        get_session_usage,
+        logout_platform,
+        clear_all_sessions,
+        get_csp_for_platform,
+        export_session,
+        import_session,
+
+        // App lock
+        set_app_lock,
+        lock_app_now,
+        unlock_app,
+        report_activity,
+        get_app_lock_status,
+
+        // Pins
+        add_pin,
+        list_pins,
+        remove_pin,
+        open_pin,
+
+        // Reminders
+        schedule_reminder,
+        list_reminders,
+        cancel_reminder,
+
+        // Incognito sessions
+        start_incognito_session,
+        end_incognito_session,
+        list_incognito_sessions,
+        add_inapp_domain,
+        remove_inapp_domain,
+        list_inapp_domains,
+
+        // Conversation windows
+        open_conversation_window,
+        close_conversation_window,
+        list_conversation_windows,
+
+        // Session keepalive
+        set_session_keepalive,
+        get_session_keepalive,
+
+        // Bandwidth saver
+        set_bandwidth_saver,
+        get_bandwidth_saver,
+
+        // Autoplay
+        set_autoplay_policy,
+        get_autoplay_policy,
+
+        // Image resize
+        set_auto_resize_images,
+        get_image_resize_config,
+        resize_image,
+
+        // Composer
+        set_send_on_enter,
+        get_composer_config,
+
+        // Snippets
+        add_snippet,
+        remove_snippet,
+        list_snippets,
+
+        // Startup
+        set_autostart,
+        is_autostart_enabled,
+        set_start_hidden,
+        set_always_start_visible,
+        get_startup_config,
+
+        // Proxy
+        set_proxy,
+        clear_proxy,
+        get_proxy,
+
+        // Hardware acceleration
+        set_hardware_acceleration,
+        get_hardware_acceleration,
+
+        // Settings
+        get_all_settings,
+        apply_settings,
+        export_settings,
+        import_settings,
+
+        // Clipboard / print
+        read_clipboard_text,
+        write_clipboard_text,
+        print_page,
+        restart_app,
+
+        // Debug / DevTools
+        open_devtools,
+        close_devtools,
+        toggle_devtools,
+        is_devtools_open,
+        get_diagnostics,
+
+        // Logging / diagnostics
+        get_log_level,
+        set_log_level,
+        open_log_file,
+        collect_diagnostics,
+
+        // Safe mode
+        enter_safe_mode,
+        exit_safe_mode,
+        is_safe_mode,
+
+        // Injections
+        list_injections,
+        set_injection_enabled,
+
+        // Data dir
+        get_data_dir,
+    ])
+}
+
+/// Writes the TypeScript bindings for every registered command to `path`.
+pub fn export_bindings(path: &str) -> Result<(), String> {
+    specta_builder()
+        .export(
+            specta_typescript::Typescript::default()
+                .bigint(specta_typescript::BigIntExportBehavior::Number)
+                .header("// @ts-nocheck\n// Generated by `npm run bindings` - do not edit by hand."),
+            path,
+        )
+        .map_err(|e| e.to_string())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let _span = tracing::info_span!("app::run").entered();
     tracing::info!("Initializing Tauri application");
 
     tauri::Builder::default()
-        // Plugins
+        // Plugins — single-instance must come first so a second launch exits before doing any work
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            crate::startup::on_second_instance(app, argv, cwd)
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            Some(vec!["--flag"]),
+            Some(vec![crate::startup::AUTOSTART_ARG]),
         ))
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
         .setup(|app| {
+            // Navigation guard keeps the webview on the active platform; other links open externally
+            let nav_handle = app.handle().clone();
+
+            let handle = app.handle().clone();
+            // Falls back to the temp dir (the frontend warns via `get_data_dir`); with
+            // nowhere to write at all, explain why and quit instead of panicking
+            let data_dir = match crate::store::resolve_data_dir(&handle) {
+                Ok(dir) => dir,
+                Err(e) => {
+                    use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+                    log::error!("[setup] no usable data directory: {}", e);
+                    let exit_handle = handle.clone();
+                    handle
+                        .dialog()
+                        .message(format!(
+                            "Messenger Desktop can't start because it has nowhere to store its data: {}.\n\nCheck that your home or temp directory is writable.",
+                            e
+                        ))
+                        .title("Messenger Desktop")
+                        .kind(MessageDialogKind::Error)
+                        .show(move |_| exit_handle.exit(1));
+                    return Ok(());
+                }
+            };
+            let app_data_dir = data_dir.path.clone();
+            app.manage(data_dir);
+
+            // Proxy must be known before the webview is built; changes apply on restart
+            let proxy_manager = ProxyManager::new(&app_data_dir);
+
+            // So must the hardware acceleration preference (WebKitGTK reads it from the environment)
+            let gpu_manager = GpuManager::new(&app_data_dir);
+            #[cfg(target_os = "linux")]
+            gpu_manager.apply_environment();
+
+            // The tray comes before the window: without one, a hidden window couldn't
+            // be brought back
+            let tray = crate::tray::TrayManager::new(&handle);
+
+            // `--hidden` (or a launch at login with start-hidden on, or a window that was
+            // hidden at quit) builds the window hidden; the tray icon restores it. The
+            // interceptor and platform init still run.
+            let startup_manager = StartupManager::new(&app_data_dir);
+            let launch_args = crate::startup::LaunchArgs::parse(std::env::args());
+            let was_visible = crate::window_manager::last_visible(&app_data_dir);
+            let start_hidden = startup_manager.should_start_hidden(&launch_args, was_visible, tray.is_ok());
+            if start_hidden {
+                tracing::info!("[setup] starting hidden in the tray");
+            }
+            // The platforms' logins live in the webview profile; a wipe that couldn't
+            // remove it while the webview had it open finishes before it starts again
+            let privacy_engine = PrivacyEngine::new(app_data_dir.clone());
+            if let Err(e) = privacy_engine.finish_pending_wipe() {
+                log::warn!("[setup] {}", e);
+            }
+            // So does a session import, staged for the same reason
+            if let Err(e) = crate::session_backup::finish_pending_restore(&privacy_engine) {
+                log::warn!("[setup] {}", e);
+            }
+
+            // An app quit while locked starts behind the lock screen, not on the page
+            let lock_manager = LockManager::new(&app_data_dir);
+            let start_locked = lock_manager.is_locked();
+            // Managed before the window exists so the first page load already skips injections
+            if launch_args.safe_mode {
+                tracing::info!("[setup] starting in safe mode");
+            }
+            app.manage(crate::safe_mode::SafeMode::new(launch_args.safe_mode));
+            app.manage(crate::injection::register_defaults(app.handle()));
+
             // Notification interceptor JS — injected into EVERY navigation including external URLs
-            let _main_window = WebviewWindowBuilder::new(
+            let builder = WebviewWindowBuilder::new(
                 app,
                 "main",
                 WebviewUrl::App("index.html".into()),
             )
-            .title("Social Hub")
+            .title(crate::window_manager::compose_title(&app.package_info().name, None, None))
             .inner_size(1200.0, 800.0)
             .resizable(true)
+            .visible(!start_hidden && !start_locked)
+            .data_directory(privacy_engine.profile_dir())
+            // Keep timers running while hidden to the tray so unread counts stay current
+            .background_throttling(tauri::utils::config::BackgroundThrottlingPolicy::Disabled)
             .initialization_script(NOTIFICATION_INTERCEPTOR_JS)
-            .build()
-            .expect("failed to create main window");
+            .initialization_script(crate::notifications::SENT_SOUND_HOOK_JS)
+            .initialization_script(crate::notifications::THREAD_READ_HOOK_JS)
+            .initialization_script(crate::lock::ACTIVITY_HOOK_JS)
+            .on_navigation(move |url| crate::navigation::on_navigation(&nav_handle, url))
+            .on_page_load(|window, payload| {
+                if matches!(payload.event(), tauri::webview::PageLoadEvent::Finished) {
+                    crate::injection::reinject_all(&window);
+                    crate::window_manager::on_page_load(&window);
+                    crate::lock::on_page_load(&window);
+                    crate::unread::on_page_load(&window);
+                    crate::connection::on_page_load(&window);
+                    crate::identity::on_page_load(&window);
+                    crate::history::on_page_load(&window);
+                }
+            });
+
+            // WebKit on macOS only supports proxies behind the `macos-proxy` feature (macOS 14+)
+            #[cfg(not(target_os = "macos"))]
+            let builder = match proxy_manager.startup_url() {
+                Some(url) => {
+                    tracing::info!("[setup] routing webview through proxy");
+                    builder.proxy_url(url)
+                }
+                None => builder,
+            };
+
+            #[cfg(target_os = "windows")]
+            let builder = match gpu_manager.browser_args() {
+                Some(args) => builder.additional_browser_args(&args),
+                None => builder,
+            };
+
+            let _main_window = builder.build().expect("failed to create main window");
 
             // Auto-open DevTools in debug builds so you can inspect immediately.
             #[cfg(debug_assertions)]
-            {
+            if !start_locked {
                 _main_window.open_devtools();
                 tracing::debug!("[setup] DevTools auto-opened (debug build)");
             }
@@ -145,175 +721,242 @@ pub fn run() {
             // Request notification permission at startup (desktop only).
             // Must happen before any notification.show() call — macOS silently drops
             // notifications if permission was never requested.
+            let features = crate::diagnostics::Features::default();
             #[cfg(desktop)]
             {
                 use tauri_plugin_notification::NotificationExt;
-                let _ = app.notification().request_permission();
+                match app.notification().request_permission() {
+                    Ok(tauri::plugin::PermissionState::Granted) => features.ok("notifications"),
+                    Ok(state) => features.degraded("notifications", format!("permission {:?}", state)),
+                    Err(e) => features.degraded("notifications", e.to_string()),
+                }
             }
 
-            let handle = app.handle().clone();
-            let app_data_dir = app
-                .path()
-                .app_data_dir()
-                .expect("failed to get app data dir");
-
             // Notification service (uses Arc internally)
             let notif_service = crate::notifications::NotificationService::new(app_data_dir.clone());
+            notif_service.attach(handle.clone());
 
             // Initialize privacy manager
-            let privacy_manager = crate::privacy::PrivacyManager::new(&handle);
+            let privacy_manager = crate::privacy::PrivacyManager::new(&handle, &app_data_dir);
 
             // Initialize theme manager
-            let theme_manager = crate::theme_manager::ThemeManager::new(&handle);
+            let theme_manager = crate::theme_manager::ThemeManager::new(&handle, &app_data_dir);
 
             // Initialize spellchecker (graceful degradation if init fails)
             let spellchecker = match crate::spellcheck::SpellcheckManager::new(&handle) {
-                Ok(s) => s,
+                Ok(s) => {
+                    // Initializes, but checking is stubbed out until hunspell builds again
+                    features.degraded("spellcheck", "disabled in this build (hunspell)");
+                    s
+                }
                 Err(e) => {
                     log::warn!("Spellcheck init failed (disabled): {}", e);
-                    crate::spellcheck::SpellcheckManager::disabled()
+                    features.degraded("spellcheck", format!("init failed: {}", e));
+                    crate::spellcheck::SpellcheckManager::disabled(&handle)
                 }
             };
 
+            // Media files live under the data dir; the media commands are unavailable without it
+            match MediaManager::new(&handle) {
+                Ok(media_manager) => {
+                    app.manage(tokio::sync::Mutex::new(media_manager));
+                }
+                Err(e) => {
+                    log::warn!("Media init failed: {}", e);
+                    features.degraded("media", format!("init failed: {}", e));
+                }
+            }
+
             // Initialize updater
             let updater = crate::updater::UpdaterManager::new(&handle);
 
-            // Initialize tray
-            let tray = crate::tray::TrayManager::new(&handle)
-                .expect("failed to create tray manager");
-            app.manage(std::sync::Mutex::new(tray));
+            // Without a tray the app runs windowed only and the tray commands report
+            // it as unavailable
+            match tray {
+                Ok(tray) => {
+                    app.manage(std::sync::Mutex::new(tray));
+                    features.ok("tray");
+                }
+                Err(e) => {
+                    log::warn!("Failed to create tray, continuing without it: {}", e);
+                    features.degraded("tray", format!("init failed: {}", e));
+                    // Nothing else could bring a hidden window back; the lock screen
+                    // shows it once unlocked
+                    if !start_locked {
+                        if let Some(window) = app.get_webview_window("main") {
+                            if let Err(e) = window.show() {
+                                log::warn!("[setup] failed to show the window: {}", e);
+                            }
+                        }
+                    }
+                }
+            }
 
             // Initialize window manager
             let window_manager = crate::window_manager::WindowManager::new(app_data_dir.clone());
 
             // Initialize shortcut manager
-            let shortcut_manager = crate::shortcuts::ShortcutManager::new();
+            let shortcut_manager = crate::shortcuts::ShortcutManager::load(&app_data_dir);
+            if let Some(keys) = shortcut_manager.bindings().get(crate::panic_button::ACTION) {
+                if let Err(e) = crate::panic_button::register_shortcut(&handle, keys) {
+                    log::warn!("{}", e);
+                }
+            }
+            crate::shortcuts::register_custom(&handle, &shortcut_manager);
 
             // Initialize platform manager and privacy engine
             let platform_manager = PlatformManager::new(&app_data_dir);
-            let privacy_engine = PrivacyEngine::new(app_data_dir.clone());
+            crate::incognito::sweep_stale(&privacy_engine);
+            let keepalive_manager = KeepaliveManager::new(&app_data_dir);
+            let bandwidth_manager = BandwidthSaverManager::new(&app_data_dir);
+            let autoplay_manager = AutoplayManager::new(&app_data_dir);
+            let image_resize_manager = ImageResizeManager::new(&app_data_dir);
+            let composer_manager = ComposerManager::new(&app_data_dir);
+            let snippet_manager = SnippetManager::new(&app_data_dir);
+            let badge_manager = BadgeManager::new(&app_data_dir);
+            let call_manager = CallManager::new(&app_data_dir);
+            let density_manager = DensityManager::new(&app_data_dir);
+            let inapp_domains = InAppDomains::new(&app_data_dir);
+            let pin_manager = PinManager::new(&app_data_dir);
+            let reminder_manager = ReminderManager::new(&app_data_dir);
+            let network_monitor = NetworkMonitor::new(&app_data_dir);
+            let account_manager = AccountManager::new(&app_data_dir);
 
             app.manage(notif_service);
-            app.manage(privacy_manager);
-            app.manage(theme_manager);
-            app.manage(spellchecker);
+            app.manage(std::sync::Mutex::new(privacy_manager));
+            app.manage(std::sync::Mutex::new(theme_manager));
+            app.manage(std::sync::Mutex::new(spellchecker));
+            app.manage(features);
             app.manage(tokio::sync::Mutex::new(updater));
             app.manage(window_manager);
             app.manage(std::sync::Mutex::new(shortcut_manager));
             app.manage(platform_manager);
             app.manage(privacy_engine);
+            app.manage(keepalive_manager);
+            app.manage(proxy_manager);
+            app.manage(gpu_manager);
+            app.manage(startup_manager);
+            app.manage(bandwidth_manager);
+            app.manage(autoplay_manager);
+            app.manage(image_resize_manager);
+            app.manage(composer_manager);
+            app.manage(snippet_manager);
+            app.manage(badge_manager);
+            app.manage(call_manager);
+            app.manage(density_manager);
+            app.manage(inapp_domains);
+            app.manage(pin_manager);
+            app.manage(reminder_manager);
+            app.manage(lock_manager);
+            app.manage(UnreadWatcher::new());
+            app.manage(ConnectionMonitor::new());
+            app.manage(IdentityCache::new());
+            app.manage(MediaExport::new());
+            app.manage(HistoryTracker::new());
+            app.manage(ConversationWindows::new());
+            app.manage(network_monitor);
+            app.manage(std::sync::Mutex::new(account_manager));
+
+            // The tray and zoom were set up before the platform was known
+            if let Some(platform) = handle.state::<PlatformManager>().get_current().ok().flatten() {
+                crate::tray::on_platform_changed(&handle, &platform);
+                crate::window_manager::on_platform_changed(&handle, &platform);
+            }
+
+            // Restore the saved window geometry on its monitor (re-docking if it was docked)
+            let window_handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::window_manager::restore_window(&window_handle).await {
+                    log::warn!("Failed to restore window state: {}", e);
+                }
+            });
+
+            // Restart the expiry timer of a timed DND that survived a restart
+            let dnd_handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let on_expire = crate::notifications::emit_dnd_expired(dnd_handle.clone());
+                dnd_handle
+                    .state::<crate::notifications::NotificationService>()
+                    .resume_dnd_timer(on_expire)
+                    .await;
+            });
+
+            // Keep the active platform's session warm (no-op until enabled)
+            crate::keepalive::start(&handle);
+
+            // Keep the unread badge current while the window is hidden
+            crate::unread::start(&handle);
+
+            // Autosave the window state when it changed, in case the app is killed
+            crate::window_manager::start_autosave(&handle);
+
+            // Fire send-later reminders, including any that came due while closed
+            crate::reminders::start(&handle);
+
+            // Raise the window and open the conversation when a notification is clicked
+            crate::notifications::init_click_handler(&handle);
+
+            // Lock the app after the configured inactivity, or right away if it was locked
+            crate::lock::restore(&handle, !start_hidden);
+            crate::lock::start(&handle);
+
+            // Watch the network and reload a stuck page when it comes back
+            crate::network::start(&handle);
 
             // Initialize platform-specific features
             platform::init(&handle);
 
+            // messenger-desktop:// links, including one the app was launched with
+            crate::deep_link::init(&handle);
+
             Ok(())
         })
         .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                let engine = window.app_handle().state::<crate::privacy_engine::PrivacyEngine>();
-                if let Err(e) = engine.clear_all_sessions() {
-                    log::warn!("[on_quit] failed to clear sessions: {}", e);
-                }
+            // While locked, only the lock screen may show
+            if let tauri::WindowEvent::Focused(focused) = event {
+                crate::lock::on_focus_changed(window, *focused);
+            }
+            // Incognito windows clean up after themselves (see `incognito`)
+            if window.label() != "main" {
+                return;
+            }
+            if let tauri::WindowEvent::Moved(position) = event {
+                crate::window_manager::on_moved(window.app_handle(), position.x, position.y);
+            }
+            if let tauri::WindowEvent::Resized(_) = event {
+                // Picked up by the next autosave, which reads the real geometry
+                window.app_handle().state::<crate::window_manager::WindowManager>().mark_dirty();
+            }
+            if let tauri::WindowEvent::Focused(focused) = event {
+                crate::search::on_focus_changed(window.app_handle(), *focused);
+                crate::history::on_focus_changed(window.app_handle(), *focused);
+                crate::debug::on_focus_changed(window.app_handle(), *focused);
+            }
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                // Saving reads the window's geometry, which can't be waited on from
+                // the event loop; the window closes once the cleanup is done
+                api.prevent_close();
+                let app = window.app_handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let Some(main) = app.get_webview_window("main") else {
+                        return;
+                    };
+                    let window_manager = app.state::<crate::window_manager::WindowManager>();
+                    if let Err(e) = crate::window_manager::capture_window(&main, &window_manager).await {
+                        log::warn!("[on_quit] failed to save window state: {}", e);
+                    }
+                    let engine = app.state::<crate::privacy_engine::PrivacyEngine>();
+                    if let Err(e) = engine.clear_all_sessions() {
+                        log::warn!("[on_quit] failed to clear sessions: {}", e);
+                    }
+                    // Unlike `close`, doesn't ask again
+                    if let Err(e) = main.destroy() {
+                        log::warn!("[on_quit] failed to close the window: {}", e);
+                    }
+                });
             }
         })
-        .invoke_handler(tauri::generate_handler![
-            // Notifications
-            show_notification,
-            set_dnd,
-            toggle_dnd,
-            is_dnd_enabled,
-            set_notification_sound,
-            get_notification_settings,
-            set_notification_enabled,
-            set_notification_sound_enabled,
-            use_default_notification_sound,
-            handle_notification,
-
-            // Window management
-            toggle_always_on_top,
-            set_always_on_top,
-            is_always_on_top,
-            set_zoom,
-            get_zoom,
-            zoom_in,
-            zoom_out,
-            reset_zoom,
-            get_zoom_formatted,
-            get_zoom_percentage,
-            toggle_focus_mode,
-            set_focus_mode,
-            is_in_focus_mode,
-            get_window_state,
-            save_window_state,
-            restore_window_state,
-            reset_window_state,
-            toggle_fullscreen,
-            toggle_maximize,
-            set_maximized,
-            is_maximized,
-            minimize_to_tray,
-            restore_from_tray,
-
-            // Tray
-            init_tray,
-            update_unread_count,
-            set_tray_tooltip,
-
-            // Shortcuts
-            init_shortcuts,
-            register_shortcuts,
-            update_shortcut,
-            unregister_shortcut,
-
-            // Theme
-            set_theme,
-            get_themes,
-            set_custom_css,
-            current_theme_name,
-
-            // Privacy
-            set_privacy,
-            get_privacy,
-            set_block_typing,
-            set_block_read_receipts,
-            set_hide_last_active,
-
-            // Updater
-            check_update,
-            install_update,
-
-            // Spellcheck (disabled due to hunspell issues)
-            // spellcheck,
-            // get_suggestions,
-
-            // Accounts
-            list_accounts,
-            add_account,
-            remove_account,
-
-            // Media
-            grant_media_permission,
-
-            // Drag & Drop
-            handle_file_drop,
-
-            // Platform
-            select_platform,
-            get_current_platform,
-            get_last_platform,
-            list_platforms,
-
-            // Privacy Engine
-            clear_platform_session,
-            clear_all_sessions,
-            get_csp_for_platform,
-
-            // Debug / DevTools
-            open_devtools,
-            close_devtools,
-            toggle_devtools,
-            is_devtools_open,
-        ])
+        .invoke_handler(specta_builder().invoke_handler())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -323,6 +966,160 @@ pub fn run() {
 mod tests {
     use super::*;
 
+    /// `(file, source)` of every Rust file under `src/`, read at test time so a new
+    /// module is covered without listing it here.
+    fn module_sources() -> Vec<(String, String)> {
+        fn walk(dir: &std::path::Path, sources: &mut Vec<(String, String)>) {
+            let mut entries: Vec<_> = std::fs::read_dir(dir).unwrap().flatten().map(|e| e.path()).collect();
+            entries.sort();
+            for path in entries {
+                if path.is_dir() {
+                    walk(&path, sources);
+                } else if path.extension().is_some_and(|e| e == "rs") && !path.ends_with("lib.rs") {
+                    let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                    sources.push((name, std::fs::read_to_string(&path).unwrap()));
+                }
+            }
+        }
+        let mut sources = Vec::new();
+        walk(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut sources);
+        sources
+    }
+
+    /// Returns `(file, name)` for every `#[tauri::command]` function in the crate.
+    fn defined_commands() -> Vec<(String, String)> {
+        let mut commands = Vec::new();
+        for (file, source) in module_sources() {
+            let mut lines = source.lines().map(str::trim);
+            while let Some(line) = lines.next() {
+                if !line.starts_with("#[tauri::command") {
+                    continue;
+                }
+                // Skip any further attributes / doc comments up to the signature
+                let Some(signature) = lines.find(|l| !l.starts_with("#[") && !l.starts_with("//")) else {
+                    break;
+                };
+                let name = signature
+                    .trim_start_matches("pub ")
+                    .trim_start_matches("async ")
+                    .trim_start_matches("fn ")
+                    .split(|c: char| c == '(' || c == '<')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                commands.push((file.clone(), name));
+            }
+        }
+        commands
+    }
+
+    /// Returns every command listed in `collect_commands![...]` in `specta_builder()`.
+    fn registered_commands() -> Vec<String> {
+        let source = include_str!("lib.rs");
+        let start = source.find("collect_commands![").expect("collect_commands! not found");
+        let list = &source[start..];
+        let end = list.find(']').expect("unterminated collect_commands!");
+        list[..end]
+            .lines()
+            .skip(1)
+            .map(|l| l.split("//").next().unwrap_or_default().trim().trim_end_matches(','))
+            .filter(|l| !l.is_empty())
+            .map(|l| l.rsplit("::").next().unwrap_or(l).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_all_commands_registered() {
+        let registered = registered_commands();
+        let defined = defined_commands();
+        let missing: Vec<String> = defined
+            .iter()
+            .filter(|(_, name)| !registered.contains(name))
+            .map(|(file, name)| format!("{} ({})", name, file))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "commands defined but not registered in specta_builder(): {}",
+            missing.join(", ")
+        );
+        for name in &registered {
+            assert!(
+                defined.iter().any(|(_, d)| d == name),
+                "{} is registered but not defined as a command",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_bindings_are_current() {
+        let path = std::env::temp_dir().join("messenger-desktop-bindings.ts");
+        export_bindings(path.to_str().unwrap()).unwrap();
+        let generated = std::fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+        let _ = std::fs::remove_file(&path);
+        let committed = include_str!("../../src/bindings.ts").replace("\r\n", "\n");
+        assert!(generated == committed, "src/bindings.ts is out of date; run `npm run bindings` and commit it");
+    }
+
+    /// Commands the injected page scripts call through `core.invoke`
+    fn bridge_commands() -> Vec<String> {
+        // Split so this file doesn't match itself
+        let needle = concat!("core.invoke(", "'");
+        let mut sources = module_sources();
+        sources.push(("lib.rs".to_string(), include_str!("lib.rs").to_string()));
+        let mut commands: Vec<String> = sources
+            .iter()
+            .flat_map(|(_, source)| source.split(needle).skip(1))
+            .filter_map(|rest| rest.split('\'').next())
+            .map(str::to_string)
+            .collect();
+        commands.sort();
+        commands.dedup();
+        commands
+    }
+
+    #[test]
+    fn test_remote_capability_grants_only_bridge_commands() {
+        let remote: serde_json::Value =
+            serde_json::from_str(include_str!("../capabilities/remote.json")).unwrap();
+        let granted: Vec<String> = remote["permissions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|p| p.as_str()?.strip_prefix("allow-"))
+            .map(|p| p.replace('-', "_"))
+            .collect();
+        let bridge = bridge_commands();
+        for name in &bridge {
+            assert!(granted.contains(name), "{} is called by a page script but not granted in remote.json", name);
+        }
+        for name in &granted {
+            assert!(bridge.contains(name), "remote.json grants {}, which no page script calls", name);
+        }
+        assert!(!granted.iter().any(|g| g == "panic_wipe" || g == "export_session"));
+        // The local frontend gets everything through the generated set
+        let default: serde_json::Value =
+            serde_json::from_str(include_str!("../capabilities/default.json")).unwrap();
+        assert!(default["permissions"].as_array().unwrap().iter().any(|p| p == "app-commands"));
+        assert!(!remote["permissions"].as_array().unwrap().iter().any(|p| p == "app-commands"));
+    }
+
+    #[test]
+    fn test_devtools_commands_registered() {
+        let registered = registered_commands();
+        for name in ["open_devtools", "close_devtools", "toggle_devtools", "is_devtools_open"] {
+            assert!(registered.iter().any(|r| r == name), "{} is not registered", name);
+        }
+    }
+
+    #[test]
+    fn test_single_main_window() {
+        // The main window is built in `run()`; a config window with the same label
+        // would be created first and make setup fail
+        let config: serde_json::Value = serde_json::from_str(include_str!("../tauri.conf.json")).unwrap();
+        assert_eq!(config["app"]["windows"], serde_json::json!([]));
+    }
+
     #[test]
     fn test_imports_compile() {
         // Test that all imports compile correctly
@@ -0,0 +1,541 @@
+//! Inactivity auto-lock with a PIN.
+//!
+//! After `idle_minutes` without input in the webview the app locks: every app window
+//! is hidden behind the lock screen (`lock.html`, the only window allowed to show),
+//! DevTools are closed and can't be opened, `app-locked` is emitted, and notification
+//! previews are hidden until `unlock_app` gets the right PIN. The page is also blurred,
+//! in case a window is shown by some other path. The locked state is persisted, so
+//! quitting doesn't unlock, and wrong PINs back off exponentially after a few tries.
+//! Only an argon2 hash of the PIN (with a random salt) is stored in `lock.json`.
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::notifications::NotificationService;
+use crate::store;
+
+/// How often the idle timer is checked
+const TICK: Duration = Duration::from_secs(15);
+
+/// Shortest accepted PIN
+const MIN_PIN_LEN: usize = 4;
+
+/// Wrong PINs allowed before unlocking is throttled
+const FREE_ATTEMPTS: u32 = 3;
+
+/// Wait after the first throttled attempt; doubles with each further wrong PIN
+const BASE_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Longest wait between attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// Label of the lock screen window
+pub const LOCK_WINDOW: &str = "lock";
+
+/// Reports input in the page to `report_activity`, at most every 30s
+pub const ACTIVITY_HOOK_JS: &str = r#"
+(function() {
+    if (window.__MD_ACTIVITY_HOOK__) return;
+    window.__MD_ACTIVITY_HOOK__ = true;
+    let last = 0;
+    function activity() {
+        const now = Date.now();
+        if (now - last < 30000) return;
+        last = now;
+        const core = window.__TAURI__ && window.__TAURI__.core;
+        if (core) core.invoke('report_activity').catch(function() {});
+    }
+    ['keydown', 'mousedown', 'mousemove', 'wheel', 'touchstart'].forEach(function(type) {
+        document.addEventListener(type, activity, { capture: true, passive: true });
+    });
+})();
+"#;
+
+const LOCK_OVERLAY_JS: &str = r#"
+(function() {
+    if (document.getElementById('__md_lock__')) return;
+    const style = document.createElement('style');
+    style.id = '__md_lock__';
+    style.textContent = 'html > body { filter: blur(24px) !important; pointer-events: none !important; user-select: none !important; }';
+    document.documentElement.appendChild(style);
+})();
+"#;
+
+const UNLOCK_OVERLAY_JS: &str = r#"
+(function() {
+    const style = document.getElementById('__md_lock__');
+    if (style) style.remove();
+})();
+"#;
+
+/// Persisted lock configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LockConfig {
+    /// Argon2 PHC string; `None` = lock disabled
+    pin_hash: Option<String>,
+    /// Minutes of inactivity before locking; 0 = only lock manually
+    idle_minutes: u32,
+    /// Whether the app is locked, so that restarting it doesn't unlock it
+    #[serde(default)]
+    locked: bool,
+    /// Wrong PINs since the last successful unlock
+    #[serde(default)]
+    failed_attempts: u32,
+    /// When the last wrong PIN was entered, in seconds since the Unix epoch
+    #[serde(default)]
+    last_failure: Option<u64>,
+}
+
+/// Lock state reported to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct LockStatus {
+    pub enabled: bool,
+    pub idle_minutes: u32,
+    pub locked: bool,
+}
+
+/// Hashes a PIN with a fresh random salt
+fn hash_pin(pin: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map(|h| h.to_string())
+        .map_err(|e| format!("Failed to hash PIN: {}", e))
+}
+
+/// Checks a PIN against a stored hash
+fn verify_pin(pin: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .is_ok_and(|parsed| Argon2::default().verify_password(pin.as_bytes(), &parsed).is_ok())
+}
+
+/// Whether the app has been idle long enough to lock
+fn is_idle(idle_for: Duration, idle_minutes: u32) -> bool {
+    idle_minutes > 0 && idle_for >= Duration::from_secs(u64::from(idle_minutes) * 60)
+}
+
+/// How long to wait before the next attempt after `failed_attempts` wrong PINs,
+/// the last one `since_failure` ago; `None` if a PIN may be tried now
+fn retry_after(failed_attempts: u32, since_failure: Duration) -> Option<Duration> {
+    let throttled = failed_attempts.checked_sub(FREE_ATTEMPTS)?;
+    let backoff = BASE_BACKOFF
+        .checked_mul(2u32.saturating_pow(throttled))
+        .map_or(MAX_BACKOFF, |b| b.min(MAX_BACKOFF));
+    backoff.checked_sub(since_failure).filter(|wait| !wait.is_zero())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+/// Manages the app lock
+pub struct LockManager {
+    config: Mutex<LockConfig>,
+    store_path: PathBuf,
+    last_activity: Mutex<Instant>,
+    /// Windows hidden by the lock, shown again on unlock
+    hidden: Mutex<Vec<String>>,
+}
+
+impl LockManager {
+    /// Creates a new LockManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("lock.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: Mutex::new(config),
+            store_path,
+            last_activity: Mutex::new(Instant::now()),
+            hidden: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Current lock state
+    pub fn status(&self) -> Result<LockStatus, String> {
+        let config = self.config.lock().map_err(|e| e.to_string())?;
+        Ok(LockStatus {
+            enabled: config.pin_hash.is_some(),
+            idle_minutes: config.idle_minutes,
+            locked: config.locked,
+        })
+    }
+
+    /// Whether the app is locked
+    pub fn is_locked(&self) -> bool {
+        self.config.lock().map(|c| c.locked).unwrap_or(true)
+    }
+
+    /// Persists the configuration to disk
+    fn persist(&self, config: &LockConfig) -> Result<(), String> {
+        store::save(&self.store_path, config).map_err(|e| format!("Failed to save lock config: {}", e))
+    }
+
+    /// Sets (or, with `None`, removes) the PIN and the idle timeout, and persists them
+    pub fn configure(&self, pin: Option<&str>, idle_minutes: u32) -> Result<(), String> {
+        let pin_hash = match pin {
+            Some(pin) if pin.chars().count() < MIN_PIN_LEN => {
+                return Err(format!("PIN must be at least {} characters", MIN_PIN_LEN));
+            }
+            Some(pin) => Some(hash_pin(pin)?),
+            None => None,
+        };
+        let config = LockConfig {
+            pin_hash,
+            idle_minutes,
+            ..LockConfig::default()
+        };
+        self.persist(&config)?;
+        *self.config.lock().map_err(|e| e.to_string())? = config;
+        self.touch();
+        Ok(())
+    }
+
+    /// How long since the user last used the app
+    pub fn idle_for(&self) -> Duration {
+        self.last_activity.lock().map(|l| l.elapsed()).unwrap_or_default()
+    }
+
+    /// Records user activity, restarting the idle timer
+    pub fn touch(&self) {
+        if let Ok(mut last) = self.last_activity.lock() {
+            *last = Instant::now();
+        }
+    }
+
+    /// Locks, if a PIN is set. Returns whether the app went from unlocked to locked.
+    fn lock(&self) -> Result<bool, String> {
+        let mut config = self.config.lock().map_err(|e| e.to_string())?;
+        if config.pin_hash.is_none() {
+            return Err("No PIN set".to_string());
+        }
+        if config.locked {
+            return Ok(false);
+        }
+        config.locked = true;
+        self.persist(&config)?;
+        Ok(true)
+    }
+
+    /// Unlocks if `pin` is right. Returns whether it was; fails without checking
+    /// the PIN while wrong ones are being throttled.
+    fn unlock(&self, pin: &str) -> Result<bool, String> {
+        let mut config = self.config.lock().map_err(|e| e.to_string())?;
+        let Some(hash) = config.pin_hash.clone() else {
+            return Ok(true);
+        };
+        let since_failure = Duration::from_secs(unix_now().saturating_sub(config.last_failure.unwrap_or_default()));
+        if let Some(wait) = retry_after(config.failed_attempts, since_failure) {
+            return Err(format!("Too many wrong PINs, try again in {} seconds", wait.as_secs().max(1)));
+        }
+        if !verify_pin(pin, &hash) {
+            config.failed_attempts += 1;
+            config.last_failure = Some(unix_now());
+            self.persist(&config)?;
+            return Ok(false);
+        }
+        config.locked = false;
+        config.failed_attempts = 0;
+        config.last_failure = None;
+        self.persist(&config)?;
+        self.touch();
+        Ok(true)
+    }
+
+    /// Records a window the lock hid
+    fn remember_hidden(&self, label: &str) {
+        if let Ok(mut hidden) = self.hidden.lock() {
+            if !hidden.iter().any(|l| l == label) {
+                hidden.push(label.to_string());
+            }
+        }
+    }
+
+    /// The windows to show again on unlock
+    fn take_hidden(&self) -> Vec<String> {
+        self.hidden.lock().map(|mut h| std::mem::take(&mut *h)).unwrap_or_default()
+    }
+
+    /// Whether the idle timeout has passed while unlocked
+    fn idle_lock_due(&self) -> bool {
+        let Ok(config) = self.config.lock() else {
+            return false;
+        };
+        config.pin_hash.is_some() && !config.locked && is_idle(self.idle_for(), config.idle_minutes)
+    }
+}
+
+fn main_window(app: &AppHandle) -> Option<WebviewWindow> {
+    app.get_webview_window("main")
+}
+
+/// Whether the app is locked; DevTools and window showing check this
+pub fn is_locked(app: &AppHandle) -> bool {
+    app.try_state::<LockManager>().is_some_and(|m| m.is_locked())
+}
+
+/// Shows and focuses the lock screen, building it if needed
+fn show_lock_window(app: &AppHandle) -> Result<(), String> {
+    let window = match app.get_webview_window(LOCK_WINDOW) {
+        Some(window) => window,
+        None => WebviewWindowBuilder::new(app, LOCK_WINDOW, WebviewUrl::App("lock.html".into()))
+            .title("Messenger Desktop")
+            .inner_size(360.0, 320.0)
+            .resizable(false)
+            .minimizable(false)
+            .center()
+            .build()
+            .map_err(|e| format!("Failed to open the lock screen: {}", e))?,
+    };
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// Hides an app window while locked, remembering to show it again on unlock
+fn hide_window(app: &AppHandle, window: &WebviewWindow) {
+    if window.is_visible().unwrap_or(true) {
+        app.state::<LockManager>().remember_hidden(window.label());
+    }
+    if window.is_devtools_open() {
+        window.close_devtools();
+    }
+    if let Err(e) = window.hide() {
+        log::warn!("[lock] failed to hide window {}: {}", window.label(), e);
+    }
+}
+
+/// Puts every app window behind the lock screen
+fn cover_windows(app: &AppHandle) -> Result<(), String> {
+    for (label, window) in app.webview_windows() {
+        if label == LOCK_WINDOW {
+            continue;
+        }
+        if label == "main" {
+            window.eval(LOCK_OVERLAY_JS).map_err(|e| e.to_string())?;
+        }
+        hide_window(app, &window);
+    }
+    show_lock_window(app)
+}
+
+/// Locks the app: hides the windows behind the lock screen, hides notification
+/// previews and emits `app-locked`
+async fn engage(app: &AppHandle) -> Result<(), String> {
+    if !app.state::<LockManager>().lock()? {
+        return Ok(());
+    }
+    cover_windows(app)?;
+    app.state::<NotificationService>().set_previews_suppressed(true).await;
+    log::info!("[lock] app locked");
+    app.emit("app-locked", ()).map_err(|e| e.to_string())
+}
+
+/// Setup hook: an app quit while locked starts locked. The main window is built
+/// hidden in that case; `show_main` is whether it would have been shown.
+pub fn restore(app: &AppHandle, show_main: bool) {
+    let manager = app.state::<LockManager>();
+    if !manager.is_locked() {
+        return;
+    }
+    if show_main {
+        manager.remember_hidden("main");
+    }
+    if let Err(e) = cover_windows(app) {
+        log::warn!("[lock] failed to restore the lock: {}", e);
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        app.state::<NotificationService>().set_previews_suppressed(true).await;
+    });
+    log::info!("[lock] app started locked");
+}
+
+/// Window-event hook: while locked, an app window that gets focused (shown from
+/// the tray, a notification, a deep link...) is hidden again behind the lock screen
+pub fn on_focus_changed(window: &tauri::Window, focused: bool) {
+    let app = window.app_handle();
+    if !focused || window.label() == LOCK_WINDOW || !is_locked(app) {
+        return;
+    }
+    if let Some(window) = app.get_webview_window(window.label()) {
+        hide_window(app, &window);
+    }
+    // Window events run on the main thread, where building the lock screen would
+    // deadlock on Windows (WebView2)
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = show_lock_window(&app) {
+            log::warn!("[lock] {}", e);
+        }
+    });
+}
+
+/// Page-load hook: a locked app stays blurred across navigations
+pub fn on_page_load(window: &WebviewWindow) {
+    let locked = window
+        .app_handle()
+        .try_state::<LockManager>()
+        .and_then(|m| m.status().ok())
+        .is_some_and(|s| s.locked);
+    if locked {
+        let _ = window.eval(LOCK_OVERLAY_JS);
+    }
+}
+
+/// Starts the background task that locks the app once it has been idle too long
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK).await;
+            if app.state::<LockManager>().idle_lock_due() {
+                if let Err(e) = engage(&app).await {
+                    log::warn!("[lock] failed to lock: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Tauri command to set the PIN and idle timeout (minutes, 0 = manual only).
+/// Passing no PIN turns the lock off.
+#[tauri::command]
+#[specta::specta]
+pub fn set_app_lock(
+    pin: Option<String>,
+    idle_minutes: u32,
+    manager: tauri::State<'_, LockManager>,
+) -> Result<(), String> {
+    if manager.status()?.locked {
+        return Err("Unlock the app before changing the lock".to_string());
+    }
+    manager.configure(pin.as_deref().filter(|p| !p.is_empty()), idle_minutes)
+}
+
+/// Tauri command to lock the app immediately
+#[tauri::command]
+#[specta::specta]
+pub async fn lock_app_now(app: AppHandle) -> Result<(), String> {
+    engage(&app).await
+}
+
+/// Tauri command to unlock the app. Returns `false` for a wrong PIN.
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_app(pin: String, app: AppHandle) -> Result<bool, String> {
+    let manager = app.state::<LockManager>();
+    if !manager.unlock(&pin)? {
+        log::warn!("[lock] wrong PIN");
+        return Ok(false);
+    }
+    if let Some(window) = main_window(&app) {
+        window.eval(UNLOCK_OVERLAY_JS).map_err(|e| e.to_string())?;
+    }
+    for label in manager.take_hidden() {
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+    if let Some(lock) = app.get_webview_window(LOCK_WINDOW) {
+        let _ = lock.close();
+    }
+    app.state::<NotificationService>().set_previews_suppressed(false).await;
+    log::info!("[lock] app unlocked");
+    app.emit("app-unlocked", ()).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Tauri command called by the injected activity hook
+#[tauri::command]
+#[specta::specta]
+pub fn report_activity(manager: tauri::State<'_, LockManager>) {
+    manager.touch();
+}
+
+/// Tauri command to get the lock state
+#[tauri::command]
+#[specta::specta]
+pub fn get_app_lock_status(manager: tauri::State<'_, LockManager>) -> Result<LockStatus, String> {
+    manager.status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_hash_verification() {
+        let hash = hash_pin("2468").unwrap();
+        assert!(!hash.contains("2468"));
+        assert!(verify_pin("2468", &hash));
+        assert!(!verify_pin("1357", &hash));
+        assert!(!verify_pin("2468", "not a hash"));
+        // Salted: the same PIN hashes differently each time
+        assert_ne!(hash, hash_pin("2468").unwrap());
+    }
+
+    #[test]
+    fn test_idle_trigger() {
+        assert!(!is_idle(Duration::from_secs(4 * 60), 5));
+        assert!(is_idle(Duration::from_secs(5 * 60), 5));
+        // 0 = manual lock only
+        assert!(!is_idle(Duration::from_secs(24 * 60 * 60), 0));
+    }
+
+    #[test]
+    fn test_unlock_backoff() {
+        // The first wrong PINs are free
+        assert_eq!(retry_after(0, Duration::ZERO), None);
+        assert_eq!(retry_after(FREE_ATTEMPTS - 1, Duration::ZERO), None);
+        // Then the wait doubles with each one, up to the cap
+        assert_eq!(retry_after(FREE_ATTEMPTS, Duration::ZERO), Some(BASE_BACKOFF));
+        assert_eq!(retry_after(FREE_ATTEMPTS + 1, Duration::ZERO), Some(BASE_BACKOFF * 2));
+        assert_eq!(retry_after(FREE_ATTEMPTS + 40, Duration::ZERO), Some(MAX_BACKOFF));
+        // Time since the last wrong PIN counts towards it
+        assert_eq!(retry_after(FREE_ATTEMPTS, Duration::from_secs(10)), Some(BASE_BACKOFF - Duration::from_secs(10)));
+        assert_eq!(retry_after(FREE_ATTEMPTS, BASE_BACKOFF), None);
+    }
+
+    #[test]
+    fn test_lock_and_unlock() {
+        let tmp = std::env::temp_dir().join("test-lock");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = LockManager::new(&tmp);
+        assert!(manager.lock().is_err());
+        assert!(manager.configure(Some("12"), 5).is_err());
+
+        manager.configure(Some("2468"), 5).unwrap();
+        assert!(!std::fs::read_to_string(tmp.join("lock.json")).unwrap().contains("2468"));
+        assert!(manager.lock().unwrap());
+        assert!(!manager.unlock("0000").unwrap());
+        assert!(manager.status().unwrap().locked);
+
+        // Restarting doesn't unlock, nor reset the wrong PIN count
+        let manager = LockManager::new(&tmp);
+        assert!(manager.status().unwrap().locked);
+        for _ in 1..FREE_ATTEMPTS {
+            assert!(!manager.unlock("0000").unwrap());
+        }
+        assert!(LockManager::new(&tmp).unlock("2468").unwrap_err().contains("try again"));
+        assert!(manager.status().unwrap().locked);
+
+        // Once the wait is over, the right PIN unlocks and resets the count
+        manager.config.lock().unwrap().last_failure = Some(unix_now() - BASE_BACKOFF.as_secs());
+        assert!(manager.unlock("2468").unwrap());
+        assert!(!manager.status().unwrap().locked);
+        assert_eq!(manager.config.lock().unwrap().failed_attempts, 0);
+
+        // The PIN survives a restart
+        let reloaded = LockManager::new(&tmp);
+        assert!(reloaded.status().unwrap().enabled);
+        assert!(!reloaded.status().unwrap().locked);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
@@ -4,27 +4,14 @@
 )]
 
 fn main() {
-    // 1. Init tracing subscriber — reads RUST_LOG env var.
-    //    Default: debug for our crate, info for everything else.
+    // Init tracing (stdout + log file) and bridge `log` macros into it.
+    // Reads RUST_LOG; default: debug for our crate, info for everything else.
     //    Examples:
     //      RUST_LOG=debug          → everything at debug+
     //      RUST_LOG=messenger_desktop=trace → just our crate at trace
     //      RUST_LOG=warn           → only warnings/errors
-    use tracing_subscriber::{fmt, EnvFilter};
-    fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| EnvFilter::new("messenger_desktop=debug,info")),
-        )
-        .with_target(true)
-        .with_thread_ids(true)
-        .with_line_number(true)
-        .pretty()
-        .init();
-
-    // 2. Bridge `log` crate macros (log::info!, log::warn!, etc.) into tracing.
-    //    This makes all deps that use `log` appear in our tracing output.
-    tracing_log::LogTracer::init().ok();
+    // The level can be changed at runtime with the `set_log_level` command.
+    messenger_desktop::init_logging();
 
     tracing::info!("Messenger Desktop starting up");
 
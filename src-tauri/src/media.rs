@@ -2,23 +2,118 @@
 //! Manages file uploads, downloads, media permissions, and previews.
 
 use tauri::AppHandle;
-use tauri::Manager;
 use serde::{Serialize, Deserialize};
+use specta::Type;
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::io::Write;
+use std::time::Duration;
 use anyhow::{Context, Result};
 use uuid::Uuid;
 
+/// Attempts at writing a media file before giving up
+const SAVE_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry of a media save; doubled for each one after
+const SAVE_RETRY_BASE: Duration = Duration::from_millis(100);
+
+/// OS error codes for a full disk (or quota)
+#[cfg(target_os = "windows")]
+const DISK_FULL_CODES: &[i32] = &[39, 112]; // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+#[cfg(not(target_os = "windows"))]
+const DISK_FULL_CODES: &[i32] = &[28]; // ENOSPC
+
+/// OS error codes for a path or file name over the limit
+#[cfg(target_os = "windows")]
+const PATH_TOO_LONG_CODES: &[i32] = &[206]; // ERROR_FILENAME_EXCED_RANGE
+#[cfg(target_os = "macos")]
+const PATH_TOO_LONG_CODES: &[i32] = &[63]; // ENAMETOOLONG
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PATH_TOO_LONG_CODES: &[i32] = &[36]; // ENAMETOOLONG
+
+/// Why a media file couldn't be saved, for the frontend to advise the user
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MediaSaveError {
+    /// The disk (or the user's quota) is full
+    DiskFull { message: String },
+    /// The media directory isn't writable, or the file is locked (e.g. by antivirus)
+    PermissionDenied { message: String },
+    /// The path is longer than the file system allows
+    PathTooLong { message: String },
+    /// Any other IO error
+    Failed { message: String },
+}
+
+impl MediaSaveError {
+    /// Classifies an IO error from saving
+    fn classify(error: &std::io::Error) -> Self {
+        let message = error.to_string();
+        match error.raw_os_error() {
+            Some(code) if DISK_FULL_CODES.contains(&code) => MediaSaveError::DiskFull { message },
+            Some(code) if PATH_TOO_LONG_CODES.contains(&code) => MediaSaveError::PathTooLong { message },
+            _ if error.kind() == std::io::ErrorKind::PermissionDenied => MediaSaveError::PermissionDenied { message },
+            _ => MediaSaveError::Failed { message },
+        }
+    }
+
+    /// A long path stays too long; space and locks may free up
+    fn is_retryable(&self) -> bool {
+        !matches!(self, MediaSaveError::PathTooLong { .. })
+    }
+}
+
+impl std::fmt::Display for MediaSaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MediaSaveError::DiskFull { message } => write!(f, "Not enough disk space to save media: {}", message),
+            MediaSaveError::PermissionDenied { message } => write!(f, "Not allowed to save media: {}", message),
+            MediaSaveError::PathTooLong { message } => write!(f, "Media path is too long: {}", message),
+            MediaSaveError::Failed { message } => write!(f, "Failed to save media: {}", message),
+        }
+    }
+}
+
+/// Writes `data` to `path` with `write`, retrying with backoff from `retry_base`.
+/// A failed attempt's partial file is removed, so a failure never leaves a
+/// corrupt file behind.
+fn write_with_retry<W>(path: &Path, data: &[u8], retry_base: Duration, mut write: W) -> std::result::Result<(), MediaSaveError>
+where
+    W: FnMut(&Path, &[u8]) -> std::io::Result<()>,
+{
+    let mut attempt = 1;
+    loop {
+        let error = match write(path, data) {
+            Ok(()) => return Ok(()),
+            Err(e) => MediaSaveError::classify(&e),
+        };
+        let _ = fs::remove_file(path);
+        if attempt >= SAVE_ATTEMPTS || !error.is_retryable() {
+            log::error!("Giving up saving {} after {} attempt(s): {}", path.display(), attempt, error);
+            return Err(error);
+        }
+        log::warn!("Saving {} failed (attempt {}), retrying: {}", path.display(), attempt, error);
+        std::thread::sleep(retry_base * 2u32.pow(attempt - 1));
+        attempt += 1;
+    }
+}
+
+/// Creates `path` and writes `data` to it
+fn write_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(data)?;
+    file.sync_all()
+}
+
 /// Media permissions state.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct MediaPermissions {
     pub camera: bool,
     pub microphone: bool,
 }
 
 /// Media file metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct MediaFile {
     pub id: String,
     pub name: String,
@@ -40,9 +135,8 @@ pub struct MediaManager {
 
 impl MediaManager {
     /// Create a new MediaManager.
-    #[allow(dead_code)]
     pub fn new(app: &AppHandle) -> Result<Self> {
-        let media_dir = app.path().app_data_dir()
+        let media_dir = crate::store::data_dir(app)
             .context("Failed to resolve app data directory")?
             .join("media");
         
@@ -89,8 +183,9 @@ impl MediaManager {
         self.permissions.clone()
     }
     
-    /// Save a media file to the app's media directory.
-    pub fn save_media_file(&self, name: &str, data: &[u8]) -> Result<MediaFile> {
+    /// Save a media file to the app's media directory. Transient IO errors are
+    /// retried a few times; a file that can't be written is not left behind.
+    pub fn save_media_file(&self, name: &str, data: &[u8]) -> std::result::Result<MediaFile, MediaSaveError> {
         let ext = Path::new(name)
             .extension()
             .and_then(|s| s.to_str())
@@ -100,14 +195,10 @@ impl MediaManager {
         let file_name = format!("{}.{}", id, ext);
         let file_path = self.media_dir.join(&file_name);
         
-        let mut file = fs::File::create(&file_path)
-            .context("Failed to create media file")?;
-        
-        file.write_all(data)
-            .context("Failed to write media file")?;
+        write_with_retry(&file_path, data, SAVE_RETRY_BASE, write_file)?;
         
         let mime_type = mime_guess::from_path(&file_path).first_or_octet_stream();
-        let size = fs::metadata(&file_path)?.len();
+        let size = data.len() as u64;
         
         Ok(MediaFile {
             id,
@@ -194,13 +285,14 @@ impl MediaManager {
 
 /// Tauri command: Get media permissions.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn get_media_permissions(state: tauri::State<MediaManager>) -> MediaPermissions {
-    state.get_permissions()
+#[specta::specta]
+pub async fn get_media_permissions(state: tauri::State<'_, tokio::sync::Mutex<MediaManager>>) -> Result<MediaPermissions, String> {
+    Ok(state.lock().await.get_permissions())
 }
 
 /// Tauri command: Grant media permission.
 #[tauri::command]
+#[specta::specta]
 pub async fn grant_media_permission(state: tauri::State<'_, tokio::sync::Mutex<MediaManager>>, permission_type: String) -> Result<bool, String> {
     match permission_type.as_str() {
         "camera" => Ok(state.lock().await.request_camera()),
@@ -214,37 +306,36 @@ pub async fn grant_media_permission(state: tauri::State<'_, tokio::sync::Mutex<M
 
 /// Tauri command: Save a media file.
 #[tauri::command]
-#[allow(dead_code)]
+#[specta::specta]
 pub async fn save_media_file(
-    state: tauri::State<'_, MediaManager>,
+    state: tauri::State<'_, tokio::sync::Mutex<MediaManager>>,
     name: String,
     data: Vec<u8>,
-) -> Result<MediaFile, String> {
-    state.save_media_file(&name, &data)
-        .map_err(|e| e.to_string())
+) -> std::result::Result<MediaFile, MediaSaveError> {
+    state.lock().await.save_media_file(&name, &data)
 }
 
 /// Tauri command: Get a media file by ID.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn get_media_file_command(state: tauri::State<MediaManager>, id: String) -> Result<MediaFile, String> {
-    state.get_media_file(&id)
+#[specta::specta]
+pub async fn get_media_file_command(state: tauri::State<'_, tokio::sync::Mutex<MediaManager>>, id: String) -> Result<MediaFile, String> {
+    state.lock().await.get_media_file(&id)
         .map_err(|e| e.to_string())
 }
 
 /// Tauri command: Generate a preview for a media file.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn generate_preview_command(state: tauri::State<MediaManager>, id: String) -> Result<PathBuf, String> {
-    state.generate_preview(&id)
+#[specta::specta]
+pub async fn generate_preview_command(state: tauri::State<'_, tokio::sync::Mutex<MediaManager>>, id: String) -> Result<PathBuf, String> {
+    state.lock().await.generate_preview(&id)
         .map_err(|e| e.to_string())
 }
 
 /// Tauri command: Delete a media file by ID.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn delete_media_file_command(state: tauri::State<MediaManager>, id: String) -> Result<(), String> {
-    state.delete_media_file(&id)
+#[specta::specta]
+pub async fn delete_media_file_command(state: tauri::State<'_, tokio::sync::Mutex<MediaManager>>, id: String) -> Result<(), String> {
+    state.lock().await.delete_media_file(&id)
         .map_err(|e| e.to_string())
 }
 
@@ -291,6 +382,63 @@ mod tests {
         assert_eq!(file.id, deserialized.id);
     }
 
+    #[test]
+    fn test_save_retries_and_cleans_up() {
+        let dir = std::env::temp_dir().join("test-media-save-retry");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("partial.jpg");
+
+        // Writes half the file, then the disk fills up
+        let mut attempts = 0;
+        let result = write_with_retry(&path, b"media bytes", Duration::ZERO, |path, data| {
+            attempts += 1;
+            fs::write(path, &data[..data.len() / 2])?;
+            Err(std::io::Error::from_raw_os_error(DISK_FULL_CODES[0]))
+        });
+        assert!(matches!(result, Err(MediaSaveError::DiskFull { .. })));
+        assert_eq!(attempts, SAVE_ATTEMPTS);
+        assert!(!path.exists(), "partial file left behind");
+
+        // A lock that clears on the second attempt
+        let mut attempts = 0;
+        let result = write_with_retry(&path, b"media bytes", Duration::ZERO, |path, data| {
+            attempts += 1;
+            if attempts == 1 {
+                return Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+            }
+            write_file(path, data)
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(attempts, 2);
+        assert_eq!(fs::read(&path).unwrap(), b"media bytes");
+
+        // A long path isn't retried
+        let mut attempts = 0;
+        let result = write_with_retry(&path, b"x", Duration::ZERO, |_, _| {
+            attempts += 1;
+            Err(std::io::Error::from_raw_os_error(PATH_TOO_LONG_CODES[0]))
+        });
+        assert!(matches!(result, Err(MediaSaveError::PathTooLong { .. })));
+        assert_eq!(attempts, 1);
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_save_error_classification() {
+        let classify = |e: std::io::Error| MediaSaveError::classify(&e);
+        assert!(matches!(classify(std::io::Error::from_raw_os_error(DISK_FULL_CODES[0])), MediaSaveError::DiskFull { .. }));
+        assert!(matches!(classify(std::io::Error::from_raw_os_error(PATH_TOO_LONG_CODES[0])), MediaSaveError::PathTooLong { .. }));
+        assert!(matches!(
+            classify(std::io::Error::from(std::io::ErrorKind::PermissionDenied)),
+            MediaSaveError::PermissionDenied { .. }
+        ));
+        assert!(matches!(classify(std::io::Error::other("boom")), MediaSaveError::Failed { .. }));
+
+        let json = serde_json::to_value(MediaSaveError::DiskFull { message: "full".to_string() }).unwrap();
+        assert_eq!(json["kind"], "disk_full");
+    }
+
     #[test]
     fn test_theme_manager_get_themes() {
         // ThemeManager lives in a separate module - skip this test
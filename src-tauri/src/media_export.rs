@@ -0,0 +1,411 @@
+//! Bulk export of a conversation's images and videos.
+//!
+//! `export_conversation_media` injects a collector into the main webview that
+//! gathers the media URLs of the rendered conversation and reports them back
+//! through `report_conversation_media`. Each URL is then downloaded with the
+//! shared HTTP client, sending the webview's cookies for it so CDN URLs that need
+//! the platform session still work, and written to the destination directory.
+//! A failed download is recorded in the manifest and the export carries on.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Url, WebviewWindow};
+use tokio::sync::oneshot;
+
+use crate::notifications::NotificationService;
+
+/// How long the collector has to report the page's media
+const COLLECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest a single media download may take
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Images smaller than this (px, either side) are avatars, emoji and reactions
+const MIN_IMAGE_SIZE: u32 = 100;
+
+/// Longest file name written, in characters, before the extension
+const MAX_STEM_LEN: usize = 80;
+
+/// A downloaded media file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct SavedMedia {
+    pub url: String,
+    /// File name within the export directory
+    pub file_name: String,
+    pub bytes: u64,
+}
+
+/// A media file that couldn't be exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct FailedMedia {
+    pub url: String,
+    pub error: String,
+}
+
+/// What an export wrote, and what it couldn't
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct MediaManifest {
+    pub conversation_id: String,
+    pub dest_dir: String,
+    pub saved: Vec<SavedMedia>,
+    pub failed: Vec<FailedMedia>,
+}
+
+impl MediaManifest {
+    fn new(conversation_id: &str, dest_dir: &Path) -> Self {
+        Self {
+            conversation_id: conversation_id.to_string(),
+            dest_dir: dest_dir.to_string_lossy().into_owned(),
+            saved: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+
+    /// How many files were attempted
+    fn total(&self) -> usize {
+        self.saved.len() + self.failed.len()
+    }
+}
+
+/// Payload emitted with `media-export-progress` after each file
+#[derive(Serialize, Clone, Debug)]
+pub struct MediaExportProgressPayload {
+    pub conversation_id: String,
+    pub done: u32,
+    pub total: u32,
+    /// The file just written; `None` if it failed
+    pub file_name: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Collector requests waiting for the page's report, by request id
+pub struct MediaExport {
+    pending: Mutex<HashMap<String, oneshot::Sender<Option<Vec<String>>>>>,
+}
+
+impl MediaExport {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for MediaExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the collector: reports the http(s) media URLs of the rendered
+/// conversation, or `null` when `conversation_id` isn't the one open
+fn collector_script(request_id: &str, conversation_id: &str) -> String {
+    let request_id = serde_json::to_string(request_id).unwrap_or_default();
+    let conversation_id = serde_json::to_string(conversation_id).unwrap_or_default();
+    format!(
+        r#"
+        (function() {{
+            const core = window.__TAURI__ && window.__TAURI__.core;
+            if (!core) return;
+            const REQUEST = {request_id};
+            const CONVERSATION = {conversation_id};
+            let urls = null;
+            if (location.pathname.indexOf(CONVERSATION) !== -1) {{
+                const root = document.querySelector('[role="main"]') || document.body;
+                const found = [];
+                root.querySelectorAll('img[src]').forEach(function(img) {{
+                    if (img.naturalWidth >= {min} && img.naturalHeight >= {min}) found.push(img.currentSrc || img.src);
+                }});
+                root.querySelectorAll('video[src], video source[src]').forEach(function(el) {{
+                    found.push(el.src);
+                }});
+                urls = found.filter(function(u) {{ return /^https?:\/\//.test(u); }});
+            }}
+            core.invoke('report_conversation_media', {{ requestId: REQUEST, urls: urls }})
+                .catch(function(e) {{ console.warn('[messenger-desktop] media report failed:', e); }});
+        }})();
+        "#,
+        request_id = request_id,
+        conversation_id = conversation_id,
+        min = MIN_IMAGE_SIZE,
+    )
+}
+
+/// Asks the page for the conversation's media URLs, in page order without repeats
+async fn collect(window: &WebviewWindow, export: &MediaExport, conversation_id: &str) -> Result<Vec<String>, String> {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let (sender, receiver) = oneshot::channel();
+    export
+        .pending
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(request_id.clone(), sender);
+
+    let reported = match window.eval(&collector_script(&request_id, conversation_id)) {
+        Ok(()) => tokio::time::timeout(COLLECT_TIMEOUT, receiver).await.ok().and_then(Result::ok),
+        Err(e) => {
+            log::warn!("[media_export] failed to inject the collector: {}", e);
+            None
+        }
+    };
+    if let Ok(mut pending) = export.pending.lock() {
+        pending.remove(&request_id);
+    }
+
+    match reported {
+        Some(Some(urls)) => {
+            let mut seen = HashSet::new();
+            Ok(urls.into_iter().filter(|u| seen.insert(u.clone())).collect())
+        }
+        Some(None) => Err(format!("Conversation {} is not open", conversation_id)),
+        None => Err("The page did not report its media in time".to_string()),
+    }
+}
+
+/// The webview's cookies for `url` as a `Cookie` header, so session-bound CDN
+/// URLs download like they do in the page
+fn cookie_header(window: &WebviewWindow, url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let cookies = window.cookies_for_url(url).ok()?;
+    if cookies.is_empty() {
+        return None;
+    }
+    Some(
+        cookies
+            .iter()
+            .map(|c| format!("{}={}", c.name(), c.value()))
+            .collect::<Vec<_>>()
+            .join("; "),
+    )
+}
+
+/// Downloads `url`, returning the body and its content type
+async fn download(client: &reqwest::Client, url: &str, cookies: Option<String>) -> Result<(Vec<u8>, Option<String>), String> {
+    let mut request = client.get(url).timeout(DOWNLOAD_TIMEOUT);
+    if let Some(cookies) = cookies {
+        request = request.header(reqwest::header::COOKIE, cookies);
+    }
+    let response = request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+    Ok((bytes.to_vec(), content_type))
+}
+
+/// Extension for a content type, for URLs whose path has none
+fn extension_for(content_type: Option<&str>) -> &'static str {
+    match content_type.and_then(|t| t.split(';').next()).map(str::trim) {
+        Some("image/jpeg") => "jpg",
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some("video/mp4") => "mp4",
+        Some("video/webm") => "webm",
+        Some("video/quicktime") => "mov",
+        _ => "bin",
+    }
+}
+
+/// A file name for the media at `url`: the last path segment with anything
+/// unsafe in a file name replaced, or `media-<n>` when there is none
+pub fn file_name_for(url: &str, content_type: Option<&str>, index: usize) -> String {
+    let segment = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .unwrap_or_default();
+    let sanitized: String = segment
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let sanitized = sanitized.trim_matches('.');
+    let (stem, ext) = match sanitized.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && (1..=5).contains(&ext.len()) => (stem, ext.to_ascii_lowercase()),
+        _ => (sanitized, extension_for(content_type).to_string()),
+    };
+    let stem: String = stem.chars().take(MAX_STEM_LEN).collect();
+    if stem.is_empty() {
+        format!("media-{}.{}", index + 1, ext)
+    } else {
+        format!("{}.{}", stem, ext)
+    }
+}
+
+/// `name`, or `name (n).ext` with the smallest `n` not in `taken`
+pub fn unique_file_name(name: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) => (stem, format!(".{}", ext)),
+        None => (name, String::new()),
+    };
+    (1..)
+        .map(|n| format!("{} ({}){}", stem, n, ext))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// File names already in `dir`, so an export never overwrites
+fn existing_names(dir: &Path) -> HashSet<String> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Tauri command called by the injected collector with the conversation's media
+/// URLs; `None` if the conversation isn't open
+#[tauri::command]
+#[specta::specta]
+pub fn report_conversation_media(
+    request_id: String,
+    urls: Option<Vec<String>>,
+    export: tauri::State<'_, MediaExport>,
+) -> Result<(), String> {
+    let sender = export.pending.lock().map_err(|e| e.to_string())?.remove(&request_id);
+    if let Some(sender) = sender {
+        let _ = sender.send(urls);
+    }
+    Ok(())
+}
+
+/// Tauri command to save every image and video of the open conversation to
+/// `dest_dir`. Emits `media-export-progress` after each file; files that fail are
+/// listed in the returned manifest instead of aborting the export.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_conversation_media(
+    conversation_id: String,
+    dest_dir: String,
+    app: AppHandle,
+    export: tauri::State<'_, MediaExport>,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<MediaManifest, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let dest = PathBuf::from(&dest_dir);
+    std::fs::create_dir_all(&dest).map_err(|e| format!("Failed to create {}: {}", dest_dir, e))?;
+
+    let urls = collect(&window, &export, &conversation_id).await?;
+    log::info!("[media_export] exporting {} file(s) of {}", urls.len(), conversation_id);
+
+    let client = notification_service.http_client();
+    let mut taken = existing_names(&dest);
+    let mut manifest = MediaManifest::new(&conversation_id, &dest);
+    for (index, url) in urls.iter().enumerate() {
+        let saved = match download(&client, url, cookie_header(&window, url)).await {
+            Ok((bytes, content_type)) => {
+                let file_name = unique_file_name(&file_name_for(url, content_type.as_deref(), index), &taken);
+                std::fs::write(dest.join(&file_name), &bytes)
+                    .map(|()| SavedMedia {
+                        url: url.clone(),
+                        file_name,
+                        bytes: bytes.len() as u64,
+                    })
+                    .map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e),
+        };
+        let (file_name, error) = match saved {
+            Ok(saved) => {
+                taken.insert(saved.file_name.clone());
+                let file_name = saved.file_name.clone();
+                manifest.saved.push(saved);
+                (Some(file_name), None)
+            }
+            Err(error) => {
+                log::warn!("[media_export] failed to export {}: {}", url, error);
+                manifest.failed.push(FailedMedia {
+                    url: url.clone(),
+                    error: error.clone(),
+                });
+                (None, Some(error))
+            }
+        };
+        let _ = app.emit(
+            "media-export-progress",
+            MediaExportProgressPayload {
+                conversation_id: conversation_id.clone(),
+                done: manifest.total() as u32,
+                total: urls.len() as u32,
+                file_name,
+                error,
+            },
+        );
+    }
+
+    log::info!(
+        "[media_export] exported {} file(s), {} failed",
+        manifest.saved.len(),
+        manifest.failed.len()
+    );
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_structure() {
+        let mut manifest = MediaManifest::new("t_1", Path::new("/tmp/export"));
+        manifest.saved.push(SavedMedia {
+            url: "https://cdn.example/a.jpg".to_string(),
+            file_name: "a.jpg".to_string(),
+            bytes: 3,
+        });
+        manifest.failed.push(FailedMedia {
+            url: "https://cdn.example/b.mp4".to_string(),
+            error: "HTTP status client error (403 Forbidden)".to_string(),
+        });
+        assert_eq!(manifest.total(), 2);
+
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["conversation_id"], "t_1");
+        assert_eq!(json["dest_dir"], "/tmp/export");
+        assert_eq!(json["saved"][0]["file_name"], "a.jpg");
+        assert_eq!(json["saved"][0]["bytes"], 3);
+        assert_eq!(json["failed"][0]["url"], "https://cdn.example/b.mp4");
+        let back: MediaManifest = serde_json::from_value(json).unwrap();
+        assert_eq!(back, manifest);
+    }
+
+    #[test]
+    fn test_file_name_deduplication() {
+        assert_eq!(file_name_for("https://cdn.example/v/t1/Photo.JPG?stp=dst&_nc=1", None, 0), "Photo.jpg");
+        assert_eq!(file_name_for("https://cdn.example/media/abc", Some("video/mp4"), 0), "abc.mp4");
+        assert_eq!(file_name_for("https://cdn.example/", Some("image/png"), 4), "media-5.png");
+        assert_eq!(file_name_for("https://cdn.example/a%20b:c.gif", None, 0), "a_20b_c.gif");
+
+        let mut taken = HashSet::new();
+        for expected in ["a.jpg", "a (1).jpg", "a (2).jpg"] {
+            let name = unique_file_name("a.jpg", &taken);
+            assert_eq!(name, expected);
+            taken.insert(name);
+        }
+        assert_eq!(unique_file_name("b.jpg", &taken), "b.jpg");
+        taken.insert("README".to_string());
+        assert_eq!(unique_file_name("README", &taken), "README (1)");
+
+        let script = collector_script("req", "t_1");
+        assert!(script.contains("report_conversation_media"));
+        assert!(script.contains("\"t_1\""));
+    }
+}
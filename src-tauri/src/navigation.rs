@@ -0,0 +1,461 @@
+//! Navigation guard for the main webview.
+//!
+//! Keeps the app webview on the active platform and hands cross-origin links
+//! off to the system browser instead of navigating the whole app away. Links to
+//! domains on the user's in-app allowlist open in a sandboxed popup window instead.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_shell::ShellExt;
+
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::store;
+
+/// File the in-app domain allowlist is kept in, under the app data dir
+const INAPP_DOMAINS_FILE: &str = "inapp_domains.json";
+
+/// Window label prefix for in-app link popups
+const POPUP_PREFIX: &str = "inapp-link-";
+
+/// Host the bundled frontend (platform selector, settings) is served from on
+/// Windows, over `http(s)://`. Elsewhere it is served over `tauri://localhost`.
+const APP_HOST: &str = "tauri.localhost";
+
+/// The Vite dev server (`server.port` in `vite.config.ts`). Only debug builds load
+/// the frontend from it; a release build treats it like any other localhost link.
+#[cfg(debug_assertions)]
+const DEV_SERVER: (&str, u16) = ("localhost", 5173);
+
+/// Outcome of checking a navigation request against the active platform
+#[derive(Debug, Clone, PartialEq)]
+pub enum NavigationDecision {
+    /// Let the webview load the URL
+    Allow,
+    /// Cancel the in-app navigation and open the URL in the system browser
+    OpenExternal,
+    /// Cancel the in-app navigation and open the URL in a popup window
+    OpenInApp(Url),
+    /// Cancel the navigation outright
+    Deny,
+}
+
+/// Payload emitted with `external-link-opened`
+#[derive(Serialize, Clone, Debug)]
+pub struct ExternalLinkPayload {
+    pub url: String,
+}
+
+/// Returns `true` if `host` is `domain` or one of its subdomains
+fn host_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+/// Returns `true` if the URL is the bundled frontend (or, in debug builds, the
+/// dev server serving it)
+fn is_app_url(url: &Url) -> bool {
+    match (url.scheme(), url.host_str()) {
+        ("tauri", Some(host)) => host == "localhost",
+        ("http" | "https", Some(host)) if host == APP_HOST => true,
+        #[cfg(debug_assertions)]
+        ("http", Some(host)) => host == DEV_SERVER.0 && url.port() == Some(DEV_SERVER.1),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the URL is one of the platforms' outbound link shims
+/// (e.g. `l.messenger.com/l.php?u=...`), which only ever redirect off-site.
+fn is_link_shim(url: &Url) -> bool {
+    url.host_str().is_some_and(|h| h.starts_with("l.")) && url.path() == "/l.php"
+}
+
+/// Where a link shim redirects to (its `u` parameter)
+fn shim_target(url: &Url) -> Option<Url> {
+    if !is_link_shim(url) {
+        return None;
+    }
+    let (_, target) = url.query_pairs().find(|(k, _)| k == "u")?;
+    Url::parse(&target).ok()
+}
+
+/// Returns `true` if `url` is an http(s) link to an allowlisted domain or one of
+/// its subdomains
+pub fn allowlisted(url: &Url, domains: &[String]) -> bool {
+    matches!(url.scheme(), "http" | "https")
+        && url
+            .host_str()
+            .is_some_and(|host| domains.iter().any(|d| host_matches(&host.to_lowercase(), d)))
+}
+
+/// Turns user input ("Docs.Google.com", "*.imgur.com", "https://cdn.x.com/a") into
+/// the bare domain the allowlist stores
+pub fn normalize_domain(input: &str) -> Result<String, String> {
+    let input = input.trim().to_lowercase();
+    let host = match Url::parse(&input) {
+        Ok(url) if url.host_str().is_some() => url.host_str().unwrap_or_default().to_string(),
+        _ => input.split(['/', '?', '#']).next().unwrap_or_default().to_string(),
+    };
+    let domain = host.trim_start_matches("*.").trim_matches('.');
+    let valid = domain.contains('.')
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+    if !valid {
+        return Err(format!("Not a domain: {}", input));
+    }
+    Ok(domain.to_string())
+}
+
+/// Decides whether a navigation may proceed in-app, sending links to allowlisted
+/// domains (directly or through a link shim) to a popup rather than the browser.
+/// The platforms' own navigation is unaffected.
+pub fn decide_with_allowlist(url: &Url, platform: Option<&Platform>, domains: &[String]) -> NavigationDecision {
+    let decision = decide(url, platform);
+    if decision != NavigationDecision::OpenExternal {
+        return decision;
+    }
+    let target = shim_target(url).unwrap_or_else(|| url.clone());
+    if allowlisted(&target, domains) {
+        NavigationDecision::OpenInApp(target)
+    } else {
+        decision
+    }
+}
+
+/// Decides whether a navigation may proceed in-app.
+///
+/// With an active platform only that platform's own domains and its sign-in
+/// domains stay in the webview. With no platform selected yet, any supported
+/// platform and its sign-in domains are allowed so the platform selector can
+/// navigate. Besides the web and the bundled frontend only `about:blank` and
+/// `blob:` URLs load; any other scheme is denied.
+pub fn decide(url: &Url, platform: Option<&Platform>) -> NavigationDecision {
+    match url.scheme() {
+        "http" | "https" | "tauri" => {}
+        "mailto" | "tel" => return NavigationDecision::OpenExternal,
+        "blob" => return NavigationDecision::Allow,
+        "about" if url.path() == "blank" => return NavigationDecision::Allow,
+        _ => return NavigationDecision::Deny,
+    }
+
+    let Some(host) = url.host_str() else {
+        return NavigationDecision::Deny;
+    };
+
+    if is_app_url(url) {
+        return NavigationDecision::Allow;
+    }
+    if url.scheme() == "tauri" {
+        return NavigationDecision::Deny;
+    }
+
+    if is_link_shim(url) {
+        return NavigationDecision::OpenExternal;
+    }
+
+    let allowed = match platform {
+        Some(p) => p
+            .domains()
+            .iter()
+            .chain(p.auth_domains())
+            .any(|d| host_matches(host, d)),
+        None => Platform::all()
+            .iter()
+            .flat_map(|p| p.domains().iter().chain(p.auth_domains()))
+            .any(|d| host_matches(host, d)),
+    };
+
+    if allowed {
+        NavigationDecision::Allow
+    } else {
+        NavigationDecision::OpenExternal
+    }
+}
+
+/// The domains whose links open in an in-app popup, persisted
+pub struct InAppDomains {
+    domains: Mutex<Vec<String>>,
+    store_path: PathBuf,
+}
+
+impl InAppDomains {
+    /// Creates the allowlist, loading any saved domains
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join(INAPP_DOMAINS_FILE);
+        Self {
+            domains: Mutex::new(store::load_or_default(&store_path)),
+            store_path,
+        }
+    }
+
+    /// The allowlisted domains, sorted
+    pub fn list(&self) -> Result<Vec<String>, String> {
+        Ok(self.domains.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Allowlists a domain (and its subdomains). Returns the updated list.
+    pub fn add(&self, domain: &str) -> Result<Vec<String>, String> {
+        let domain = normalize_domain(domain)?;
+        self.update(|domains| {
+            if !domains.contains(&domain) {
+                domains.push(domain);
+                domains.sort();
+            }
+        })
+    }
+
+    /// Removes a domain. Returns the updated list.
+    pub fn remove(&self, domain: &str) -> Result<Vec<String>, String> {
+        let domain = normalize_domain(domain)?;
+        self.update(|domains| domains.retain(|d| *d != domain))
+    }
+
+    fn update(&self, change: impl FnOnce(&mut Vec<String>)) -> Result<Vec<String>, String> {
+        let mut domains = self.domains.lock().map_err(|e| e.to_string())?;
+        change(&mut domains);
+        store::save(&self.store_path, &*domains)
+            .map_err(|e| format!("Failed to save in-app domains: {}", e))?;
+        Ok(domains.clone())
+    }
+}
+
+/// The allowlisted domains, or none before the allowlist is managed
+fn inapp_domains(app: &AppHandle) -> Vec<String> {
+    app.try_state::<InAppDomains>()
+        .and_then(|d| d.list().ok())
+        .unwrap_or_default()
+}
+
+/// Navigation handler for the main webview. Returns `false` to cancel the navigation.
+pub fn on_navigation(app: &AppHandle, url: &Url) -> bool {
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    guard(app, url, platform.as_ref())
+}
+
+/// Navigation handler for a window that stays on one platform whatever the active
+/// one is (a popped-out conversation). Returns `false` to cancel the navigation.
+pub fn on_platform_navigation(app: &AppHandle, url: &Url, platform: &Platform) -> bool {
+    guard(app, url, Some(platform))
+}
+
+/// Lets the navigation proceed, or opens the URL externally or in a popup and
+/// cancels it
+fn guard(app: &AppHandle, url: &Url, platform: Option<&Platform>) -> bool {
+    match decide_with_allowlist(url, platform, &inapp_domains(app)) {
+        NavigationDecision::Allow => true,
+        NavigationDecision::OpenExternal => {
+            open_external(app, url);
+            false
+        }
+        NavigationDecision::OpenInApp(target) => {
+            open_in_app(app, &target);
+            false
+        }
+        NavigationDecision::Deny => {
+            log::warn!("[navigation] blocked a {}: URL", url.scheme());
+            false
+        }
+    }
+}
+
+/// Navigation handler for in-app popups: they stay on allowlisted domains, and
+/// anything else goes to the system browser
+fn popup_guard(app: &AppHandle, url: &Url) -> bool {
+    if allowlisted(url, &inapp_domains(app)) || url.scheme() == "about" {
+        return true;
+    }
+    open_external(app, url);
+    false
+}
+
+/// Opens an allowlisted link in a transient popup window. The popup is sandboxed:
+/// an ephemeral session shared with nothing (not the platforms, not other popups),
+/// no app scripts, and it can't navigate off the allowlist.
+///
+/// Called from the navigation handler, on the main thread: the window is built
+/// from the async runtime, as building it there deadlocks on Windows (WebView2).
+fn open_in_app(app: &AppHandle, url: &Url) {
+    log::info!("[navigation] opening link in-app: {}", url);
+    let app = app.clone();
+    let url = url.clone();
+    tauri::async_runtime::spawn(async move {
+        let label = format!("{}{}", POPUP_PREFIX, uuid::Uuid::new_v4());
+        let handle = app.clone();
+        let built = WebviewWindowBuilder::new(&app, label, WebviewUrl::External(url.clone()))
+            .title(url.host_str().unwrap_or("Link"))
+            .inner_size(1000.0, 750.0)
+            .incognito(true)
+            .on_navigation(move |url| popup_guard(&handle, url))
+            .build();
+        if let Err(e) = built {
+            log::warn!("[navigation] failed to open {} in-app, using the browser: {}", url, e);
+            open_external(&app, &url);
+        }
+    });
+}
+
+/// Opens a URL in the system browser and emits `external-link-opened`.
+#[allow(deprecated)]
+fn open_external(app: &AppHandle, url: &Url) {
+    log::info!("[navigation] opening external link: {}", url);
+    if let Err(e) = app.shell().open(url.as_str(), None) {
+        log::warn!("[navigation] failed to open {} externally: {}", url, e);
+        return;
+    }
+    let _ = app.emit(
+        "external-link-opened",
+        ExternalLinkPayload {
+            url: url.to_string(),
+        },
+    );
+}
+
+/// Tauri command to open links to `domain` (and its subdomains) in an in-app popup.
+/// Returns the updated allowlist.
+#[tauri::command]
+#[specta::specta]
+pub fn add_inapp_domain(domain: String, domains: tauri::State<'_, InAppDomains>) -> Result<Vec<String>, String> {
+    domains.add(&domain)
+}
+
+/// Tauri command to send links to `domain` back to the system browser. Returns the
+/// updated allowlist.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_inapp_domain(domain: String, domains: tauri::State<'_, InAppDomains>) -> Result<Vec<String>, String> {
+    domains.remove(&domain)
+}
+
+/// Tauri command to list the domains whose links open in-app
+#[tauri::command]
+#[specta::specta]
+pub fn list_inapp_domains(domains: tauri::State<'_, InAppDomains>) -> Result<Vec<String>, String> {
+    domains.list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_same_origin_allowed() {
+        let p = Platform::Messenger;
+        assert_eq!(decide(&url("https://www.messenger.com/t/123"), Some(&p)), NavigationDecision::Allow);
+        assert_eq!(decide(&url("https://www.facebook.com/login"), Some(&p)), NavigationDecision::Allow);
+    }
+
+    #[test]
+    fn test_cross_origin_opens_externally() {
+        let p = Platform::Instagram;
+        assert_eq!(decide(&url("https://example.com/article"), Some(&p)), NavigationDecision::OpenExternal);
+        assert_eq!(decide(&url("https://x.com/home"), Some(&p)), NavigationDecision::OpenExternal);
+        assert_eq!(
+            decide(&url("https://l.messenger.com/l.php?u=https%3A%2F%2Fexample.com"), Some(&Platform::Messenger)),
+            NavigationDecision::OpenExternal
+        );
+    }
+
+    #[test]
+    fn test_no_platform_allows_known_platforms() {
+        assert_eq!(decide(&url("https://x.com/messages"), None), NavigationDecision::Allow);
+        assert_eq!(decide(&url("https://evil-instagram.com/"), None), NavigationDecision::OpenExternal);
+        // Signing in starts before a platform is picked
+        assert_eq!(decide(&url("https://www.facebook.com/login"), None), NavigationDecision::Allow);
+        assert_eq!(decide(&url("https://accounts.google.com/o/oauth2"), None), NavigationDecision::Allow);
+    }
+
+    #[test]
+    fn test_unknown_schemes_denied() {
+        assert_eq!(decide(&url("about:blank"), None), NavigationDecision::Allow);
+        assert_eq!(
+            decide(&url("blob:https://www.messenger.com/1f2e"), Some(&Platform::Messenger)),
+            NavigationDecision::Allow
+        );
+        for denied in [
+            "about:config",
+            "data:text/html,<p>hi</p>",
+            "javascript:alert(1)",
+            "file:///etc/passwd",
+            "ms-settings:privacy",
+            "tauri://evil.example/index.html",
+        ] {
+            assert_eq!(decide(&url(denied), None), NavigationDecision::Deny, "{}", denied);
+        }
+    }
+
+    #[test]
+    fn test_app_urls_allowed() {
+        assert_eq!(decide(&url("tauri://localhost/index.html"), None), NavigationDecision::Allow);
+        assert_eq!(decide(&url("http://tauri.localhost/index.html"), Some(&Platform::X)), NavigationDecision::Allow);
+        assert_eq!(decide(&url("mailto:someone@example.com"), None), NavigationDecision::OpenExternal);
+    }
+
+    #[test]
+    fn test_localhost_links_open_externally() {
+        // A local server linked from a message never loads in the app webview
+        for link in ["http://localhost:8080/", "http://localhost/", "https://localhost:5173/", "http://127.0.0.1:5173/"] {
+            assert_eq!(decide(&url(link), Some(&Platform::X)), NavigationDecision::OpenExternal, "{}", link);
+            assert_eq!(decide(&url(link), None), NavigationDecision::OpenExternal, "{}", link);
+        }
+        // Only debug builds trust the dev server, on its own port
+        let dev = decide(&url("http://localhost:5173/index.html"), Some(&Platform::X));
+        let expected = if cfg!(debug_assertions) {
+            NavigationDecision::Allow
+        } else {
+            NavigationDecision::OpenExternal
+        };
+        assert_eq!(dev, expected);
+    }
+
+    #[test]
+    fn test_inapp_allowlist_matching() {
+        let domains = vec!["docs.google.com".to_string(), "imgur.com".to_string()];
+        let p = Platform::Messenger;
+
+        assert_eq!(
+            decide_with_allowlist(&url("https://docs.google.com/document/d/1"), Some(&p), &domains),
+            NavigationDecision::OpenInApp(url("https://docs.google.com/document/d/1"))
+        );
+        // Subdomains match; the parent, look-alikes and other hosts don't
+        assert!(allowlisted(&url("https://i.imgur.com/a.png"), &domains));
+        assert!(allowlisted(&url("https://I.IMGUR.COM/a.png"), &domains));
+        assert!(!allowlisted(&url("https://google.com/"), &domains));
+        assert!(!allowlisted(&url("https://notimgur.com/"), &domains));
+        assert!(!allowlisted(&url("https://imgur.com.evil.net/"), &domains));
+        assert!(!allowlisted(&url("mailto:me@imgur.com"), &domains));
+
+        // Links through the platform's shim open their target in-app
+        assert_eq!(
+            decide_with_allowlist(&url("https://l.messenger.com/l.php?u=https%3A%2F%2Fi.imgur.com%2Fx.png"), Some(&p), &domains),
+            NavigationDecision::OpenInApp(url("https://i.imgur.com/x.png"))
+        );
+        // Everything else is unchanged
+        assert_eq!(decide_with_allowlist(&url("https://example.com/"), Some(&p), &domains), NavigationDecision::OpenExternal);
+        assert_eq!(decide_with_allowlist(&url("https://www.messenger.com/t/1"), Some(&p), &domains), NavigationDecision::Allow);
+
+        assert_eq!(normalize_domain(" *.Imgur.com ").unwrap(), "imgur.com");
+        assert_eq!(normalize_domain("https://docs.google.com/document").unwrap(), "docs.google.com");
+        assert!(normalize_domain("localhost").is_err());
+        assert!(normalize_domain("bad domain.com").is_err());
+    }
+
+    #[test]
+    fn test_inapp_domains_persist() {
+        let tmp = std::env::temp_dir().join("test-inapp-domains");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let domains = InAppDomains::new(&tmp);
+        domains.add("imgur.com").unwrap();
+        domains.add("*.IMGUR.com").unwrap();
+        assert_eq!(domains.add("docs.google.com").unwrap(), ["docs.google.com", "imgur.com"]);
+        assert_eq!(InAppDomains::new(&tmp).list().unwrap(), ["docs.google.com", "imgur.com"]);
+        assert_eq!(domains.remove("imgur.com").unwrap(), ["docs.google.com"]);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
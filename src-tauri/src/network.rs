@@ -0,0 +1,261 @@
+//! Network monitoring and auto-reconnect.
+//!
+//! A background task checks that the active platform's origin answers. Flapping is
+//! debounced: the state only changes after `STABLE_CHECKS` checks in a row agree.
+//! Each change is emitted as `network-changed`. When the network comes back and the
+//! page hasn't recovered by itself (it is still on an error page, or the connection
+//! probe never reported back), the platform is reloaded, if auto-reconnect is on.
+//!
+//! Keepalive pings are held off while offline and right after a reconnect reload
+//! (see `NetworkMonitor::is_settling`), so the two never hit the page together.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::connection::{ConnectionMonitor, ConnectionStatus};
+use crate::platform_manager::{open_platform, PlatformManager};
+use crate::store;
+
+/// How often reachability is checked
+const TICK: Duration = Duration::from_secs(10);
+
+/// How long a reachability check may take
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Consecutive agreeing checks needed before the state changes
+const STABLE_CHECKS: u32 = 2;
+
+/// How long keepalive stays quiet after a reconnect reload
+const SETTLE_TIME: Duration = Duration::from_secs(60);
+
+/// Persisted network configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct NetworkConfig {
+    /// Reload the platform when the network comes back
+    pub auto_reconnect: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self { auto_reconnect: true }
+    }
+}
+
+/// Payload emitted with `network-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct NetworkChangedPayload {
+    pub online: bool,
+    /// Whether the platform was reloaded because of the change
+    pub reloaded: bool,
+}
+
+/// Debounces reachability checks into online/offline transitions
+#[derive(Debug)]
+struct NetworkTracker {
+    online: bool,
+    streak: u32,
+}
+
+impl NetworkTracker {
+    fn new() -> Self {
+        Self { online: true, streak: 0 }
+    }
+
+    /// Records a check. Returns the new state when it changes.
+    fn observe(&mut self, reachable: bool) -> Option<bool> {
+        if reachable == self.online {
+            self.streak = 0;
+            return None;
+        }
+        self.streak += 1;
+        if self.streak < STABLE_CHECKS {
+            return None;
+        }
+        self.streak = 0;
+        self.online = reachable;
+        Some(reachable)
+    }
+}
+
+/// Whether coming back online should reload the page: only if the page hasn't
+/// reported itself healthy since
+pub fn needs_reload(auto_reconnect: bool, page: Option<ConnectionStatus>) -> bool {
+    auto_reconnect && !matches!(page, Some(ConnectionStatus::Connected) | Some(ConnectionStatus::LoggedOut))
+}
+
+/// Tracks network state and the auto-reconnect setting
+pub struct NetworkMonitor {
+    config: Mutex<NetworkConfig>,
+    store_path: PathBuf,
+    tracker: Mutex<NetworkTracker>,
+    last_reload: Mutex<Option<Instant>>,
+}
+
+impl NetworkMonitor {
+    /// Creates a new NetworkMonitor, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("network.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: Mutex::new(config),
+            store_path,
+            tracker: Mutex::new(NetworkTracker::new()),
+            last_reload: Mutex::new(None),
+        }
+    }
+
+    /// Gets the current configuration
+    pub fn get_config(&self) -> Result<NetworkConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Turns auto-reconnect on or off and persists it
+    pub fn set_auto_reconnect(&self, enabled: bool) -> Result<(), String> {
+        let config = NetworkConfig { auto_reconnect: enabled };
+        store::save(&self.store_path, &config).map_err(|e| format!("Failed to save network config: {}", e))?;
+        *self.config.lock().map_err(|e| e.to_string())? = config;
+        Ok(())
+    }
+
+    /// Whether the network is (debounced) online
+    pub fn is_online(&self) -> bool {
+        self.tracker.lock().map(|t| t.online).unwrap_or(true)
+    }
+
+    /// Whether background page activity should wait: offline, or just reloaded
+    pub fn is_settling(&self) -> bool {
+        let reloaded_recently = self
+            .last_reload
+            .lock()
+            .ok()
+            .and_then(|l| *l)
+            .is_some_and(|at| at.elapsed() < SETTLE_TIME);
+        !self.is_online() || reloaded_recently
+    }
+
+    fn observe(&self, reachable: bool) -> Option<bool> {
+        self.tracker.lock().ok()?.observe(reachable)
+    }
+
+    fn mark_reloaded(&self) {
+        if let Ok(mut last) = self.last_reload.lock() {
+            *last = Some(Instant::now());
+        }
+    }
+}
+
+/// Checks whether `url` answers at all; any HTTP response counts
+async fn reachable(client: &reqwest::Client, url: &str) -> bool {
+    client.head(url).send().await.is_ok()
+}
+
+/// Reloads the active platform in the main window
+fn reload(app: &AppHandle) -> Result<bool, String> {
+    let manager = app.state::<PlatformManager>();
+    let (Some(window), Some(platform)) = (app.get_webview_window("main"), manager.get_current()?) else {
+        return Ok(false);
+    };
+    open_platform(&window, &manager, platform)?;
+    Ok(true)
+}
+
+/// Handles a debounced online/offline transition
+fn on_change(app: &AppHandle, online: bool) {
+    let monitor = app.state::<NetworkMonitor>();
+    let page = app.state::<ConnectionMonitor>().status().ok().flatten();
+    let auto_reconnect = monitor.get_config().map(|c| c.auto_reconnect).unwrap_or(false);
+
+    let mut reloaded = false;
+    if online && needs_reload(auto_reconnect, page) {
+        match reload(app) {
+            Ok(done) => reloaded = done,
+            Err(e) => log::warn!("[network] reconnect reload failed: {}", e),
+        }
+        if reloaded {
+            monitor.mark_reloaded();
+        }
+    }
+
+    log::info!("[network] {} (reloaded: {})", if online { "online" } else { "offline" }, reloaded);
+    let _ = app.emit("network-changed", NetworkChangedPayload { online, reloaded });
+}
+
+/// Starts the background reachability check
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let client = match reqwest::Client::builder().timeout(CHECK_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("[network] monitoring disabled: {}", e);
+                return;
+            }
+        };
+        loop {
+            tokio::time::sleep(TICK).await;
+            let Some(platform) = app.state::<PlatformManager>().get_current().ok().flatten() else {
+                continue;
+            };
+            let up = reachable(&client, platform.url()).await;
+            if let Some(online) = app.state::<NetworkMonitor>().observe(up) {
+                on_change(&app, online);
+            }
+        }
+    });
+}
+
+/// Tauri command to turn auto-reload on reconnect on or off
+#[tauri::command]
+#[specta::specta]
+pub fn set_auto_reconnect(enabled: bool, monitor: tauri::State<'_, NetworkMonitor>) -> Result<(), String> {
+    monitor.set_auto_reconnect(enabled)
+}
+
+/// Tauri command to get the network configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_auto_reconnect(monitor: tauri::State<'_, NetworkMonitor>) -> Result<NetworkConfig, String> {
+    monitor.get_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_debounces_flapping() {
+        let mut tracker = NetworkTracker::new();
+        assert_eq!(tracker.observe(false), None);
+        assert_eq!(tracker.observe(true), None);
+        assert_eq!(tracker.observe(false), None);
+        assert_eq!(tracker.observe(false), Some(false));
+        assert_eq!(tracker.observe(false), None);
+        assert_eq!(tracker.observe(true), None);
+        assert_eq!(tracker.observe(true), Some(true));
+    }
+
+    #[test]
+    fn test_needs_reload_only_when_page_is_stuck() {
+        assert!(needs_reload(true, Some(ConnectionStatus::LoadError)));
+        assert!(needs_reload(true, Some(ConnectionStatus::Offline)));
+        assert!(needs_reload(true, None));
+        assert!(!needs_reload(true, Some(ConnectionStatus::Connected)));
+        assert!(!needs_reload(true, Some(ConnectionStatus::LoggedOut)));
+        assert!(!needs_reload(false, Some(ConnectionStatus::LoadError)));
+    }
+
+    #[test]
+    fn test_auto_reconnect_persists() {
+        let tmp = std::env::temp_dir().join("test-network");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let monitor = NetworkMonitor::new(&tmp);
+        assert!(monitor.get_config().unwrap().auto_reconnect);
+        monitor.set_auto_reconnect(false).unwrap();
+        assert!(!NetworkMonitor::new(&tmp).get_config().unwrap().auto_reconnect);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
@@ -3,14 +3,21 @@
 // Supports Do Not Disturb mode, custom sounds, and quick reply (platform-specific)
 
 use anyhow::Result;
+use regex::RegexBuilder;
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use crate::icon_cache::IconCache;
+use crate::store;
+
 /// Notification data received from JavaScript injection
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct NotificationData {
@@ -24,6 +31,176 @@ pub struct NotificationData {
     pub timestamp: Option<u64>,
     pub require_interaction: bool,
     pub silent: bool,
+    /// Platform the notification came from, for per-platform preview modes
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// Account the notification belongs to
+    #[serde(default)]
+    pub account_id: Option<String>,
+    /// Name of that account, shown in the toast when there are several
+    #[serde(default)]
+    pub account_name: Option<String>,
+}
+
+impl NotificationData {
+    /// Whether the notification mentions the user or asks to stay until seen
+    pub fn is_mention(&self) -> bool {
+        self.require_interaction
+            || [&self.title, &self.body]
+                .iter()
+                .any(|text| text.to_lowercase().contains("mentioned you"))
+    }
+
+    /// Sample notification fired by `send_test_notification`
+    pub fn test_sample() -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: "Messenger Desktop".to_string(),
+            body: "Notifications are working.".to_string(),
+            icon_url: None,
+            conversation_id: None,
+            sender_name: None,
+            sender_avatar: None,
+            timestamp: None,
+            require_interaction: false,
+            silent: false,
+            platform: None,
+            account_id: None,
+            account_name: None,
+        }
+    }
+
+    /// Builds notification data from a web `Notification(title, options)` call.
+    /// The page's `tag` identifies the conversation on the supported platforms.
+    pub fn from_web(title: String, options: Option<&serde_json::Value>) -> Self {
+        let field = |key: &str| {
+            options
+                .and_then(|o| o.get(key))
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let flag = |key: &str| {
+            options
+                .and_then(|o| o.get(key))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        };
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            body: field("body").unwrap_or_default(),
+            icon_url: field("icon"),
+            conversation_id: field("conversationId").or_else(|| field("tag")),
+            sender_name: Some(title.clone()),
+            sender_avatar: None,
+            timestamp: options
+                .and_then(|o| o.get("timestamp"))
+                .and_then(|v| v.as_u64()),
+            require_interaction: flag("requireInteraction"),
+            silent: flag("silent"),
+            platform: None,
+            account_id: None,
+            account_name: None,
+            title,
+        }
+    }
+}
+
+/// Built-in toast action ids
+pub const ACTION_REPLY: &str = "reply";
+pub const ACTION_MARK_READ: &str = "mark-read";
+pub const ACTION_MUTE: &str = "mute";
+
+/// How long the toast's mute action mutes a conversation
+const MUTE_ACTION_MINUTES: u32 = 60;
+
+/// A button shown on a notification toast
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct NotificationAction {
+    pub id: String,
+    pub title: String,
+}
+
+impl NotificationAction {
+    fn new(id: &str, title: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    /// The built-in actions: reply, mark as read and mute
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::new(ACTION_REPLY, "Reply"),
+            Self::new(ACTION_MARK_READ, "Mark as read"),
+            Self::new(ACTION_MUTE, "Mute"),
+        ]
+    }
+
+    pub fn is_builtin(id: &str) -> bool {
+        [ACTION_REPLY, ACTION_MARK_READ, ACTION_MUTE].contains(&id)
+    }
+}
+
+/// Payload emitted with `notification-action` when a toast button is clicked
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NotificationActionPayload {
+    pub conversation_id: Option<String>,
+    pub action_id: String,
+}
+
+/// Whether this platform's toasts can carry buttons (macOS and Windows; elsewhere
+/// notifications are shown without them)
+pub fn supports_actions() -> bool {
+    cfg!(any(target_os = "macos", target_os = "windows"))
+}
+
+/// The buttons to put on a toast. Reply only shows with quick reply on, and
+/// notifications without a conversation get none (there is nothing to act on).
+fn toast_actions(
+    actions: &[NotificationAction],
+    settings: &NotificationSettings,
+    data: &NotificationData,
+    supported: bool,
+) -> Vec<NotificationAction> {
+    if !supported || data.conversation_id.is_none() {
+        return Vec::new();
+    }
+    actions
+        .iter()
+        .filter(|a| a.id != ACTION_REPLY || settings.quick_reply_enabled)
+        .cloned()
+        .collect()
+}
+
+/// Maps a clicked toast button to the `notification-action` event, if the action
+/// is registered
+pub fn action_event(
+    actions: &[NotificationAction],
+    action_id: &str,
+    conversation_id: Option<String>,
+) -> Option<NotificationActionPayload> {
+    actions.iter().any(|a| a.id == action_id).then(|| NotificationActionPayload {
+        conversation_id: conversation_id.filter(|id| !id.is_empty()),
+        action_id: action_id.to_string(),
+    })
+}
+
+/// Splits the activation arguments of a Windows toast button
+/// (`action=<id>&conversation=<id>`) into the action and conversation ids
+pub fn parse_toast_arguments(arguments: &str) -> Option<(String, Option<String>)> {
+    let mut action = None;
+    let mut conversation = None;
+    for pair in arguments.split('&') {
+        match pair.split_once('=') {
+            Some(("action", value)) if !value.is_empty() => action = Some(value.to_string()),
+            Some(("conversation", value)) if !value.is_empty() => conversation = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    action.map(|action| (action, conversation))
 }
 
 /// Platform-specific notification settings
@@ -36,563 +213,2710 @@ pub struct NotificationSettings {
     pub dnd_schedule: Option<DNDSchedule>,
     pub show_preview: bool,
     pub quick_reply_enabled: bool,
+    #[serde(default)]
+    pub filters: Vec<NotificationFilter>,
+    /// Muted conversation ids mapped to the mute expiry (Unix ms), `None` = indefinite
+    #[serde(default)]
+    pub muted_conversations: HashMap<String, Option<u64>>,
+    /// Conversations that break through DND and mutes, mapped to the priority
+    /// expiry (Unix ms), `None` = until cleared
+    #[serde(default)]
+    pub prioritized_conversations: HashMap<String, Option<u64>>,
+    /// Play a sound when one of the user's own messages is sent
+    #[serde(default)]
+    pub sent_sound_enabled: bool,
+    /// Custom sent sound; `None` uses the system default
+    #[serde(default)]
+    pub sent_sound_path: Option<String>,
+    /// What a notification reveals, unless overridden for its platform
+    #[serde(default)]
+    pub preview_mode: PreviewMode,
+    /// Per-platform preview mode overrides, keyed by platform name
+    #[serde(default)]
+    pub platform_preview_modes: HashMap<String, PreviewMode>,
+    /// Per-platform DND schedules, keyed by platform name; others use `dnd_schedule`
+    #[serde(default)]
+    pub platform_dnd_schedules: HashMap<String, DNDSchedule>,
+    /// Bounce the dock / flash the taskbar on a mention while unfocused
+    #[serde(default = "default_bounce_on_mention")]
+    pub bounce_on_mention: bool,
+    /// Raise the window and open the conversation when a notification is clicked
+    #[serde(default = "default_focus_on_click")]
+    pub focus_on_click: bool,
+    /// When DND ends, show one summary of what it held back instead of nothing
+    #[serde(default)]
+    pub digest_on_dnd_end: bool,
+    /// How many notifications may be shown per second (see `RateLimit`)
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+    /// How toasts are grouped in the Windows Action Center
+    #[serde(default)]
+    pub toast_grouping: ToastGrouping,
+    /// Hold notifications back while the system's own Do Not Disturb (Focus
+    /// Assist, Focus) is on
+    #[serde(default)]
+    pub respect_system_dnd: bool,
 }
 
-/// Do Not Disturb schedule
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
-pub struct DNDSchedule {
-    pub start_time: String, // HH:MM format
-    pub end_time: String,   // HH:MM format
+/// Flood protection for notifications reported by the page: a token bucket that
+/// refills `per_second` tokens a second up to `burst`. Notifications beyond it are
+/// recorded but not shown; the most recent of them is shown once a token frees up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+pub struct RateLimit {
+    pub per_second: u32,
+    pub burst: u32,
 }
 
-/// Notification service state
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-pub struct NotificationState {
-    pub settings: NotificationSettings,
-    #[allow(dead_code)]
-    pub temporary_icons: Vec<PathBuf>,
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            per_second: 1,
+            burst: 5,
+        }
+    }
 }
 
-/// Native Notification Service - manages OS-native notifications
-#[allow(dead_code)]
-pub struct NotificationService {
-    state: Arc<RwLock<NotificationState>>,
-    #[allow(dead_code)]
-    app_data_dir: PathBuf,
+/// Token bucket state for `RateLimit`
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
 }
 
-impl NotificationService {
-    /// Create a new notification service
-    pub fn new(app_data_dir: PathBuf) -> Self {
+impl TokenBucket {
+    fn full(limit: RateLimit) -> Self {
         Self {
-            state: Arc::new(RwLock::new(NotificationState {
-                settings: NotificationSettings {
-                    enabled: true,
-                    sound_enabled: false,
-                    sound_path: None,
-                    do_not_disturb: false,
-                    dnd_schedule: None,
-                    show_preview: true,
-                    quick_reply_enabled: false,
-                },
-                temporary_icons: Vec::new(),
-            })),
-            app_data_dir,
+            tokens: f64::from(limit.burst),
+            refilled_at: Instant::now(),
         }
     }
 
-    /// Show a native notification
-    pub async fn show_notification(&self, data: NotificationData) -> Result<()> {
-        debug!("Showing notification: {}", data.title);
-
-        // Check if notifications are enabled
-        let state = self.state.read().await;
-        let settings_enabled = state.settings.enabled;
-        let settings_do_not_disturb = state.settings.do_not_disturb;
-        let settings_dnd_schedule = state.settings.dnd_schedule.clone();
-        let settings_sound_enabled = state.settings.sound_enabled;
-        let settings_sound_path = state.settings.sound_path.clone();
-
-        if !settings_enabled {
-            info!("Notifications disabled, skipping: {}", data.title);
-            return Ok(());
-        }
-
-        // Check Do Not Disturb mode
-        if settings_do_not_disturb {
-            info!("DND active, suppressing notification: {}", data.title);
-            return Ok(());
-        }
-
-        // Check DND schedule if configured
-        if let Some(schedule) = &settings_dnd_schedule {
-            if self.is_in_dnd_schedule(schedule).await {
-                info!("In DND schedule, suppressing notification: {}", data.title);
-                return Ok(());
-            }
-        }
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * f64::from(limit.per_second)).min(f64::from(limit.burst));
+        self.refilled_at = now;
+    }
 
-        // Download and prepare icon if provided
-        let icon_path = if let Some(icon_url) = &data.icon_url {
-            self.download_and_save_icon(icon_url, &data.id).await?
+    /// Takes a token if one is available
+    fn try_take(&mut self, limit: RateLimit, now: Instant) -> bool {
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
         } else {
-            None
-        };
-
-        // Prepare notification payload
-        let payload = NotificationPayload {
-            id: data.id.clone(),
-            title: data.title.clone(),
-            body: data.body.clone(),
-            icon_path,
-            conversation_id: data.conversation_id.clone(),
-            sender_name: data.sender_name.clone(),
-            silent: data.silent,
-        };
+            false
+        }
+    }
 
-        drop(state); // Release the lock before calling platform-specific code
+    /// How long until a token is available
+    fn wait(&self, limit: RateLimit) -> Duration {
+        let missing = (1.0 - self.tokens).max(0.0);
+        Duration::from_secs_f64(missing / f64::from(limit.per_second.max(1)))
+    }
+}
 
-        // Show the notification using platform-specific implementation
-        #[cfg(target_os = "macos")]
-        self.show_native_macos(&payload).await?;
+/// Longest toast group Windows accepts
+const MAX_TOAST_GROUP_LEN: usize = 64;
 
-        #[cfg(target_os = "windows")]
-        self.show_native_windows(&payload).await?;
+/// Longest App User Model ID Windows accepts
+const MAX_APP_ID_LEN: usize = 128;
 
-        #[cfg(target_os = "linux")]
-        self.show_native_linux(&payload).await?;
+/// How toasts are grouped in the Windows Action Center. Windows groups toasts by
+/// the app identity (AUMID) they are shown under, and collapses toasts sharing a
+/// group within it; other platforms ignore this.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ToastGrouping {
+    /// Collapse each conversation's toasts into one group
+    pub enabled: bool,
+    /// App User Model ID the toasts are shown under; `None` uses the bundle
+    /// identifier, which the installer gives the Start menu shortcut
+    pub app_id: Option<String>,
+}
 
-        // Play sound if enabled
-        if settings_sound_enabled {
-            self.play_notification_sound(&settings_sound_path).await?;
+impl Default for ToastGrouping {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            app_id: None,
         }
-
-        info!("Notification shown: {} - {}", data.title, data.body);
-        Ok(())
     }
+}
 
-    /// Download and save icon from URL to temporary location
-    async fn download_and_save_icon(
-        &self,
-        url: &str,
-        _notification_id: &str,
-    ) -> Result<Option<String>> {
-        debug!("Downloading icon from: {}", url);
-
-        // In a real implementation, you would use reqwest to download:
-        // let response = reqwest::get(url).await?;
-        // let bytes = response.bytes().await?;
-        // let icon_path = self.app_data_dir.join(format!("notification_{}.png", notification_id));
-        // fs::write(&icon_path, &bytes)?;
-        // 
-        // self.state.write().await.temporary_icons.push(icon_path.clone());
-        
-        // For now, return the URL as-is since we can't download in this environment
-        // The actual implementation should save to a temp file
-        
-        Ok(Some(url.to_string()))
+/// The Action Center group of a conversation's toasts: derived from the
+/// conversation id alone, so every toast of a conversation shares it. Ids that
+/// don't fit a group (too long, or odd characters) are hashed.
+pub fn toast_group(conversation_id: &str) -> String {
+    let fits = !conversation_id.is_empty()
+        && "conv-".len() + conversation_id.len() <= MAX_TOAST_GROUP_LEN
+        && conversation_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if fits {
+        format!("conv-{}", conversation_id)
+    } else {
+        format!("conv-{:08x}", native_id(conversation_id))
     }
+}
 
-    /// Check if current time is within DND schedule
-    async fn is_in_dnd_schedule(&self, _schedule: &DNDSchedule) -> bool {
-        // Parse start and end times
-        // Compare with current time
-        
-        // For now, return false (not in DND)
-        // In a real implementation:
-        // let now = Local::now();
-        // let start = Self::parse_time(&schedule.start_time).unwrap();
-        // let end = Self::parse_time(&schedule.end_time).unwrap();
-        // 
-        // if start <= end {
-        //     now.time() >= start && now.time() <= end
-        // } else {
-        //     // Overnight schedule
-        //     now.time() >= start || now.time() <= end
-        // }
-        
-        false
+/// The group to show a toast in: none with grouping off or outside a conversation
+pub fn toast_group_for(grouping: &ToastGrouping, conversation_id: Option<&str>) -> Option<String> {
+    if !grouping.enabled {
+        return None;
     }
+    conversation_id.filter(|c| !c.is_empty()).map(toast_group)
+}
 
-    /// Play notification sound
-    async fn play_notification_sound(&self, sound_path: &Option<String>) -> Result<()> {
-        if let Some(path) = sound_path {
-            debug!("Playing notification sound: {}", path);
-            
-            // In a real implementation, you would use:
-            // - macOS:NSSound with file path
-            // - Windows:Windows.Media.Playback
-            // - Linux:pactl or paplay for ALSA/PulseAudio
-            
-            // For now, just log since we can't play sounds in this environment
-            info!("Would play sound from: {}", path);
-        } else {
-            debug!("Playing default notification sound");
-            
-            // Default sound based on platform:
-            // - macOS: NSAlertDefaultSound
-            // - Windows: SystemSound::Notification
-            // - Linux: /usr/share/sounds/generic.wav
-            
-            info!("Would play default system notification sound");
-        }
-        
-        Ok(())
+/// An AUMID is a short dotted name without spaces, e.g. `Company.App`
+fn validate_app_id(app_id: &str) -> Result<()> {
+    if app_id.is_empty() || app_id.len() > MAX_APP_ID_LEN || app_id.chars().any(char::is_whitespace) {
+        return Err(anyhow::anyhow!(
+            "An app identity must be 1 to {} characters without spaces",
+            MAX_APP_ID_LEN
+        ));
     }
+    Ok(())
+}
 
-    /// Set Do Not Disturb mode
-    pub async fn set_dnd(&self, enabled: bool) -> Result<()> {
-        debug!("Setting DND to: {}", enabled);
+/// Whether the system's Do Not Disturb holds a notification back. `system_dnd`
+/// is only asked with `respect_system_dnd` on; where it can't tell (`None`), the
+/// app's own DND is all that applies.
+pub fn held_by_system_dnd(settings: &NotificationSettings, system_dnd: impl FnOnce() -> Option<bool>) -> bool {
+    settings.respect_system_dnd && system_dnd() == Some(true)
+}
 
-        self.state.write().await.settings.do_not_disturb = enabled;
+fn default_focus_on_click() -> bool {
+    true
+}
 
-        info!("Do Not Disturb mode: {}", if enabled { "enabled" } else { "disabled" });
-        Ok(())
-    }
+fn default_bounce_on_mention() -> bool {
+    true
+}
 
-    /// Set notification sound path
-    pub async fn set_notification_sound(&self, path: String) -> Result<()> {
-        debug!("Setting notification sound to: {}", path);
+/// What a notification reveals about the message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewMode {
+    /// Sender and message
+    #[default]
+    Full,
+    /// Sender only
+    SenderOnly,
+    /// Message only
+    BodyOnly,
+    /// Neither: a generic "New message."
+    Hidden,
+}
 
-        // Verify the file exists
-        if !PathBuf::from(&path).exists() {
-            warn!("Sound file does not exist: {}", path);
-            return Err(anyhow::anyhow!("Sound file does not exist"));
+impl NotificationSettings {
+    /// The preview mode for a notification from `platform`. Turning `show_preview`
+    /// off hides previews everywhere.
+    pub fn preview_mode_for(&self, platform: Option<&str>) -> PreviewMode {
+        if !self.show_preview {
+            return PreviewMode::Hidden;
         }
-
-        self.state.write().await.settings.sound_path = Some(path);
-
-        info!("Notification sound updated");
-        Ok(())
+        platform
+            .and_then(|p| self.platform_preview_modes.get(p))
+            .copied()
+            .unwrap_or(self.preview_mode)
     }
 
-    /// Enable/disable notifications
-    pub async fn set_enabled(&self, enabled: bool) -> Result<()> {
-        debug!("Setting notifications enabled to: {}", enabled);
-
-        self.state.write().await.settings.enabled = enabled;
+    /// The DND schedule for a notification from `platform`: its own, else the global one
+    pub fn dnd_schedule_for(&self, platform: Option<&str>) -> Option<&DNDSchedule> {
+        platform
+            .and_then(|p| self.platform_dnd_schedules.get(p))
+            .or(self.dnd_schedule.as_ref())
+    }
 
-        info!("Notifications {}", if enabled { "enabled" } else { "disabled" });
+    /// Checks what `add_filter` and the DND setters would refuse, for settings
+    /// that arrive whole (e.g. an imported settings file)
+    pub fn validate(&self) -> Result<()> {
+        for filter in &self.filters {
+            if filter.pattern.trim().is_empty() {
+                return Err(anyhow::anyhow!("Filter pattern must not be empty"));
+            }
+            if filter.regex {
+                RegexBuilder::new(&filter.pattern).build()?;
+            }
+        }
+        for schedule in self.dnd_schedule.iter().chain(self.platform_dnd_schedules.values()) {
+            DNDSchedule::new(&schedule.start_time, &schedule.end_time)?;
+        }
         Ok(())
     }
+}
 
-    /// Set sound enabled state
-    pub async fn set_sound_enabled(&self, enabled: bool) -> Result<()> {
-        debug!("Setting sound enabled to: {}", enabled);
+/// Title and body to display for `data` under `mode`
+pub fn preview_text(data: &NotificationData, mode: PreviewMode) -> (String, String) {
+    let generic_title = data.platform.clone().unwrap_or_else(|| GENERIC_TITLE.to_string());
+    match mode {
+        PreviewMode::Full => (data.title.clone(), data.body.clone()),
+        PreviewMode::SenderOnly => (data.title.clone(), HIDDEN_PREVIEW_BODY.to_string()),
+        PreviewMode::BodyOnly => (generic_title, data.body.clone()),
+        PreviewMode::Hidden => (generic_title, HIDDEN_PREVIEW_BODY.to_string()),
+    }
+}
 
-        self.state.write().await.settings.sound_enabled = enabled;
+/// Attributes a toast title to its account
+fn with_account(title: String, account_name: Option<&str>) -> String {
+    match account_name {
+        Some(name) => format!("{} · {}", title, name),
+        None => title,
+    }
+}
 
-        info!("Notification sound {}", if enabled { "enabled" } else { "disabled" });
-        Ok(())
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sound_enabled: false,
+            sound_path: None,
+            do_not_disturb: false,
+            dnd_schedule: None,
+            show_preview: true,
+            quick_reply_enabled: false,
+            filters: Vec::new(),
+            muted_conversations: HashMap::new(),
+            prioritized_conversations: HashMap::new(),
+            sent_sound_enabled: false,
+            sent_sound_path: None,
+            preview_mode: PreviewMode::Full,
+            platform_preview_modes: HashMap::new(),
+            platform_dnd_schedules: HashMap::new(),
+            bounce_on_mention: true,
+            focus_on_click: true,
+            digest_on_dnd_end: false,
+            rate_limit: RateLimit::default(),
+            toast_grouping: ToastGrouping::default(),
+            respect_system_dnd: false,
+        }
     }
+}
 
-    /// Set show preview preference
-    #[allow(dead_code)]
-    pub async fn set_show_preview(&self, enabled: bool) -> Result<()> {
-        debug!("Setting show preview to: {}", enabled);
+/// Whether the sent sound should play. Sending is user-initiated, so DND doesn't
+/// apply; turning notifications off entirely (the global mute) does.
+pub fn should_play_sent_sound(settings: &NotificationSettings) -> bool {
+    settings.enabled && settings.sent_sound_enabled
+}
 
-        self.state.write().await.settings.show_preview = enabled;
+/// Returns `true` if the conversation has a mute that hasn't expired at `now` (Unix ms)
+pub fn is_conversation_muted(settings: &NotificationSettings, conversation_id: &str, now: u64) -> bool {
+    match settings.muted_conversations.get(conversation_id) {
+        Some(None) => true,
+        Some(Some(expires_at)) => *expires_at > now,
+        None => false,
+    }
+}
 
-        Ok(())
+/// Returns `true` if the conversation has a priority that hasn't expired at `now` (Unix ms)
+pub fn is_conversation_prioritized(settings: &NotificationSettings, conversation_id: &str, now: u64) -> bool {
+    match settings.prioritized_conversations.get(conversation_id) {
+        Some(None) => true,
+        Some(Some(expires_at)) => *expires_at > now,
+        None => false,
     }
+}
 
-    /// Set quick reply preference
-    #[allow(dead_code)]
-    pub async fn set_quick_reply_enabled(&self, enabled: bool) -> Result<()> {
-        debug!("Setting quick reply enabled to: {}", enabled);
+/// Which part of a notification a filter matches against
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterField {
+    Sender,
+    Body,
+}
 
-        self.state.write().await.settings.quick_reply_enabled = enabled;
+/// What happens to a notification that matches a filter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// Always show, even if a deny filter also matches
+    Allow,
+    /// Suppress (the notification is still recorded in history)
+    Deny,
+}
 
-        Ok(())
-    }
+/// Sender / keyword filter applied before a notification is shown
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct NotificationFilter {
+    pub id: String,
+    pub field: FilterField,
+    pub action: FilterAction,
+    pub pattern: String,
+    /// Treat `pattern` as a (case-insensitive) regex instead of a substring
+    #[serde(default)]
+    pub regex: bool,
+}
 
-    /// Get current notification settings
-    pub async fn get_settings(&self) -> NotificationSettings {
-        self.state.read().await.settings.clone()
+impl NotificationFilter {
+    /// Returns `true` if the filter matches the notification (case-insensitive)
+    pub fn matches(&self, data: &NotificationData) -> bool {
+        let text = match self.field {
+            FilterField::Sender => data.sender_name.as_deref().unwrap_or(""),
+            FilterField::Body => &data.body,
+        };
+        if self.regex {
+            RegexBuilder::new(&self.pattern)
+                .case_insensitive(true)
+                .build()
+                .map(|re| re.is_match(text))
+                .unwrap_or(false)
+        } else {
+            !self.pattern.is_empty() && text.to_lowercase().contains(&self.pattern.to_lowercase())
+        }
     }
+}
 
-    /// Close the notification service and clean up temporary files
-    #[allow(dead_code)]
-    pub async fn cleanup(&self) -> Result<()> {
-        debug!("Cleaning up notification service");
+/// Returns `true` if the filters suppress the notification.
+/// A matching allow filter wins over any matching deny filter.
+pub fn is_filtered(filters: &[NotificationFilter], data: &NotificationData) -> bool {
+    let matching = |action: FilterAction| filters.iter().any(|f| f.action == action && f.matches(data));
+    !matching(FilterAction::Allow) && matching(FilterAction::Deny)
+}
 
-        let mut state = self.state.write().await;
-        
-        for icon_path in &state.temporary_icons {
-            if icon_path.exists() {
-                if let Err(e) = fs::remove_file(icon_path) {
-                    warn!("Failed to remove temporary icon {}: {}", icon_path.display(), e);
-                }
+/// A notification as recorded in history
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct NotificationRecord {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub sender_name: Option<String>,
+    pub conversation_id: Option<String>,
+    /// Unix timestamp (ms) the notification was received
+    pub timestamp: u64,
+    /// `true` if DND, a filter, a mute or the rate limit kept it from being shown
+    pub suppressed: bool,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub account_name: Option<String>,
+}
+
+/// Maximum number of notifications kept in history
+const MAX_HISTORY: usize = 200;
+
+/// Conversations named in a digest summary before the rest are counted as "more"
+const DIGEST_SUMMARY_GROUPS: usize = 3;
+
+/// Notifications held back by one DND period from one conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct DigestGroup {
+    pub conversation_id: Option<String>,
+    /// Conversation (or sender) name, from the notification title
+    pub name: String,
+    pub count: usize,
+}
+
+/// What the last (or current) DND period held back, busiest conversation first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+pub struct DndDigest {
+    pub total: usize,
+    pub groups: Vec<DigestGroup>,
+}
+
+impl DndDigest {
+    /// Groups suppressed records by conversation, falling back to the title for
+    /// notifications without one
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a NotificationRecord>) -> Self {
+        let mut groups: Vec<DigestGroup> = Vec::new();
+        for record in records.into_iter().filter(|r| r.suppressed) {
+            let key = record.conversation_id.clone();
+            let group = groups.iter_mut().find(|g| match &key {
+                Some(_) => g.conversation_id == key,
+                None => g.conversation_id.is_none() && g.name == record.title,
+            });
+            match group {
+                Some(group) => group.count += 1,
+                None => groups.push(DigestGroup {
+                    conversation_id: key,
+                    name: record.title.clone(),
+                    count: 1,
+                }),
             }
         }
-        
-        state.temporary_icons.clear();
-        
-        info!("Notification service cleanup complete");
-        Ok(())
+        // Stable, so equally busy conversations keep their arrival order
+        groups.sort_by(|a, b| b.count.cmp(&a.count));
+        Self {
+            total: groups.iter().map(|g| g.count).sum(),
+            groups,
+        }
     }
 
-    // Platform-specific notification implementations
-    #[cfg(target_os = "macos")]
-    async fn show_native_macos(&self, payload: &NotificationPayload) -> Result<()> {
-        debug!("Showing macOS native notification");
+    /// One-line summary, e.g. "3 messages from Alice, 5 from Project group".
+    /// With `private`, only the count is given.
+    pub fn summary(&self, private: bool) -> Option<String> {
+        if self.total == 0 {
+            return None;
+        }
+        let plural = |n: usize| if n == 1 { "message" } else { "messages" };
+        if private {
+            return Some(format!("{} {} while Do Not Disturb was on", self.total, plural(self.total)));
+        }
+        let mut parts: Vec<String> = self
+            .groups
+            .iter()
+            .take(DIGEST_SUMMARY_GROUPS)
+            .enumerate()
+            .map(|(i, g)| match i {
+                0 => format!("{} {} from {}", g.count, plural(g.count), g.name),
+                _ => format!("{} from {}", g.count, g.name),
+            })
+            .collect();
+        let rest: usize = self.groups.iter().skip(DIGEST_SUMMARY_GROUPS).map(|g| g.count).sum();
+        if rest > 0 {
+            parts.push(format!("{} more", rest));
+        }
+        Some(parts.join(", "))
+    }
+}
 
-        // Use NSUserNotification on macOS
-        // Cocoa bindings or user_notifications crate
-        
-        info!(
-            "macOS notification: {} - {}",
-            payload.title, payload.body
-        );
+/// Do Not Disturb schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DNDSchedule {
+    pub start_time: String, // HH:MM format
+    pub end_time: String,   // HH:MM format
+}
 
-        // In a real implementation:
-        // let notification = NSUserNotification::new(nil);
-        // notification.setTitle(payload.title.to_nsstring());
-        // notification.setInformativeText(payload.body.to_nsstring());
-        // 
-        // if let Some(icon_path) = &payload.icon_path {
-        //     // Set icon from file
-        // }
-        // 
-        // let center = NSUserNotificationCenter::defaultUserNotificationCenter(nil);
-        // center.scheduleNotification(notification);
+impl DNDSchedule {
+    /// Creates a schedule, rejecting times that aren't HH:MM
+    pub fn new(start_time: &str, end_time: &str) -> Result<Self> {
+        for time in [start_time, end_time] {
+            Self::parse_time(time).ok_or_else(|| anyhow::anyhow!("Invalid time {:?}, expected HH:MM", time))?;
+        }
+        Ok(Self {
+            start_time: start_time.to_string(),
+            end_time: end_time.to_string(),
+        })
+    }
 
-        Ok(())
+    fn parse_time(time: &str) -> Option<chrono::NaiveTime> {
+        chrono::NaiveTime::parse_from_str(time, "%H:%M").ok()
     }
 
-    #[cfg(target_os = "windows")]
-    async fn show_native_windows(&self, payload: &NotificationPayload) -> Result<()> {
-        debug!("Showing Windows native notification");
+    /// Whether `time` falls in the schedule. A start after the end is an overnight
+    /// schedule (e.g. 22:00–07:00). Unparseable times never match.
+    pub fn contains(&self, time: chrono::NaiveTime) -> bool {
+        let (Some(start), Some(end)) = (Self::parse_time(&self.start_time), Self::parse_time(&self.end_time)) else {
+            return false;
+        };
+        if start <= end {
+            time >= start && time <= end
+        } else {
+            // Overnight schedule
+            time >= start || time <= end
+        }
+    }
+}
 
-        // Use Windows Notification API on Windows 10+
-        // windows-rs or winapi crate
-        
-        info!(
-            "Windows notification: {} - {}",
-            payload.title, payload.body
-        );
+/// Payload emitted with `dnd-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct DndChangedPayload {
+    pub enabled: bool,
+    /// Unix timestamp (ms) at which a timed DND ends, if any
+    pub until: Option<u64>,
+}
 
-        // In a real implementation:
-        // let notifier = ToastNotificationManager::CreateToastNotifier().unwrap();
-        // let xml = Self::create_toast_xml(payload);
-        // let notification = ToastNotification::from_xml(&xml).unwrap();
-        // notifier.show(&notification).unwrap();
+/// Payload emitted (once per session) with `notifications-degraded`
+#[derive(Serialize, Clone, Debug)]
+pub struct NotificationsDegradedPayload {
+    /// Why the native display failed the first time
+    pub error: String,
+}
 
-        Ok(())
+/// How a notification ended up on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayPath {
+    /// The platform's own notification API
+    Native,
+    /// The notification plugin, after the native display failed
+    Fallback,
+    /// Neither could show it
+    Failed,
+}
+
+/// Shows through `fallback` only when the native display failed
+fn display_with_fallback(native: &Result<()>, fallback: impl FnOnce() -> Result<()>) -> (DisplayPath, Option<anyhow::Error>) {
+    if native.is_ok() {
+        return (DisplayPath::Native, None);
+    }
+    match fallback() {
+        Ok(()) => (DisplayPath::Fallback, None),
+        Err(e) => (DisplayPath::Failed, Some(e)),
     }
+}
 
-    #[cfg(target_os = "linux")]
-    async fn show_native_linux(&self, payload: &NotificationPayload) -> Result<()> {
-        debug!("Showing Linux native notification");
+/// Native display failures this session. Only the first one is reported to the UI.
+#[derive(Debug, Default)]
+pub struct NativeFailures {
+    count: std::sync::atomic::AtomicU32,
+}
 
-        // Use D-Bus notification interface (freedesktop spec)
-        // dbus crate or zbus crate
-        
-        info!(
-            "Linux notification: {} - {}",
-            payload.title, payload.body
-        );
+impl NativeFailures {
+    /// Counts a failure; true for the first of the session
+    pub fn record(&self) -> bool {
+        self.count.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0
+    }
+}
 
-        // In a real implementation:
-        // let connection = zbus::Connection::session().await?;
-        // let notification = zbus::Message::new_signal(
-        //     "/org/freedesktop/Notifications",
-        //     "org.freedesktop.Notifications",
-        //     "Notify",
-        // )?;
-        // 
-        // // Build notification payload and send via D-Bus
+/// Most notifications tracked as still shown; the OS drops older ones too
+const MAX_ACTIVE: usize = 100;
 
-        Ok(())
+/// A notification that may still be in the notification center
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveNotification {
+    pub id: String,
+    /// Id the OS knows it by (the plugin's numeric id, the D-Bus id on Linux)
+    pub native_id: i32,
+    pub conversation_id: Option<String>,
+}
+
+/// Stable positive numeric id for a notification id, for APIs that only take numbers
+pub fn native_id(id: &str) -> i32 {
+    // FNV-1a, folded into the positive i32 range
+    let hash = id.bytes().fold(0x811c9dc5u32, |h, b| (h ^ b as u32).wrapping_mul(0x01000193));
+    (hash & 0x7fff_ffff).max(1) as i32
+}
+
+/// Shown notifications by id, oldest first. The source of truth for what can be
+/// dismissed: entries leave it before the OS is asked to remove them.
+#[derive(Debug, Clone, Default)]
+pub struct ActiveNotifications {
+    entries: Vec<ActiveNotification>,
+}
+
+impl ActiveNotifications {
+    /// Tracks a shown notification, replacing one with the same id
+    pub fn insert(&mut self, data: &NotificationData) -> i32 {
+        self.remove(&data.id);
+        let native_id = native_id(&data.id);
+        self.entries.push(ActiveNotification {
+            id: data.id.clone(),
+            native_id,
+            conversation_id: data.conversation_id.clone(),
+        });
+        let overflow = self.entries.len().saturating_sub(MAX_ACTIVE);
+        self.entries.drain(..overflow);
+        native_id
     }
 
-    // Helper to create toast XML for Windows
-    #[cfg(target_os = "windows")]
-    fn create_toast_xml(payload: &NotificationPayload) -> String {
-        let icon_xml = payload
-            .icon_path
-            .as_ref()
-            .map(|icon| format!(r#"<image id="1" src="{}"/>"#, icon))
-            .unwrap_or_default();
+    pub fn remove(&mut self, id: &str) -> Option<ActiveNotification> {
+        let index = self.entries.iter().position(|e| e.id == id)?;
+        Some(self.entries.remove(index))
+    }
 
-        let body_xml = if payload.sender_name.is_some() {
-            format!(
-                r#"<text id="1">{}</text>
-            <text id="2">{}</text>"#,
-                payload.sender_name.as_ref().unwrap(),
-                payload.body
-            )
-        } else {
-            format!(r#"<text id="1">{}</text>"#, payload.body)
-        };
+    /// Removes and returns the conversation's notifications
+    pub fn remove_conversation(&mut self, conversation_id: &str) -> Vec<ActiveNotification> {
+        let (taken, kept) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|e| e.conversation_id.as_deref() == Some(conversation_id));
+        self.entries = kept;
+        taken
+    }
 
-        format!(
-            r#"<?xml version="1.0" encoding="utf-8"?>
-<toast>
-    <visual>
-        <binding template="ToastGeneric">
-            <text>{}</text>
-            {}
-            {}
-        </binding>
-    </visual>
-</toast>"#,
-            payload.title, body_xml, icon_xml
-        )
+    pub fn clear(&mut self) -> Vec<ActiveNotification> {
+        std::mem::take(&mut self.entries)
     }
 }
 
-impl Default for NotificationService {
-    fn default() -> Self {
-        // Use standard app data directory
-        let app_data_dir = std::env::var("HOME")
-            .ok()
-            .map(PathBuf::from)
-            .unwrap_or_else(|| PathBuf::from("."));
-
-        Self::new(app_data_dir)
+/// Asks the OS to remove each notification, logging the ones it couldn't (most
+/// often because the user already dismissed them)
+fn dismiss_each<F>(entries: &[ActiveNotification], dispatch: F)
+where
+    F: Fn(&str, i32) -> std::result::Result<(), String>,
+{
+    for entry in entries {
+        if let Err(e) = dispatch(&entry.id, entry.native_id) {
+            debug!("Failed to dismiss notification {}: {}", entry.id, e);
+        }
     }
 }
 
-// Notification payload structure
+/// Notification service state
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
-struct NotificationPayload {
-    #[allow(dead_code)]
-    id: String,
-    title: String,
-    body: String,
-    #[allow(dead_code)]
-    icon_path: Option<String>,
-    #[allow(dead_code)]
-    conversation_id: Option<String>,
+pub struct NotificationState {
+    pub settings: NotificationSettings,
     #[allow(dead_code)]
-    sender_name: Option<String>,
+    pub temporary_icons: Vec<PathBuf>,
+    /// Expiry (Unix ms) of a timed DND started with `set_dnd_until`
+    pub dnd_until: Option<u64>,
+    /// Bumped on every DND change so a stale expiry timer knows it was superseded
+    pub dnd_generation: u64,
+    /// Most recent notifications, oldest first
+    pub history: Vec<NotificationRecord>,
+    /// Set while the app is locked: previews are hidden regardless of `show_preview`
+    pub previews_suppressed: bool,
+    /// Ids of the notifications held back by the current or last DND period
+    pub dnd_held: Vec<String>,
+    /// Toast buttons: the built-ins followed by any registered custom actions
+    pub actions: Vec<NotificationAction>,
+    /// Set when DND ends until its digest summary has been shown
+    pub digest_pending: bool,
+    /// Rate limiter for shown notifications
+    bucket: TokenBucket,
+    /// Most recent notification the rate limiter held back, and how many it held
+    held: Option<(NotificationData, u32)>,
+    /// Set while a flush of the held notification is scheduled
+    flush_scheduled: bool,
+    /// Set while a call keeps sounds quiet (see `calls`)
+    sounds_paused: bool,
+    /// Notifications that may still be in the notification center
+    active: ActiveNotifications,
+}
+
+/// Body shown instead of the message when previews are hidden
+const HIDDEN_PREVIEW_BODY: &str = "New message.";
+
+/// Title shown instead of the sender when the platform isn't known
+const GENERIC_TITLE: &str = "Messenger Desktop";
+
+/// How many times the taskbar button flashes for a mention
+const MENTION_FLASH_COUNT: u32 = 5;
+
+/// File in the app data dir holding the notification settings
+const SETTINGS_FILE: &str = "notification_settings.json";
+
+/// Current time as a Unix timestamp in milliseconds
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// File in the app data dir holding the timed DND expiry
+const DND_UNTIL_FILE: &str = "dnd_until.json";
+
+/// Hooks the platforms' message composers and reports each successful send to
+/// `play_sent_sound`. A send counts as successful once the composer clears.
+pub const SENT_SOUND_HOOK_JS: &str = r#"
+(function() {
+    if (window.__MD_SENT_HOOK__) return;
+    window.__MD_SENT_HOOK__ = true;
+
+    const COMPOSER = '[role="textbox"][contenteditable="true"], [data-testid="dmComposerTextInput"], textarea[placeholder]';
+    const SEND_BUTTON = '[aria-label="Press enter to send"], [aria-label="Send"], [data-testid="dmComposerSendButton"]';
+
+    function text(composer) {
+        return (composer.value !== undefined ? composer.value : composer.textContent || '').trim();
+    }
+
+    function watchSend(composer) {
+        if (!composer || !text(composer)) return;
+        setTimeout(function() {
+            if (text(composer)) return; // still there: the send didn't go through
+            const core = window.__TAURI__ && window.__TAURI__.core;
+            if (core) core.invoke('play_sent_sound').catch(function() {});
+        }, 300);
+    }
+
+    document.addEventListener('keydown', function(e) {
+        if (e.key !== 'Enter' || e.shiftKey || e.isComposing) return;
+        watchSend(e.target.closest && e.target.closest(COMPOSER));
+    }, true);
+
+    document.addEventListener('click', function(e) {
+        if (!e.target.closest || !e.target.closest(SEND_BUTTON)) return;
+        watchSend(document.querySelector(COMPOSER));
+    }, true);
+})();
+"#;
+
+/// Reports the conversation open in the page whenever it is being looked at (the
+/// window focused and visible), so `report_conversation_read` can clear its
+/// notifications.
+pub const THREAD_READ_HOOK_JS: &str = r#"
+(function() {
+    if (window.__MD_THREAD_READ_HOOK__) return;
+    window.__MD_THREAD_READ_HOOK__ = true;
+
+    // /t/<id>, /e2ee/t/<id>, /direct/t/<id>, /messages/t/<id>, /messages/e2ee/t/<id>, /messages/<id>
+    const CONVERSATION = /\/(?:direct\/t|messages\/e2ee\/t|messages\/t|e2ee\/t|t|messages)\/([^\/]+)/;
+    let lastReported = null;
+
+    function check() {
+        if (document.visibilityState !== 'visible' || !document.hasFocus()) {
+            lastReported = null;
+            return;
+        }
+        const match = location.pathname.match(CONVERSATION);
+        const id = match ? decodeURIComponent(match[1]) : null;
+        if (!id || id === lastReported) return;
+        lastReported = id;
+        const core = window.__TAURI__ && window.__TAURI__.core;
+        if (core) core.invoke('report_conversation_read', { conversationId: id }).catch(function() {});
+    }
+
+    // SPA navigation doesn't reload the page; a cheap poll catches it
+    setInterval(check, 1000);
+    window.addEventListener('focus', check);
+    document.addEventListener('visibilitychange', check);
+})();
+"#;
+
+/// Native Notification Service - manages OS-native notifications
+#[allow(dead_code)]
+pub struct NotificationService {
+    state: Arc<RwLock<NotificationState>>,
     #[allow(dead_code)]
-    silent: bool,
+    app_data_dir: PathBuf,
+    /// Shared client and cache for notification icons
+    icons: Arc<IconCache>,
+    /// Icon being fetched ahead for the held notification: its URL and the task
+    icon_prefetch: std::sync::Mutex<Option<(String, tauri::async_runtime::JoinHandle<()>)>>,
+    /// App handle for the plugin fallback and the `notifications-degraded` event;
+    /// set once the app is up (see `attach`)
+    app: std::sync::OnceLock<AppHandle>,
+    native_failures: NativeFailures,
+}
+
+impl NotificationService {
+    /// Create a new notification service
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        let mut settings: NotificationSettings = store::load_or_default(&app_data_dir.join(SETTINGS_FILE));
+        if settings.rate_limit.per_second == 0 || settings.rate_limit.burst == 0 {
+            settings.rate_limit = RateLimit::default();
+        }
+
+        // Mutes and priorities that ran out while the app was closed
+        let now = now_millis();
+        settings.muted_conversations.retain(|_, until| until.is_none_or(|until| until > now));
+        settings.prioritized_conversations.retain(|_, until| until.is_none_or(|until| until > now));
+
+        // Resume a timed DND only if it hasn't expired while the app was closed
+        let dnd_until = store::load::<u64>(&app_data_dir.join(DND_UNTIL_FILE))
+            .filter(|until| *until > now);
+        if dnd_until.is_some() {
+            settings.do_not_disturb = true;
+        }
+        let bucket = TokenBucket::full(settings.rate_limit);
+
+        Self {
+            state: Arc::new(RwLock::new(NotificationState {
+                settings,
+                temporary_icons: Vec::new(),
+                dnd_until,
+                dnd_generation: 0,
+                history: Vec::new(),
+                previews_suppressed: false,
+                dnd_held: Vec::new(),
+                actions: NotificationAction::builtin(),
+                digest_pending: false,
+                bucket,
+                held: None,
+                flush_scheduled: false,
+                sounds_paused: false,
+                active: ActiveNotifications::default(),
+            })),
+            icons: Arc::new(IconCache::new(app_data_dir.join("notification_icons"))),
+            icon_prefetch: std::sync::Mutex::new(None),
+            app: std::sync::OnceLock::new(),
+            native_failures: NativeFailures::default(),
+            app_data_dir,
+        }
+    }
+
+    /// Gives the service the app handle it needs to fall back to the notification
+    /// plugin when the native display fails
+    pub fn attach(&self, app: AppHandle) {
+        let _ = self.app.set(app);
+        // Nothing else holds the state this early
+        if let Ok(state) = self.state.try_read() {
+            self.register_app_id(&state.settings.toast_grouping);
+        }
+    }
+
+    /// The shared HTTP client the notification icons are downloaded with
+    pub fn http_client(&self) -> reqwest::Client {
+        self.icons.client()
+    }
+
+    /// The app identity toasts are shown under
+    fn toast_app_id(&self, grouping: &ToastGrouping) -> String {
+        grouping
+            .app_id
+            .clone()
+            .or_else(|| self.app.get().map(|app| app.config().identifier.clone()))
+            .unwrap_or_default()
+    }
+
+    /// Tells Windows which app identity the process's toasts belong to
+    fn register_app_id(&self, grouping: &ToastGrouping) {
+        let app_id = self.toast_app_id(grouping);
+        if app_id.is_empty() {
+            return;
+        }
+        if let Err(e) = crate::platform::register_app_id(&app_id) {
+            warn!("Failed to register app identity {}: {}", app_id, e);
+        }
+    }
+
+    /// Enable/disable holding notifications back during the system's own DND
+    pub async fn set_respect_system_dnd(&self, enabled: bool) -> Result<()> {
+        self.state.write().await.settings.respect_system_dnd = enabled;
+        self.persist().await?;
+
+        info!("Respecting system DND {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Set how toasts are grouped in the Windows Action Center, and the app
+    /// identity they are shown under (`None` for the bundle identifier)
+    pub async fn set_toast_grouping(&self, grouping: ToastGrouping) -> Result<()> {
+        if let Some(app_id) = &grouping.app_id {
+            validate_app_id(app_id)?;
+        }
+        self.state.write().await.settings.toast_grouping = grouping.clone();
+        self.persist().await?;
+        self.register_app_id(&grouping);
+
+        info!("Toast grouping {}", if grouping.enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Show a native notification
+    pub async fn show_notification(&self, data: NotificationData) -> Result<()> {
+        debug!("Showing notification: {}", data.title);
+
+        // Take one consistent snapshot of the settings; the guard is released immediately
+        let settings = self.get_settings().await;
+
+        if !self.admit(&data, &settings).await {
+            return Ok(());
+        }
+
+        // Download and prepare icon if provided
+        let icon_path = if let Some(icon_url) = &data.icon_url {
+            self.download_and_save_icon(icon_url, &data.id).await?
+        } else {
+            None
+        };
+
+        // Prepare notification payload
+        let (title, body) = self.display_text(&data, &settings).await;
+        let actions = toast_actions(&self.state.read().await.actions, &settings, &data, supports_actions());
+        let payload = NotificationPayload {
+            id: data.id.clone(),
+            title,
+            body,
+            icon_path,
+            conversation_id: data.conversation_id.clone(),
+            sender_name: data.sender_name.clone(),
+            silent: data.silent,
+            actions,
+        };
+
+        // Show the notification using platform-specific implementation, and through
+        // the notification plugin if that fails (no D-Bus session, no WinRT identity)
+        let native = self.show_native(&payload).await;
+        let (path, fallback_error) = display_with_fallback(&native, || self.show_fallback(&payload));
+        if let Err(e) = &native {
+            warn!("Native notification failed ({:?}): {}", path, e);
+            self.on_native_failure(e);
+        }
+        if let Some(e) = fallback_error {
+            warn!("Notification could not be shown: native display and the plugin both failed: {}", e);
+            return Ok(());
+        }
+
+        self.track(&data).await;
+        self.play_sound_if_enabled(&settings).await?;
+
+        info!("Notification shown: {} - {}", data.title, data.body);
+        Ok(())
+    }
+
+    /// Tracks a shown notification so it can be dismissed later. Returns the id to
+    /// show it under.
+    pub async fn track(&self, data: &NotificationData) -> i32 {
+        self.state.write().await.active.insert(data)
+    }
+
+    /// Removes a still-shown notification. False if it isn't tracked (already
+    /// dismissed, or too old).
+    pub async fn dismiss<F>(&self, id: &str, dispatch: F) -> bool
+    where
+        F: Fn(&str, i32) -> std::result::Result<(), String>,
+    {
+        let Some(entry) = self.state.write().await.active.remove(id) else {
+            return false;
+        };
+        dismiss_each(&[entry], dispatch);
+        true
+    }
+
+    /// Removes a conversation's outstanding notifications, e.g. once it was read.
+    /// Returns how many were dismissed.
+    pub async fn dismiss_conversation<F>(&self, conversation_id: &str, dispatch: F) -> usize
+    where
+        F: Fn(&str, i32) -> std::result::Result<(), String>,
+    {
+        let entries = self.state.write().await.active.remove_conversation(conversation_id);
+        dismiss_each(&entries, dispatch);
+        entries.len()
+    }
+
+    /// Removes every still-shown notification. Returns how many were dismissed.
+    pub async fn dismiss_all<F>(&self, dispatch: F) -> usize
+    where
+        F: Fn(&str, i32) -> std::result::Result<(), String>,
+    {
+        let entries = self.state.write().await.active.clear();
+        dismiss_each(&entries, dispatch);
+        entries.len()
+    }
+
+    /// Decides whether a notification may be shown, recording it in history.
+    /// Notifications kept back by a filter, a conversation mute or DND are recorded as suppressed.
+    pub async fn admit(&self, data: &NotificationData, settings: &NotificationSettings) -> bool {
+        if !settings.enabled {
+            info!("Notifications disabled, skipping: {}", data.title);
+            return false;
+        }
+
+        // Check sender / keyword filters
+        if is_filtered(&settings.filters, data) {
+            info!("Filtered, suppressing notification: {}", data.title);
+            self.record(data, true).await;
+            return false;
+        }
+
+        // A prioritized conversation breaks through mutes and DND
+        let prioritized = data
+            .conversation_id
+            .as_deref()
+            .is_some_and(|id| is_conversation_prioritized(settings, id, now_millis()));
+        if prioritized {
+            info!("Conversation prioritized, showing notification: {}", data.title);
+        } else if !self.admit_past_dnd(data, settings).await {
+            return false;
+        }
+
+        // Flood protection: past the rate limit, only the latest is kept for later
+        if !self.take_token(settings.rate_limit).await {
+            self.record(data, true).await;
+            self.hold(data).await;
+            return false;
+        }
+
+        self.record(data, false).await;
+        true
+    }
+
+    /// Applies the conversation mute and DND (manual and scheduled), recording what
+    /// they hold back
+    async fn admit_past_dnd(&self, data: &NotificationData, settings: &NotificationSettings) -> bool {
+        // Check per-conversation mute
+        if let Some(conversation_id) = &data.conversation_id {
+            if is_conversation_muted(settings, conversation_id, now_millis()) {
+                info!("Conversation muted, suppressing notification: {}", data.title);
+                self.record(data, true).await;
+                return false;
+            }
+        }
+
+        // Check Do Not Disturb mode
+        if settings.do_not_disturb {
+            info!("DND active, suppressing notification: {}", data.title);
+            self.record_held_by_dnd(data).await;
+            return false;
+        }
+
+        // The system's own DND; it ends on its own, so nothing is kept for a digest
+        if held_by_system_dnd(settings, crate::platform::system_dnd_active) {
+            info!("System DND active, suppressing notification: {}", data.title);
+            self.record(data, true).await;
+            return false;
+        }
+
+        // Check the DND schedule of the notification's platform, or the global one
+        if let Some(schedule) = settings.dnd_schedule_for(data.platform.as_deref()) {
+            if self.is_in_dnd_schedule(schedule) {
+                info!("In DND schedule, suppressing notification: {}", data.title);
+                self.record_held_by_dnd(data).await;
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Takes a rate limiter token, if one is available
+    async fn take_token(&self, limit: RateLimit) -> bool {
+        self.state.write().await.bucket.try_take(limit, Instant::now())
+    }
+
+    /// Holds a throttled notification, replacing any older one
+    async fn hold(&self, data: &NotificationData) {
+        let mut state = self.state.write().await;
+        let count = match state.held.take() {
+            Some((_, count)) => count + 1,
+            None => {
+                warn!("Notification rate limit reached, throttling");
+                1
+            }
+        };
+        state.held = Some((data.clone(), count));
+        drop(state);
+        self.prefetch_icon(data);
+    }
+
+    /// Starts fetching the held notification's icon, cancelling the download for the
+    /// one it replaced unless both use the same icon
+    fn prefetch_icon(&self, data: &NotificationData) {
+        let Ok(mut prefetch) = self.icon_prefetch.lock() else {
+            return;
+        };
+        if let Some((url, task)) = prefetch.take() {
+            if data.icon_url.as_deref() == Some(url.as_str()) {
+                *prefetch = Some((url, task));
+                return;
+            }
+            debug!("Cancelling icon download for superseded notification: {}", url);
+            task.abort();
+        }
+        *prefetch = data
+            .icon_url
+            .as_ref()
+            .map(|url| (url.clone(), self.icons.prefetch(url)));
+    }
+
+    /// Schedules the held notification to be shown once a token frees up. Returns
+    /// the delay, or `None` if nothing is held or a flush is already scheduled.
+    pub async fn schedule_flush(&self) -> Option<Duration> {
+        let mut state = self.state.write().await;
+        if state.held.is_none() || state.flush_scheduled {
+            return None;
+        }
+        state.flush_scheduled = true;
+        Some(state.bucket.wait(state.settings.rate_limit))
+    }
+
+    /// Takes the most recent throttled notification, with a "+N more" note when it
+    /// stands in for several, spending a token on it
+    pub async fn take_held(&self) -> Option<NotificationData> {
+        let mut state = self.state.write().await;
+        state.flush_scheduled = false;
+        let (mut data, count) = state.held.take()?;
+        // The flush picks up the prefetched icon from the cache (or joins its download)
+        if let Ok(mut prefetch) = self.icon_prefetch.lock() {
+            prefetch.take();
+        }
+        let limit = state.settings.rate_limit;
+        state.bucket.try_take(limit, Instant::now());
+        if count > 1 {
+            data.body = format!("{} (+{} more)", data.body, count - 1);
+        }
+        info!("Showing latest of {} throttled notifications", count);
+        Some(data)
+    }
+
+    /// Set the notification rate limit
+    pub async fn set_rate_limit(&self, limit: RateLimit) -> Result<()> {
+        if limit.per_second == 0 || limit.burst == 0 {
+            return Err(anyhow::anyhow!("Rate limit must allow at least one notification"));
+        }
+        {
+            let mut state = self.state.write().await;
+            state.settings.rate_limit = limit;
+            state.bucket = TokenBucket::full(limit);
+        }
+        self.persist().await?;
+
+        info!("Notification rate limit: {}/s, burst {}", limit.per_second, limit.burst);
+        Ok(())
+    }
+
+    /// Mute a conversation for `minutes` (0 = until unmuted)
+    pub async fn mute_conversation(&self, conversation_id: String, minutes: u32) -> Result<()> {
+        let duration = (minutes > 0).then(|| Duration::from_secs(u64::from(minutes) * 60));
+        self.mute_conversation_for(conversation_id, duration).await
+    }
+
+    async fn mute_conversation_for(&self, conversation_id: String, duration: Option<Duration>) -> Result<()> {
+        if conversation_id.trim().is_empty() {
+            return Err(anyhow::anyhow!("Conversation id must not be empty"));
+        }
+        let expires_at = duration.map(|d| now_millis() + d.as_millis() as u64);
+        self.state
+            .write()
+            .await
+            .settings
+            .muted_conversations
+            .insert(conversation_id.clone(), expires_at);
+        self.persist().await?;
+
+        if let Some(expires_at) = expires_at {
+            let state = self.state.clone();
+            let id = conversation_id.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(expires_at.saturating_sub(now_millis()))).await;
+                let mut state = state.write().await;
+                // Only clear the mute this timer was started for; a re-mute replaces it
+                if state.settings.muted_conversations.get(&id) == Some(&Some(expires_at)) {
+                    state.settings.muted_conversations.remove(&id);
+                    info!("Conversation mute expired: {}", id);
+                }
+            });
+        }
+
+        info!("Conversation muted: {}", conversation_id);
+        Ok(())
+    }
+
+    /// Unmute a conversation
+    pub async fn unmute_conversation(&self, conversation_id: &str) -> Result<()> {
+        self.state
+            .write()
+            .await
+            .settings
+            .muted_conversations
+            .remove(conversation_id)
+            .ok_or_else(|| anyhow::anyhow!("Conversation is not muted: {}", conversation_id))?;
+        self.persist().await?;
+
+        info!("Conversation unmuted: {}", conversation_id);
+        Ok(())
+    }
+
+    /// Let a conversation through DND and mutes for `minutes` (0 = until cleared)
+    pub async fn prioritize_conversation(&self, conversation_id: String, minutes: u32) -> Result<()> {
+        let duration = (minutes > 0).then(|| Duration::from_secs(u64::from(minutes) * 60));
+        self.prioritize_conversation_for(conversation_id, duration).await
+    }
+
+    async fn prioritize_conversation_for(&self, conversation_id: String, duration: Option<Duration>) -> Result<()> {
+        if conversation_id.trim().is_empty() {
+            return Err(anyhow::anyhow!("Conversation id must not be empty"));
+        }
+        let expires_at = duration.map(|d| now_millis() + d.as_millis() as u64);
+        self.state
+            .write()
+            .await
+            .settings
+            .prioritized_conversations
+            .insert(conversation_id.clone(), expires_at);
+        self.persist().await?;
+
+        if let Some(expires_at) = expires_at {
+            let state = self.state.clone();
+            let id = conversation_id.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(expires_at.saturating_sub(now_millis()))).await;
+                let mut state = state.write().await;
+                // Only clear the priority this timer was started for; a re-prioritize replaces it
+                if state.settings.prioritized_conversations.get(&id) == Some(&Some(expires_at)) {
+                    state.settings.prioritized_conversations.remove(&id);
+                    info!("Conversation priority expired: {}", id);
+                }
+            });
+        }
+
+        info!("Conversation prioritized: {}", conversation_id);
+        Ok(())
+    }
+
+    /// Remove a conversation's priority
+    pub async fn clear_priority(&self, conversation_id: &str) -> Result<()> {
+        self.state
+            .write()
+            .await
+            .settings
+            .prioritized_conversations
+            .remove(conversation_id)
+            .ok_or_else(|| anyhow::anyhow!("Conversation is not prioritized: {}", conversation_id))?;
+        self.persist().await?;
+
+        info!("Conversation priority cleared: {}", conversation_id);
+        Ok(())
+    }
+
+    /// Appends a notification to history, dropping the oldest beyond `MAX_HISTORY`
+    async fn record(&self, data: &NotificationData, suppressed: bool) {
+        let mut state = self.state.write().await;
+        state.history.push(NotificationRecord {
+            id: data.id.clone(),
+            title: data.title.clone(),
+            body: data.body.clone(),
+            sender_name: data.sender_name.clone(),
+            conversation_id: data.conversation_id.clone(),
+            timestamp: data.timestamp.unwrap_or_else(now_millis),
+            suppressed,
+            account_id: data.account_id.clone(),
+            account_name: data.account_name.clone(),
+        });
+        let overflow = state.history.len().saturating_sub(MAX_HISTORY);
+        state.history.drain(..overflow);
+    }
+
+    /// Records a notification DND kept back, for the digest
+    async fn record_held_by_dnd(&self, data: &NotificationData) {
+        self.record(data, true).await;
+        self.state.write().await.dnd_held.push(data.id.clone());
+    }
+
+    /// Get notification history, oldest first
+    pub async fn get_history(&self) -> Vec<NotificationRecord> {
+        self.state.read().await.history.clone()
+    }
+
+    /// What the current or last DND period held back, grouped by conversation.
+    /// Only what is still in history is counted.
+    pub async fn get_dnd_digest(&self) -> DndDigest {
+        let state = self.state.read().await;
+        DndDigest::from_records(state.history.iter().filter(|r| state.dnd_held.contains(&r.id)))
+    }
+
+    /// The summary to show now that DND has ended, if the digest is enabled and
+    /// DND held anything back. Returns it once per DND period.
+    pub async fn take_dnd_summary(&self) -> Option<String> {
+        let digest = self.get_dnd_digest().await;
+        let mut state = self.state.write().await;
+        if !std::mem::take(&mut state.digest_pending) || !state.settings.digest_on_dnd_end {
+            return None;
+        }
+        let private = state.previews_suppressed
+            || matches!(state.settings.preview_mode_for(None), PreviewMode::Hidden | PreviewMode::BodyOnly);
+        digest.summary(private)
+    }
+
+    /// Enable/disable the summary shown when DND ends
+    pub async fn set_digest_on_dnd_end(&self, enabled: bool) -> Result<()> {
+        self.state.write().await.settings.digest_on_dnd_end = enabled;
+        self.persist().await?;
+
+        info!("DND digest {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// The toast actions, built-ins first
+    pub async fn get_actions(&self) -> Vec<NotificationAction> {
+        self.state.read().await.actions.clone()
+    }
+
+    /// Register a custom toast action, or retitle one already registered
+    pub async fn register_action(&self, id: String, title: String) -> Result<()> {
+        if id.trim().is_empty() || title.trim().is_empty() {
+            return Err(anyhow::anyhow!("Action id and title must not be empty"));
+        }
+        if NotificationAction::is_builtin(&id) {
+            return Err(anyhow::anyhow!("Cannot replace built-in action: {}", id));
+        }
+        let mut state = self.state.write().await;
+        match state.actions.iter_mut().find(|a| a.id == id) {
+            Some(action) => action.title = title,
+            None => state.actions.push(NotificationAction { id: id.clone(), title }),
+        }
+
+        info!("Notification action registered: {}", id);
+        Ok(())
+    }
+
+    /// Remove a custom toast action
+    pub async fn unregister_action(&self, id: &str) -> Result<()> {
+        if NotificationAction::is_builtin(id) {
+            return Err(anyhow::anyhow!("Cannot remove built-in action: {}", id));
+        }
+        let mut state = self.state.write().await;
+        let before = state.actions.len();
+        state.actions.retain(|a| a.id != id);
+        if state.actions.len() == before {
+            return Err(anyhow::anyhow!("Action not found: {}", id));
+        }
+
+        info!("Notification action removed: {}", id);
+        Ok(())
+    }
+
+    /// Handles a clicked toast button: built-in mute is applied here, and every
+    /// action is returned as the `notification-action` payload for the frontend
+    pub async fn invoke_action(&self, action_id: &str, conversation_id: Option<String>) -> Result<NotificationActionPayload> {
+        let payload = action_event(&self.state.read().await.actions, action_id, conversation_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown notification action: {}", action_id))?;
+        if payload.action_id == ACTION_MUTE {
+            let conversation_id = payload
+                .conversation_id
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Mute needs a conversation"))?;
+            self.mute_conversation(conversation_id, MUTE_ACTION_MINUTES).await?;
+        }
+        Ok(payload)
+    }
+
+    /// Add a sender / keyword filter
+    pub async fn add_filter(
+        &self,
+        field: FilterField,
+        action: FilterAction,
+        pattern: String,
+        regex: bool,
+    ) -> Result<NotificationFilter> {
+        if pattern.trim().is_empty() {
+            return Err(anyhow::anyhow!("Filter pattern must not be empty"));
+        }
+        if regex {
+            RegexBuilder::new(&pattern).build()?;
+        }
+
+        let filter = NotificationFilter {
+            id: uuid::Uuid::new_v4().to_string(),
+            field,
+            action,
+            pattern,
+            regex,
+        };
+        self.state.write().await.settings.filters.push(filter.clone());
+        self.persist().await?;
+
+        info!("Notification filter added: {}", filter.id);
+        Ok(filter)
+    }
+
+    /// Remove a filter by id
+    pub async fn remove_filter(&self, id: &str) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            let before = state.settings.filters.len();
+            state.settings.filters.retain(|f| f.id != id);
+            if state.settings.filters.len() == before {
+                return Err(anyhow::anyhow!("Filter not found: {}", id));
+            }
+        }
+        self.persist().await?;
+
+        info!("Notification filter removed: {}", id);
+        Ok(())
+    }
+
+    /// Local path of a notification's icon: downloaded through the shared client,
+    /// or reused from the icon cache. A failed download shows the notification
+    /// without an icon.
+    async fn download_and_save_icon(&self, url: &str, notification_id: &str) -> Result<Option<String>> {
+        debug!("Fetching icon for {} from: {}", notification_id, url);
+        Ok(self.icons.fetch(url).await)
+    }
+
+    /// Check if current time is within DND schedule
+    fn is_in_dnd_schedule(&self, schedule: &DNDSchedule) -> bool {
+        schedule.contains(chrono::Local::now().time())
+    }
+
+    /// Play the notification sound if sound is enabled in `settings`
+    pub async fn play_sound_if_enabled(&self, settings: &NotificationSettings) -> Result<()> {
+        if settings.sound_enabled && !self.state.read().await.sounds_paused {
+            self.play_notification_sound(&settings.sound_path).await?;
+        }
+        Ok(())
+    }
+
+    /// Play the sent sound, unless it is off or notifications are muted.
+    /// Returns whether a sound was played.
+    pub async fn play_sent_sound(&self) -> Result<bool> {
+        let settings = self.get_settings().await;
+        if !should_play_sent_sound(&settings) || self.state.read().await.sounds_paused {
+            return Ok(false);
+        }
+        self.play_notification_sound(&settings.sent_sound_path).await?;
+        Ok(true)
+    }
+
+    /// Play notification sound
+    async fn play_notification_sound(&self, sound_path: &Option<String>) -> Result<()> {
+        if let Some(path) = sound_path {
+            debug!("Playing notification sound: {}", path);
+            
+            // In a real implementation, you would use:
+            // - macOS:NSSound with file path
+            // - Windows:Windows.Media.Playback
+            // - Linux:pactl or paplay for ALSA/PulseAudio
+            
+            // For now, just log since we can't play sounds in this environment
+            info!("Would play sound from: {}", path);
+        } else {
+            debug!("Playing default notification sound");
+            
+            // Default sound based on platform:
+            // - macOS: NSAlertDefaultSound
+            // - Windows: SystemSound::Notification
+            // - Linux: /usr/share/sounds/generic.wav
+            
+            info!("Would play default system notification sound");
+        }
+        
+        Ok(())
+    }
+
+    /// Pause or resume all sounds, without touching the sound settings
+    pub async fn set_sounds_paused(&self, paused: bool) {
+        self.state.write().await.sounds_paused = paused;
+    }
+
+    /// Set Do Not Disturb mode. Cancels any pending timed DND.
+    pub async fn set_dnd(&self, enabled: bool) -> Result<()> {
+        debug!("Setting DND to: {}", enabled);
+
+        {
+            let mut state = self.state.write().await;
+            Self::on_dnd_transition(&mut state, enabled);
+            state.settings.do_not_disturb = enabled;
+            state.dnd_until = None;
+            state.dnd_generation += 1;
+        }
+        self.persist_dnd_until(None)?;
+        self.persist().await?;
+
+        info!("Do Not Disturb mode: {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Enable Do Not Disturb for `minutes`, turning it back off automatically afterwards.
+    /// `on_expire` runs when the timer turns DND off. Returns the expiry (Unix ms).
+    pub async fn set_dnd_until<F>(&self, minutes: u32, on_expire: F) -> Result<u64>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if minutes == 0 {
+            return Err(anyhow::anyhow!("DND duration must be at least one minute"));
+        }
+        self.set_dnd_for(Duration::from_secs(u64::from(minutes) * 60), on_expire)
+            .await
+    }
+
+    async fn set_dnd_for<F>(&self, duration: Duration, on_expire: F) -> Result<u64>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let expires_at = now_millis() + duration.as_millis() as u64;
+        let generation = {
+            let mut state = self.state.write().await;
+            Self::on_dnd_transition(&mut state, true);
+            state.settings.do_not_disturb = true;
+            state.dnd_until = Some(expires_at);
+            state.dnd_generation += 1;
+            state.dnd_generation
+        };
+        self.persist_dnd_until(Some(expires_at))?;
+        self.persist().await?;
+        self.spawn_dnd_expiry(generation, expires_at, on_expire);
+
+        info!("Do Not Disturb enabled for {}s", duration.as_secs());
+        Ok(expires_at)
+    }
+
+    /// Restarts the expiry timer for a timed DND restored from disk
+    pub async fn resume_dnd_timer<F>(&self, on_expire: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let (generation, dnd_until) = {
+            let state = self.state.read().await;
+            (state.dnd_generation, state.dnd_until)
+        };
+        if let Some(expires_at) = dnd_until {
+            info!("Resuming timed Do Not Disturb");
+            self.spawn_dnd_expiry(generation, expires_at, on_expire);
+        }
+    }
+
+    /// Seconds left on a timed DND, or 0 if none is active
+    pub async fn get_dnd_remaining(&self) -> u64 {
+        self.state
+            .read()
+            .await
+            .dnd_until
+            .map(|until| until.saturating_sub(now_millis()).div_ceil(1000))
+            .unwrap_or(0)
+    }
+
+    fn spawn_dnd_expiry<F>(&self, generation: u64, expires_at: u64, on_expire: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let state = self.state.clone();
+        let store_path = self.app_data_dir.join(DND_UNTIL_FILE);
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(expires_at.saturating_sub(now_millis()))).await;
+
+            let mut state = state.write().await;
+            // A manual set_dnd or a newer timer superseded this one
+            if state.dnd_generation != generation {
+                return;
+            }
+            Self::on_dnd_transition(&mut state, false);
+            state.settings.do_not_disturb = false;
+            state.dnd_until = None;
+            drop(state);
+
+            if let Err(e) = store::remove(&store_path) {
+                warn!("Failed to clear DND expiry: {}", e);
+            }
+            info!("Timed Do Not Disturb expired");
+            on_expire();
+        });
+    }
+
+    /// Starts a new digest when DND turns on, and marks it due when DND turns off
+    fn on_dnd_transition(state: &mut NotificationState, enabled: bool) {
+        match (state.settings.do_not_disturb, enabled) {
+            (false, true) => {
+                state.dnd_held.clear();
+                state.digest_pending = false;
+            }
+            (true, false) => state.digest_pending = true,
+            _ => {}
+        }
+    }
+
+    /// Saves the settings as one document. A timed DND is saved as off: its
+    /// expiry is kept in `DND_UNTIL_FILE`, which turns it back on at startup
+    /// only while it is still due.
+    async fn persist(&self) -> Result<()> {
+        let settings = {
+            let state = self.state.read().await;
+            let mut settings = state.settings.clone();
+            if state.dnd_until.is_some() {
+                settings.do_not_disturb = false;
+            }
+            settings
+        };
+        store::save(&self.app_data_dir.join(SETTINGS_FILE), &settings)?;
+        Ok(())
+    }
+
+    /// Persists (or clears) the timed DND expiry
+    fn persist_dnd_until(&self, until: Option<u64>) -> Result<()> {
+        let path = self.app_data_dir.join(DND_UNTIL_FILE);
+        match until {
+            Some(until) => store::save(&path, &until)?,
+            None => store::remove(&path)?,
+        }
+        Ok(())
+    }
+
+    /// Set notification sound path
+    pub async fn set_notification_sound(&self, path: String) -> Result<()> {
+        debug!("Setting notification sound to: {}", path);
+
+        // Verify the file exists
+        if !PathBuf::from(&path).exists() {
+            warn!("Sound file does not exist: {}", path);
+            return Err(anyhow::anyhow!("Sound file does not exist"));
+        }
+
+        self.state.write().await.settings.sound_path = Some(path);
+        self.persist().await?;
+
+        info!("Notification sound updated");
+        Ok(())
+    }
+
+    /// Go back to the system notification sound
+    pub async fn use_default_sound(&self) -> Result<()> {
+        self.state.write().await.settings.sound_path = None;
+        self.persist().await?;
+
+        info!("Notification sound reset to the system default");
+        Ok(())
+    }
+
+    /// Enable/disable the sent sound
+    pub async fn set_sent_sound_enabled(&self, enabled: bool) -> Result<()> {
+        self.state.write().await.settings.sent_sound_enabled = enabled;
+        self.persist().await?;
+
+        info!("Sent sound {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Set the sent sound path; `None` restores the system default
+    pub async fn set_sent_sound(&self, path: Option<String>) -> Result<()> {
+        if let Some(path) = &path {
+            if !PathBuf::from(path).exists() {
+                warn!("Sound file does not exist: {}", path);
+                return Err(anyhow::anyhow!("Sound file does not exist"));
+            }
+        }
+        self.state.write().await.settings.sent_sound_path = path;
+        self.persist().await?;
+
+        info!("Sent sound updated");
+        Ok(())
+    }
+
+    /// Enable/disable notifications
+    pub async fn set_enabled(&self, enabled: bool) -> Result<()> {
+        debug!("Setting notifications enabled to: {}", enabled);
+
+        self.state.write().await.settings.enabled = enabled;
+        self.persist().await?;
+
+        info!("Notifications {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Set sound enabled state
+    pub async fn set_sound_enabled(&self, enabled: bool) -> Result<()> {
+        debug!("Setting sound enabled to: {}", enabled);
+
+        self.state.write().await.settings.sound_enabled = enabled;
+        self.persist().await?;
+
+        info!("Notification sound {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Hides previews regardless of `show_preview` (used while the app is locked)
+    pub async fn set_previews_suppressed(&self, suppressed: bool) {
+        self.state.write().await.previews_suppressed = suppressed;
+    }
+
+    /// The title and body to display for `data`, following the preview mode for its
+    /// platform. Everything is hidden while previews are suppressed.
+    pub async fn display_text(&self, data: &NotificationData, settings: &NotificationSettings) -> (String, String) {
+        let mode = if self.state.read().await.previews_suppressed {
+            PreviewMode::Hidden
+        } else {
+            settings.preview_mode_for(data.platform.as_deref())
+        };
+        let (title, body) = preview_text(data, mode);
+        (with_account(title, data.account_name.as_deref()), body)
+    }
+
+    /// Set the preview mode, for one platform or (with `None`) as the default
+    pub async fn set_preview_mode(&self, mode: PreviewMode, platform: Option<String>) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            match platform {
+                Some(platform) => {
+                    state.settings.platform_preview_modes.insert(platform, mode);
+                }
+                None => state.settings.preview_mode = mode,
+            }
+        }
+        self.persist().await
+    }
+
+    /// Remove a platform's preview mode override
+    pub async fn clear_preview_mode(&self, platform: &str) -> Result<()> {
+        self.state.write().await.settings.platform_preview_modes.remove(platform);
+        self.persist().await
+    }
+
+    /// Give a platform its own DND schedule, overriding the global one
+    pub async fn set_platform_dnd_schedule(&self, platform: String, schedule: DNDSchedule) -> Result<()> {
+        info!("DND schedule for {}: {}–{}", platform, schedule.start_time, schedule.end_time);
+        self.state.write().await.settings.platform_dnd_schedules.insert(platform, schedule);
+        self.persist().await
+    }
+
+    /// Remove a platform's DND schedule, so the global one applies again
+    pub async fn clear_platform_dnd_schedule(&self, platform: &str) -> Result<()> {
+        self.state.write().await.settings.platform_dnd_schedules.remove(platform);
+        self.persist().await
+    }
+
+    /// Set show preview preference
+    #[allow(dead_code)]
+    pub async fn set_show_preview(&self, enabled: bool) -> Result<()> {
+        debug!("Setting show preview to: {}", enabled);
+
+        self.state.write().await.settings.show_preview = enabled;
+
+        self.persist().await
+    }
+
+    /// Set quick reply preference
+    #[allow(dead_code)]
+    pub async fn set_quick_reply_enabled(&self, enabled: bool) -> Result<()> {
+        debug!("Setting quick reply enabled to: {}", enabled);
+
+        self.state.write().await.settings.quick_reply_enabled = enabled;
+
+        self.persist().await
+    }
+
+    /// Replace all notification settings at once
+    pub async fn apply_settings(&self, mut settings: NotificationSettings) -> Result<()> {
+        debug!("Applying notification settings");
+
+        if settings.rate_limit.per_second == 0 || settings.rate_limit.burst == 0 {
+            settings.rate_limit = RateLimit::default();
+        }
+        self.state.write().await.settings = settings;
+
+        self.persist().await
+    }
+
+    /// Restore the default settings, on disk too, and the default toast app
+    /// identity. Ends any timed DND.
+    pub async fn reset_settings(&self) -> Result<NotificationSettings> {
+        debug!("Resetting notification settings");
+
+        {
+            let mut state = self.state.write().await;
+            state.settings = NotificationSettings::default();
+            state.dnd_until = None;
+            state.dnd_generation += 1;
+            state.bucket = TokenBucket::full(state.settings.rate_limit);
+        }
+        self.persist_dnd_until(None)?;
+        self.persist().await?;
+        self.register_app_id(&ToastGrouping::default());
+
+        Ok(self.get_settings().await)
+    }
+
+    /// Get current notification settings
+    pub async fn get_settings(&self) -> NotificationSettings {
+        self.state.read().await.settings.clone()
+    }
+
+    /// Close the notification service and clean up temporary files
+    #[allow(dead_code)]
+    pub async fn cleanup(&self) -> Result<()> {
+        debug!("Cleaning up notification service");
+
+        let mut state = self.state.write().await;
+        
+        for icon_path in &state.temporary_icons {
+            if icon_path.exists() {
+                if let Err(e) = fs::remove_file(icon_path) {
+                    warn!("Failed to remove temporary icon {}: {}", icon_path.display(), e);
+                }
+            }
+        }
+        
+        state.temporary_icons.clear();
+        
+        info!("Notification service cleanup complete");
+        Ok(())
+    }
+
+    /// Shows `payload` through the platform's own notification API
+    async fn show_native(&self, payload: &NotificationPayload) -> Result<()> {
+        #[cfg(target_os = "macos")]
+        {
+            self.show_native_macos(payload).await
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            self.show_native_windows(payload).await
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.show_native_linux(payload).await
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+        {
+            let _ = payload;
+            Err(anyhow::anyhow!("No native notifications on this platform"))
+        }
+    }
+
+    /// Second attempt through `tauri_plugin_notification`
+    fn show_fallback(&self, payload: &NotificationPayload) -> Result<()> {
+        use tauri_plugin_notification::NotificationExt;
+
+        let app = self
+            .app
+            .get()
+            .ok_or_else(|| anyhow::anyhow!("Notification plugin not attached"))?;
+        app.notification()
+            .builder()
+            .id(native_id(&payload.id))
+            .title(&payload.title)
+            .body(&payload.body)
+            .show()?;
+        Ok(())
+    }
+
+    /// Counts a native failure; the first one of the session marks notifications
+    /// degraded and tells the UI they may be unreliable
+    fn on_native_failure(&self, error: &anyhow::Error) {
+        if !self.native_failures.record() {
+            return;
+        }
+        let Some(app) = self.app.get() else {
+            return;
+        };
+        use tauri::Manager;
+        if let Some(features) = app.try_state::<crate::diagnostics::Features>() {
+            features.degraded("notifications", format!("native display failed: {}", error));
+        }
+        let payload = NotificationsDegradedPayload { error: error.to_string() };
+        if let Err(e) = app.emit("notifications-degraded", payload) {
+            warn!("Failed to emit notifications-degraded: {}", e);
+        }
+    }
+
+    // Platform-specific notification implementations
+    #[cfg(target_os = "macos")]
+    async fn show_native_macos(&self, payload: &NotificationPayload) -> Result<()> {
+        debug!("Showing macOS native notification");
+
+        // Use NSUserNotification on macOS
+        // Cocoa bindings or user_notifications crate
+        
+        info!(
+            "macOS notification: {} - {}",
+            payload.title, payload.body
+        );
+
+        // In a real implementation:
+        // let notification = NSUserNotification::new(nil);
+        // notification.setTitle(payload.title.to_nsstring());
+        // notification.setInformativeText(payload.body.to_nsstring());
+        // 
+        // if let Some(icon_path) = &payload.icon_path {
+        //     // Set icon from file
+        // }
+        // 
+        // let center = NSUserNotificationCenter::defaultUserNotificationCenter(nil);
+        // center.scheduleNotification(notification);
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "windows")]
+    async fn show_native_windows(&self, payload: &NotificationPayload) -> Result<()> {
+        debug!("Showing Windows native notification");
+
+        let grouping = self.state.read().await.settings.toast_grouping.clone();
+        let group = toast_group_for(&grouping, payload.conversation_id.as_deref());
+        crate::platform::show_toast(
+            &self.toast_app_id(&grouping),
+            &Self::create_toast_xml(payload),
+            &payload.id,
+            group.as_deref(),
+        )
+        .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    #[cfg(target_os = "linux")]
+    async fn show_native_linux(&self, payload: &NotificationPayload) -> Result<()> {
+        debug!("Showing Linux native notification");
+
+        // Use D-Bus notification interface (freedesktop spec)
+        // dbus crate or zbus crate
+        
+        info!(
+            "Linux notification: {} - {}",
+            payload.title, payload.body
+        );
+
+        // In a real implementation:
+        // let connection = zbus::Connection::session().await?;
+        // let notification = zbus::Message::new_signal(
+        //     "/org/freedesktop/Notifications",
+        //     "org.freedesktop.Notifications",
+        //     "Notify",
+        // )?;
+        // 
+        // // Build notification payload and send via D-Bus
+
+        Ok(())
+    }
+
+    // Helper to create toast XML for Windows
+    #[cfg(target_os = "windows")]
+    fn create_toast_xml(payload: &NotificationPayload) -> String {
+        let icon_xml = payload
+            .icon_path
+            .as_ref()
+            .map(|icon| format!(r#"<image id="1" src="{}"/>"#, icon))
+            .unwrap_or_default();
+
+        let body_xml = if payload.sender_name.is_some() {
+            format!(
+                r#"<text id="1">{}</text>
+            <text id="2">{}</text>"#,
+                payload.sender_name.as_ref().unwrap(),
+                payload.body
+            )
+        } else {
+            format!(r#"<text id="1">{}</text>"#, payload.body)
+        };
+
+        // Buttons activate in the background with the action and conversation ids
+        let conversation = payload.conversation_id.as_deref().unwrap_or_default();
+        let actions_xml = if payload.actions.is_empty() {
+            String::new()
+        } else {
+            let buttons: String = payload
+                .actions
+                .iter()
+                .map(|a| {
+                    format!(
+                        r#"<action content="{}" arguments="action={}&amp;conversation={}" activationType="background"/>"#,
+                        a.title, a.id, conversation
+                    )
+                })
+                .collect();
+            format!("<actions>{}</actions>", buttons)
+        };
+
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<toast>
+    <visual>
+        <binding template="ToastGeneric">
+            <text>{}</text>
+            {}
+            {}
+        </binding>
+    </visual>
+    {}
+</toast>"#,
+            payload.title, body_xml, icon_xml, actions_xml
+        )
+    }
+}
+
+impl Default for NotificationService {
+    fn default() -> Self {
+        // Use standard app data directory
+        let app_data_dir = std::env::var("HOME")
+            .ok()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        Self::new(app_data_dir)
+    }
+}
+
+// Notification payload structure
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+struct NotificationPayload {
+    #[allow(dead_code)]
+    id: String,
+    title: String,
+    body: String,
+    #[allow(dead_code)]
+    icon_path: Option<String>,
+    #[allow(dead_code)]
+    conversation_id: Option<String>,
+    #[allow(dead_code)]
+    sender_name: Option<String>,
+    #[allow(dead_code)]
+    silent: bool,
+    /// Toast buttons; empty where the platform can't show them
+    #[allow(dead_code)]
+    actions: Vec<NotificationAction>,
+}
+
+// Tauri commands
+
+/// Handle notification from JavaScript frontend
+#[tauri::command]
+#[specta::specta]
+pub async fn handle_notification(
+    title: String,
+    options: Option<serde_json::Value>,
+    service: tauri::State<'_, NotificationService>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    use tauri::Manager;
+    use tauri_plugin_notification::NotificationExt;
+
+    let mut data = NotificationData::from_web(title, options.as_ref());
+    data.platform = app
+        .try_state::<crate::platform_manager::PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten())
+        .map(|p| p.name().to_string());
+    if let Some((id, name)) = crate::accounts::notification_identity(&crate::accounts::managed_accounts(&app)) {
+        data.account_id = Some(id);
+        data.account_name = name;
+    }
+
+    // A popped-out conversation with focus is already being read
+    if crate::conversation_window::on_notification(&app, data.conversation_id.as_deref(), &data.title) {
+        debug!("Conversation window focused, skipping: {}", data.title);
+        return Ok(());
+    }
+
+    // Filters, conversation mutes, DND and the rate limit
+    let settings = service.get_settings().await;
+    if !service.admit(&data, &settings).await {
+        if let Some(delay) = service.schedule_flush().await {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(delay).await;
+                if let Err(e) = show_held(&app).await {
+                    warn!("Failed to show throttled notification: {}", e);
+                }
+            });
+        }
+        return Ok(());
+    }
+
+    let (title, body) = service.display_text(&data, &settings).await;
+    let id = service.track(&data).await;
+    app.notification()
+        .builder()
+        .id(id)
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| e.to_string())?;
+
+    if settings.bounce_on_mention && data.is_mention() {
+        crate::platform::request_attention(&app, MENTION_FLASH_COUNT);
+    }
+
+    Ok(())
+}
+
+/// Shows the most recent notification the rate limiter held back
+async fn show_held(app: &AppHandle) -> Result<(), String> {
+    use tauri::Manager;
+    use tauri_plugin_notification::NotificationExt;
+
+    let service = app.state::<NotificationService>();
+    let Some(data) = service.take_held().await else {
+        return Ok(());
+    };
+    let settings = service.get_settings().await;
+    let (title, body) = service.display_text(&data, &settings).await;
+    let id = service.track(&data).await;
+    app.notification()
+        .builder()
+        .id(id)
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+/// Show a notification using the notification plugin
+#[tauri::command]
+#[specta::specta]
+pub async fn show_notification(
+    title: String,
+    body: String,
+    icon_url: Option<String>,
+    app: tauri::AppHandle,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    use tauri_plugin_notification::NotificationExt;
+    
+    let state = notification_service.state.read().await;
+    if !state.settings.enabled || state.settings.do_not_disturb {
+        return Ok(());
+    }
+    drop(state);
+    
+    let mut builder = app.notification().builder().title(&title);
+    if !body.is_empty() {
+        builder = builder.body(&body);
+    }
+    builder.show().map_err(|e| e.to_string())
+}
+
+/// Payload of the `notification-clicked` event sent by the frontend
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct NotificationClickedPayload {
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+    #[serde(default)]
+    pub account_id: Option<String>,
+}
+
+/// Payload emitted with `account-switch-requested`: switch to the account, then
+/// navigate to `route`
+#[derive(Serialize, Clone, Debug)]
+pub struct AccountSwitchRequestedPayload {
+    pub account_id: String,
+    pub route: Option<String>,
+}
+
+/// Where a clicked notification navigates to: its conversation, unless the app is
+/// locked (the lock screen must not be bypassed)
+fn click_target(conversation_id: Option<String>, locked: bool) -> Option<String> {
+    conversation_id
+        .filter(|id| !locked && !id.is_empty())
+        .map(|id| format!("conversation/{}", id))
+}
+
+/// Raises the window for a clicked notification, then opens its conversation
+async fn on_notification_clicked(app: &AppHandle, payload: NotificationClickedPayload) {
+    use tauri::Manager;
+
+    let settings = app.state::<NotificationService>().get_settings().await;
+    if !settings.focus_on_click {
+        return;
+    }
+    crate::platform::bring_to_front(app);
+
+    let locked = app
+        .try_state::<crate::lock::LockManager>()
+        .and_then(|m| m.status().ok())
+        .is_some_and(|s| s.locked);
+    let route = click_target(payload.conversation_id, locked);
+
+    // A notification for another account switches to it first
+    let active = crate::accounts::notification_identity(&crate::accounts::managed_accounts(app)).map(|(id, _)| id);
+    if let Some(account_id) = payload.account_id.filter(|id| !locked && active.as_ref() != Some(id)) {
+        let _ = app.emit("account-switch-requested", AccountSwitchRequestedPayload { account_id, route });
+    } else if let Some(route) = route {
+        let _ = app.emit("navigate", route);
+    }
+}
+
+/// Handles `notification-clicked` events from the frontend
+pub fn init_click_handler(app: &AppHandle) {
+    use tauri::Listener;
+
+    let app_handle = app.clone();
+    app.listen("notification-clicked", move |event| {
+        let payload = serde_json::from_str(event.payload()).unwrap_or_default();
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            on_notification_clicked(&app_handle, payload).await;
+        });
+    });
+}
+
+/// Shows the DND digest summary, if one is due
+pub async fn show_dnd_summary(app: &AppHandle) -> Result<(), String> {
+    use tauri::Manager;
+    use tauri_plugin_notification::NotificationExt;
+
+    let Some(summary) = app.state::<NotificationService>().take_dnd_summary().await else {
+        return Ok(());
+    };
+    app.notification()
+        .builder()
+        .title("While Do Not Disturb was on")
+        .body(summary)
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+/// Returns a callback that emits `dnd-changed` (and shows the digest) when a timed
+/// DND expires
+pub fn emit_dnd_expired(app: AppHandle) -> impl FnOnce() + Send + 'static {
+    move || {
+        let _ = app.emit(
+            "dnd-changed",
+            DndChangedPayload {
+                enabled: false,
+                until: None,
+            },
+        );
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = show_dnd_summary(&app).await {
+                warn!("Failed to show DND digest: {}", e);
+            }
+        });
+    }
+}
+
+/// Send a test notification through the OS display path and play the sound.
+///
+/// Unlike `show_notification` this ignores DND, mutes and filters so the test always
+/// shows, and it is not recorded in history. Fails if the OS refuses the notification.
+#[tauri::command]
+#[specta::specta]
+pub async fn send_test_notification(
+    app: tauri::AppHandle,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    use tauri_plugin_notification::NotificationExt;
+
+    let data = NotificationData::test_sample();
+    app.notification()
+        .builder()
+        .title(&data.title)
+        .body(&data.body)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+
+    let settings = notification_service.get_settings().await;
+    notification_service
+        .play_sound_if_enabled(&settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Set Do Not Disturb mode
+#[tauri::command]
+#[specta::specta]
+pub async fn set_dnd(
+    enabled: bool,
+    app: AppHandle,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.set_dnd(enabled).await.map_err(|e| e.to_string())?;
+    crate::calls::on_manual_dnd(&app).await;
+    app.emit("dnd-changed", DndChangedPayload { enabled, until: None })
+        .map_err(|e| e.to_string())?;
+    show_dnd_summary(&app).await
+}
+
+/// Toggle Do Not Disturb mode
+#[tauri::command]
+#[specta::specta]
+pub async fn toggle_dnd(
+    app: AppHandle,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<bool, String> {
+    let current = notification_service.get_settings().await.do_not_disturb;
+    notification_service.set_dnd(!current).await.map_err(|e| e.to_string())?;
+    crate::calls::on_manual_dnd(&app).await;
+    app.emit("dnd-changed", DndChangedPayload { enabled: !current, until: None })
+        .map_err(|e| e.to_string())?;
+    show_dnd_summary(&app).await?;
+    Ok(!current)
+}
+
+/// Enable Do Not Disturb for the given number of minutes. Returns the expiry (Unix ms).
+#[tauri::command]
+#[specta::specta]
+pub async fn set_dnd_until(
+    minutes: u32,
+    app: AppHandle,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<u64, String> {
+    let until = notification_service
+        .set_dnd_until(minutes, emit_dnd_expired(app.clone()))
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::calls::on_manual_dnd(&app).await;
+    app.emit("dnd-changed", DndChangedPayload { enabled: true, until: Some(until) })
+        .map_err(|e| e.to_string())?;
+    Ok(until)
+}
+
+/// Get the seconds left on a timed Do Not Disturb (0 if none)
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dnd_remaining(
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<u64, String> {
+    Ok(notification_service.get_dnd_remaining().await)
+}
+
+/// Get what the current or last Do Not Disturb period held back, by conversation
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dnd_digest(
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<DndDigest, String> {
+    Ok(notification_service.get_dnd_digest().await)
+}
+
+/// Enable/disable the summary notification shown when Do Not Disturb ends
+#[tauri::command]
+#[specta::specta]
+pub async fn set_digest_on_dnd_end(
+    enabled: bool,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.set_digest_on_dnd_end(enabled).await.map_err(|e| e.to_string())
+}
+
+/// Set how many notifications may be shown per second, and the burst allowed
+#[tauri::command]
+#[specta::specta]
+pub async fn set_notification_rate_limit(
+    per_second: u32,
+    burst: u32,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service
+        .set_rate_limit(RateLimit { per_second, burst })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Enable/disable holding notifications back while the system's Do Not Disturb
+/// (Focus Assist on Windows, Focus on macOS) is on
+#[tauri::command]
+#[specta::specta]
+pub async fn set_respect_system_dnd(
+    enabled: bool,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.set_respect_system_dnd(enabled).await.map_err(|e| e.to_string())
+}
+
+/// Set whether a conversation's toasts collapse into one group in the Windows
+/// Action Center, and the app identity (AUMID) they are shown under
+#[tauri::command]
+#[specta::specta]
+pub async fn set_toast_grouping(
+    enabled: bool,
+    app_id: Option<String>,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    let app_id = app_id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty());
+    notification_service
+        .set_toast_grouping(ToastGrouping { enabled, app_id })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List the notification toast actions (built-ins first)
+#[tauri::command]
+#[specta::specta]
+pub async fn list_notification_actions(
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<Vec<NotificationAction>, String> {
+    Ok(notification_service.get_actions().await)
+}
+
+/// Register a custom notification toast action
+#[tauri::command]
+#[specta::specta]
+pub async fn register_notification_action(
+    id: String,
+    title: String,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.register_action(id, title).await.map_err(|e| e.to_string())
+}
+
+/// Remove a custom notification toast action
+#[tauri::command]
+#[specta::specta]
+pub async fn unregister_notification_action(
+    id: String,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.unregister_action(&id).await.map_err(|e| e.to_string())
+}
+
+/// Called by the platform glue when a toast button is clicked. Applies built-in
+/// actions and emits `notification-action`.
+#[tauri::command]
+#[specta::specta]
+pub async fn invoke_notification_action(
+    action_id: String,
+    conversation_id: Option<String>,
+    app: AppHandle,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    let payload = notification_service
+        .invoke_action(&action_id, conversation_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    app.emit("notification-action", payload).map_err(|e| e.to_string())
+}
+
+/// Removes a still-shown notification from the notification center. Returns false
+/// if it was no longer tracked.
+#[tauri::command]
+#[specta::specta]
+pub async fn dismiss_notification(
+    id: String,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<bool, String> {
+    Ok(notification_service
+        .dismiss(&id, crate::platform::dismiss_notification)
+        .await)
+}
+
+/// Clears all of the app's notifications from the notification center
+#[tauri::command]
+#[specta::specta]
+pub async fn dismiss_all_notifications(
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<u32, String> {
+    let dismissed = notification_service
+        .dismiss_all(crate::platform::dismiss_notification)
+        .await;
+    Ok(dismissed as u32)
+}
+
+/// Called by `THREAD_READ_HOOK_JS` when a conversation is open in the focused
+/// window: its notifications have been read, so they leave the notification center
+#[tauri::command]
+#[specta::specta]
+pub async fn report_conversation_read(
+    conversation_id: String,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<u32, String> {
+    let dismissed = notification_service
+        .dismiss_conversation(&conversation_id, crate::platform::dismiss_notification)
+        .await;
+    if dismissed > 0 {
+        debug!("Dismissed {} notification(s) of read conversation {}", dismissed, conversation_id);
+    }
+    Ok(dismissed as u32)
+}
+
+/// Called with the activation arguments of a Windows toast button
+/// (`action=<id>&conversation=<id>`); see `invoke_notification_action`
+#[tauri::command]
+#[specta::specta]
+pub async fn handle_toast_activation(
+    arguments: String,
+    app: AppHandle,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    let (action_id, conversation_id) =
+        parse_toast_arguments(&arguments).ok_or_else(|| format!("Not a toast action: {}", arguments))?;
+    invoke_notification_action(action_id, conversation_id, app, notification_service).await
+}
+
+/// Get Do Not Disturb status
+#[tauri::command]
+#[specta::specta]
+pub async fn is_dnd_enabled(
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<bool, String> {
+    Ok(notification_service.get_settings().await.do_not_disturb)
+}
+
+/// Set notification sound path
+#[tauri::command]
+#[specta::specta]
+pub async fn set_notification_sound(
+    path: String,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.set_notification_sound(path).await.map_err(|e| e.to_string())
+}
+
+/// Get notification settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_notification_settings(
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<NotificationSettings, String> {
+    Ok(notification_service.get_settings().await)
+}
+
+/// Reset notification settings to their defaults (emits `notification-settings-changed`)
+#[tauri::command]
+#[specta::specta]
+pub async fn reset_notification_settings(
+    notification_service: tauri::State<'_, NotificationService>,
+    app: AppHandle,
+) -> Result<NotificationSettings, String> {
+    let settings = notification_service.reset_settings().await.map_err(|e| e.to_string())?;
+    app.emit("notification-settings-changed", &settings).map_err(|e| e.to_string())?;
+    app.emit("dnd-changed", DndChangedPayload { enabled: false, until: None })
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// Enable/disable notifications
+#[tauri::command]
+#[specta::specta]
+pub async fn set_notification_enabled(
+    enabled: bool,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.set_enabled(enabled).await.map_err(|e| e.to_string())
+}
+
+/// Enable/disable notification sound
+#[tauri::command]
+#[specta::specta]
+pub async fn set_notification_sound_enabled(
+    enabled: bool,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.set_sound_enabled(enabled).await.map_err(|e| e.to_string())
+}
+
+/// Add a notification filter by sender or body keyword
+#[tauri::command]
+#[specta::specta]
+pub async fn add_notification_filter(
+    field: FilterField,
+    action: FilterAction,
+    pattern: String,
+    regex: bool,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<NotificationFilter, String> {
+    notification_service
+        .add_filter(field, action, pattern, regex)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a notification filter
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_notification_filter(
+    id: String,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service.remove_filter(&id).await.map_err(|e| e.to_string())
+}
+
+/// List notification filters
+#[tauri::command]
+#[specta::specta]
+pub async fn list_notification_filters(
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<Vec<NotificationFilter>, String> {
+    Ok(notification_service.get_settings().await.filters)
+}
+
+/// Mute a conversation for the given number of minutes (0 = until unmuted)
+#[tauri::command]
+#[specta::specta]
+pub async fn mute_conversation(
+    conversation_id: String,
+    minutes: u32,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service
+        .mute_conversation(conversation_id, minutes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Unmute a conversation
+#[tauri::command]
+#[specta::specta]
+pub async fn unmute_conversation(
+    conversation_id: String,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service
+        .unmute_conversation(&conversation_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Let a conversation through DND and mutes for the given number of minutes
+/// (0 = until cleared)
+#[tauri::command]
+#[specta::specta]
+pub async fn prioritize_conversation(
+    conversation_id: String,
+    minutes: u32,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service
+        .prioritize_conversation(conversation_id, minutes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a conversation's priority
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_priority(
+    conversation_id: String,
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<(), String> {
+    notification_service
+        .clear_priority(&conversation_id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-// Tauri commands
-
-/// Handle notification from JavaScript frontend
+/// List prioritized conversations with their expiry (Unix ms, `None` = until cleared)
 #[tauri::command]
-pub async fn handle_notification(
-    title: String,
-    options: Option<serde_json::Value>,
-    service: tauri::State<'_, NotificationService>,
-    app: tauri::AppHandle,
-) -> Result<(), String> {
-    use tauri_plugin_notification::NotificationExt;
-    
-    let body = options
-        .as_ref()
-        .and_then(|o| o.get("body"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
-    
-    // Check DND
-    let state = service.state.read().await;
-    if state.settings.do_not_disturb {
-        return Ok(());
-    }
-    if !state.settings.enabled {
-        return Ok(());
-    }
-    drop(state);
-    
-    app.notification()
-        .builder()
-        .title(&title)
-        .body(&body)
-        .show()
-        .map_err(|e| e.to_string())?;
-    
-    Ok(())
+#[specta::specta]
+pub async fn list_prioritized(
+    notification_service: tauri::State<'_, NotificationService>,
+) -> Result<HashMap<String, Option<u64>>, String> {
+    Ok(notification_service.get_settings().await.prioritized_conversations)
 }
 
-/// Show a notification using the notification plugin
+/// Get notification history (including suppressed notifications), oldest first
 #[tauri::command]
 #[specta::specta]
-pub async fn show_notification(
-    title: String,
-    body: String,
-    icon_url: Option<String>,
-    app: tauri::AppHandle,
+pub async fn get_notification_history(
     notification_service: tauri::State<'_, NotificationService>,
-) -> Result<(), String> {
-    use tauri_plugin_notification::NotificationExt;
-    
-    let state = notification_service.state.read().await;
-    if !state.settings.enabled || state.settings.do_not_disturb {
-        return Ok(());
-    }
-    drop(state);
-    
-    let mut builder = app.notification().builder().title(&title);
-    if !body.is_empty() {
-        builder = builder.body(&body);
-    }
-    builder.show().map_err(|e| e.to_string())
+) -> Result<Vec<NotificationRecord>, String> {
+    Ok(notification_service.get_history().await)
 }
 
-/// Set Do Not Disturb mode
+/// Set notification sound to default
 #[tauri::command]
 #[specta::specta]
-pub async fn set_dnd(
-    enabled: bool,
+pub async fn use_default_notification_sound(
     notification_service: tauri::State<'_, NotificationService>,
 ) -> Result<(), String> {
-    notification_service.set_dnd(enabled).await.map_err(|e| e.to_string())
+    notification_service.use_default_sound().await.map_err(|e| e.to_string())
 }
 
-/// Toggle Do Not Disturb mode
+/// Set the notification preview mode for a platform, or the default with no platform
 #[tauri::command]
 #[specta::specta]
-pub async fn toggle_dnd(
+pub async fn set_preview_mode(
+    mode: PreviewMode,
+    platform: Option<String>,
     notification_service: tauri::State<'_, NotificationService>,
-) -> Result<bool, String> {
-    let current = notification_service.get_settings().await.do_not_disturb;
-    notification_service.set_dnd(!current).await.map_err(|e| e.to_string())?;
-    Ok(!current)
+) -> Result<(), String> {
+    notification_service.set_preview_mode(mode, platform).await.map_err(|e| e.to_string())
 }
 
-/// Get Do Not Disturb status
+/// Give a platform its own DND schedule (HH:MM, overnight allowed); platforms
+/// without one use the global schedule
 #[tauri::command]
 #[specta::specta]
-pub async fn is_dnd_enabled(
+pub async fn set_platform_dnd_schedule(
+    platform: String,
+    start: String,
+    end: String,
     notification_service: tauri::State<'_, NotificationService>,
-) -> Result<bool, String> {
-    Ok(notification_service.get_settings().await.do_not_disturb)
+) -> Result<(), String> {
+    let schedule = DNDSchedule::new(&start, &end).map_err(|e| e.to_string())?;
+    notification_service
+        .set_platform_dnd_schedule(platform, schedule)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Set notification sound path
+/// Remove a platform's DND schedule, so the global one applies again
 #[tauri::command]
 #[specta::specta]
-pub async fn set_notification_sound(
-    path: String,
+pub async fn clear_platform_dnd_schedule(
+    platform: String,
     notification_service: tauri::State<'_, NotificationService>,
 ) -> Result<(), String> {
-    notification_service.set_notification_sound(path).await.map_err(|e| e.to_string())
+    notification_service
+        .clear_platform_dnd_schedule(&platform)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Get notification settings
+/// Remove a platform's preview mode override
 #[tauri::command]
 #[specta::specta]
-pub async fn get_notification_settings(
+pub async fn clear_preview_mode(
+    platform: String,
     notification_service: tauri::State<'_, NotificationService>,
-) -> Result<NotificationSettings, String> {
-    Ok(notification_service.get_settings().await)
+) -> Result<(), String> {
+    notification_service.clear_preview_mode(&platform).await.map_err(|e| e.to_string())
 }
 
-/// Enable/disable notifications
+/// Play the message-sent sound (called by the injected composer hook)
 #[tauri::command]
 #[specta::specta]
-pub async fn set_notification_enabled(
-    enabled: bool,
+pub async fn play_sent_sound(
     notification_service: tauri::State<'_, NotificationService>,
-) -> Result<(), String> {
-    notification_service.set_enabled(enabled).await.map_err(|e| e.to_string())
+) -> Result<bool, String> {
+    notification_service.play_sent_sound().await.map_err(|e| e.to_string())
 }
 
-/// Enable/disable notification sound
+/// Enable/disable the message-sent sound
 #[tauri::command]
 #[specta::specta]
-pub async fn set_notification_sound_enabled(
+pub async fn set_sent_sound_enabled(
     enabled: bool,
     notification_service: tauri::State<'_, NotificationService>,
 ) -> Result<(), String> {
-    notification_service.set_sound_enabled(enabled).await.map_err(|e| e.to_string())
+    notification_service.set_sent_sound_enabled(enabled).await.map_err(|e| e.to_string())
 }
 
-/// Set notification sound to default
+/// Set the message-sent sound (`None` for the system default)
 #[tauri::command]
 #[specta::specta]
-pub async fn use_default_notification_sound(
+pub async fn set_sent_sound(
+    path: Option<String>,
     notification_service: tauri::State<'_, NotificationService>,
 ) -> Result<(), String> {
-    notification_service.set_notification_sound(String::new()).await.map_err(|e| e.to_string())
+    notification_service.set_sent_sound(path).await.map_err(|e| e.to_string())
 }
 
 // Unit tests
@@ -600,6 +2924,14 @@ pub async fn use_default_notification_sound(
 mod tests {
     use super::*;
 
+    /// A service over a data dir of its own, emptied first, so tests don't load
+    /// each other's saved settings
+    fn fresh_service(name: &str) -> NotificationService {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        NotificationService::new(dir)
+    }
+
     #[test]
     fn test_notification_data_default() {
         let data = NotificationData {
@@ -613,6 +2945,9 @@ mod tests {
             timestamp: None,
             require_interaction: false,
             silent: false,
+            platform: None,
+            account_id: None,
+            account_name: None,
         };
         assert_eq!(data.id, "test-id");
         assert_eq!(data.title, "Test");
@@ -628,6 +2963,20 @@ mod tests {
             dnd_schedule: None,
             show_preview: true,
             quick_reply_enabled: false,
+            filters: Vec::new(),
+            muted_conversations: HashMap::new(),
+            prioritized_conversations: HashMap::new(),
+            sent_sound_enabled: false,
+            sent_sound_path: None,
+            preview_mode: PreviewMode::Full,
+            platform_preview_modes: HashMap::new(),
+            platform_dnd_schedules: HashMap::new(),
+            bounce_on_mention: true,
+            focus_on_click: true,
+            digest_on_dnd_end: false,
+            rate_limit: RateLimit::default(),
+            toast_grouping: ToastGrouping::default(),
+            respect_system_dnd: false,
         };
         assert!(settings.enabled);
         assert!(!settings.do_not_disturb);
@@ -644,9 +2993,949 @@ mod tests {
         assert_eq!(deserialized.start_time, "22:00");
     }
 
+    #[test]
+    fn test_platform_dnd_schedule_overrides_global() {
+        let at = |time: &str| chrono::NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+        let mut settings = NotificationSettings {
+            dnd_schedule: Some(DNDSchedule::new("23:00", "07:00").unwrap()),
+            ..NotificationSettings::default()
+        };
+        settings
+            .platform_dnd_schedules
+            .insert("Messenger".to_string(), DNDSchedule::new("18:00", "09:00").unwrap());
+
+        let messenger = settings.dnd_schedule_for(Some("Messenger")).unwrap();
+        assert!(messenger.contains(at("19:30")));
+        assert!(messenger.contains(at("08:59")));
+        assert!(!messenger.contains(at("12:00")));
+
+        // No override: the global schedule
+        let instagram = settings.dnd_schedule_for(Some("Instagram")).unwrap();
+        assert!(!instagram.contains(at("19:30")));
+        assert!(instagram.contains(at("23:30")));
+        assert_eq!(settings.dnd_schedule_for(None), settings.dnd_schedule.as_ref());
+
+        // Same-day windows and bad input
+        assert!(DNDSchedule::new("09:00", "17:00").unwrap().contains(at("12:00")));
+        assert!(!DNDSchedule::new("09:00", "17:00").unwrap().contains(at("18:00")));
+        assert!(DNDSchedule::new("25:00", "07:00").is_err());
+
+        settings.dnd_schedule = None;
+        assert!(settings.dnd_schedule_for(Some("Instagram")).is_none());
+    }
+
+    #[test]
+    fn test_platform_dnd_schedules_persist() {
+        let tmp = std::env::temp_dir().join("test-platform-dnd-schedules");
+        let _ = fs::remove_dir_all(&tmp);
+        fs::create_dir_all(&tmp).unwrap();
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            let work = DNDSchedule::new("18:00", "09:00").unwrap();
+            service.set_platform_dnd_schedule("Messenger".to_string(), work.clone()).await.unwrap();
+            service.set_platform_dnd_schedule("X".to_string(), work.clone()).await.unwrap();
+            service.clear_platform_dnd_schedule("X").await.unwrap();
+
+            let restarted = NotificationService::new(tmp.clone()).get_settings().await;
+            assert_eq!(restarted.platform_dnd_schedules.get("Messenger"), Some(&work));
+            assert!(!restarted.platform_dnd_schedules.contains_key("X"));
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_settings_persist_as_one_document() {
+        let tmp = std::env::temp_dir().join("test-notification-settings-document");
+        let _ = fs::remove_dir_all(&tmp);
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            service.set_enabled(false).await.unwrap();
+            service.set_sound_enabled(true).await.unwrap();
+            service.set_dnd(true).await.unwrap();
+            let schedule = DNDSchedule::new("22:00", "07:00").unwrap();
+            service
+                .apply_settings(NotificationSettings {
+                    dnd_schedule: Some(schedule.clone()),
+                    ..service.get_settings().await
+                })
+                .await
+                .unwrap();
+
+            let restarted = NotificationService::new(tmp.clone()).get_settings().await;
+            assert!(!restarted.enabled);
+            assert!(restarted.sound_enabled);
+            assert!(restarted.do_not_disturb);
+            assert_eq!(restarted.dnd_schedule, Some(schedule));
+
+            // A timed DND is only restored while its expiry is due
+            service.set_dnd_until(30, || {}).await.unwrap();
+            let saved = store::load::<NotificationSettings>(&tmp.join(SETTINGS_FILE)).unwrap();
+            assert!(!saved.do_not_disturb);
+        });
+        let mut files: Vec<_> = fs::read_dir(&tmp)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, [DND_UNTIL_FILE, SETTINGS_FILE]);
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_settings_snapshot_is_not_torn() {
+        let service = Arc::new(fresh_service("test-notification-settings-snapshot-is-not-torn"));
+        // Either snapshot suppresses Alice: one by its filter, the other by DND. Only
+        // the filters of one mixed with the DND of the other would let her through.
+        let filtered = NotificationSettings {
+            filters: vec![NotificationFilter {
+                id: "f1".to_string(),
+                field: FilterField::Sender,
+                action: FilterAction::Deny,
+                pattern: "alice".to_string(),
+                regex: false,
+            }],
+            ..NotificationSettings::default()
+        };
+        let dnd = NotificationSettings {
+            do_not_disturb: true,
+            ..NotificationSettings::default()
+        };
+
+        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let writer = {
+            let (service, done) = (service.clone(), done.clone());
+            std::thread::spawn(move || {
+                tauri::async_runtime::block_on(async {
+                    let mut i = 0;
+                    while !done.load(std::sync::atomic::Ordering::SeqCst) {
+                        let next = if i % 2 == 0 { filtered.clone() } else { dnd.clone() };
+                        service.apply_settings(next).await.unwrap();
+                        i += 1;
+                    }
+                })
+            })
+        };
+
+        let senders: Vec<_> = (0..4)
+            .map(|_| {
+                let service = service.clone();
+                tauri::async_runtime::spawn(async move {
+                    for _ in 0..MAX_HISTORY / 4 {
+                        service.show_notification(message("Alice", "hi")).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+        tauri::async_runtime::block_on(async {
+            for sender in senders {
+                sender.await.unwrap();
+            }
+        });
+        done.store(true, std::sync::atomic::Ordering::SeqCst);
+        writer.join().unwrap();
+
+        let history = tauri::async_runtime::block_on(service.get_history());
+        assert_eq!(history.len(), MAX_HISTORY);
+        assert!(history.iter().all(|r| r.suppressed));
+    }
+
+    #[test]
+    fn test_timed_dnd_expires() {
+        let tmp = std::env::temp_dir().join("test-dnd-expires");
+        fs::create_dir_all(&tmp).unwrap();
+        let service = NotificationService::new(tmp.clone());
+        let expired = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        tauri::async_runtime::block_on(async {
+            let flag = expired.clone();
+            service
+                .set_dnd_for(Duration::from_millis(50), move || {
+                    flag.store(true, std::sync::atomic::Ordering::SeqCst)
+                })
+                .await
+                .unwrap();
+            assert!(service.get_settings().await.do_not_disturb);
+            assert!(service.get_dnd_remaining().await > 0);
+
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            assert!(!service.get_settings().await.do_not_disturb);
+            assert_eq!(service.get_dnd_remaining().await, 0);
+        });
+        assert!(expired.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(!tmp.join(DND_UNTIL_FILE).exists());
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_manual_dnd_cancels_timer() {
+        let tmp = std::env::temp_dir().join("test-dnd-cancel");
+        fs::create_dir_all(&tmp).unwrap();
+        let service = NotificationService::new(tmp.clone());
+        let expired = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        tauri::async_runtime::block_on(async {
+            let flag = expired.clone();
+            service
+                .set_dnd_for(Duration::from_millis(50), move || {
+                    flag.store(true, std::sync::atomic::Ordering::SeqCst)
+                })
+                .await
+                .unwrap();
+            service.set_dnd(false).await.unwrap();
+            service.set_dnd(true).await.unwrap();
+
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            // The cancelled timer must not turn the manual DND off
+            assert!(service.get_settings().await.do_not_disturb);
+        });
+        assert!(!expired.load(std::sync::atomic::Ordering::SeqCst));
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_timed_dnd_survives_restart_until_expiry() {
+        let tmp = std::env::temp_dir().join("test-dnd-restart");
+        fs::create_dir_all(&tmp).unwrap();
+
+        let service = NotificationService::new(tmp.clone());
+        tauri::async_runtime::block_on(service.set_dnd_until(60, || {})).unwrap();
+        let restarted = NotificationService::new(tmp.clone());
+        tauri::async_runtime::block_on(async {
+            assert!(restarted.get_settings().await.do_not_disturb);
+            assert!(restarted.get_dnd_remaining().await > 59 * 60);
+        });
+
+        fs::write(tmp.join(DND_UNTIL_FILE), (now_millis() - 1000).to_string()).unwrap();
+        let expired = NotificationService::new(tmp.clone());
+        assert!(!tauri::async_runtime::block_on(expired.get_settings()).do_not_disturb);
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    fn message(sender: &str, body: &str) -> NotificationData {
+        NotificationData {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: sender.to_string(),
+            body: body.to_string(),
+            icon_url: None,
+            conversation_id: None,
+            sender_name: Some(sender.to_string()),
+            sender_avatar: None,
+            timestamp: None,
+            require_interaction: false,
+            silent: false,
+            platform: None,
+            account_id: None,
+            account_name: None,
+        }
+    }
+
+    #[test]
+    fn test_denied_sender_suppressed_allowed_passes() {
+        let service = fresh_service("test-notification-denied-sender-suppressed-allowed-passes");
+        tauri::async_runtime::block_on(async {
+            service
+                .add_filter(FilterField::Sender, FilterAction::Deny, "spammer".to_string(), false)
+                .await
+                .unwrap();
+
+            service.show_notification(message("Spammer Bob", "hi")).await.unwrap();
+            service.show_notification(message("Alice", "hi")).await.unwrap();
+
+            let history = service.get_history().await;
+            assert_eq!(history.len(), 2);
+            assert!(history[0].suppressed);
+            assert!(!history[1].suppressed);
+        });
+    }
+
+    fn in_conversation(conversation_id: &str) -> NotificationData {
+        NotificationData {
+            conversation_id: Some(conversation_id.to_string()),
+            ..message("Alice", "hi")
+        }
+    }
+
+    #[test]
+    fn test_indefinite_conversation_mute() {
+        let service = fresh_service("test-notification-indefinite-conversation-mute");
+        tauri::async_runtime::block_on(async {
+            service.mute_conversation("t1".to_string(), 0).await.unwrap();
+            service.show_notification(in_conversation("t1")).await.unwrap();
+            service.show_notification(in_conversation("t2")).await.unwrap();
+
+            let history = service.get_history().await;
+            assert!(history[0].suppressed);
+            assert!(!history[1].suppressed);
+            assert_eq!(service.get_settings().await.muted_conversations.get("t1"), Some(&None));
+        });
+    }
+
+    #[test]
+    fn test_timed_conversation_mute_expires() {
+        let service = fresh_service("test-notification-timed-conversation-mute-expires");
+        tauri::async_runtime::block_on(async {
+            service
+                .mute_conversation_for("t1".to_string(), Some(Duration::from_millis(50)))
+                .await
+                .unwrap();
+            let settings = service.get_settings().await;
+            assert!(is_conversation_muted(&settings, "t1", now_millis()));
+
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            assert!(service.get_settings().await.muted_conversations.is_empty());
+            service.show_notification(in_conversation("t1")).await.unwrap();
+            assert!(!service.get_history().await[0].suppressed);
+        });
+    }
+
+    #[test]
+    fn test_unmute_conversation() {
+        let service = fresh_service("test-notification-unmute-conversation");
+        tauri::async_runtime::block_on(async {
+            service.mute_conversation("t1".to_string(), 30).await.unwrap();
+            service.unmute_conversation("t1").await.unwrap();
+            assert!(service.unmute_conversation("t1").await.is_err());
+
+            service.show_notification(in_conversation("t1")).await.unwrap();
+            assert!(!service.get_history().await[0].suppressed);
+        });
+    }
+
+    #[test]
+    fn test_conversation_mutes_survive_restart() {
+        let tmp = std::env::temp_dir().join("test-notification-mutes-persist");
+        let _ = fs::remove_dir_all(&tmp);
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            service.mute_conversation("t1".to_string(), 0).await.unwrap();
+            service.mute_conversation("t2".to_string(), 30).await.unwrap();
+            service.mute_conversation("t3".to_string(), 0).await.unwrap();
+            service.unmute_conversation("t3").await.unwrap();
+
+            let muted = NotificationService::new(tmp.clone()).get_settings().await.muted_conversations;
+            assert_eq!(muted.get("t1"), Some(&None));
+            assert!(muted.get("t2").is_some_and(|until| until.is_some()));
+            assert!(!muted.contains_key("t3"));
+
+            // A mute that ran out while the app was closed isn't restored
+            let mut saved = service.get_settings().await;
+            saved.muted_conversations.insert("t4".to_string(), Some(now_millis() - 1000));
+            store::save(&tmp.join(SETTINGS_FILE), &saved).unwrap();
+            let restarted = NotificationService::new(tmp.clone()).get_settings().await;
+            assert!(!restarted.muted_conversations.contains_key("t4"));
+            assert!(restarted.muted_conversations.contains_key("t1"));
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_prioritized_conversation_breaks_through_dnd() {
+        let service = fresh_service("test-priority");
+        tauri::async_runtime::block_on(async {
+            service.prioritize_conversation("t1".to_string(), 0).await.unwrap();
+            service.mute_conversation("t1".to_string(), 0).await.unwrap();
+            service.set_dnd(true).await.unwrap();
+            service.show_notification(in_conversation("t1")).await.unwrap();
+            service.show_notification(in_conversation("t2")).await.unwrap();
+
+            let history = service.get_history().await;
+            assert!(!history[0].suppressed);
+            assert!(history[1].suppressed);
+
+            service.clear_priority("t1").await.unwrap();
+            assert!(service.clear_priority("t1").await.is_err());
+            service.show_notification(in_conversation("t1")).await.unwrap();
+            assert!(service.get_history().await[2].suppressed);
+        });
+    }
+
+    #[test]
+    fn test_timed_priority_expires() {
+        let service = fresh_service("test-priority-expires");
+        tauri::async_runtime::block_on(async {
+            service
+                .prioritize_conversation_for("t1".to_string(), Some(Duration::from_millis(50)))
+                .await
+                .unwrap();
+            assert!(is_conversation_prioritized(&service.get_settings().await, "t1", now_millis()));
+
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            assert!(service.get_settings().await.prioritized_conversations.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_sample_notification() {
+        // A silent or empty sample would make a working setup look broken
+        let data = NotificationData::test_sample();
+        assert!(!data.title.is_empty() && !data.body.is_empty());
+        assert!(data.conversation_id.is_none());
+        assert!(!data.silent);
+    }
+
+    #[test]
+    fn test_notification_data_from_web() {
+        let options = serde_json::json!({"body": "hey", "tag": "thread-42", "silent": true});
+        let data = NotificationData::from_web("Alice".to_string(), Some(&options));
+        assert_eq!(data.body, "hey");
+        assert_eq!(data.conversation_id.as_deref(), Some("thread-42"));
+        assert_eq!(data.sender_name.as_deref(), Some("Alice"));
+        assert!(data.silent);
+    }
+
+    #[test]
+    fn test_filter_matching() {
+        let keyword = NotificationFilter {
+            id: "1".to_string(),
+            field: FilterField::Body,
+            action: FilterAction::Deny,
+            pattern: r"\bsale\b".to_string(),
+            regex: true,
+        };
+        let allow_mom = NotificationFilter {
+            id: "2".to_string(),
+            field: FilterField::Sender,
+            action: FilterAction::Allow,
+            pattern: "Mom".to_string(),
+            regex: false,
+        };
+        let filters = vec![keyword, allow_mom];
+
+        assert!(is_filtered(&filters, &message("Shop", "Big SALE today")));
+        assert!(!is_filtered(&filters, &message("Shop", "wholesale prices")));
+        // Allow wins over deny
+        assert!(!is_filtered(&filters, &message("mom", "sale at the market")));
+    }
+
+    #[test]
+    fn test_add_filter_rejects_invalid() {
+        let service = fresh_service("test-notification-add-filter-rejects-invalid");
+        tauri::async_runtime::block_on(async {
+            assert!(service
+                .add_filter(FilterField::Body, FilterAction::Deny, "(".to_string(), true)
+                .await
+                .is_err());
+            assert!(service
+                .add_filter(FilterField::Body, FilterAction::Deny, " ".to_string(), false)
+                .await
+                .is_err());
+            assert!(service.remove_filter("missing").await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_filters_survive_restart() {
+        let tmp = std::env::temp_dir().join("test-notification-filters-persist");
+        let _ = fs::remove_dir_all(&tmp);
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            let spam = service
+                .add_filter(FilterField::Sender, FilterAction::Deny, "spammer".to_string(), false)
+                .await
+                .unwrap();
+            let promo = service
+                .add_filter(FilterField::Body, FilterAction::Deny, "^sale".to_string(), true)
+                .await
+                .unwrap();
+            service.remove_filter(&promo.id).await.unwrap();
+
+            let restarted = NotificationService::new(tmp.clone());
+            let ids: Vec<_> = restarted.get_settings().await.filters.into_iter().map(|f| f.id).collect();
+            assert_eq!(ids, [spam.id]);
+            restarted.show_notification(message("Spammer Bob", "hi")).await.unwrap();
+            assert!(restarted.get_history().await[0].suppressed);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_settings_validate() {
+        let mut settings = NotificationSettings::default();
+        assert!(settings.validate().is_ok());
+
+        settings.filters.push(NotificationFilter {
+            id: "1".to_string(),
+            field: FilterField::Body,
+            action: FilterAction::Deny,
+            pattern: "(".to_string(),
+            regex: true,
+        });
+        assert!(settings.validate().is_err());
+
+        settings.filters.clear();
+        settings.platform_dnd_schedules.insert(
+            "Messenger".to_string(),
+            DNDSchedule { start_time: "25:00".to_string(), end_time: "07:00".to_string() },
+        );
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_previews_hidden_while_suppressed() {
+        let service = fresh_service("test-previews");
+        let data = message("Alice", "the secret plan");
+        tauri::async_runtime::block_on(async {
+            let settings = service.get_settings().await;
+            assert_eq!(service.display_text(&data, &settings).await.1, "the secret plan");
+
+            service.set_previews_suppressed(true).await;
+            assert_eq!(service.display_text(&data, &settings).await.1, HIDDEN_PREVIEW_BODY);
+
+            service.set_previews_suppressed(false).await;
+            let no_preview = NotificationSettings {
+                show_preview: false,
+                ..settings
+            };
+            assert_eq!(service.display_text(&data, &no_preview).await.1, HIDDEN_PREVIEW_BODY);
+        });
+    }
+
+    #[test]
+    fn test_click_target_respects_lock() {
+        assert_eq!(click_target(Some("t1".to_string()), false).as_deref(), Some("conversation/t1"));
+        assert_eq!(click_target(Some("t1".to_string()), true), None);
+        assert_eq!(click_target(None, false), None);
+    }
+
+    #[test]
+    fn test_toast_names_the_account() {
+        let service = fresh_service("test-account-toast");
+        let data = NotificationData {
+            account_id: Some("acc-2".to_string()),
+            account_name: Some("Work".to_string()),
+            ..message("Alice", "hi")
+        };
+        tauri::async_runtime::block_on(async {
+            let settings = service.get_settings().await;
+            assert_eq!(service.display_text(&data, &settings).await.0, "Alice · Work");
+            assert_eq!(service.display_text(&message("Alice", "hi"), &settings).await.0, "Alice");
+        });
+    }
+
+    #[test]
+    fn test_reset_restores_defaults_on_disk() {
+        let tmp = std::env::temp_dir().join("test-notification-reset");
+        fs::create_dir_all(&tmp).unwrap();
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            service.set_sent_sound_enabled(true).await.unwrap();
+            service.set_preview_mode(PreviewMode::Hidden, None).await.unwrap();
+            service.set_dnd_until(30, || {}).await.unwrap();
+            service.set_enabled(false).await.unwrap();
+
+            let reset = service.reset_settings().await.unwrap();
+            assert!(reset.enabled);
+            assert!(!reset.do_not_disturb);
+            assert_eq!(service.get_dnd_remaining().await, 0);
+
+            // A restart doesn't revive the old values
+            let restarted = NotificationService::new(tmp.clone()).get_settings().await;
+            assert!(!restarted.sent_sound_enabled);
+            assert!(!restarted.do_not_disturb);
+            assert_eq!(restarted.preview_mode, PreviewMode::Full);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_mention_detection() {
+        assert!(!message("Alice", "lunch?").is_mention());
+        assert!(message("Alice", "Alice mentioned you in Team").is_mention());
+        assert!(NotificationData {
+            require_interaction: true,
+            ..message("Alice", "lunch?")
+        }
+        .is_mention());
+    }
+
+    #[test]
+    fn test_preview_text_per_mode() {
+        let data = NotificationData {
+            platform: Some("Instagram".to_string()),
+            ..message("Alice", "the secret plan")
+        };
+        assert_eq!(
+            preview_text(&data, PreviewMode::Full),
+            ("Alice".to_string(), "the secret plan".to_string())
+        );
+        assert_eq!(
+            preview_text(&data, PreviewMode::SenderOnly),
+            ("Alice".to_string(), HIDDEN_PREVIEW_BODY.to_string())
+        );
+        assert_eq!(
+            preview_text(&data, PreviewMode::BodyOnly),
+            ("Instagram".to_string(), "the secret plan".to_string())
+        );
+        let (title, body) = preview_text(&message("Alice", "the secret plan"), PreviewMode::Hidden);
+        assert_eq!((title.as_str(), body.as_str()), (GENERIC_TITLE, "New message."));
+    }
+
+    #[test]
+    fn test_platform_preview_override() {
+        let tmp = std::env::temp_dir().join("test-preview-modes");
+        std::fs::create_dir_all(&tmp).unwrap();
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            service.set_preview_mode(PreviewMode::SenderOnly, None).await.unwrap();
+            service
+                .set_preview_mode(PreviewMode::Hidden, Some("X".to_string()))
+                .await
+                .unwrap();
+
+            let settings = NotificationService::new(tmp.clone()).get_settings().await;
+            assert_eq!(settings.preview_mode_for(Some("X")), PreviewMode::Hidden);
+            assert_eq!(settings.preview_mode_for(Some("Messenger")), PreviewMode::SenderOnly);
+            assert_eq!(settings.preview_mode_for(None), PreviewMode::SenderOnly);
+
+            service.clear_preview_mode("X").await.unwrap();
+            assert_eq!(service.get_settings().await.preview_mode_for(Some("X")), PreviewMode::SenderOnly);
+        });
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_sent_sound_ignores_dnd_but_not_global_mute() {
+        let mut settings = NotificationSettings {
+            sent_sound_enabled: true,
+            do_not_disturb: true,
+            ..NotificationSettings::default()
+        };
+        assert!(should_play_sent_sound(&settings));
+        settings.enabled = false;
+        assert!(!should_play_sent_sound(&settings));
+        assert!(!should_play_sent_sound(&NotificationSettings::default()));
+    }
+
+    #[test]
+    fn test_sent_sound_settings_persist() {
+        let tmp = std::env::temp_dir().join("test-sent-sound");
+        std::fs::create_dir_all(&tmp).unwrap();
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            service.set_sent_sound_enabled(true).await.unwrap();
+            assert!(service.set_sent_sound(Some("/no/such/sound.wav".to_string())).await.is_err());
+            assert!(service.play_sent_sound().await.unwrap());
+
+            let restarted = NotificationService::new(tmp.clone()).get_settings().await;
+            assert!(restarted.sent_sound_enabled);
+            assert_eq!(restarted.sent_sound_path, None);
+        });
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_dnd_digest_groups_by_conversation() {
+        let tmp = std::env::temp_dir().join("test-dnd-digest");
+        fs::create_dir_all(&tmp).unwrap();
+        let service = NotificationService::new(tmp.clone());
+        let in_group = |id: &str, title: &str| NotificationData {
+            title: title.to_string(),
+            conversation_id: Some(id.to_string()),
+            ..message(title, "hi")
+        };
+        tauri::async_runtime::block_on(async {
+            // Held back before this DND period: not part of its digest
+            service.set_dnd(true).await.unwrap();
+            service.show_notification(in_conversation("old")).await.unwrap();
+            service.set_dnd(false).await.unwrap();
+
+            service.set_dnd(true).await.unwrap();
+            for _ in 0..3 {
+                service.show_notification(in_group("alice", "Alice")).await.unwrap();
+            }
+            for _ in 0..5 {
+                service.show_notification(in_group("project", "Project group")).await.unwrap();
+            }
+            service.set_dnd(false).await.unwrap();
+
+            let digest = service.get_dnd_digest().await;
+            assert_eq!(digest.total, 8);
+            assert_eq!(digest.groups.len(), 2);
+            assert_eq!((digest.groups[0].name.as_str(), digest.groups[0].count), ("Project group", 5));
+            assert_eq!((digest.groups[1].name.as_str(), digest.groups[1].count), ("Alice", 3));
+            assert_eq!(
+                digest.summary(false).as_deref(),
+                Some("5 messages from Project group, 3 from Alice")
+            );
+            assert_eq!(digest.summary(true).as_deref(), Some("8 messages while Do Not Disturb was on"));
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_dnd_digest_shows_one_summary() {
+        let tmp = std::env::temp_dir().join("test-dnd-digest-summary");
+        fs::create_dir_all(&tmp).unwrap();
+        let service = NotificationService::new(tmp.clone());
+        tauri::async_runtime::block_on(async {
+            // Off by default: DND ends silently
+            service.set_dnd(true).await.unwrap();
+            service.show_notification(in_conversation("t1")).await.unwrap();
+            service.set_dnd(false).await.unwrap();
+            assert_eq!(service.take_dnd_summary().await, None);
+
+            service.set_digest_on_dnd_end(true).await.unwrap();
+            service.set_dnd(true).await.unwrap();
+            for _ in 0..4 {
+                service.show_notification(in_conversation("t1")).await.unwrap();
+            }
+            assert_eq!(service.take_dnd_summary().await, None);
+            service.set_dnd(false).await.unwrap();
+
+            // One summary for the whole period, not one per message
+            assert_eq!(service.take_dnd_summary().await.as_deref(), Some("4 messages from Alice"));
+            assert_eq!(service.take_dnd_summary().await, None);
+            assert!(NotificationService::new(tmp.clone()).get_settings().await.digest_on_dnd_end);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_rate_limit_bounds_a_flood() {
+        let tmp = std::env::temp_dir().join("test-notification-flood");
+        fs::create_dir_all(&tmp).unwrap();
+        let service = NotificationService::new(tmp.clone());
+        tauri::async_runtime::block_on(async {
+            service.set_rate_limit(RateLimit { per_second: 2, burst: 10 }).await.unwrap();
+            for i in 0..1000 {
+                service.show_notification(message("Spammer", &format!("#{}", i))).await.unwrap();
+            }
+
+            let history = service.get_history().await;
+            let shown = history.iter().filter(|r| !r.suppressed).count();
+            // The burst, plus whatever refilled while the loop ran
+            assert!((10..=12).contains(&shown), "shown {}", shown);
+            assert_eq!(history.len(), MAX_HISTORY);
+
+            // The most recent one is kept and shown once, standing in for the rest
+            assert!(service.schedule_flush().await.is_some());
+            assert!(service.schedule_flush().await.is_none());
+            let held = service.take_held().await.unwrap();
+            assert!(held.body.starts_with("#999 (+"));
+            assert!(service.take_held().await.is_none());
+
+            assert!(service.set_rate_limit(RateLimit { per_second: 0, burst: 1 }).await.is_err());
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_toast_actions_and_events() {
+        let actions = NotificationAction::builtin();
+        let data = in_conversation("t1");
+        let mut settings = NotificationSettings::default();
+
+        let ids = |list: Vec<NotificationAction>| list.into_iter().map(|a| a.id).collect::<Vec<_>>();
+        assert_eq!(ids(toast_actions(&actions, &settings, &data, true)), [ACTION_MARK_READ, ACTION_MUTE]);
+        settings.quick_reply_enabled = true;
+        assert_eq!(ids(toast_actions(&actions, &settings, &data, true)).len(), 3);
+        // No buttons where unsupported, or without a conversation to act on
+        assert!(toast_actions(&actions, &settings, &data, false).is_empty());
+        assert!(toast_actions(&actions, &settings, &message("Alice", "hi"), true).is_empty());
+
+        assert_eq!(
+            action_event(&actions, ACTION_MARK_READ, Some("t1".to_string())),
+            Some(NotificationActionPayload {
+                conversation_id: Some("t1".to_string()),
+                action_id: ACTION_MARK_READ.to_string(),
+            })
+        );
+        assert_eq!(action_event(&actions, "archive", Some("t1".to_string())), None);
+        assert_eq!(
+            parse_toast_arguments("action=mute&conversation=t1"),
+            Some(("mute".to_string(), Some("t1".to_string())))
+        );
+        assert_eq!(parse_toast_arguments("conversation=t1"), None);
+    }
+
+    #[test]
+    fn test_custom_and_builtin_actions() {
+        let service = fresh_service("test-notification-custom-and-builtin-actions");
+        tauri::async_runtime::block_on(async {
+            service.register_action("archive".to_string(), "Archive".to_string()).await.unwrap();
+            assert!(service.register_action(ACTION_MUTE.to_string(), "Silence".to_string()).await.is_err());
+            let payload = service.invoke_action("archive", Some("t1".to_string())).await.unwrap();
+            assert_eq!(payload.action_id, "archive");
+
+            // Built-in mute mutes the conversation itself
+            service.invoke_action(ACTION_MUTE, Some("t1".to_string())).await.unwrap();
+            let settings = service.get_settings().await;
+            assert!(is_conversation_muted(&settings, "t1", now_millis()));
+            assert!(service.invoke_action(ACTION_MUTE, None).await.is_err());
+
+            service.unregister_action("archive").await.unwrap();
+            assert!(service.invoke_action("archive", None).await.is_err());
+            assert!(service.unregister_action(ACTION_REPLY).await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_fallback_on_native_error() {
+        let native_error: Result<()> = Err(anyhow::anyhow!("no D-Bus session"));
+
+        // Native display worked: the plugin is never tried
+        let mut tried = false;
+        let (path, error) = display_with_fallback(&Ok(()), || {
+            tried = true;
+            Ok(())
+        });
+        assert_eq!(path, DisplayPath::Native);
+        assert!(error.is_none() && !tried);
+
+        // Native display failed: the plugin shows it
+        let (path, error) = display_with_fallback(&native_error, || Ok(()));
+        assert_eq!(path, DisplayPath::Fallback);
+        assert!(error.is_none());
+
+        // Both failed: only then is there an error to log
+        let (path, error) = display_with_fallback(&native_error, || Err(anyhow::anyhow!("plugin unavailable")));
+        assert_eq!(path, DisplayPath::Failed);
+        assert_eq!(error.unwrap().to_string(), "plugin unavailable");
+
+        // The UI is warned once per session
+        let failures = NativeFailures::default();
+        assert!(failures.record());
+        assert!(!failures.record());
+    }
+
+    #[test]
+    fn test_active_notification_tracking() {
+        let mut active = ActiveNotifications::default();
+        let mut a1 = in_conversation("a");
+        a1.id = "n1".to_string();
+        let mut a2 = in_conversation("a");
+        a2.id = "n2".to_string();
+        let mut b1 = in_conversation("b");
+        b1.id = "n3".to_string();
+
+        let id = active.insert(&a1);
+        assert_eq!(id, native_id("n1"));
+        assert!(id > 0);
+        // Re-showing the same notification doesn't track it twice
+        active.insert(&a1);
+        active.insert(&a2);
+        active.insert(&b1);
+
+        let read: Vec<String> = active.remove_conversation("a").into_iter().map(|e| e.id).collect();
+        assert_eq!(read, ["n1", "n2"]);
+        assert!(active.remove("n1").is_none());
+        assert_eq!(active.remove("n3").map(|e| e.native_id), Some(native_id("n3")));
+
+        // Only the most recent are kept
+        for i in 0..MAX_ACTIVE + 5 {
+            let mut data = in_conversation("c");
+            data.id = format!("flood-{}", i);
+            active.insert(&data);
+        }
+        assert!(active.remove("flood-0").is_none());
+        assert_eq!(active.clear().len(), MAX_ACTIVE);
+    }
+
+    #[test]
+    fn test_dismiss_dispatch() {
+        let service = fresh_service("test-notification-dismiss-dispatch");
+        let dispatched = std::sync::Mutex::new(Vec::new());
+        let dispatch = |id: &str, native: i32| -> std::result::Result<(), String> {
+            dispatched.lock().unwrap().push((id.to_string(), native));
+            // Already dismissed by the user: logged, not an error
+            if id == "gone" { Err("not delivered".to_string()) } else { Ok(()) }
+        };
+        tauri::async_runtime::block_on(async {
+            for (id, conversation) in [("n1", "t1"), ("n2", "t1"), ("n3", "t2"), ("gone", "t3")] {
+                let mut data = in_conversation(conversation);
+                data.id = id.to_string();
+                service.track(&data).await;
+            }
+
+            assert!(service.dismiss("n3", dispatch).await);
+            assert!(!service.dismiss("n3", dispatch).await);
+            assert_eq!(dispatched.lock().unwrap().as_slice(), [("n3".to_string(), native_id("n3"))]);
+
+            assert_eq!(service.dismiss_conversation("t1", dispatch).await, 2);
+            assert_eq!(service.dismiss_conversation("t1", dispatch).await, 0);
+
+            assert_eq!(service.dismiss_all(dispatch).await, 1);
+            assert_eq!(dispatched.lock().unwrap().len(), 4);
+            assert_eq!(service.dismiss_all(dispatch).await, 0);
+        });
+    }
+
+    #[test]
+    fn test_system_dnd_gating() {
+        let mut settings = NotificationSettings::default();
+        // Off by default: the system state isn't even asked
+        assert!(!held_by_system_dnd(&settings, || panic!("system DND queried while not respected")));
+
+        settings.respect_system_dnd = true;
+        assert!(held_by_system_dnd(&settings, || Some(true)));
+        assert!(!held_by_system_dnd(&settings, || Some(false)));
+        // Undetectable: only the app's own DND applies
+        assert!(!held_by_system_dnd(&settings, || None));
+    }
+
+    #[test]
+    fn test_reset_restores_system_dnd_on_disk() {
+        let tmp = std::env::temp_dir().join("test-notification-reset-system-dnd");
+        let _ = fs::remove_dir_all(&tmp);
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            service.set_respect_system_dnd(true).await.unwrap();
+            assert!(NotificationService::new(tmp.clone()).get_settings().await.respect_system_dnd);
+
+            assert!(!service.reset_settings().await.unwrap().respect_system_dnd);
+            assert!(!NotificationService::new(tmp.clone()).get_settings().await.respect_system_dnd);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_toast_group_derivation() {
+        // Same conversation, same group; different conversations, different groups
+        assert_eq!(toast_group("t_123"), toast_group("t_123"));
+        assert_eq!(toast_group("t_123"), "conv-t_123");
+        assert_ne!(toast_group("t_123"), toast_group("t_124"));
+
+        // Ids that don't fit a group are hashed, still stably
+        let long = "x".repeat(100);
+        assert_eq!(toast_group(&long), toast_group(&long));
+        assert!(toast_group(&long).len() <= MAX_TOAST_GROUP_LEN);
+        assert_eq!(toast_group("a b/c"), format!("conv-{:08x}", native_id("a b/c")));
+
+        let grouping = ToastGrouping::default();
+        assert_eq!(toast_group_for(&grouping, Some("t1")), Some("conv-t1".to_string()));
+        assert_eq!(toast_group_for(&grouping, None), None);
+        assert_eq!(toast_group_for(&grouping, Some("")), None);
+        let off = ToastGrouping { enabled: false, app_id: None };
+        assert_eq!(toast_group_for(&off, Some("t1")), None);
+
+        assert!(validate_app_id("com.messenger.desktop").is_ok());
+        assert!(validate_app_id("with space").is_err());
+        assert!(validate_app_id("").is_err());
+    }
+
+    #[test]
+    fn test_reset_restores_toast_grouping_on_disk() {
+        let tmp = std::env::temp_dir().join("test-notification-reset-toast-grouping");
+        let _ = fs::remove_dir_all(&tmp);
+        tauri::async_runtime::block_on(async {
+            let service = NotificationService::new(tmp.clone());
+            let custom = ToastGrouping {
+                enabled: false,
+                app_id: Some("com.example.other".to_string()),
+            };
+            service.set_toast_grouping(custom.clone()).await.unwrap();
+            assert_eq!(NotificationService::new(tmp.clone()).get_settings().await.toast_grouping, custom);
+
+            service.reset_settings().await.unwrap();
+            let restarted = NotificationService::new(tmp.clone()).get_settings().await;
+            assert_eq!(restarted.toast_grouping, ToastGrouping::default());
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
     #[test]
     fn test_notification_service_new() {
-        let _service = NotificationService::new(PathBuf::from("/tmp"));
+        let _service = fresh_service("test-notification-service-new");
         // Service instantiated successfully
         assert!(true);
     }
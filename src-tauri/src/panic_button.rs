@@ -0,0 +1,129 @@
+//! Panic button: wipe everything and quit, without asking.
+//!
+//! Bound to a global shortcut (`DEFAULT_SHORTCUT` unless rebound through
+//! `update_shortcut("panic", ...)`) and exposed as `panic_wipe`. The window is hidden
+//! first so nothing stays on screen; every later step runs even if an earlier one
+//! fails, and failures are only logged. Nothing is emitted to the page.
+//!
+//! The logins live in the webview profile: every webview's browsing data is cleared
+//! through the webview itself, then the profile directory is removed (or, if the
+//! webview still holds it open, on the next launch).
+
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::privacy_engine::PrivacyEngine;
+
+/// Shortcut action name for the panic button
+pub const ACTION: &str = "panic";
+
+/// Default panic shortcut
+pub const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+Escape";
+
+/// Deletes the on-disk data and clears the clipboard. Every step is attempted;
+/// the errors of the ones that failed are returned.
+fn wipe_data(
+    app_data_dir: &Path,
+    wipe_media: bool,
+    clear_clipboard: impl FnOnce() -> Result<(), String>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let engine = PrivacyEngine::new(app_data_dir.to_path_buf());
+    if let Err(e) = engine.clear_profile() {
+        errors.push(e);
+    }
+    if let Err(e) = engine.clear_all_sessions() {
+        errors.push(e);
+    }
+    if let Err(e) = clear_clipboard() {
+        errors.push(format!("clipboard: {}", e));
+    }
+    let media = app_data_dir.join("media");
+    if wipe_media && media.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&media) {
+            errors.push(format!("media: {}", e));
+        }
+    }
+    errors
+}
+
+/// Hides every window, wipes the logins, sessions, clipboard and (optionally)
+/// media, then exits
+pub fn run(app: &AppHandle, wipe_media: bool) {
+    let windows = app.webview_windows();
+    for window in windows.values() {
+        let _ = window.hide();
+    }
+
+    let mut errors: Vec<String> = windows
+        .values()
+        .filter_map(|window| window.clear_all_browsing_data().err())
+        .map(|e| format!("browsing data: {}", e))
+        .collect();
+    match crate::store::data_dir(app) {
+        Ok(dir) => errors.extend(wipe_data(&dir, wipe_media, || {
+            app.clipboard().clear().map_err(|e| e.to_string())
+        })),
+        Err(e) => errors.push(e.to_string()),
+    }
+    for e in errors {
+        log::warn!("[panic] {}", e);
+    }
+    app.exit(0);
+}
+
+/// Binds the panic button to `keys`
+pub fn register_shortcut(app: &AppHandle, keys: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(keys, |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                run(app, false);
+            }
+        })
+        .map_err(|e| format!("Failed to register panic shortcut {}: {}", keys, e))
+}
+
+/// Tauri command to hide the app, wipe all sessions and the clipboard (and media
+/// when `wipe_media` is set), then quit. Does not prompt.
+#[tauri::command]
+#[specta::specta]
+pub fn panic_wipe(wipe_media: bool, app: AppHandle) {
+    run(&app, wipe_media);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_wipe_removes_sessions_and_clipboard() {
+        let tmp = std::env::temp_dir().join("test-panic-wipe");
+        let session = tmp.join("sessions").join("Messenger").join("Network");
+        std::fs::create_dir_all(&session).unwrap();
+        std::fs::write(session.join("Cookies"), b"auth").unwrap();
+        let profile = tmp.join("webview").join("EBWebView").join("Default").join("Network");
+        std::fs::create_dir_all(&profile).unwrap();
+        std::fs::write(profile.join("Cookies"), b"auth").unwrap();
+        std::fs::create_dir_all(tmp.join("media")).unwrap();
+
+        let clipboard = RefCell::new("secret".to_string());
+        let errors = wipe_data(&tmp, false, || {
+            clipboard.borrow_mut().clear();
+            Ok(())
+        });
+        assert!(errors.is_empty());
+        assert!(!tmp.join("sessions").exists());
+        assert!(!tmp.join("webview").exists());
+        assert_eq!(clipboard.borrow().as_str(), "");
+        assert!(tmp.join("media").exists());
+
+        // A failing step doesn't stop the rest
+        let errors = wipe_data(&tmp, true, || Err("busy".to_string()));
+        assert_eq!(errors.len(), 1);
+        assert!(!tmp.join("media").exists());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
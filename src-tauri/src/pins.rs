@@ -0,0 +1,177 @@
+//! Pinned messages ("read it later").
+//!
+//! Pins are local bookmarks to a conversation with a timestamp and an optional note.
+//! They are never synced to the platform. Opening a pin emits `navigate-conversation`.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::platform_manager::Platform;
+use crate::store;
+
+/// A pinned conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Pin {
+    pub id: String,
+    pub platform: String,
+    pub conversation_id: String,
+    pub note: Option<String>,
+    /// Unix timestamp (ms) the pin was created
+    pub created_at: u64,
+}
+
+/// Payload emitted with `navigate-conversation`
+#[derive(Serialize, Clone, Debug)]
+pub struct NavigateConversationPayload {
+    pub platform: String,
+    pub conversation_id: String,
+}
+
+/// Manages pins and their persistence
+pub struct PinManager {
+    pins: std::sync::Mutex<Vec<Pin>>,
+    store_path: PathBuf,
+}
+
+impl PinManager {
+    /// Creates a new PinManager, loading any saved pins
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("pins.json");
+        let pins = store::load_or_default(&store_path);
+        Self {
+            pins: std::sync::Mutex::new(pins),
+            store_path,
+        }
+    }
+
+    /// Adds a pin and persists it
+    pub fn add(&self, platform: &str, conversation_id: &str, note: Option<String>) -> Result<Pin, String> {
+        let platform = Platform::from_str(platform).ok_or_else(|| format!("Unknown platform: {}", platform))?;
+        if conversation_id.trim().is_empty() {
+            return Err("Conversation id must not be empty".to_string());
+        }
+        let pin = Pin {
+            id: uuid::Uuid::new_v4().to_string(),
+            platform: platform.name().to_string(),
+            conversation_id: conversation_id.to_string(),
+            note: note.filter(|n| !n.trim().is_empty()),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        };
+        let mut pins = self.pins.lock().map_err(|e| e.to_string())?;
+        pins.push(pin.clone());
+        self.persist(&pins)?;
+        Ok(pin)
+    }
+
+    /// All pins, oldest first
+    pub fn list(&self) -> Result<Vec<Pin>, String> {
+        Ok(self.pins.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Looks up a pin by id
+    pub fn get(&self, id: &str) -> Result<Pin, String> {
+        self.list()?
+            .into_iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Pin not found: {}", id))
+    }
+
+    /// Removes a pin by id and persists the change
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        let mut pins = self.pins.lock().map_err(|e| e.to_string())?;
+        let before = pins.len();
+        pins.retain(|p| p.id != id);
+        if pins.len() == before {
+            return Err(format!("Pin not found: {}", id));
+        }
+        self.persist(&pins)
+    }
+
+    /// Persists the pins to disk
+    fn persist(&self, pins: &[Pin]) -> Result<(), String> {
+        store::save(&self.store_path, pins).map_err(|e| format!("Failed to save pins: {}", e))
+    }
+}
+
+/// Tauri command to pin a conversation with an optional note
+#[tauri::command]
+#[specta::specta]
+pub fn add_pin(
+    platform: String,
+    conversation_id: String,
+    note: Option<String>,
+    manager: tauri::State<'_, PinManager>,
+) -> Result<Pin, String> {
+    manager.add(&platform, &conversation_id, note)
+}
+
+/// Tauri command to list pins, oldest first
+#[tauri::command]
+#[specta::specta]
+pub fn list_pins(manager: tauri::State<'_, PinManager>) -> Result<Vec<Pin>, String> {
+    manager.list()
+}
+
+/// Tauri command to remove a pin
+#[tauri::command]
+#[specta::specta]
+pub fn remove_pin(id: String, manager: tauri::State<'_, PinManager>) -> Result<(), String> {
+    manager.remove(&id)
+}
+
+/// Tauri command to open a pinned conversation (emits `navigate-conversation`)
+#[tauri::command]
+#[specta::specta]
+pub fn open_pin(id: String, app: AppHandle, manager: tauri::State<'_, PinManager>) -> Result<(), String> {
+    let pin = manager.get(&id)?;
+    app.emit(
+        "navigate-conversation",
+        NavigateConversationPayload {
+            platform: pin.platform,
+            conversation_id: pin.conversation_id,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_list_remove() {
+        let tmp = std::env::temp_dir().join("test-pins");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = PinManager::new(&tmp);
+        let pin = manager.add("Messenger", "t1", Some("reply later".to_string())).unwrap();
+        manager.add("X", "t2", Some(" ".to_string())).unwrap();
+
+        let pins = manager.list().unwrap();
+        assert_eq!(pins.len(), 2);
+        assert_eq!(pins[0].note.as_deref(), Some("reply later"));
+        assert_eq!(pins[1].note, None);
+
+        manager.remove(&pin.id).unwrap();
+        assert!(manager.remove(&pin.id).is_err());
+        assert_eq!(manager.list().unwrap().len(), 1);
+
+        assert!(manager.add("MySpace", "t3", None).is_err());
+        assert!(manager.add("Messenger", "", None).is_err());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_pins_persist_across_restarts() {
+        let tmp = std::env::temp_dir().join("test-pins-persist");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let pin = PinManager::new(&tmp).add("Instagram", "t1", None).unwrap();
+        assert_eq!(PinManager::new(&tmp).get(&pin.id).unwrap(), pin);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
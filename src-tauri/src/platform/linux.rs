@@ -30,6 +30,27 @@ pub fn send_dbus_notification(title: &str, body: &str, icon: &str) {
     }
 }
 
+/// Closes a notification through the freedesktop `CloseNotification` D-Bus call.
+/// - `id`: The id the notification server knows it by.
+pub fn close_notification(id: u32) -> Result<(), String> {
+    let status = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest=org.freedesktop.Notifications",
+            "--object-path=/org/freedesktop/Notifications",
+            "--method=org.freedesktop.Notifications.CloseNotification",
+        ])
+        .arg(id.to_string())
+        .status()
+        .map_err(|e| format!("Failed to run gdbus: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("CloseNotification failed: {}", status))
+    }
+}
+
 /// Generate a desktop entry file for the application.
 /// - `app_name`: Application name (e.g., "Messenger Desktop").
 /// - `exec_path`: Path to the executable.
@@ -69,6 +90,16 @@ pub fn generate_desktop_file(app_name: &str, exec_path: &str) {
     }
 }
 
+/// Makes the window sticky, so it shows on every workspace. GTK sets
+/// `_NET_WM_STATE_STICKY`, and the window manager puts it on all desktops
+/// (`_NET_WM_DESKTOP` = 0xFFFFFFFF).
+pub fn set_sticky(window: &tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+    if enabled && std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        log::info!("Sticky windows are up to the Wayland compositor, which may ignore the request");
+    }
+    window.set_visible_on_all_workspaces(enabled).map_err(|e| e.to_string())
+}
+
 // Unit tests
 #[cfg(test)]
 mod tests {
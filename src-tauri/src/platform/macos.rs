@@ -5,17 +5,24 @@ use tauri::{AppHandle, Emitter, Listener};
 use objc::{class, msg_send, sel, sel_impl};
 use objc::runtime::Object;
 use std::ptr;
+use std::sync::Once;
 use objc_foundation::INSString;
 use objc_id::ShareId;
 
+/// Guards the `request-focus` listener so it is registered exactly once
+static REQUEST_FOCUS_LISTENER: Once = Once::new();
+
 /// Initialize macOS-specific features.
 pub fn init(app: &AppHandle) {
     log::info!("Initializing macOS platform features");
 
-    // Listen for request-focus events and bring app to foreground
-    let app_handle = app.clone();
-    app.listen("request-focus", move |_event| {
-        request_foreground_activation(&app_handle);
+    // The single `request-focus` listener: the tray and notification clicks emit the
+    // event instead of activating directly, so each request activates exactly once
+    REQUEST_FOCUS_LISTENER.call_once(|| {
+        let app_handle = app.clone();
+        app.listen("request-focus", move |_event| {
+            request_foreground_activation(&app_handle);
+        });
     });
 }
 
@@ -61,6 +68,97 @@ pub fn bounce_dock(critical: bool) {
     }
 }
 
+/// `NSWindowCollectionBehaviorCanJoinAllSpaces | NSWindowCollectionBehaviorFullScreenAuxiliary`
+const ABOVE_FULLSCREEN_BEHAVIOR: u64 = (1 << 0) | (1 << 8);
+
+/// `NSStatusWindowLevel`: above full-screen windows
+const STATUS_WINDOW_LEVEL: i64 = 25;
+
+/// `NSFloatingWindowLevel`: what plain always-on-top uses
+const FLOATING_WINDOW_LEVEL: i64 = 3;
+
+/// Lets an always-on-top window show over full-screen apps, or returns it to the
+/// normal floating level. Call after `set_always_on_top`.
+pub fn set_above_fullscreen(window: &tauri::WebviewWindow, on_top: bool, above_fullscreen: bool) -> Result<(), String> {
+    let ns_window = window.ns_window().map_err(|e| e.to_string())? as *mut Object;
+    let enabled = on_top && above_fullscreen;
+    unsafe {
+        let behavior: u64 = msg_send![ns_window, collectionBehavior];
+        let behavior = if enabled {
+            behavior | ABOVE_FULLSCREEN_BEHAVIOR
+        } else {
+            behavior & !ABOVE_FULLSCREEN_BEHAVIOR
+        };
+        let _: () = msg_send![ns_window, setCollectionBehavior: behavior];
+        if enabled {
+            let _: () = msg_send![ns_window, setLevel: STATUS_WINDOW_LEVEL];
+        } else if on_top {
+            let _: () = msg_send![ns_window, setLevel: FLOATING_WINDOW_LEVEL];
+        }
+    }
+    Ok(())
+}
+
+/// `NSWindowCollectionBehaviorCanJoinAllSpaces`
+const ALL_SPACES_BEHAVIOR: u64 = 1 << 0;
+
+/// Shows the window on every Space (the Dock's "Assign To: All Desktops"), or only
+/// on its own.
+pub fn set_all_spaces(window: &tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+    let ns_window = window.ns_window().map_err(|e| e.to_string())? as *mut Object;
+    unsafe {
+        let behavior: u64 = msg_send![ns_window, collectionBehavior];
+        let behavior = if enabled {
+            behavior | ALL_SPACES_BEHAVIOR
+        } else {
+            behavior & !ALL_SPACES_BEHAVIOR
+        };
+        let _: () = msg_send![ns_window, setCollectionBehavior: behavior];
+    }
+    Ok(())
+}
+
+/// Removes the delivered notification with `identifier` from Notification Center
+pub fn remove_delivered_notification(identifier: &str) -> Result<(), String> {
+    unsafe {
+        let center: *mut Object = msg_send![class!(NSUserNotificationCenter), defaultUserNotificationCenter];
+        if center.is_null() {
+            return Err("Notification Center unavailable".to_string());
+        }
+        // A snapshot: removing while walking it is fine
+        let delivered: *mut Object = msg_send![center, deliveredNotifications];
+        let count: usize = msg_send![delivered, count];
+        for i in 0..count {
+            let notification: *mut Object = msg_send![delivered, objectAtIndex: i];
+            let id: *mut Object = msg_send![notification, identifier];
+            if id.is_null() {
+                continue;
+            }
+            let utf8: *const std::os::raw::c_char = msg_send![id, UTF8String];
+            if !utf8.is_null() && std::ffi::CStr::from_ptr(utf8).to_str() == Ok(identifier) {
+                let _: () = msg_send![center, removeDeliveredNotification: notification];
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a Focus (Do Not Disturb) is on. macOS has no public API for this
+/// outside the Intents framework's entitlement, so it reads the assertions the
+/// Focus daemon keeps for manually enabled Focuses. `None` if they can't be read.
+pub fn focus_active() -> Option<bool> {
+    let path = dirs::home_dir()?.join("Library/DoNotDisturb/DB/Assertions.json");
+    let assertions: serde_json::Value = serde_json::from_slice(&std::fs::read(path).ok()?).ok()?;
+    let records = assertions
+        .get("data")?
+        .as_array()?
+        .iter()
+        .filter_map(|entry| entry.get("storeAssertionRecords")?.as_array())
+        .map(Vec::len)
+        .sum::<usize>();
+    Some(records > 0)
+}
+
 // Unit tests
 #[cfg(test)]
 mod tests {
@@ -1,7 +1,7 @@
 //! Platform-specific detection and dispatch for Tauri app.
 //! Uses conditional compilation to load OS-specific modules.
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 
 /// Initialize platform-specific features.
 pub fn init(app: &AppHandle) {
@@ -17,6 +17,165 @@ pub fn init(app: &AppHandle) {
     linux::init(app);
 }
 
+/// Raises the main window above other apps and gives it focus: `activateIgnoringOtherApps`
+/// on macOS (through the `request-focus` listener), `SetForegroundWindow` on Windows. On Linux, focusing presents the GTK
+/// window, which sends the `_NET_ACTIVE_WINDOW` request to the window manager.
+pub fn bring_to_front(app: &AppHandle) {
+    crate::startup::focus_main_window(app);
+
+    #[cfg(target_os = "macos")]
+    {
+        use tauri::Emitter;
+        let _ = app.emit("request-focus", ());
+    }
+
+    #[cfg(target_os = "windows")]
+    windows::set_foreground(app);
+}
+
+/// Asks for the user's attention, e.g. after a mention: bounces the dock on macOS,
+/// flashes the taskbar button on Windows and sets the urgency hint elsewhere.
+/// No-op while the main window is focused.
+pub fn request_attention(app: &AppHandle, count: u32) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_focused().unwrap_or(false) {
+        return;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = count;
+        macos::bounce_dock(true);
+    }
+
+    #[cfg(target_os = "windows")]
+    windows::flash_window(app, count);
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = count;
+        if let Err(e) = window.request_user_attention(Some(tauri::UserAttentionType::Critical)) {
+            log::warn!("Failed to request attention: {}", e);
+        }
+    }
+}
+
+/// Keeps `window` above other windows. On macOS `above_fullscreen` also lifts it
+/// over full-screen apps and onto every Space; elsewhere always-on-top already does.
+pub fn set_always_on_top(window: &tauri::WebviewWindow, on_top: bool, above_fullscreen: bool) -> Result<(), String> {
+    window.set_always_on_top(on_top).map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "macos")]
+    macos::set_above_fullscreen(window, on_top, above_fullscreen)?;
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = above_fullscreen;
+
+    Ok(())
+}
+
+/// Keeps `window` on the user's workspace: sticky (shown on every workspace) on
+/// Linux, on every Space on macOS, and on its virtual desktop on Windows, moved back
+/// to `desktop` first when one was saved. Returns the Windows desktop id to
+/// remember; `None` elsewhere. Setups that can't do it log and carry on.
+pub fn set_sticky_workspace(
+    window: &tauri::WebviewWindow,
+    enabled: bool,
+    desktop: Option<&str>,
+) -> Result<Option<String>, String> {
+    #[cfg(target_os = "macos")]
+    let pinned = {
+        let _ = desktop;
+        macos::set_all_spaces(window, enabled)?;
+        None
+    };
+
+    #[cfg(target_os = "windows")]
+    let pinned = windows::pin_to_desktop(window, enabled, desktop)?;
+
+    #[cfg(target_os = "linux")]
+    let pinned = {
+        let _ = desktop;
+        linux::set_sticky(window, enabled)?;
+        None
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    let pinned = {
+        let _ = (window, desktop);
+        if enabled {
+            log::info!("Sticky workspace not supported on this platform");
+        }
+        None
+    };
+
+    Ok(pinned)
+}
+
+/// Removes a shown notification from the notification center: by identifier
+/// (`tag`) on macOS and Windows, by its D-Bus id (`native_id`) on Linux.
+pub fn dismiss_notification(tag: &str, native_id: i32) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = native_id;
+        macos::remove_delivered_notification(tag)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = native_id;
+        windows::remove_toast(tag)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = tag;
+        linux::close_notification(native_id as u32)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (tag, native_id);
+        Ok(())
+    }
+}
+
+/// Whether the system's own Do Not Disturb is on: Focus Assist on Windows, a
+/// Focus on macOS. `None` where it can't be detected, leaving only the app's DND.
+pub fn system_dnd_active() -> Option<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::focus_assist_active()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos::focus_active()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Sets the app identity (AUMID) the process's notifications are attributed to
+/// and grouped under. Only Windows has one; elsewhere this does nothing.
+pub fn register_app_id(app_id: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::register_app_id(app_id)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = app_id;
+        Ok(())
+    }
+}
+
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "macos")]
@@ -1,11 +1,17 @@
 //! Windows-specific features for Tauri app.
 //! All functions are wrapped in `#[cfg(target_os = "windows")]`.
 
-use tauri::AppHandle;
-use windows::Win32::UI::Shell::{ITaskbarList3, TBPF_NORMAL, TBPF_ERROR, TaskbarList};
-use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::Win32::UI::Shell::{ITaskbarList3, IVirtualDesktopManager, SetCurrentProcessExplicitAppUserModelID, SHQueryUserNotificationState, TBPF_NORMAL, TBPF_ERROR, TaskbarList, VirtualDesktopManager};
+use windows::Win32::UI::Shell::{QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME, QUNS_RUNNING_D3D_FULL_SCREEN};
+use windows::Win32::UI::WindowsAndMessaging::{FlashWindowEx, SetForegroundWindow, FLASHWINFO, FLASHW_ALL, FLASHW_TIMERNOFG};
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_APARTMENTTHREADED};
 use windows::Win32::Foundation::HWND;
-use windows::core::Result;
+use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+use windows::core::{Result, GUID, HSTRING};
 
 /// Initialize Windows-specific features.
 pub fn init(app: &AppHandle) {
@@ -53,10 +59,137 @@ pub fn show_toast_notification(title: &str, body: &str) {
     log::info!("Toast: {} - {}", title, body);
 }
 
+/// Groups of the toasts shown with one, by tag: Action Center only removes a
+/// grouped toast given its group too
+static TOAST_GROUPS: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Bound on `TOAST_GROUPS`; toasts evicted from the active list are never removed
+const MAX_TOAST_GROUPS: usize = 256;
+
+/// Attributes the process's toasts to `app_id`. Windows only shows them under it
+/// once a Start menu shortcut carries the same AUMID, as the installer's does.
+pub fn register_app_id(app_id: &str) -> std::result::Result<(), String> {
+    unsafe { SetCurrentProcessExplicitAppUserModelID(&HSTRING::from(app_id)) }.map_err(|e| e.to_string())
+}
+
+/// Shows a toast from its XML under `app_id`, tagged `tag`. Toasts sharing a
+/// `group` collapse together in Action Center.
+pub fn show_toast(app_id: &str, xml: &str, tag: &str, group: Option<&str>) -> std::result::Result<(), String> {
+    let document = XmlDocument::new().map_err(|e| e.to_string())?;
+    document.LoadXml(&HSTRING::from(xml)).map_err(|e| e.to_string())?;
+    let toast = ToastNotification::CreateToastNotification(&document).map_err(|e| e.to_string())?;
+    toast.SetTag(&HSTRING::from(tag)).map_err(|e| e.to_string())?;
+    if let Some(group) = group {
+        toast.SetGroup(&HSTRING::from(group)).map_err(|e| e.to_string())?;
+        if let Ok(mut groups) = TOAST_GROUPS.lock() {
+            let groups = groups.get_or_insert_with(HashMap::new);
+            if groups.len() >= MAX_TOAST_GROUPS {
+                groups.clear();
+            }
+            groups.insert(tag.to_string(), group.to_string());
+        }
+    }
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_id))
+        .and_then(|notifier| notifier.Show(&toast))
+        .map_err(|e| e.to_string())
+}
+
+/// Removes the toast tagged `tag` from Action Center
+pub fn remove_toast(tag: &str) -> std::result::Result<(), String> {
+    let group = TOAST_GROUPS
+        .lock()
+        .ok()
+        .and_then(|mut groups| groups.as_mut().and_then(|g| g.remove(tag)));
+    let history = ToastNotificationManager::History().map_err(|e| e.to_string())?;
+    match group {
+        Some(group) => history.RemoveGroupedTag(&HSTRING::from(tag), &HSTRING::from(group)),
+        None => history.Remove(&HSTRING::from(tag)),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Whether Windows is holding back notifications itself: Focus Assist's quiet
+/// hours, a presentation, or a full-screen app or game. `None` if the state
+/// can't be queried.
+pub fn focus_assist_active() -> Option<bool> {
+    let state = unsafe { SHQueryUserNotificationState() }.ok()?;
+    Some(matches!(
+        state,
+        QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE | QUNS_QUIET_TIME
+    ))
+}
+
+/// Flash the taskbar button until the window comes to the foreground.
+/// - `count`: Number of flashes before the button stays highlighted.
+pub fn flash_window(app: &AppHandle, count: u32) {
+    let hwnd = get_app_window_handle(app);
+    if hwnd == HWND::default() {
+        log::error!("Failed to get window handle for taskbar flash");
+        return;
+    }
+
+    let info = FLASHWINFO {
+        cbSize: std::mem::size_of::<FLASHWINFO>() as u32,
+        hwnd,
+        dwFlags: FLASHW_ALL | FLASHW_TIMERNOFG,
+        uCount: count,
+        dwTimeout: 0,
+    };
+    unsafe {
+        let _ = FlashWindowEx(&info);
+    }
+}
+
+/// Bring the main window to the foreground.
+pub fn set_foreground(app: &AppHandle) {
+    let hwnd = get_app_window_handle(app);
+    if hwnd == HWND::default() {
+        log::error!("Failed to get window handle for foreground activation");
+        return;
+    }
+    unsafe {
+        if !SetForegroundWindow(hwnd).as_bool() {
+            log::warn!("SetForegroundWindow was refused");
+        }
+    }
+}
+
+/// Keeps the window on a virtual desktop: moves it to `desktop` (a saved id) if
+/// given, and returns the id of the desktop it is on, to save. Windows has no public
+/// API to show a window on every desktop, so it stays on this one.
+pub fn pin_to_desktop(
+    window: &tauri::WebviewWindow,
+    enabled: bool,
+    desktop: Option<&str>,
+) -> std::result::Result<Option<String>, String> {
+    if !enabled {
+        return Ok(None);
+    }
+    let hwnd = window.hwnd().map(|hwnd| HWND(hwnd.0 as _)).map_err(|e| e.to_string())?;
+    unsafe {
+        // Commands run off the main thread, which may not have COM set up yet
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let manager: IVirtualDesktopManager =
+            CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL).map_err(|e| e.to_string())?;
+        if let Some(id) = desktop.and_then(|d| u128::from_str_radix(d, 16).ok()) {
+            // The desktop may have been closed since it was saved
+            if let Err(e) = manager.MoveWindowToDesktop(hwnd, &GUID::from_u128(id)) {
+                log::warn!("Failed to move window to its virtual desktop: {}", e);
+            }
+        }
+        let current = manager.GetWindowDesktopId(hwnd).map_err(|e| e.to_string())?;
+        Ok(Some(format!("{:032x}", current.to_u128())))
+    }
+}
+
 /// Get the application window handle.
 /// Returns HWND or null if not found.
-fn get_app_window_handle(_app: &AppHandle) -> HWND {
-    HWND(std::ptr::null_mut()) // Placeholder - actual implementation would use app.get_window()
+fn get_app_window_handle(app: &AppHandle) -> HWND {
+    app.get_webview_window("main")
+        .and_then(|window| window.hwnd().ok())
+        // Tauri may link a different `windows` version; only the raw handle crosses over
+        .map(|hwnd| HWND(hwnd.0 as _))
+        .unwrap_or_default()
 }
 
 // Required dependency note:
@@ -1,11 +1,14 @@
 //! Platform manager for multi-platform messenger support
 //!
-//! This module manages platform selection, navigation, and state persistence.
+//! This module manages platform selection, navigation, and state persistence,
+//! including the order and visibility of platforms in the switcher.
 
 use serde::{Deserialize, Serialize};
-use std::fs;
+use specta::Type;
+use crate::store;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use tauri::Url;
+use tauri::{Manager, Url, WebviewWindow};
 
 /// Represents the supported social media platforms
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -31,6 +34,35 @@ impl Platform {
         }
     }
 
+    /// Returns the URL of one conversation (thread id as used in the platform's own URLs)
+    pub fn conversation_url(&self, conversation_id: &str) -> Result<Url, String> {
+        let base = match self {
+            Platform::Instagram => "https://www.instagram.com/direct/t/",
+            Platform::Messenger => "https://www.messenger.com/t/",
+            Platform::Facebook => "https://www.facebook.com/messages/t/",
+            Platform::X => "https://x.com/messages/",
+        };
+        if conversation_id.trim().is_empty() {
+            return Err("Conversation id is empty".to_string());
+        }
+        let mut url = Url::parse(base).map_err(|e| e.to_string())?;
+        url.path_segments_mut()
+            .map_err(|_| "Invalid conversation URL".to_string())?
+            .pop_if_empty()
+            .push(conversation_id);
+        Ok(url)
+    }
+
+    /// Returns the URL of the platform's login page
+    pub fn login_url(&self) -> &'static str {
+        match self {
+            Platform::Instagram => "https://www.instagram.com/accounts/login/",
+            Platform::Messenger => "https://www.messenger.com/login/",
+            Platform::Facebook => "https://www.facebook.com/login/",
+            Platform::X => "https://x.com/i/flow/login",
+        }
+    }
+
     /// Returns the display name of the platform
     pub fn name(&self) -> &'static str {
         match self {
@@ -41,6 +73,40 @@ impl Platform {
         }
     }
 
+    /// Returns the domains (including subdomains) that belong to the platform itself
+    pub fn domains(&self) -> &'static [&'static str] {
+        match self {
+            Platform::Instagram => &["instagram.com", "cdninstagram.com"],
+            Platform::Messenger => &["messenger.com"],
+            Platform::Facebook => &["facebook.com"],
+            Platform::X => &["x.com", "twitter.com"],
+        }
+    }
+
+    /// Returns the login/OAuth domains the platform redirects through while signing in
+    pub fn auth_domains(&self) -> &'static [&'static str] {
+        match self {
+            Platform::Instagram => &["facebook.com", "accountscenter.instagram.com"],
+            Platform::Messenger => &["facebook.com", "fbsbx.com"],
+            Platform::Facebook => &["fbsbx.com", "accountscenter.facebook.com"],
+            Platform::X => &["accounts.google.com", "appleid.apple.com"],
+        }
+    }
+
+    /// Returns the platform a host belongs to (including its subdomains)
+    pub fn from_host(host: &str) -> Option<Platform> {
+        Platform::all().into_iter().find(|p| {
+            p.domains()
+                .iter()
+                .any(|d| host == *d || host.ends_with(&format!(".{}", d)))
+        })
+    }
+
+    /// Returns all supported platforms in display order
+    pub fn all() -> [Platform; 4] {
+        [Platform::Instagram, Platform::Messenger, Platform::Facebook, Platform::X]
+    }
+
     /// Parses a platform name string into a Platform enum
     pub fn from_str(s: &str) -> Option<Platform> {
         match s {
@@ -53,62 +119,218 @@ impl Platform {
     }
 }
 
+/// Persisted platform switcher layout, by platform name. Names are kept as strings
+/// so any platform offered to the switcher can take part.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct SwitcherConfig {
+    /// Platforms in the order the user arranged them
+    pub order: Vec<String>,
+    /// Platforms hidden from the switcher
+    pub hidden: Vec<String>,
+}
+
+/// A platform as shown in the switcher
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitcherEntry {
+    pub name: String,
+    pub visible: bool,
+}
+
+/// Arranges `available` (in their default order) by the saved layout: ordered
+/// platforms first, then any the order doesn't mention. Unknown names are ignored.
+pub fn arrange(available: &[&str], config: &SwitcherConfig) -> Vec<SwitcherEntry> {
+    let mut names: Vec<&str> = Vec::with_capacity(available.len());
+    for name in config.order.iter().map(String::as_str).chain(available.iter().copied()) {
+        if available.contains(&name) && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+        .into_iter()
+        .map(|name| SwitcherEntry {
+            name: name.to_string(),
+            visible: !config.hidden.iter().any(|h| h == name),
+        })
+        .collect()
+}
+
+/// Names of the platforms the switcher offers
+fn available_platforms() -> Vec<&'static str> {
+    Platform::all().iter().map(Platform::name).collect()
+}
+
 /// Manages platform state and persistence
 pub struct PlatformManager {
     current: std::sync::Mutex<Option<Platform>>,
     store_path: PathBuf,
+    switcher: std::sync::Mutex<SwitcherConfig>,
+    switcher_path: PathBuf,
+    /// Last platform selected per account id, by platform name
+    account_platforms: std::sync::Mutex<HashMap<String, String>>,
+    account_platforms_path: PathBuf,
+}
+
+/// The platform to open for `account_id`: the one last used with it, or the
+/// global `fallback` when it has none (or one no longer known)
+pub fn platform_for_account(
+    account_platforms: &HashMap<String, String>,
+    account_id: &str,
+    fallback: Option<Platform>,
+) -> Option<Platform> {
+    account_platforms
+        .get(account_id)
+        .and_then(|name| Platform::from_str(name))
+        .or(fallback)
 }
 
 impl PlatformManager {
     /// Creates a new PlatformManager with the given app data directory
     pub fn new(app_data_dir: &Path) -> Self {
         let store_path = app_data_dir.join("platform.json");
+        let switcher_path = app_data_dir.join("platform_switcher.json");
+        let account_platforms_path = app_data_dir.join("account_platforms.json");
         let manager = Self {
             current: std::sync::Mutex::new(None),
             store_path,
+            switcher: std::sync::Mutex::new(store::load_or_default(&switcher_path)),
+            switcher_path,
+            account_platforms: std::sync::Mutex::new(store::load_or_default(&account_platforms_path)),
+            account_platforms_path,
         };
-        manager.load_last();
+        if let Err(e) = manager.load_last() {
+            log::warn!("Failed to load last platform: {}", e);
+        }
         manager
     }
 
     /// Gets the currently selected platform
-    pub fn get_current(&self) -> Option<Platform> {
-        self.current.lock().unwrap().clone()
+    pub fn get_current(&self) -> Result<Option<Platform>, String> {
+        Ok(self.current.lock().map_err(|e| e.to_string())?.clone())
     }
 
     /// Sets the current platform and persists it to disk
-    pub fn set_current(&self, platform: Platform) {
-        *self.current.lock().unwrap() = Some(platform);
-        self.persist();
+    pub fn set_current(&self, platform: Platform) -> Result<(), String> {
+        *self.current.lock().map_err(|e| e.to_string())? = Some(platform);
+        self.persist()
     }
 
     /// Loads the last used platform from disk
-    pub fn load_last(&self) -> Option<Platform> {
-        if self.store_path.exists() {
-            let content = fs::read_to_string(&self.store_path).ok()?;
-            let platform = serde_json::from_str::<String>(&content).ok()?;
-            Platform::from_str(&platform).map(|p| {
-                *self.current.lock().unwrap() = Some(p.clone());
-                p
-            })
-        } else {
-            None
+    pub fn load_last(&self) -> Result<Option<Platform>, String> {
+        let platform = store::load::<String>(&self.store_path)
+            .and_then(|name| Platform::from_str(&name));
+        if let Some(p) = &platform {
+            *self.current.lock().map_err(|e| e.to_string())? = Some(p.clone());
         }
+        Ok(platform)
     }
 
     /// Persists the current platform to disk
-    fn persist(&self) {
-        if let Some(platform) = self.current.lock().unwrap().as_ref() {
-            let _ = fs::write(
-                &self.store_path,
-                serde_json::to_string(platform.name()).unwrap(),
-            );
+    fn persist(&self) -> Result<(), String> {
+        if let Some(platform) = self.current.lock().map_err(|e| e.to_string())?.as_ref() {
+            store::save(&self.store_path, platform.name())
+                .map_err(|e| format!("Failed to save platform: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// The platforms in switcher order, with their visibility
+    pub fn switcher(&self) -> Result<Vec<SwitcherEntry>, String> {
+        let config = self.switcher.lock().map_err(|e| e.to_string())?;
+        Ok(arrange(&available_platforms(), &config))
+    }
+
+    /// Saves the switcher order; unknown and repeated names are dropped
+    pub fn set_order(&self, order: Vec<String>) -> Result<(), String> {
+        let order = arrange(
+            &available_platforms(),
+            &SwitcherConfig {
+                order,
+                hidden: Vec::new(),
+            },
+        )
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect();
+        let mut config = self.switcher.lock().map_err(|e| e.to_string())?;
+        config.order = order;
+        self.persist_switcher(&config)
+    }
+
+    /// Shows or hides a platform in the switcher. At least one stays visible.
+    pub fn set_visibility(&self, name: &str, visible: bool) -> Result<(), String> {
+        let available = available_platforms();
+        if !available.contains(&name) {
+            return Err(format!("Unknown platform: {}", name));
+        }
+        let mut config = self.switcher.lock().map_err(|e| e.to_string())?;
+        config.hidden.retain(|h| h != name);
+        if !visible {
+            config.hidden.push(name.to_string());
+            if arrange(&available, &config).iter().all(|e| !e.visible) {
+                config.hidden.pop();
+                return Err("At least one platform must stay visible".to_string());
+            }
+        }
+        self.persist_switcher(&config)
+    }
+
+    fn persist_switcher(&self, config: &SwitcherConfig) -> Result<(), String> {
+        store::save(&self.switcher_path, config)
+            .map_err(|e| format!("Failed to save platform switcher: {}", e))
+    }
+
+    /// Records `platform` as the last one used with `account_id`
+    pub fn record_for_account(&self, account_id: &str, platform: &Platform) -> Result<(), String> {
+        let mut map = self.account_platforms.lock().map_err(|e| e.to_string())?;
+        if map.get(account_id).map(String::as_str) == Some(platform.name()) {
+            return Ok(());
+        }
+        map.insert(account_id.to_string(), platform.name().to_string());
+        self.persist_account_platforms(&map)
+    }
+
+    /// Forgets the last platform of a removed account
+    pub fn forget_account(&self, account_id: &str) -> Result<(), String> {
+        let mut map = self.account_platforms.lock().map_err(|e| e.to_string())?;
+        if map.remove(account_id).is_some() {
+            self.persist_account_platforms(&map)?;
         }
+        Ok(())
     }
+
+    /// The platform to open for `account_id`, falling back to the global current one
+    pub fn platform_for_account(&self, account_id: &str) -> Result<Option<Platform>, String> {
+        let fallback = self.get_current()?;
+        let map = self.account_platforms.lock().map_err(|e| e.to_string())?;
+        Ok(platform_for_account(&map, account_id, fallback))
+    }
+
+    fn persist_account_platforms(&self, map: &HashMap<String, String>) -> Result<(), String> {
+        store::save(&self.account_platforms_path, map)
+            .map_err(|e| format!("Failed to save account platforms: {}", e))
+    }
+}
+
+/// Makes `platform` the current platform (and the active account's last one, so
+/// switching back to the account restores it) and navigates the window to it
+pub fn open_platform(window: &WebviewWindow, manager: &PlatformManager, platform: Platform) -> Result<(), String> {
+    manager.set_current(platform.clone())?;
+    let accounts = crate::accounts::managed_accounts(window.app_handle());
+    if let Some(active) = accounts.iter().find(|a| a.is_active) {
+        manager.record_for_account(&active.id, &platform)?;
+    }
+    crate::tray::on_platform_changed(window.app_handle(), &platform);
+    crate::window_manager::on_platform_changed(window.app_handle(), &platform);
+    let url = Url::parse(platform.url())
+        .map_err(|e| format!("Invalid platform URL: {}", e))?;
+    window
+        .navigate(url)
+        .map_err(|e| format!("Failed to navigate: {}", e))
 }
 
 /// Tauri command to select a platform by name
 #[tauri::command]
+#[specta::specta]
 pub fn select_platform(
     platform_name: String,
     manager: tauri::State<'_, PlatformManager>,
@@ -117,37 +339,60 @@ pub fn select_platform(
     let platform = Platform::from_str(&platform_name)
         .ok_or_else(|| format!("Unknown platform: {}", platform_name))?;
 
-    manager.set_current(platform.clone());
-    let url = Url::parse(platform.url())
-        .map_err(|e| format!("Invalid platform URL: {}", e))?;
-    window
-        .navigate(url)
-        .map_err(|e| format!("Failed to navigate: {}", e))?;
-
+    open_platform(&window, &manager, platform.clone())?;
     Ok(format!("Selected platform: {}", platform.name()))
 }
 
 /// Tauri command to get the currently selected platform
 #[tauri::command]
-pub fn get_current_platform(manager: tauri::State<'_, PlatformManager>) -> Option<String> {
-    manager.get_current().map(|p| p.name().to_string())
+#[specta::specta]
+pub fn get_current_platform(manager: tauri::State<'_, PlatformManager>) -> Result<Option<String>, String> {
+    Ok(manager.get_current()?.map(|p| p.name().to_string()))
 }
 
 /// Tauri command to get the last used platform from storage
 #[tauri::command]
-pub fn get_last_platform(manager: tauri::State<'_, PlatformManager>) -> Option<String> {
-    manager.load_last().map(|p| p.name().to_string())
+#[specta::specta]
+pub fn get_last_platform(manager: tauri::State<'_, PlatformManager>) -> Result<Option<String>, String> {
+    Ok(manager.load_last()?.map(|p| p.name().to_string()))
+}
+
+/// Tauri command to list the platforms in switcher order. Hidden platforms are
+/// left out unless `include_hidden` is set; each entry carries a `visible` flag.
+#[tauri::command]
+#[specta::specta]
+pub fn list_platforms(
+    include_hidden: Option<bool>,
+    manager: tauri::State<'_, PlatformManager>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let include_hidden = include_hidden.unwrap_or(false);
+    Ok(manager
+        .switcher()?
+        .into_iter()
+        .filter(|entry| include_hidden || entry.visible)
+        .filter_map(|entry| {
+            let platform = Platform::from_str(&entry.name)?;
+            Some(serde_json::json!({"name": entry.name, "url": platform.url(), "visible": entry.visible}))
+        })
+        .collect())
+}
+
+/// Tauri command to save the order of platforms in the switcher
+#[tauri::command]
+#[specta::specta]
+pub fn set_platform_order(order: Vec<String>, manager: tauri::State<'_, PlatformManager>) -> Result<(), String> {
+    manager.set_order(order)
 }
 
-/// Tauri command to list all available platforms
+/// Tauri command to show or hide a platform in the switcher
 #[tauri::command]
-pub fn list_platforms() -> Vec<serde_json::Value> {
-    vec![
-        serde_json::json!({"name": "Instagram", "url": Platform::Instagram.url()}),
-        serde_json::json!({"name": "Messenger", "url": Platform::Messenger.url()}),
-        serde_json::json!({"name": "Facebook", "url": Platform::Facebook.url()}),
-        serde_json::json!({"name": "X", "url": Platform::X.url()}),
-    ]
+#[specta::specta]
+pub fn set_platform_visibility(
+    name: String,
+    visible: bool,
+    manager: tauri::State<'_, PlatformManager>,
+) -> Result<(), String> {
+    manager.set_visibility(&name, visible)
 }
 
 #[cfg(test)]
@@ -172,6 +417,96 @@ mod tests {
         assert!(Platform::from_str("").is_none());
     }
 
+    #[test]
+    fn test_last_platform_per_account() {
+        let tmp = std::env::temp_dir().join("test-platform-per-account");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = PlatformManager::new(&tmp);
+        manager.set_current(Platform::Messenger).unwrap();
+
+        manager.record_for_account("work", &Platform::X).unwrap();
+        manager.record_for_account("home", &Platform::Instagram).unwrap();
+        manager.record_for_account("work", &Platform::Facebook).unwrap();
+        assert_eq!(manager.platform_for_account("work").unwrap(), Some(Platform::Facebook));
+
+        // Persisted across restarts
+        let reloaded = PlatformManager::new(&tmp);
+        assert_eq!(reloaded.platform_for_account("home").unwrap(), Some(Platform::Instagram));
+
+        // No recorded platform: the global one
+        assert_eq!(reloaded.platform_for_account("new").unwrap(), Some(Platform::Messenger));
+        reloaded.forget_account("home").unwrap();
+        assert_eq!(reloaded.platform_for_account("home").unwrap(), Some(Platform::Messenger));
+        let _ = std::fs::remove_dir_all(&tmp);
+
+        let stale = HashMap::from([("old".to_string(), "TikTok".to_string())]);
+        assert_eq!(platform_for_account(&stale, "old", Some(Platform::X)), Some(Platform::X));
+        assert_eq!(platform_for_account(&stale, "old", None), None);
+    }
+
+    #[test]
+    fn test_poisoned_lock_returns_error() {
+        let tmp = std::env::temp_dir().join("test-platform-poison");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = std::sync::Arc::new(PlatformManager::new(&tmp));
+
+        let poisoner = manager.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.current.lock().unwrap();
+            panic!("poison the platform lock");
+        })
+        .join();
+
+        assert!(manager.get_current().is_err());
+        assert!(manager.set_current(Platform::X).is_err());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    fn names(entries: &[SwitcherEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_switcher_order_and_visibility() {
+        let available = ["Instagram", "Messenger", "Facebook", "X"];
+        assert_eq!(
+            names(&arrange(&available, &SwitcherConfig::default())),
+            ["Instagram", "Messenger", "Facebook", "X"]
+        );
+
+        // Unknown and repeated names are ignored; unmentioned platforms follow
+        let config = SwitcherConfig {
+            order: vec!["X".into(), "TikTok".into(), "Messenger".into(), "X".into()],
+            hidden: vec!["Facebook".into()],
+        };
+        let entries = arrange(&available, &config);
+        assert_eq!(names(&entries), ["X", "Messenger", "Instagram", "Facebook"]);
+        assert!(entries[0].visible);
+        assert!(!entries[3].visible);
+    }
+
+    #[test]
+    fn test_switcher_persistence() {
+        let tmp = std::env::temp_dir().join("test-platform-switcher");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = PlatformManager::new(&tmp);
+        manager.set_order(vec!["Facebook".into(), "Nope".into(), "Instagram".into()]).unwrap();
+        manager.set_visibility("X", false).unwrap();
+        assert!(manager.set_visibility("Nope", false).is_err());
+        for name in ["Facebook", "Instagram", "Messenger"] {
+            let _ = manager.set_visibility(name, false);
+        }
+        // The last visible platform can't be hidden
+        assert_eq!(manager.switcher().unwrap().iter().filter(|e| e.visible).count(), 1);
+
+        let reloaded = PlatformManager::new(&tmp).switcher().unwrap();
+        assert_eq!(names(&reloaded), ["Facebook", "Instagram", "Messenger", "X"]);
+        assert!(!reloaded[3].visible);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
     #[test]
     fn test_platform_names() {
         assert_eq!(Platform::Instagram.name(), "Instagram");
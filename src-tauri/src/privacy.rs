@@ -1,28 +1,188 @@
 use tauri::{AppHandle, Emitter};
 use serde::{Serialize, Deserialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+use crate::store;
+
+/// File the privacy config is kept in, under the app data dir
+const PRIVACY_FILE: &str = "privacy.json";
+
+/// Request fragments that start/stop the typing indicator on the supported platforms
+const TYPING_PATTERNS: &[&str] = &[
+    "TypingIndicator",
+    "typing_indicator",
+    "/typing",
+    "is_typing",
+    "indicate_activity",
+    "activity_indicator",
+];
+
+/// Request fragments for presence heartbeats ("Active now" / last active)
+const PRESENCE_PATTERNS: &[&str] = &[
+    "/presence",
+    "active_status",
+    "ChatPresence",
+    "/ajax/chat/",
+    "set_presence",
+];
+
+/// Request fragments that mark a conversation as seen (the read receipt)
+const READ_RECEIPT_PATTERNS: &[&str] = &[
+    "markSeen",
+    "mark_seen",
+    "MarkThreadRead",
+    "markThreadAsRead",
+    "mark_read",
+    "read_receipt",
+];
+
+/// Whether read receipts are blocked in `conversation_id`: everywhere with the
+/// global flag, otherwise only in the conversations singled out.
+pub fn receipts_blocked(config: &PrivacyConfig, conversation_id: Option<&str>) -> bool {
+    config.block_read_receipts
+        || conversation_id.is_some_and(|id| config.blocked_receipt_conversations.iter().any(|c| c == id))
+}
+
+/// Builds the request interceptor for `config`. Typing, presence and read-receipt
+/// blocking are independent; with all off the script only removes a previously
+/// installed one. Read receipts are checked per request against the conversation
+/// open in the page (its id in the URL), mirroring `receipts_blocked`.
+pub fn privacy_script(config: &PrivacyConfig) -> String {
+    let mut patterns: Vec<&str> = Vec::new();
+    if config.block_typing {
+        patterns.extend(TYPING_PATTERNS);
+    }
+    if config.hide_last_active {
+        patterns.extend(PRESENCE_PATTERNS);
+    }
+    let blocks_receipts = config.block_read_receipts || !config.blocked_receipt_conversations.is_empty();
+    if patterns.is_empty() && !blocks_receipts {
+        return r#"
+        (function() {
+            if (window.__MD_PRIVACY__) window.__MD_PRIVACY__.disable();
+        })();
+        "#
+        .to_string();
+    }
+    let patterns = serde_json::to_string(&patterns).unwrap_or_else(|_| "[]".to_string());
+    let receipt_patterns: &[&str] = if blocks_receipts { READ_RECEIPT_PATTERNS } else { &[] };
+    let receipt_patterns = serde_json::to_string(receipt_patterns).unwrap_or_else(|_| "[]".to_string());
+    let receipt_conversations =
+        serde_json::to_string(&config.blocked_receipt_conversations).unwrap_or_else(|_| "[]".to_string());
+
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_PRIVACY__) window.__MD_PRIVACY__.disable();
+
+            const PATTERNS = {patterns};
+            const RECEIPT_PATTERNS = {receipt_patterns};
+            const BLOCK_ALL_RECEIPTS = {block_all_receipts};
+            const RECEIPT_CONVERSATIONS = {receipt_conversations};
+            const decoder = new TextDecoder('utf-8', {{ fatal: false }});
+
+            function asText(data) {{
+                if (typeof data === 'string') return data;
+                if (data instanceof URLSearchParams) return data.toString();
+                if (data instanceof ArrayBuffer) return decoder.decode(data);
+                if (ArrayBuffer.isView(data)) return decoder.decode(data);
+                return '';
+            }}
+            // The open conversation: /t/<id>, /e2ee/t/<id>, /direct/t/<id>,
+            // /messages/t/<id>, /messages/e2ee/t/<id> or /messages/<id>
+            function currentConversation() {{
+                const m = location.pathname.match(/\/(?:direct\/t|messages\/e2ee\/t|messages\/t|e2ee\/t|t|messages)\/([^\/]+)/);
+                return m ? decodeURIComponent(m[1]) : null;
+            }}
+            function receiptsBlocked() {{
+                if (BLOCK_ALL_RECEIPTS) return true;
+                const id = currentConversation();
+                return id !== null && RECEIPT_CONVERSATIONS.indexOf(id) !== -1;
+            }}
+            function blocked(url, body) {{
+                const haystack = String(url || '') + ' ' + asText(body);
+                function found(p) {{ return haystack.indexOf(p) !== -1; }}
+                return PATTERNS.some(found) || (RECEIPT_PATTERNS.some(found) && receiptsBlocked());
+            }}
+
+            const originalFetch = window.fetch;
+            const originalOpen = XMLHttpRequest.prototype.open;
+            const originalSend = XMLHttpRequest.prototype.send;
+            const originalWsSend = WebSocket.prototype.send;
+
+            window.fetch = function(input, init) {{
+                const url = (input && input.url) || input;
+                if (blocked(url, init && init.body)) {{
+                    return Promise.resolve(new Response('{{}}', {{ status: 200 }}));
+                }}
+                return originalFetch.call(this, input, init);
+            }};
+            XMLHttpRequest.prototype.open = function(method, url) {{
+                this.__mdUrl = url;
+                return originalOpen.apply(this, arguments);
+            }};
+            XMLHttpRequest.prototype.send = function(body) {{
+                if (blocked(this.__mdUrl, body)) return;
+                return originalSend.apply(this, arguments);
+            }};
+            WebSocket.prototype.send = function(data) {{
+                if (blocked('', data)) return;
+                return originalWsSend.apply(this, arguments);
+            }};
+
+            window.__MD_PRIVACY__ = {{
+                disable: function() {{
+                    window.fetch = originalFetch;
+                    XMLHttpRequest.prototype.open = originalOpen;
+                    XMLHttpRequest.prototype.send = originalSend;
+                    WebSocket.prototype.send = originalWsSend;
+                    delete window.__MD_PRIVACY__;
+                }},
+            }};
+        }})();
+        "#,
+        patterns = patterns,
+        receipt_patterns = receipt_patterns,
+        block_all_receipts = config.block_read_receipts,
+        receipt_conversations = receipt_conversations,
+    )
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Type)]
 pub struct PrivacyConfig {
     pub block_typing: bool,
     pub block_read_receipts: bool,
     pub hide_last_active: bool,
     pub block_link_previews: bool,
+    /// Conversations whose read receipts are blocked even with `block_read_receipts` off
+    #[serde(default)]
+    pub blocked_receipt_conversations: Vec<String>,
 }
 
 pub struct PrivacyManager {
     pub config: PrivacyConfig,
     app: AppHandle,
+    store_path: PathBuf,
 }
 
 impl PrivacyManager {
-    pub fn new(app: &AppHandle) -> Self {
+    pub fn new(app: &AppHandle, app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join(PRIVACY_FILE);
         Self {
-            config: PrivacyConfig::default(),
+            config: store::load_or_default(&store_path),
             app: app.clone(),
+            store_path,
         }
     }
 
+    /// Persists the config, then installs it on the page and emits it
     pub fn apply(&self) -> tauri::Result<()> {
+        store::save(&self.store_path, &self.config)?;
+        // Re-installed on every navigation through the injection registry
+        if let Err(e) = crate::injection::apply(&self.app, crate::injection::PRIVACY, &privacy_script(&self.config)) {
+            log::warn!("[privacy] failed to inject privacy interceptors: {}", e);
+        }
         self.app.emit("update-privacy", &self.config)?;
         Ok(())
     }
@@ -47,79 +207,152 @@ impl PrivacyManager {
         self.apply()
     }
 
-    #[allow(dead_code)]
     pub fn set_block_link_previews(&mut self, value: bool) -> tauri::Result<()> {
         self.config.block_link_previews = value;
         self.apply()
     }
 
+    /// Blocks or allows read receipts in one conversation. Returns whether they end
+    /// up blocked there (the global setting may still block them).
+    pub fn set_conversation_read_receipts(&mut self, conversation_id: &str, blocked: bool) -> tauri::Result<bool> {
+        let conversations = &mut self.config.blocked_receipt_conversations;
+        conversations.retain(|c| c != conversation_id);
+        if blocked {
+            conversations.push(conversation_id.to_string());
+            conversations.sort();
+        }
+        self.apply()?;
+        Ok(receipts_blocked(&self.config, Some(conversation_id)))
+    }
+
+    /// Stops blocking read receipts in every singled-out conversation
+    pub fn clear_blocked_receipt_conversations(&mut self) -> tauri::Result<()> {
+        self.config.blocked_receipt_conversations.clear();
+        self.apply()
+    }
+
+    /// Restores the default (all off) configuration
+    pub fn reset(&mut self) -> tauri::Result<()> {
+        self.update(PrivacyConfig::default())
+    }
+
     pub fn config(&self) -> &PrivacyConfig {
         &self.config
     }
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn set_privacy(
     state: tauri::State<std::sync::Mutex<PrivacyManager>>,
     block_typing: bool,
     block_read_receipts: bool,
     hide_last_active: bool,
     block_link_previews: bool,
-) -> tauri::Result<()> {
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
     let new_config = PrivacyConfig {
         block_typing,
         block_read_receipts,
         hide_last_active,
         block_link_previews,
+        ..manager.config().clone()
     };
-
-    let mut manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    manager.update(new_config)
+    manager.update(new_config).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn get_privacy(
     state: tauri::State<std::sync::Mutex<PrivacyManager>>,
-) -> tauri::Result<PrivacyConfig> {
-    let manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+) -> Result<PrivacyConfig, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
     Ok(manager.config().clone())
 }
 
+/// Resets the privacy guard to its defaults (re-emits `update-privacy`).
+/// The defaults are saved over `privacy.json`.
 #[tauri::command]
+#[specta::specta]
+pub fn reset_privacy_config(
+    state: tauri::State<std::sync::Mutex<PrivacyManager>>,
+) -> Result<PrivacyConfig, String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.reset().map_err(|e| e.to_string())?;
+    Ok(manager.config().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
 pub fn set_block_typing(
     state: tauri::State<std::sync::Mutex<PrivacyManager>>,
     value: bool,
-) -> tauri::Result<()> {
-    let mut manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    manager.set_block_typing(value)
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.set_block_typing(value).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn set_block_read_receipts(
     state: tauri::State<std::sync::Mutex<PrivacyManager>>,
     value: bool,
-) -> tauri::Result<()> {
-    let mut manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    manager.set_block_read_receipts(value)
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.set_block_read_receipts(value).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn set_hide_last_active(
     state: tauri::State<std::sync::Mutex<PrivacyManager>>,
     value: bool,
-) -> tauri::Result<()> {
-    let mut manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    manager.set_hide_last_active(value)
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.set_hide_last_active(value).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-#[allow(dead_code)]
+#[specta::specta]
 pub fn set_block_link_previews(
     state: tauri::State<std::sync::Mutex<PrivacyManager>>,
     value: bool,
-) -> tauri::Result<()> {
-    let mut manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    manager.set_block_link_previews(value)
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.set_block_link_previews(value).map_err(|e| e.to_string())
+}
+
+/// Blocks or allows read receipts in one conversation (keyed by the id in its URL).
+/// Returns whether receipts end up blocked there.
+#[tauri::command]
+#[specta::specta]
+pub fn set_conversation_read_receipts(
+    state: tauri::State<std::sync::Mutex<PrivacyManager>>,
+    conversation_id: String,
+    blocked: bool,
+) -> Result<bool, String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager
+        .set_conversation_read_receipts(&conversation_id, blocked)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_blocked_receipt_conversations(
+    state: tauri::State<std::sync::Mutex<PrivacyManager>>,
+) -> Result<Vec<String>, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.config().blocked_receipt_conversations.clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn clear_blocked_receipt_conversations(
+    state: tauri::State<std::sync::Mutex<PrivacyManager>>,
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.clear_blocked_receipt_conversations().map_err(|e| e.to_string())
 }
 
 // Unit tests
@@ -143,6 +376,7 @@ mod tests {
             block_read_receipts: true,
             hide_last_active: true,
             block_link_previews: true,
+            blocked_receipt_conversations: Vec::new(),
         };
         let cloned = config.clone();
         assert_eq!(config.block_typing, cloned.block_typing);
@@ -156,12 +390,64 @@ mod tests {
             block_read_receipts: false,
             hide_last_active: true,
             block_link_previews: false,
+            blocked_receipt_conversations: Vec::new(),
         };
         let json = serde_json::to_string(&config).unwrap();
         let deserialized: PrivacyConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(config.block_typing, deserialized.block_typing);
     }
 
+    #[test]
+    fn test_interceptors_follow_config() {
+        let typing_only = privacy_script(&PrivacyConfig {
+            block_typing: true,
+            ..PrivacyConfig::default()
+        });
+        assert!(typing_only.contains("TypingIndicator"));
+        assert!(!typing_only.contains("/presence"));
+
+        let presence_only = privacy_script(&PrivacyConfig {
+            hide_last_active: true,
+            ..PrivacyConfig::default()
+        });
+        assert!(presence_only.contains("/presence"));
+        assert!(!presence_only.contains("TypingIndicator"));
+
+        let off = privacy_script(&PrivacyConfig::default());
+        assert!(off.contains("disable()"));
+        assert!(!off.contains("PATTERNS"));
+    }
+
+    #[test]
+    fn test_read_receipts_per_conversation() {
+        let mut config = PrivacyConfig {
+            blocked_receipt_conversations: vec!["100012345".to_string()],
+            ..PrivacyConfig::default()
+        };
+        assert!(receipts_blocked(&config, Some("100012345")));
+        assert!(!receipts_blocked(&config, Some("999")));
+        assert!(!receipts_blocked(&config, None));
+
+        let script = privacy_script(&config);
+        assert!(script.contains("markSeen"));
+        assert!(script.contains(r#"const RECEIPT_CONVERSATIONS = ["100012345"];"#));
+        assert!(script.contains("const BLOCK_ALL_RECEIPTS = false;"));
+        assert!(!script.contains("TypingIndicator"));
+
+        // The global flag blocks them everywhere, singled out or not
+        config.block_read_receipts = true;
+        assert!(receipts_blocked(&config, Some("999")));
+        assert!(receipts_blocked(&config, None));
+        assert!(privacy_script(&config).contains("const BLOCK_ALL_RECEIPTS = true;"));
+
+        // Older files without the set still load
+        let old: PrivacyConfig = serde_json::from_str(
+            r#"{"block_typing":false,"block_read_receipts":true,"hide_last_active":false,"block_link_previews":false}"#,
+        )
+        .unwrap();
+        assert!(old.blocked_receipt_conversations.is_empty());
+    }
+
     #[test]
     fn test_privacy_manager_new() {
         // Need AppHandle for testing, so skip actual instantiation
@@ -1,7 +1,112 @@
 //! Privacy Engine for multi-platform session isolation and privacy enforcement.
 //! Provides session directory management, cookie clearing, and Content Security Policy (CSP) per platform.
 
-use std::path::PathBuf;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::webview::Cookie;
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+use crate::platform_manager::{Platform, PlatformManager};
+
+/// Cookie store file names used by the webview engines (WebView2, WebKitGTK, WKWebView).
+const COOKIE_FILES: &[&str] = &["Cookies", "cookies.sqlite", "cookies.db", "Cookies.binarycookies"];
+
+/// Clears the page's own storage and script-visible cookies before logging out
+const CLEAR_PAGE_STORAGE_JS: &str = r#"
+(function() {
+    try { localStorage.clear(); } catch (e) {}
+    try { sessionStorage.clear(); } catch (e) {}
+    document.cookie.split(';').forEach(function(c) {
+        const name = c.split('=')[0].trim();
+        if (name) document.cookie = name + '=; expires=Thu, 01 Jan 1970 00:00:00 GMT; path=/; domain=.' + location.hostname.replace(/^www\./, '');
+    });
+})();
+"#;
+
+/// Cache directory names used by the webview engines. Everything else in the profile
+/// (cookies, Local Storage, IndexedDB, service worker registrations) is kept.
+///
+/// - WebView2: `Cache`, `Code Cache`, `GPUCache`, `DawnCache`, `GrShaderCache`,
+///   `ShaderCache`, and `Service Worker/{CacheStorage,ScriptCache}`
+/// - WebKitGTK: `WebKitCache`, `CacheStorage`, `offline-web-application-cache`
+/// - WKWebView: `NetworkCache`, `CacheStorage`
+const CACHE_DIRS: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "GrShaderCache",
+    "ShaderCache",
+    "CacheStorage",
+    "ScriptCache",
+    "WebKitCache",
+    "NetworkCache",
+    "offline-web-application-cache",
+];
+
+/// Payload emitted with `logged-out`
+#[derive(Serialize, Clone, Debug)]
+pub struct LoggedOutPayload {
+    pub platform: String,
+}
+
+/// Payload emitted with `webview-cache-cleared`
+#[derive(Serialize, Clone, Debug)]
+pub struct CacheClearedPayload {
+    pub bytes_freed: u64,
+}
+
+/// Disk used by the webview profile the platforms share
+#[derive(Serialize, Clone, Debug, PartialEq, Type)]
+pub struct SessionUsage {
+    /// Bytes of site storage (IndexedDB, Local Storage) each platform keeps, by name.
+    /// Every platform is listed, at 0 if it stores nothing.
+    pub platforms: HashMap<String, u64>,
+    /// Bytes no single platform owns: the HTTP cache, the cookie store and the
+    /// webview's own files
+    pub shared: u64,
+    /// Bytes `clear_webview_cache` can free (part of `shared`)
+    pub cache: u64,
+    /// Bytes used in all
+    pub total: u64,
+}
+
+/// Returns the platform a profile entry belongs to, for the entries the webview
+/// engines name after the site's origin (WebView2's
+/// `https_www.instagram.com_0.indexeddb.leveldb`, WebKitGTK's
+/// `https_www.instagram.com_0.localstorage`).
+fn origin_platform(name: &str) -> Option<Platform> {
+    let rest = name.strip_prefix("https_").or_else(|| name.strip_prefix("http_"))?;
+    let host = rest.split('_').next()?;
+    Platform::from_host(&host.to_lowercase())
+}
+
+/// Total size in bytes of the files under `path`
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(m) if m.is_dir() => dir_size(&entry.path()),
+            Ok(m) => m.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Tracker / ad domains that are never needed for messaging
+pub const BLOCKED_DOMAINS: &[&str] = &[
+    "doubleclick.net",
+    "googlesyndication.com",
+    "google-analytics.com",
+    "analytics.facebook.com",
+    "pixel.facebook.com",
+];
 
 /// Privacy Engine for managing session isolation and privacy enforcement.
 #[derive(Debug)]
@@ -36,6 +141,61 @@ impl PrivacyEngine {
         self.app_data_dir.join("sessions").join(platform)
     }
 
+    /// Returns the data directory of the platform webviews (the main window and the
+    /// conversation windows): cookies, site storage and cache. One webview serves every
+    /// platform, so they all share it. WKWebView (macOS) ignores it and keeps its data
+    /// in the system's store, which only `clear_all_browsing_data` reaches.
+    ///
+    /// # Returns
+    ///
+    /// The `webview/` directory under the app data directory.
+    pub fn profile_dir(&self) -> PathBuf {
+        self.app_data_dir.join("webview")
+    }
+
+    /// Marker left when the profile couldn't be removed because the webview held it open
+    fn pending_wipe_marker(&self) -> PathBuf {
+        self.app_data_dir.join("webview.wipe")
+    }
+
+    /// Removes the webview profile, logging every platform out. A profile the running
+    /// webview keeps open (WebView2 locks its files) is removed on the next launch,
+    /// before the webview starts (see `finish_pending_wipe`).
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` once removed or scheduled, or an error message on failure.
+    pub fn clear_profile(&self) -> Result<(), String> {
+        let dir = self.profile_dir();
+        if dir.exists() && std::fs::remove_dir_all(&dir).is_err() {
+            std::fs::write(self.pending_wipe_marker(), b"").map_err(|e| format!("clear_profile: {e}"))?;
+            log::info!("[PrivacyEngine] webview profile in use, removing it on the next launch");
+            return Ok(());
+        }
+        log::info!("[PrivacyEngine] cleared the webview profile");
+        Ok(())
+    }
+
+    /// Completes a profile removal scheduled by `clear_profile`. Must run before the
+    /// first webview is built.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success (including when nothing was pending), or an error message on failure.
+    pub fn finish_pending_wipe(&self) -> Result<(), String> {
+        let marker = self.pending_wipe_marker();
+        if !marker.exists() {
+            return Ok(());
+        }
+        let dir = self.profile_dir();
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| format!("finish_pending_wipe: {e}"))?;
+        }
+        std::fs::remove_file(&marker).map_err(|e| format!("finish_pending_wipe: {e}"))?;
+        log::info!("[PrivacyEngine] removed the webview profile left by a wipe");
+        Ok(())
+    }
+
     /// Clears the session for a specific platform by removing its directory and recreating it.
     ///
     /// # Arguments
@@ -55,6 +215,196 @@ impl PrivacyEngine {
         Ok(())
     }
 
+    /// Clears only the cache directories of the webview profile, keeping the logins.
+    /// The running webview keeps some cache files open; those are skipped.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes freed, or an error message on failure.
+    pub fn clear_cache(&self) -> Result<u64, String> {
+        /// Removes what can be removed under `path`, returning the bytes freed
+        fn remove(path: &Path) -> u64 {
+            let freed = match std::fs::read_dir(path) {
+                Ok(entries) => entries.flatten().map(|entry| remove(&entry.path())).sum(),
+                Err(_) => {
+                    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+                    return if std::fs::remove_file(path).is_ok() { size } else { 0 };
+                }
+            };
+            let _ = std::fs::remove_dir(path);
+            freed
+        }
+        fn walk(dir: &Path) -> Result<u64, String> {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return Ok(0);
+            };
+            let mut freed = 0;
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !path.is_dir() {
+                    continue;
+                }
+                let is_cache = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| CACHE_DIRS.contains(&n));
+                if is_cache {
+                    freed += remove(&path);
+                } else {
+                    freed += walk(&path)?;
+                }
+            }
+            Ok(freed)
+        }
+        let freed = walk(&self.profile_dir())?;
+        log::info!("[PrivacyEngine] cleared {} bytes of cache", freed);
+        Ok(freed)
+    }
+
+    /// Measures the webview profile. Site storage named after a platform's origin
+    /// counts for that platform; the rest (cache, cookies) is shared, as the webview
+    /// doesn't keep it per site. Without a profile (never opened, or on macOS, where
+    /// WKWebView keeps its own store) everything is 0 bytes.
+    ///
+    /// # Returns
+    ///
+    /// The bytes used per platform, shared, by the cache directories, and in total.
+    pub fn session_usage(&self) -> SessionUsage {
+        fn walk(dir: &Path, usage: &mut SessionUsage) {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+                let size = if meta.is_dir() { dir_size(&path) } else { meta.len() };
+                let owner = path.file_name().and_then(|n| n.to_str()).and_then(origin_platform);
+                if let Some(platform) = owner {
+                    *usage.platforms.entry(platform.name().to_string()).or_default() += size;
+                } else if meta.is_dir() {
+                    walk(&path, usage);
+                } else {
+                    usage.shared += size;
+                }
+            }
+        }
+        fn cache_size(dir: &Path) -> u64 {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return 0;
+            };
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .map(|path| {
+                    let is_cache = path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| CACHE_DIRS.contains(&n));
+                    if is_cache {
+                        dir_size(&path)
+                    } else {
+                        cache_size(&path)
+                    }
+                })
+                .sum()
+        }
+        let profile = self.profile_dir();
+        let mut usage = SessionUsage {
+            platforms: Platform::all().iter().map(|p| (p.name().to_string(), 0)).collect(),
+            shared: 0,
+            cache: cache_size(&profile),
+            total: 0,
+        };
+        walk(&profile, &mut usage);
+        usage.total = usage.shared + usage.platforms.values().sum::<u64>();
+        usage
+    }
+
+    /// Returns whether the webview profile holds a cookie store, i.e. whether a
+    /// platform may be logged in.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a cookie file exists anywhere under the profile directory.
+    pub fn has_session_cookie(&self) -> bool {
+        fn walk(dir: &Path) -> bool {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return false;
+            };
+            entries.flatten().any(|entry| {
+                let path = entry.path();
+                if path.is_dir() {
+                    walk(&path)
+                } else {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| COOKIE_FILES.contains(&n))
+                }
+            })
+        }
+        walk(&self.profile_dir())
+    }
+
+    /// Returns the throwaway session directory for an incognito session.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The incognito session id.
+    ///
+    /// # Returns
+    ///
+    /// The directory under `sessions/incognito/`.
+    pub fn incognito_dir(&self, id: &str) -> PathBuf {
+        self.session_dir("incognito").join(id)
+    }
+
+    /// Removes an incognito session directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The incognito session id.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success (including when it is already gone), or an error message on failure.
+    pub fn clear_incognito(&self, id: &str) -> Result<(), String> {
+        let dir = self.incognito_dir(id);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| format!("clear_incognito: {e}"))?;
+        }
+        log::info!("[PrivacyEngine] cleared incognito session {}", id);
+        Ok(())
+    }
+
+    /// Removes incognito directories left behind by a crash.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time.
+    /// * `max_age` - Directories last modified longer ago than this are removed.
+    ///
+    /// # Returns
+    ///
+    /// The number of directories removed.
+    pub fn sweep_stale_incognito(&self, now: SystemTime, max_age: Duration) -> usize {
+        let Ok(entries) = std::fs::read_dir(self.session_dir("incognito")) else {
+            return 0;
+        };
+        entries
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|modified| now.duration_since(modified).is_ok_and(|age| age > max_age))
+            })
+            .filter(|entry| std::fs::remove_dir_all(entry.path()).is_ok())
+            .count()
+    }
+
     /// Clears all sessions by removing the entire sessions directory.
     ///
     /// # Returns
@@ -99,9 +449,7 @@ impl PrivacyEngine {
     /// `true` if the URL contains a blocked domain, `false` otherwise.
     #[allow(dead_code)]
     pub fn is_blocked_domain(url: &str) -> bool {
-        ["doubleclick.net","googlesyndication.com","google-analytics.com",
-         "analytics.facebook.com","pixel.facebook.com"]
-            .iter().any(|d| url.contains(d))
+        BLOCKED_DOMAINS.iter().any(|d| url.contains(d))
     }
 }
 
@@ -116,10 +464,133 @@ impl PrivacyEngine {
 ///
 /// `Ok(())` on success, or an error message on failure.
 #[tauri::command]
+#[specta::specta]
 pub fn clear_platform_session(platform: String, engine: tauri::State<'_, PrivacyEngine>) -> Result<(), String> {
     engine.clear_session(&platform)
 }
 
+/// Clears the cache of the webview profile while keeping the platforms logged in.
+/// The platforms share the profile and the webview doesn't keep its cache per site,
+/// so this is profile-wide: it frees the cache of every platform at once.
+///
+/// # Arguments
+///
+/// * `app` - The app handle, used to emit `webview-cache-cleared`.
+/// * `engine` - The Tauri state containing the `PrivacyEngine` instance.
+///
+/// # Returns
+///
+/// The number of bytes freed, or an error message on failure.
+#[tauri::command]
+#[specta::specta]
+pub fn clear_webview_cache(app: AppHandle, engine: tauri::State<'_, PrivacyEngine>) -> Result<u64, String> {
+    let bytes_freed = engine.clear_cache()?;
+    app.emit("webview-cache-cleared", CacheClearedPayload { bytes_freed })
+        .map_err(|e| e.to_string())?;
+    Ok(bytes_freed)
+}
+
+/// Returns how much disk the platforms' shared webview profile uses: per platform,
+/// shared between them, and how much of it is cache (see `clear_webview_cache` to
+/// free it). The profile is walked off the main thread, as a long-used one can hold
+/// many files.
+///
+/// # Arguments
+///
+/// * `engine` - The Tauri state containing the `PrivacyEngine` instance.
+///
+/// # Returns
+///
+/// The bytes used per platform, shared, by the cache and in total, or an error
+/// message on failure.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_usage(engine: tauri::State<'_, PrivacyEngine>) -> Result<SessionUsage, String> {
+    let engine = PrivacyEngine::new(engine.app_data_dir.clone());
+    tauri::async_runtime::spawn_blocking(move || engine.session_usage())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Returns `true` if a cookie set for `domain` (e.g. `.instagram.com`) logs `platform` in.
+/// Cookies of the shared sign-in domains (see `Platform::auth_domains`) belong to
+/// whichever platform owns the domain, so another platform's login survives.
+fn is_login_cookie(platform: &Platform, domain: &str) -> bool {
+    let host = domain.trim_start_matches('.').to_lowercase();
+    Platform::from_host(&host).as_ref() == Some(platform)
+}
+
+/// Removes the cookies of `platform` from `jar`, leaving every other platform's.
+///
+/// # Arguments
+///
+/// * `platform` - The platform to log out of.
+/// * `jar` - The cookies of the webview profile.
+/// * `delete` - Removes one cookie from the webview.
+///
+/// # Returns
+///
+/// The number of cookies removed, or an error message on failure.
+fn remove_login_cookies(
+    platform: &Platform,
+    jar: Vec<Cookie<'static>>,
+    mut delete: impl FnMut(Cookie<'static>) -> Result<(), String>,
+) -> Result<usize, String> {
+    let mut removed = 0;
+    for cookie in jar {
+        if cookie.domain().is_some_and(|d| is_login_cookie(platform, d)) {
+            delete(cookie)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Logs out of a platform by removing the webview's cookies for its domains
+/// (unlike `clear_webview_cache`, which keeps the login). The platforms share the
+/// webview profile, but their cookies are scoped by domain, so the other platforms
+/// stay logged in. If it is the active platform, the page's storage is cleared too
+/// and it is sent to the login page. Emits `logged-out`.
+///
+/// The cookie store is read off the main thread, as WebView2 deadlocks otherwise.
+///
+/// # Arguments
+///
+/// * `platform` - The platform name.
+/// * `app` - The app handle, used to emit `logged-out`.
+/// * `manager` - The Tauri state containing the `PlatformManager` instance.
+///
+/// # Returns
+///
+/// `Ok(())` on success, or an error message on failure.
+#[tauri::command]
+#[specta::specta]
+pub async fn logout_platform(
+    platform: String,
+    app: AppHandle,
+    manager: tauri::State<'_, PlatformManager>,
+) -> Result<(), String> {
+    let target = Platform::from_str(&platform).ok_or_else(|| format!("Unknown platform: {}", platform))?;
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    // The conversation windows use the same cookie store, so this logs them out too
+    let jar = window.cookies().map_err(|e| format!("Failed to read cookies: {}", e))?;
+    let removed = remove_login_cookies(&target, jar, |cookie| {
+        window.delete_cookie(cookie).map_err(|e| format!("Failed to clear cookies: {}", e))
+    })?;
+    log::info!("[PrivacyEngine] logged out of {} ({} cookies removed)", platform, removed);
+
+    if manager.get_current()?.as_ref() == Some(&target) {
+        window.eval(CLEAR_PAGE_STORAGE_JS).map_err(|e| e.to_string())?;
+        let url = Url::parse(target.login_url()).map_err(|e| format!("Invalid login URL: {}", e))?;
+        window.navigate(url).map_err(|e| format!("Failed to navigate: {}", e))?;
+    }
+
+    app.emit("logged-out", LoggedOutPayload { platform }).map_err(|e| e.to_string())
+}
+
 /// Clears all sessions.
 ///
 /// # Arguments
@@ -130,6 +601,7 @@ pub fn clear_platform_session(platform: String, engine: tauri::State<'_, Privacy
 ///
 /// `Ok(())` on success, or an error message on failure.
 #[tauri::command]
+#[specta::specta]
 pub fn clear_all_sessions(engine: tauri::State<'_, PrivacyEngine>) -> Result<(), String> {
     engine.clear_all_sessions()
 }
@@ -144,6 +616,7 @@ pub fn clear_all_sessions(engine: tauri::State<'_, PrivacyEngine>) -> Result<(),
 ///
 /// The CSP string for the platform.
 #[tauri::command]
+#[specta::specta]
 pub fn get_csp_for_platform(platform: String) -> String {
     PrivacyEngine::csp_for_platform(&platform).to_string()
 }
@@ -167,6 +640,22 @@ mod tests {
         assert!(dir.to_str().unwrap().contains("Instagram"));
     }
 
+    #[test]
+    fn test_pending_profile_wipe() {
+        let tmp = std::env::temp_dir().join("test-privacy-pending-wipe");
+        let engine = PrivacyEngine::new(tmp.clone());
+        std::fs::create_dir_all(engine.profile_dir().join("EBWebView")).unwrap();
+        // Nothing pending: the profile is left alone
+        engine.finish_pending_wipe().unwrap();
+        assert!(engine.profile_dir().exists());
+
+        std::fs::write(engine.pending_wipe_marker(), b"").unwrap();
+        engine.finish_pending_wipe().unwrap();
+        assert!(!engine.profile_dir().exists());
+        assert!(!engine.pending_wipe_marker().exists());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
     #[test]
     fn test_clear_session_creates_dir() {
         let tmp = std::env::temp_dir().join("test-privacy-engine");
@@ -177,6 +666,132 @@ mod tests {
         let _ = std::fs::remove_dir_all(&tmp);
     }
 
+    #[test]
+    fn test_has_session_cookie() {
+        let tmp = std::env::temp_dir().join("test-privacy-engine-cookie");
+        let engine = PrivacyEngine::new(tmp.clone());
+        assert!(!engine.has_session_cookie());
+        // The per-platform session directories aren't the webview's
+        let unused = engine.session_dir("Messenger").join("Default").join("Network");
+        std::fs::create_dir_all(&unused).unwrap();
+        std::fs::write(unused.join("Cookies"), b"").unwrap();
+        assert!(!engine.has_session_cookie());
+        let network = engine.profile_dir().join("EBWebView").join("Default").join("Network");
+        std::fs::create_dir_all(&network).unwrap();
+        std::fs::write(network.join("Cookies"), b"").unwrap();
+        assert!(engine.has_session_cookie());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_clear_cache_keeps_login() {
+        let tmp = std::env::temp_dir().join("test-privacy-engine-cache");
+        let engine = PrivacyEngine::new(tmp.clone());
+        let profile = engine.profile_dir().join("EBWebView").join("Default");
+        for dir in ["Cache/Cache_Data", "Code Cache/js", "Network", "Local Storage/leveldb", "Service Worker/CacheStorage"] {
+            std::fs::create_dir_all(profile.join(dir)).unwrap();
+        }
+        std::fs::write(profile.join("Cache/Cache_Data/data_1"), [0u8; 100]).unwrap();
+        std::fs::write(profile.join("Code Cache/js/index"), [0u8; 20]).unwrap();
+        std::fs::write(profile.join("Service Worker/CacheStorage/entry"), [0u8; 5]).unwrap();
+        std::fs::write(profile.join("Network/Cookies"), b"session").unwrap();
+        std::fs::write(profile.join("Local Storage/leveldb/000003.log"), b"state").unwrap();
+
+        assert_eq!(engine.clear_cache().unwrap(), 125);
+        assert!(!profile.join("Cache").exists());
+        assert!(!profile.join("Code Cache").exists());
+        assert!(!profile.join("Service Worker/CacheStorage").exists());
+        assert!(profile.join("Service Worker").exists());
+        assert!(profile.join("Network/Cookies").exists());
+        assert!(profile.join("Local Storage/leveldb/000003.log").exists());
+        assert!(engine.has_session_cookie());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_logout_removes_only_the_platforms_cookies() {
+        let mut jar: Vec<Cookie<'static>> = [
+            ("sessionid", ".instagram.com"),
+            ("csrftoken", "www.instagram.com"),
+            ("c_user", ".facebook.com"),
+            ("xs", ".messenger.com"),
+            ("auth_token", ".x.com"),
+        ]
+        .into_iter()
+        .map(|(name, domain)| Cookie::build((name, "1")).domain(domain).build())
+        .collect();
+
+        let removed = remove_login_cookies(&Platform::Instagram, jar.clone(), |cookie| {
+            jar.retain(|c| c != &cookie);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(removed, 2);
+        // Instagram's session cookies are gone, the other platforms' are kept
+        let names: Vec<&str> = jar.iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["c_user", "xs", "auth_token"]);
+
+        // A failed delete is reported
+        let err = remove_login_cookies(&Platform::X, jar.clone(), |_| Err("locked".to_string()));
+        assert_eq!(err, Err("locked".to_string()));
+    }
+
+    #[test]
+    fn test_session_usage() {
+        let tmp = std::env::temp_dir().join("test-session-usage");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let engine = PrivacyEngine::new(tmp.clone());
+        let bytes = |usage: &SessionUsage, name: &str| usage.platforms[name];
+        // No profile yet
+        let usage = engine.session_usage();
+        assert_eq!(usage.platforms.len(), Platform::all().len());
+        assert!(Platform::all().iter().all(|p| bytes(&usage, p.name()) == 0));
+        assert_eq!((usage.shared, usage.cache, usage.total), (0, 0, 0));
+
+        let profile = engine.profile_dir().join("EBWebView");
+        let cache = profile.join("Default").join("Cache");
+        std::fs::create_dir_all(&cache).unwrap();
+        std::fs::write(cache.join("data_0"), vec![0u8; 4096]).unwrap();
+        std::fs::write(profile.join("Local State"), vec![0u8; 100]).unwrap();
+        std::fs::write(profile.join("Default").join("Cookies"), vec![0u8; 24]).unwrap();
+        // Site storage named after the origin (WebView2 and WebKitGTK layouts)
+        let idb = profile.join("Default/IndexedDB/https_www.instagram.com_0.indexeddb.leveldb");
+        std::fs::create_dir_all(&idb).unwrap();
+        std::fs::write(idb.join("000003.log"), vec![0u8; 300]).unwrap();
+        let local = profile.join("localstorage");
+        std::fs::create_dir_all(&local).unwrap();
+        std::fs::write(local.join("https_x.com_0.localstorage"), vec![0u8; 50]).unwrap();
+        std::fs::write(local.join("https_example.com_0.localstorage"), vec![0u8; 7]).unwrap();
+        // The unused per-platform directories don't count
+        std::fs::create_dir_all(engine.session_dir("X")).unwrap();
+        std::fs::write(engine.session_dir("X").join("Cookies"), vec![0u8; 24]).unwrap();
+
+        let usage = engine.session_usage();
+        assert_eq!(bytes(&usage, "Instagram"), 300);
+        assert_eq!(bytes(&usage, "X"), 50);
+        assert_eq!(bytes(&usage, "Messenger"), 0);
+        // Other sites' storage is shared
+        assert_eq!(usage.shared, 4227);
+        assert_eq!(usage.cache, 4096);
+        assert_eq!(usage.total, 4577);
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_sweep_stale_incognito() {
+        let tmp = std::env::temp_dir().join("test-privacy-engine-incognito");
+        let engine = PrivacyEngine::new(tmp.clone());
+        std::fs::create_dir_all(engine.incognito_dir("abc")).unwrap();
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        assert_eq!(engine.sweep_stale_incognito(SystemTime::now(), day), 0);
+        assert!(engine.incognito_dir("abc").exists());
+        assert_eq!(engine.sweep_stale_incognito(SystemTime::now() + 2 * day, day), 1);
+        assert!(!engine.incognito_dir("abc").exists());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
     #[test]
     fn test_csp_per_platform() {
         let csp = PrivacyEngine::csp_for_platform("Instagram");
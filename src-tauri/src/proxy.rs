@@ -0,0 +1,178 @@
+//! Proxy configuration for the main webview.
+//!
+//! The proxy is persisted to disk and applied when the main window is built, before
+//! the first navigation. The webview can't switch proxies once it exists, so a change
+//! takes effect on the next launch: `proxy-changed` is emitted with `restart_required`
+//! and the frontend offers a restart (`restart_app`).
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Url};
+
+use crate::store;
+
+/// Proxy schemes the webview supports. wry has no https proxies: building the
+/// window with one fails, so they are rejected here.
+const SUPPORTED_SCHEMES: &[&str] = &["http", "socks5"];
+
+/// Payload emitted with `proxy-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct ProxyChangedPayload {
+    pub url: Option<String>,
+    pub restart_required: bool,
+}
+
+/// Persisted proxy configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct ProxyConfig {
+    pub url: Option<String>,
+}
+
+/// Manages the proxy configuration and its persistence
+pub struct ProxyManager {
+    config: std::sync::Mutex<ProxyConfig>,
+    store_path: PathBuf,
+}
+
+impl ProxyManager {
+    /// Creates a new ProxyManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("proxy.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: std::sync::Mutex::new(config),
+            store_path,
+        }
+    }
+
+    /// Gets the current proxy configuration
+    pub fn get_config(&self) -> Result<ProxyConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// The saved proxy as a URL to hand to the webview builder, if one is set and valid
+    pub fn startup_url(&self) -> Option<Url> {
+        let url = self.get_config().ok()?.url?;
+        match validate_proxy_url(&url) {
+            Ok(url) => Some(url),
+            Err(e) => {
+                log::warn!("[proxy] ignoring saved proxy: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Sets (or clears, with `None`) and persists the proxy
+    pub fn set(&self, url: Option<&str>) -> Result<ProxyConfig, String> {
+        let config = ProxyConfig {
+            url: url
+                .map(validate_proxy_url)
+                .transpose()?
+                .map(|u| u.to_string()),
+        };
+        *self.config.lock().map_err(|e| e.to_string())? = config.clone();
+        self.persist(&config)?;
+        Ok(config)
+    }
+
+    /// Persists the configuration to disk
+    fn persist(&self, config: &ProxyConfig) -> Result<(), String> {
+        store::save(&self.store_path, config).map_err(|e| format!("Failed to save proxy config: {}", e))
+    }
+}
+
+/// Parses a proxy URL, accepting only http and socks5 proxies with a host
+pub fn validate_proxy_url(url: &str) -> Result<Url, String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    if !SUPPORTED_SCHEMES.contains(&parsed.scheme()) {
+        return Err(format!(
+            "Unsupported proxy scheme '{}' (expected http or socks5)",
+            parsed.scheme()
+        ));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err("Proxy URL has no host".to_string());
+    }
+    Ok(parsed)
+}
+
+fn emit_changed(app: &AppHandle, config: &ProxyConfig) -> Result<(), String> {
+    app.emit(
+        "proxy-changed",
+        ProxyChangedPayload {
+            url: config.url.clone(),
+            restart_required: true,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command to route the webview through a proxy (applies after restart)
+#[tauri::command]
+#[specta::specta]
+pub fn set_proxy(
+    url: String,
+    app: AppHandle,
+    manager: tauri::State<'_, ProxyManager>,
+) -> Result<ProxyConfig, String> {
+    let config = manager.set(Some(&url))?;
+    log::info!("[proxy] proxy set, restart required");
+    emit_changed(&app, &config)?;
+    Ok(config)
+}
+
+/// Tauri command to remove the proxy (applies after restart)
+#[tauri::command]
+#[specta::specta]
+pub fn clear_proxy(app: AppHandle, manager: tauri::State<'_, ProxyManager>) -> Result<ProxyConfig, String> {
+    let config = manager.set(None)?;
+    log::info!("[proxy] proxy cleared, restart required");
+    emit_changed(&app, &config)?;
+    Ok(config)
+}
+
+/// Tauri command to get the proxy configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_proxy(manager: tauri::State<'_, ProxyManager>) -> Result<ProxyConfig, String> {
+    manager.get_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_proxy_url() {
+        assert!(validate_proxy_url("http://proxy.corp:8080").is_ok());
+        assert!(validate_proxy_url("socks5://127.0.0.1:1080").is_ok());
+
+        assert!(validate_proxy_url("ftp://proxy.corp").unwrap_err().contains("Unsupported"));
+        assert!(validate_proxy_url("https://proxy.corp").unwrap_err().contains("Unsupported"));
+        assert!(validate_proxy_url("socks4://127.0.0.1:1080").is_err());
+        assert!(validate_proxy_url("proxy.corp:8080").is_err());
+        assert!(validate_proxy_url("").is_err());
+    }
+
+    #[test]
+    fn test_proxy_persistence() {
+        let tmp = std::env::temp_dir().join("test-proxy");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = ProxyManager::new(&tmp);
+        assert!(manager.set(Some("gopher://x")).is_err());
+        manager.set(Some("socks5://127.0.0.1:1080")).unwrap();
+
+        let reloaded = ProxyManager::new(&tmp);
+        assert_eq!(reloaded.startup_url().unwrap().as_str(), "socks5://127.0.0.1:1080");
+
+        // A saved proxy the webview can't use (e.g. https from an older version)
+        // is skipped at launch instead of failing the window build
+        store::save(&tmp.join("proxy.json"), &ProxyConfig { url: Some("https://proxy.corp/".to_string()) }).unwrap();
+        assert!(ProxyManager::new(&tmp).startup_url().is_none());
+
+        reloaded.set(None).unwrap();
+        assert!(ProxyManager::new(&tmp).startup_url().is_none());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
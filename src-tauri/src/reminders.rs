@@ -0,0 +1,206 @@
+//! Send-later reminders.
+//!
+//! A reminder never sends anything itself (that would break the platforms' terms).
+//! When it comes due, a notification ("Reminder: message …") is shown and
+//! `reminder-due` is emitted with the conversation and draft text so the frontend
+//! can pre-fill the composer. Pending reminders live in `reminders.json`; ones that
+//! came due while the app was closed fire on the next start.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::store;
+
+/// How often pending reminders are checked
+const TICK: Duration = Duration::from_secs(15);
+
+/// A pending reminder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Reminder {
+    pub id: String,
+    pub conversation_id: String,
+    /// Draft message to pre-fill
+    pub text: String,
+    /// Unix timestamp (ms) the reminder is due
+    pub at: u64,
+}
+
+/// Current time as a Unix timestamp in milliseconds
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Manages pending reminders and their persistence
+pub struct ReminderManager {
+    reminders: std::sync::Mutex<Vec<Reminder>>,
+    store_path: PathBuf,
+}
+
+impl ReminderManager {
+    /// Creates a new ReminderManager, loading any pending reminders
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("reminders.json");
+        let reminders = store::load_or_default(&store_path);
+        Self {
+            reminders: std::sync::Mutex::new(reminders),
+            store_path,
+        }
+    }
+
+    /// Schedules a reminder for `at` (Unix ms), which must be in the future
+    pub fn schedule(&self, conversation_id: &str, text: String, at: u64, now: u64) -> Result<Reminder, String> {
+        if conversation_id.trim().is_empty() {
+            return Err("Conversation id must not be empty".to_string());
+        }
+        if at <= now {
+            return Err("Reminder time must be in the future".to_string());
+        }
+        let reminder = Reminder {
+            id: uuid::Uuid::new_v4().to_string(),
+            conversation_id: conversation_id.to_string(),
+            text,
+            at,
+        };
+        let mut reminders = self.reminders.lock().map_err(|e| e.to_string())?;
+        reminders.push(reminder.clone());
+        reminders.sort_by_key(|r| r.at);
+        self.persist(&reminders)?;
+        Ok(reminder)
+    }
+
+    /// Pending reminders, soonest first
+    pub fn list(&self) -> Result<Vec<Reminder>, String> {
+        Ok(self.reminders.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Cancels a pending reminder
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        let mut reminders = self.reminders.lock().map_err(|e| e.to_string())?;
+        let before = reminders.len();
+        reminders.retain(|r| r.id != id);
+        if reminders.len() == before {
+            return Err(format!("Reminder not found: {}", id));
+        }
+        self.persist(&reminders)
+    }
+
+    /// Removes and returns every reminder due at `now`
+    pub fn take_due(&self, now: u64) -> Result<Vec<Reminder>, String> {
+        let mut reminders = self.reminders.lock().map_err(|e| e.to_string())?;
+        let (due, pending): (Vec<_>, Vec<_>) = reminders.drain(..).partition(|r| r.at <= now);
+        *reminders = pending;
+        if !due.is_empty() {
+            self.persist(&reminders)?;
+        }
+        Ok(due)
+    }
+
+    /// Persists the pending reminders to disk
+    fn persist(&self, reminders: &[Reminder]) -> Result<(), String> {
+        store::save(&self.store_path, reminders).map_err(|e| format!("Failed to save reminders: {}", e))
+    }
+}
+
+/// Shows the reminder notification and emits `reminder-due`
+fn fire(app: &AppHandle, reminder: &Reminder) {
+    use tauri_plugin_notification::NotificationExt;
+
+    log::info!("[reminders] reminder {} due", reminder.id);
+    let shown = app
+        .notification()
+        .builder()
+        .title(format!("Reminder: message {}", reminder.conversation_id))
+        .body(&reminder.text)
+        .show();
+    if let Err(e) = shown {
+        log::warn!("[reminders] failed to show reminder: {}", e);
+    }
+    let _ = app.emit("reminder-due", reminder);
+}
+
+/// Starts the background task that fires reminders as they come due, starting
+/// with any that came due while the app was closed
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match app.state::<ReminderManager>().take_due(now_millis()) {
+                Ok(due) => due.iter().for_each(|r| fire(&app, r)),
+                Err(e) => log::warn!("[reminders] {}", e),
+            }
+            tokio::time::sleep(TICK).await;
+        }
+    });
+}
+
+/// Tauri command to schedule a reminder to send `text` at `at_timestamp` (Unix ms)
+#[tauri::command]
+#[specta::specta]
+pub fn schedule_reminder(
+    conversation_id: String,
+    text: String,
+    at_timestamp: u64,
+    manager: tauri::State<'_, ReminderManager>,
+) -> Result<Reminder, String> {
+    manager.schedule(&conversation_id, text, at_timestamp, now_millis())
+}
+
+/// Tauri command to list pending reminders, soonest first
+#[tauri::command]
+#[specta::specta]
+pub fn list_reminders(manager: tauri::State<'_, ReminderManager>) -> Result<Vec<Reminder>, String> {
+    manager.list()
+}
+
+/// Tauri command to cancel a pending reminder
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_reminder(id: String, manager: tauri::State<'_, ReminderManager>) -> Result<(), String> {
+    manager.cancel(&id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_and_cancel() {
+        let tmp = std::env::temp_dir().join("test-reminders");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = ReminderManager::new(&tmp);
+        assert!(manager.schedule("t1", "hi".to_string(), 1_000, 2_000).is_err());
+
+        let late = manager.schedule("t1", "later".to_string(), 9_000, 1_000).unwrap();
+        let soon = manager.schedule("t2", "soon".to_string(), 5_000, 1_000).unwrap();
+        assert_eq!(manager.list().unwrap(), vec![soon.clone(), late]);
+
+        manager.cancel(&soon.id).unwrap();
+        assert!(manager.cancel(&soon.id).is_err());
+        assert_eq!(manager.list().unwrap().len(), 1);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_due_reminders_fire_once_and_survive_restart() {
+        let tmp = std::env::temp_dir().join("test-reminders-due");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = ReminderManager::new(&tmp);
+        let first = manager.schedule("t1", "a".to_string(), 5_000, 1_000).unwrap();
+        let second = manager.schedule("t2", "b".to_string(), 9_000, 1_000).unwrap();
+
+        assert!(manager.take_due(4_999).unwrap().is_empty());
+
+        // Came due while "closed": a fresh manager still has it
+        let restarted = ReminderManager::new(&tmp);
+        assert_eq!(restarted.take_due(6_000).unwrap(), vec![first]);
+        assert!(restarted.take_due(6_000).unwrap().is_empty());
+        assert_eq!(ReminderManager::new(&tmp).list().unwrap(), vec![second]);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
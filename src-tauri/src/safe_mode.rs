@@ -0,0 +1,107 @@
+//! Safe mode: load the platform without any of our page modifications.
+//!
+//! Started with `--safe-mode` or toggled at runtime with `enter_safe_mode` /
+//! `exit_safe_mode`. While active, the privacy interceptors and bandwidth-saver
+//! scripts aren't injected and theme CSS (including custom CSS) isn't applied, so a
+//! broken theme or interceptor can be ruled out or escaped. Changes are emitted as
+//! `safe-mode-changed` so the frontend can show its banner.
+//!
+//! Safe mode is never saved: the next normal launch is back to normal, and the
+//! user's theme and settings are untouched.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::theme_manager::ThemeManager;
+
+/// Payload emitted with `safe-mode-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct SafeModeChangedPayload {
+    pub active: bool,
+}
+
+/// Whether safe mode is on for this run
+pub struct SafeMode {
+    active: AtomicBool,
+}
+
+impl SafeMode {
+    pub fn new(active: bool) -> Self {
+        Self {
+            active: AtomicBool::new(active),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Turns safe mode on or off. Returns whether it changed.
+    fn set(&self, active: bool) -> bool {
+        self.active.swap(active, Ordering::SeqCst) != active
+    }
+}
+
+/// Whether safe mode is on; false before the state is managed
+pub fn is_active(app: &AppHandle) -> bool {
+    app.try_state::<SafeMode>().is_some_and(|s| s.is_active())
+}
+
+/// Switches safe mode, then re-applies the theme and reloads the page so the
+/// injections come off (or back on)
+fn switch(app: &AppHandle, active: bool) -> Result<(), String> {
+    if !app.state::<SafeMode>().set(active) {
+        return Ok(());
+    }
+    log::info!("[safe_mode] {}", if active { "entered" } else { "exited" });
+
+    app.state::<Mutex<ThemeManager>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .reapply()
+        .map_err(|e| e.to_string())?;
+    if let Some(window) = app.get_webview_window("main") {
+        window.eval("window.location.reload();").map_err(|e| e.to_string())?;
+    }
+    app.emit("safe-mode-changed", SafeModeChangedPayload { active })
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command to turn safe mode on for the rest of this run
+#[tauri::command]
+#[specta::specta]
+pub fn enter_safe_mode(app: AppHandle) -> Result<(), String> {
+    switch(&app, true)
+}
+
+/// Tauri command to leave safe mode, restoring injections and the theme
+#[tauri::command]
+#[specta::specta]
+pub fn exit_safe_mode(app: AppHandle) -> Result<(), String> {
+    switch(&app, false)
+}
+
+/// Tauri command to check whether safe mode is on (e.g. to show the banner after
+/// a `--safe-mode` launch)
+#[tauri::command]
+#[specta::specta]
+pub fn is_safe_mode(safe_mode: tauri::State<'_, SafeMode>) -> bool {
+    safe_mode.is_active()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_reports_changes() {
+        let safe_mode = SafeMode::new(false);
+        assert!(!safe_mode.set(false));
+        assert!(safe_mode.set(true));
+        assert!(safe_mode.is_active());
+        assert!(!safe_mode.set(true));
+        assert!(safe_mode.set(false));
+    }
+}
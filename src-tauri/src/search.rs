@@ -0,0 +1,148 @@
+//! Quick conversation search (Ctrl+K / Cmd+K).
+//!
+//! `focus_search` focuses the active platform's own search box, retrying briefly
+//! while the page is still rendering it. If it never appears, the page emits
+//! `search-overlay-requested` and the frontend shows its own overlay instead.
+//!
+//! The shortcut is bound through the shortcut manager (`ACTION`) but only held as a
+//! global shortcut while the main window is focused, so Ctrl+K keeps working in
+//! other apps.
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::injection::{InjectionRegistry, QUICK_SEARCH};
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::shortcuts::ShortcutManager;
+
+/// Shortcut action name for quick search
+pub const ACTION: &str = "search";
+
+/// Default quick search shortcut
+pub const DEFAULT_SHORTCUT: &str = "CommandOrControl+K";
+
+/// How many times the script looks for the search box, 100ms apart
+const FIND_ATTEMPTS: u32 = 20;
+
+/// Selector for each platform's conversation search input
+fn search_selector(platform: Option<&Platform>) -> &'static str {
+    match platform {
+        Some(Platform::Messenger) | Some(Platform::Facebook) => {
+            "input[type=\"search\"][aria-label*=\"Messenger\" i], input[placeholder*=\"Search Messenger\" i]"
+        }
+        Some(Platform::Instagram) => "input[aria-label=\"Search input\" i], input[placeholder=\"Search\" i]",
+        Some(Platform::X) => "[data-testid=\"SearchBox_Search_Input\"], input[placeholder*=\"Search Direct Messages\" i]",
+        None => "input[type=\"search\"]",
+    }
+}
+
+/// Builds the script that focuses the search box or asks for the overlay
+pub fn focus_search_script(platform: Option<&Platform>) -> String {
+    let selector = serde_json::to_string(search_selector(platform)).unwrap_or_default();
+    let platform = serde_json::to_string(&platform.map(Platform::name)).unwrap_or_else(|_| "null".to_string());
+    format!(
+        r#"
+        (function() {{
+            let attempts = 0;
+            (function tryFocus() {{
+                const input = document.querySelector({selector});
+                if (input) {{
+                    input.focus();
+                    if (input.select) input.select();
+                    return;
+                }}
+                if (++attempts < {attempts}) return setTimeout(tryFocus, 100);
+                const events = window.__TAURI__ && window.__TAURI__.event;
+                if (events) events.emit('search-overlay-requested', {{ platform: {platform} }});
+            }})();
+        }})();
+        "#,
+        selector = selector,
+        attempts = FIND_ATTEMPTS,
+        platform = platform,
+    )
+}
+
+/// The focus script for the active platform
+pub fn current_script(app: &AppHandle) -> String {
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    focus_search_script(platform.as_ref())
+}
+
+/// Focuses the search box of the active platform in `window`
+pub fn focus(window: &WebviewWindow) -> Result<(), String> {
+    let app = window.app_handle();
+    match app.try_state::<InjectionRegistry>() {
+        Some(registry) => registry.run(window, QUICK_SEARCH).map(|_| ()),
+        None => window.eval(&current_script(app)).map_err(|e| e.to_string()),
+    }
+}
+
+/// The bound search keys, if quick search hasn't been unbound
+fn bound_keys(app: &AppHandle) -> Option<String> {
+    app.try_state::<Mutex<ShortcutManager>>()?
+        .lock()
+        .ok()?
+        .bindings()
+        .get(ACTION)
+        .cloned()
+}
+
+/// Registers `keys` as the quick search shortcut
+pub fn register_shortcut(app: &AppHandle, keys: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(keys, |app, _shortcut, event| {
+            if event.state != ShortcutState::Pressed {
+                return;
+            }
+            if let Some(window) = app.get_webview_window("main") {
+                if let Err(e) = focus(&window) {
+                    log::warn!("[search] {}", e);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to register search shortcut {}: {}", keys, e))
+}
+
+/// Holds the search shortcut only while the main window has focus
+pub fn on_focus_changed(app: &AppHandle, focused: bool) {
+    let Some(keys) = bound_keys(app) else {
+        return;
+    };
+    if focused {
+        if !app.global_shortcut().is_registered(keys.as_str()) {
+            if let Err(e) = register_shortcut(app, &keys) {
+                log::warn!("[search] {}", e);
+            }
+        }
+    } else {
+        let _ = app.global_shortcut().unregister(keys.as_str());
+    }
+}
+
+/// Tauri command to focus the platform's search box (or request the overlay)
+#[tauri::command]
+#[specta::specta]
+pub fn focus_search(window: WebviewWindow) -> Result<(), String> {
+    focus(&window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_script_is_platform_specific() {
+        let x = focus_search_script(Some(&Platform::X));
+        assert!(x.contains("SearchBox_Search_Input"));
+        assert!(x.contains("\"X\""));
+
+        let none = focus_search_script(None);
+        assert!(none.contains("input[type=\\\"search\\\"]"));
+        assert!(none.contains("platform: null"));
+        assert!(none.contains("search-overlay-requested"));
+    }
+}
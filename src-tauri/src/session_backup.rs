@@ -0,0 +1,223 @@
+//! Session backup and restore.
+//!
+//! Zips the webview profile (`PrivacyEngine::profile_dir`) so the logins survive a
+//! reinstall or a move to another machine. The platforms share the profile, so an
+//! archive holds all of them. **A session archive holds the auth cookies: anyone
+//! with the file can use the accounts.** The frontend must say so before exporting.
+//!
+//! Archives carry a `session-manifest.json`, and are extracted to a staging
+//! directory first so a bad archive never leaves a half-restored profile behind.
+//! The running webview keeps the profile open, so the staged profile is swapped in
+//! on the next launch, before the webview starts (`finish_pending_restore`).
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::privacy_engine::PrivacyEngine;
+
+/// Manifest entry written at the root of every archive
+const MANIFEST: &str = "session-manifest.json";
+
+/// Archive format version. Version 1 archives held a per-platform directory no
+/// webview used, so they have no login to restore.
+const FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionManifest {
+    version: u32,
+}
+
+/// Where an imported profile waits for the next launch
+fn staging_dir(engine: &PrivacyEngine) -> PathBuf {
+    engine.profile_dir().with_extension("restoring")
+}
+
+/// Writes the contents of `dir` into the archive under `prefix`
+fn add_dir<W: Write + io::Seek>(zip: &mut ZipWriter<W>, dir: &Path, prefix: &str) -> Result<(), String> {
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let entries = fs::read_dir(dir).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        if path.is_dir() {
+            zip.add_directory(format!("{}/", name), options).map_err(|e| e.to_string())?;
+            add_dir(zip, &path, &format!("{}/", name))?;
+        } else {
+            zip.start_file(name, options).map_err(|e| e.to_string())?;
+            let mut file = File::open(&path).map_err(|e| e.to_string())?;
+            io::copy(&mut file, zip).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Zips the webview profile to `archive`
+pub fn export_to(engine: &PrivacyEngine, archive: &Path) -> Result<(), String> {
+    let dir = engine.profile_dir();
+    if !dir.is_dir() {
+        return Err("No session to export".to_string());
+    }
+
+    let file = File::create(archive).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let manifest = SessionManifest { version: FORMAT_VERSION };
+    zip.start_file(MANIFEST, FileOptions::default())
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    add_dir(&mut zip, &dir, "session/")?;
+    zip.finish().map_err(|e| format!("Failed to write archive: {}", e))?;
+
+    log::info!("[session_backup] exported the session to {}", archive.display());
+    Ok(())
+}
+
+/// Stages the profile in `archive`, to replace the current one on the next launch.
+/// Refuses to replace a logged-in profile unless `force` is set.
+pub fn import_from(engine: &PrivacyEngine, archive: &Path, force: bool) -> Result<(), String> {
+    if engine.has_session_cookie() && !force {
+        return Err("A platform is already logged in; pass force to replace its session".to_string());
+    }
+
+    let file = File::open(archive).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Not a session archive: {}", e))?;
+
+    let manifest: SessionManifest = {
+        let entry = zip
+            .by_name(MANIFEST)
+            .map_err(|_| "Not a session archive: manifest missing".to_string())?;
+        serde_json::from_reader(entry).map_err(|e| format!("Invalid session manifest: {}", e))?
+    };
+    if manifest.version != FORMAT_VERSION {
+        return Err(format!("Unsupported session archive version {}", manifest.version));
+    }
+
+    // Extract next to the live profile; it is swapped in on the next launch
+    let staging = staging_dir(engine);
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    let extracted = extract(&mut zip, &staging);
+    if let Err(e) = extracted {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    log::info!("[session_backup] staged the session from {}, restored on restart", archive.display());
+    Ok(())
+}
+
+/// Swaps in a profile staged by `import_from`. Must run before the first webview
+/// is built. Returns whether one was restored.
+pub fn finish_pending_restore(engine: &PrivacyEngine) -> Result<bool, String> {
+    let staging = staging_dir(engine);
+    if !staging.is_dir() {
+        return Ok(false);
+    }
+    let target = engine.profile_dir();
+    if target.exists() {
+        fs::remove_dir_all(&target).map_err(|e| format!("Failed to replace session: {}", e))?;
+    }
+    fs::rename(&staging, &target).map_err(|e| format!("Failed to restore session: {}", e))?;
+    log::info!("[session_backup] restored the staged session");
+    Ok(true)
+}
+
+/// Extracts the `session/` entries of the archive into `dest`
+fn extract(zip: &mut ZipArchive<File>, dest: &Path) -> Result<(), String> {
+    fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+        if entry.name() == MANIFEST {
+            continue;
+        }
+        // `enclosed_name` rejects absolute paths and `..` components
+        let relative: PathBuf = entry
+            .enclosed_name()
+            .and_then(|p| p.strip_prefix("session").ok())
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("Unexpected entry in session archive: {}", entry.name()))?;
+        let out = dest.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out).map_err(|e| e.to_string())?;
+        } else {
+            if let Some(parent) = out.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            let mut file = File::create(&out).map_err(|e| e.to_string())?;
+            io::copy(&mut entry, &mut file).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Tauri command to back up the platforms' sessions to a zip archive.
+/// The archive contains auth data and must be kept private.
+#[tauri::command]
+#[specta::specta]
+pub fn export_session(path: String, engine: tauri::State<'_, PrivacyEngine>) -> Result<(), String> {
+    export_to(&engine, Path::new(&path))
+}
+
+/// Tauri command to restore the platforms' sessions from a zip archive. Applies
+/// after a restart (`restart_app`).
+#[tauri::command]
+#[specta::specta]
+pub fn import_session(path: String, force: bool, engine: tauri::State<'_, PrivacyEngine>) -> Result<(), String> {
+    import_from(&engine, Path::new(&path), force)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let tmp = std::env::temp_dir().join("test-session-backup");
+        let _ = fs::remove_dir_all(&tmp);
+        let source = PrivacyEngine::new(tmp.join("source"));
+        let network = source.profile_dir().join("EBWebView").join("Default").join("Network");
+        fs::create_dir_all(&network).unwrap();
+        fs::write(network.join("Cookies"), b"auth").unwrap();
+
+        let archive = tmp.join("sessions.zip");
+        export_to(&source, &archive).unwrap();
+
+        // Staged, and only swapped in on the next launch
+        let dest = PrivacyEngine::new(tmp.join("dest"));
+        import_from(&dest, &archive, false).unwrap();
+        assert!(!dest.profile_dir().exists());
+        assert!(finish_pending_restore(&dest).unwrap());
+        let restored = dest.profile_dir().join("EBWebView/Default/Network/Cookies");
+        assert_eq!(fs::read(restored).unwrap(), b"auth");
+        assert!(!finish_pending_restore(&dest).unwrap());
+
+        // A logged-in profile is only replaced with force
+        assert!(import_from(&dest, &archive, false).is_err());
+        import_from(&dest, &archive, true).unwrap();
+        assert!(finish_pending_restore(&dest).unwrap());
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_rejects_non_session_archive() {
+        let tmp = std::env::temp_dir().join("test-session-backup-invalid");
+        fs::create_dir_all(&tmp).unwrap();
+        let archive = tmp.join("other.zip");
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        zip.start_file("readme.txt", FileOptions::default()).unwrap();
+        zip.write_all(b"hello").unwrap();
+        zip.finish().unwrap();
+
+        let engine = PrivacyEngine::new(tmp.join("data"));
+        let err = import_from(&engine, &archive, false).unwrap_err();
+        assert!(err.contains("manifest"));
+        assert!(!finish_pending_restore(&engine).unwrap());
+        assert!(!engine.profile_dir().exists());
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,430 @@
+//! App-wide settings aggregation.
+//!
+//! Collects every subsystem's settings into one snapshot so a settings UI can load
+//! them with a single call, applies a full snapshot back in one step, and exports /
+//! imports snapshots as versioned JSON files for moving between machines.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Mutex as TokioMutex;
+
+use crate::accounts::{Account, AccountManager};
+use crate::density::{DensityManager, MessageDensity};
+use crate::notifications::{NotificationService, NotificationSettings};
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::privacy::{PrivacyConfig, PrivacyManager};
+use crate::shortcuts::ShortcutManager;
+use crate::theme_manager::{Theme, ThemeManager};
+use crate::updater::UpdaterManager;
+use crate::window_manager::{WindowManager, WindowState};
+
+/// Current schema version of exported settings files.
+///
+/// - 1: first version
+/// - 2: `settings.message_density` is always written
+pub const SETTINGS_SCHEMA_VERSION: u32 = 2;
+
+/// Snapshot of all user-facing settings
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AppSettings {
+    pub notifications: NotificationSettings,
+    pub privacy: PrivacyConfig,
+    pub window: WindowState,
+    pub zoom_level: f64,
+    pub theme: String,
+    pub custom_css: Option<String>,
+    pub message_density: MessageDensity,
+    pub platform: Option<String>,
+    pub update_channel: String,
+}
+
+/// On-disk format of an exported settings file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsExport {
+    pub version: u32,
+    pub exported_at: String,
+    pub settings: AppSettings,
+    pub shortcuts: HashMap<String, String>,
+    /// Only present when exported with `include_accounts`; contains session tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accounts: Option<Vec<Account>>,
+}
+
+impl AppSettings {
+    /// Checks every field that can be rejected before anything is applied
+    pub fn validate(&self) -> Result<(), String> {
+        if !ThemeManager::get_themes().contains(&self.theme) {
+            return Err(format!("Unknown theme: {}", self.theme));
+        }
+        if let Some(name) = &self.platform {
+            if Platform::from_str(name).is_none() {
+                return Err(format!("Unknown platform: {}", name));
+            }
+        }
+        if !self.zoom_level.is_finite() {
+            return Err("Invalid zoom level".to_string());
+        }
+        if self.update_channel.trim().is_empty() {
+            return Err("Update channel must not be empty".to_string());
+        }
+        self.notifications
+            .validate()
+            .map_err(|e| format!("Invalid notification settings: {}", e))
+    }
+}
+
+/// Brings an exported settings document up to the current schema version.
+fn migrate(mut doc: serde_json::Value) -> Result<serde_json::Value, String> {
+    let version = doc
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Settings file has no schema version".to_string())? as u32;
+
+    if version > SETTINGS_SCHEMA_VERSION {
+        return Err(format!(
+            "Settings file was created by a newer version (schema {}, supported up to {})",
+            version, SETTINGS_SCHEMA_VERSION
+        ));
+    }
+    if version == 0 {
+        return Err("Unsupported settings schema version 0".to_string());
+    }
+
+    if version < 2 {
+        // Version 1 files from before message density existed don't have it
+        let settings = doc
+            .get_mut("settings")
+            .and_then(|s| s.as_object_mut())
+            .ok_or_else(|| "Settings file has no settings".to_string())?;
+        if !settings.contains_key("message_density") {
+            let density = serde_json::to_value(MessageDensity::default()).map_err(|e| e.to_string())?;
+            settings.insert("message_density".to_string(), density);
+        }
+    }
+    doc["version"] = serde_json::json!(SETTINGS_SCHEMA_VERSION);
+    Ok(doc)
+}
+
+/// Parses an exported settings document, migrating older schema versions.
+pub fn parse_export(contents: &str) -> Result<SettingsExport, String> {
+    let doc: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid settings file: {}", e))?;
+    let export: SettingsExport = serde_json::from_value(migrate(doc)?)
+        .map_err(|e| format!("Invalid settings file: {}", e))?;
+    export.settings.validate()?;
+    Ok(export)
+}
+
+/// Reads every subsystem's settings into one snapshot.
+pub async fn collect(app: &AppHandle) -> Result<AppSettings, String> {
+    let notifications = app.state::<NotificationService>().get_settings().await;
+    let window_manager = app.state::<WindowManager>();
+    let window = window_manager.get_window_state().await;
+    let zoom_level = window_manager.get_zoom().await;
+    let update_channel = app
+        .state::<TokioMutex<UpdaterManager>>()
+        .lock()
+        .await
+        .get_channel();
+
+    let privacy = app
+        .state::<std::sync::Mutex<PrivacyManager>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .config()
+        .clone();
+    let (theme, custom_css) = {
+        let state = app.state::<std::sync::Mutex<ThemeManager>>();
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        let css = match manager.current_theme() {
+            Theme::Custom(css) => Some(css.clone()),
+            _ => None,
+        };
+        (manager.current_theme_name().to_string(), css)
+    };
+    let message_density = app.state::<DensityManager>().get()?;
+    let platform = app
+        .state::<PlatformManager>()
+        .get_current()?
+        .map(|p| p.name().to_string());
+
+    Ok(AppSettings {
+        notifications,
+        privacy,
+        window,
+        zoom_level,
+        theme,
+        custom_css,
+        message_density,
+        platform,
+        update_channel,
+    })
+}
+
+/// Applies a full snapshot to every subsystem.
+///
+/// The whole snapshot is validated before any subsystem is touched, so an invalid
+/// snapshot leaves the current settings unchanged. If a subsystem still fails
+/// (e.g. its file can't be written) the settings from before are put back. Privacy
+/// and theme re-emit their own update events; `settings-imported` is emitted once
+/// everything is applied.
+pub async fn apply(app: &AppHandle, settings: AppSettings) -> Result<(), String> {
+    settings.validate()?;
+    let previous = collect(app).await?;
+    let snapshot = settings.clone();
+
+    if let Err(e) = apply_all(app, settings).await {
+        roll_back(app, previous).await;
+        return Err(e);
+    }
+    app.emit("settings-imported", snapshot).map_err(|e| e.to_string())
+}
+
+/// Hands a validated snapshot to each subsystem in turn, stopping at the first failure
+async fn apply_all(app: &AppHandle, settings: AppSettings) -> Result<(), String> {
+    app.state::<NotificationService>()
+        .apply_settings(settings.notifications)
+        .await
+        .map_err(|e| e.to_string())?;
+    let window_manager = app.state::<WindowManager>();
+    window_manager
+        .update_window_state(settings.window)
+        .await
+        .map_err(|e| e.to_string())?;
+    window_manager
+        .set_zoom(settings.zoom_level)
+        .await
+        .map_err(|e| e.to_string())?;
+    app.state::<TokioMutex<UpdaterManager>>()
+        .lock()
+        .await
+        .set_channel(&settings.update_channel);
+
+    app.state::<std::sync::Mutex<PrivacyManager>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .update(settings.privacy)
+        .map_err(|e| e.to_string())?;
+    {
+        let state = app.state::<std::sync::Mutex<ThemeManager>>();
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        let applied = match settings.custom_css {
+            Some(css) if settings.theme == "custom" => manager.set_custom_css(css),
+            _ => manager.set_theme(&settings.theme),
+        };
+        applied.map_err(|e| e.to_string())?;
+    }
+    crate::density::apply(app, settings.message_density)?;
+    if let Some(p) = settings.platform.as_deref().and_then(Platform::from_str) {
+        app.state::<PlatformManager>().set_current(p)?;
+    }
+    Ok(())
+}
+
+/// Puts back the settings from before a failed apply. Only logs failures: the
+/// caller is already returning the error that started this.
+async fn roll_back(app: &AppHandle, previous: AppSettings) {
+    if let Err(e) = apply_all(app, previous).await {
+        log::warn!("[settings] failed to restore the previous settings: {}", e);
+    }
+}
+
+/// Get all settings as one consistent snapshot
+#[tauri::command]
+#[specta::specta]
+pub async fn get_all_settings(app: AppHandle) -> Result<AppSettings, String> {
+    collect(&app).await
+}
+
+/// Apply a full settings snapshot
+#[tauri::command]
+#[specta::specta]
+pub async fn apply_settings(settings: AppSettings, app: AppHandle) -> Result<(), String> {
+    apply(&app, settings).await
+}
+
+/// Export all settings to a versioned JSON file.
+///
+/// Accounts (which carry session tokens) are only written when `include_accounts` is set.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_settings(path: String, include_accounts: bool, app: AppHandle) -> Result<(), String> {
+    let settings = collect(&app).await?;
+    let shortcuts = app
+        .state::<std::sync::Mutex<ShortcutManager>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .bindings();
+    let accounts = if include_accounts {
+        let accounts = app
+            .state::<std::sync::Mutex<AccountManager>>()
+            .lock()
+            .map_err(|e| e.to_string())?
+            .list_accounts();
+        Some(accounts)
+    } else {
+        None
+    };
+
+    let export = SettingsExport {
+        version: SETTINGS_SCHEMA_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        settings,
+        shortcuts,
+        accounts,
+    };
+    let json = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    fs::write(PathBuf::from(&path), json).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Import settings from a file written by `export_settings`.
+///
+/// Accounts in the file are ignored unless `include_accounts` is set. Everything is
+/// parsed and validated before anything is applied; if applying fails part way the
+/// settings and shortcuts from before are put back.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_settings(path: String, include_accounts: bool, app: AppHandle) -> Result<(), String> {
+    let contents = fs::read_to_string(PathBuf::from(&path))
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let export = parse_export(&contents)?;
+    crate::shortcuts::validate_bindings(&export.shortcuts).map_err(|e| e.to_string())?;
+    let accounts = if include_accounts {
+        let accounts = export
+            .accounts
+            .ok_or_else(|| "The settings file has no accounts".to_string())?;
+        crate::accounts::validate_accounts(&accounts)?;
+        Some(accounts)
+    } else {
+        None
+    };
+
+    let previous = collect(&app).await?;
+    let previous_shortcuts = app
+        .state::<std::sync::Mutex<ShortcutManager>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .bindings();
+    let snapshot = export.settings.clone();
+
+    if let Err(e) = apply_all(&app, export.settings).await {
+        roll_back(&app, previous).await;
+        return Err(e);
+    }
+    if let Err(e) = crate::shortcuts::replace_bindings(&app, export.shortcuts) {
+        roll_back(&app, previous).await;
+        return Err(e);
+    }
+    if let Some(accounts) = accounts {
+        let replaced = app
+            .state::<std::sync::Mutex<AccountManager>>()
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|mut manager| manager.replace_accounts(accounts).map_err(|e| e.to_string()));
+        if let Err(e) = replaced {
+            roll_back(&app, previous).await;
+            if let Err(e) = crate::shortcuts::replace_bindings(&app, previous_shortcuts) {
+                log::warn!("[settings] failed to restore the previous shortcuts: {}", e);
+            }
+            return Err(e);
+        }
+    }
+
+    app.emit("settings-imported", snapshot).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> AppSettings {
+        AppSettings {
+            notifications: NotificationSettings::default(),
+            privacy: PrivacyConfig::default(),
+            window: WindowState::default(),
+            zoom_level: 0.0,
+            theme: "dark".to_string(),
+            custom_css: None,
+            message_density: MessageDensity::Compact,
+            platform: Some("Messenger".to_string()),
+            update_channel: "stable".to_string(),
+        }
+    }
+
+    fn export_json(version: u32) -> String {
+        let export = SettingsExport {
+            version,
+            exported_at: "2024-01-01T00:00:00Z".to_string(),
+            settings: sample(),
+            shortcuts: HashMap::new(),
+            accounts: None,
+        };
+        serde_json::to_string(&export).unwrap()
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let json = serde_json::to_string(&sample()).unwrap();
+        let parsed: AppSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.theme, "dark");
+        assert_eq!(parsed.platform.as_deref(), Some("Messenger"));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_values() {
+        assert!(sample().validate().is_ok());
+
+        let mut bad_theme = sample();
+        bad_theme.theme = "neon".to_string();
+        assert!(bad_theme.validate().is_err());
+
+        let mut bad_platform = sample();
+        bad_platform.platform = Some("TikTok".to_string());
+        assert!(bad_platform.validate().is_err());
+
+        let mut bad_zoom = sample();
+        bad_zoom.zoom_level = f64::NAN;
+        assert!(bad_zoom.validate().is_err());
+
+        let mut bad_schedule = sample();
+        bad_schedule.notifications.dnd_schedule = Some(crate::notifications::DNDSchedule {
+            start_time: "22:00".to_string(),
+            end_time: "late".to_string(),
+        });
+        assert!(bad_schedule.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_export_versions() {
+        let parsed = parse_export(&export_json(SETTINGS_SCHEMA_VERSION)).unwrap();
+        assert_eq!(parsed.settings.theme, "dark");
+        assert!(parsed.accounts.is_none());
+
+        let newer = parse_export(&export_json(SETTINGS_SCHEMA_VERSION + 1));
+        assert!(newer.unwrap_err().contains("newer version"));
+
+        assert!(parse_export(r#"{"settings": {}}"#).is_err());
+    }
+
+    #[test]
+    fn test_migrate_v1() {
+        let mut doc: serde_json::Value = serde_json::from_str(&export_json(1)).unwrap();
+        doc["settings"].as_object_mut().unwrap().remove("message_density");
+
+        let parsed = parse_export(&doc.to_string()).unwrap();
+        assert_eq!(parsed.version, SETTINGS_SCHEMA_VERSION);
+        assert_eq!(parsed.settings.message_density, MessageDensity::default());
+        // A v1 file that already has it keeps it
+        let parsed = parse_export(&export_json(1)).unwrap();
+        assert_eq!(parsed.settings.message_density, MessageDensity::Compact);
+
+        // Without the step, a current file missing it is rejected
+        let mut doc: serde_json::Value = serde_json::from_str(&export_json(SETTINGS_SCHEMA_VERSION)).unwrap();
+        doc["settings"].as_object_mut().unwrap().remove("message_density");
+        assert!(parse_export(&doc.to_string()).is_err());
+    }
+}
@@ -1,19 +1,113 @@
-use tauri::AppHandle;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter};
 use tauri::Manager;
+use tauri_plugin_global_shortcut::Shortcut;
 use std::sync::Mutex;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::store;
+
+/// Shortcuts defined by the frontend, persisted in `shortcuts.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CustomShortcuts {
+    custom: HashMap<String, String>, // action -> keys
+}
+
+/// Why a custom shortcut was refused
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShortcutError {
+    /// `keys` is not a valid accelerator
+    InvalidKeys { keys: String, reason: String },
+    /// An action with this name is already bound
+    DuplicateAction { action: String },
+    /// `keys` is already bound to `action`
+    Conflict { keys: String, action: String },
+    /// No custom shortcut has this action name
+    NotFound { action: String },
+    /// Registering with the OS or saving failed
+    Failed { message: String },
+}
+
+impl std::fmt::Display for ShortcutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShortcutError::InvalidKeys { keys, reason } => write!(f, "Invalid shortcut {}: {}", keys, reason),
+            ShortcutError::DuplicateAction { action } => write!(f, "A shortcut for {} already exists", action),
+            ShortcutError::Conflict { keys, action } => write!(f, "{} is already used by {}", keys, action),
+            ShortcutError::NotFound { action } => write!(f, "No custom shortcut named {}", action),
+            ShortcutError::Failed { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+/// One binding as listed by `list_shortcuts`
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct ShortcutBinding {
+    pub action: String,
+    pub keys: String,
+    /// Defined by the frontend with `register_custom_shortcut`
+    pub custom: bool,
+}
 
 pub struct ShortcutManager {
     registered: HashMap<String, String>, // action -> keys
+    /// Actions in `registered` that were defined by the frontend
+    custom: HashMap<String, String>,
+    store_path: Option<PathBuf>,
 }
 
 impl ShortcutManager {
     pub fn new() -> Self {
         Self {
             registered: HashMap::new(),
+            custom: HashMap::new(),
+            store_path: None,
         }
     }
 
+    /// The built-in bindings plus the custom shortcuts saved in `app_data_dir`
+    pub fn load(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("shortcuts.json");
+        let saved: CustomShortcuts = store::load_or_default(&store_path);
+        let mut manager = Self::with_defaults();
+        manager.store_path = Some(store_path);
+        for (action, keys) in saved.custom {
+            if let Err(e) = manager.add_custom(&action, &keys) {
+                log::warn!("[shortcuts] dropping saved shortcut {}: {}", action, e);
+            }
+        }
+        manager
+    }
+
+    /// A manager with the built-in bindings (panic button, quick search, back/forward)
+    pub fn with_defaults() -> Self {
+        let mut manager = Self::new();
+        manager.registered.insert(
+            crate::panic_button::ACTION.to_string(),
+            crate::panic_button::DEFAULT_SHORTCUT.to_string(),
+        );
+        manager.registered.insert(
+            crate::search::ACTION.to_string(),
+            crate::search::DEFAULT_SHORTCUT.to_string(),
+        );
+        manager.registered.insert(
+            crate::history::ACTION_BACK.to_string(),
+            crate::history::DEFAULT_BACK_SHORTCUT.to_string(),
+        );
+        manager.registered.insert(
+            crate::history::ACTION_FORWARD.to_string(),
+            crate::history::DEFAULT_FORWARD_SHORTCUT.to_string(),
+        );
+        manager.registered.insert(
+            crate::debug::ACTION.to_string(),
+            crate::debug::DEFAULT_SHORTCUT.to_string(),
+        );
+        manager
+    }
+
     pub fn register_all(app: &AppHandle) -> Result<(), String> {
         let manager = Self::new();
         app.manage(Mutex::new(manager));
@@ -23,18 +117,233 @@ impl ShortcutManager {
         Ok(())
     }
 
+    /// Returns the stored action -> keys bindings
+    pub fn bindings(&self) -> HashMap<String, String> {
+        self.registered.clone()
+    }
+
+    /// Replaces the stored bindings wholesale. Actions that aren't built in become
+    /// custom shortcuts. Only updates the manager; see `replace_bindings`.
+    pub fn set_bindings(&mut self, bindings: HashMap<String, String>) {
+        let builtin = Self::with_defaults().registered;
+        self.custom = bindings
+            .iter()
+            .filter(|(action, _)| !builtin.contains_key(*action))
+            .map(|(action, keys)| (action.clone(), keys.clone()))
+            .collect();
+        self.registered = bindings;
+    }
+
+    /// The custom shortcuts, action -> keys
+    pub fn custom_bindings(&self) -> HashMap<String, String> {
+        self.custom.clone()
+    }
+
+    /// All bindings, sorted by action
+    pub fn list(&self) -> Vec<ShortcutBinding> {
+        let mut list: Vec<ShortcutBinding> = self
+            .registered
+            .iter()
+            .map(|(action, keys)| ShortcutBinding {
+                action: action.clone(),
+                keys: keys.clone(),
+                custom: self.custom.contains_key(action),
+            })
+            .collect();
+        list.sort_by(|a, b| a.action.cmp(&b.action));
+        list
+    }
+
+    /// Validates and stores a new custom binding: `keys` must parse, and neither the
+    /// action name nor the accelerator may already be in use
+    fn add_custom(&mut self, action: &str, keys: &str) -> Result<(), ShortcutError> {
+        let shortcut: Shortcut = keys.parse().map_err(|e| ShortcutError::InvalidKeys {
+            keys: keys.to_string(),
+            reason: format!("{}", e),
+        })?;
+        if self.registered.contains_key(action) {
+            return Err(ShortcutError::DuplicateAction {
+                action: action.to_string(),
+            });
+        }
+        // Compare parsed accelerators so "Ctrl+K" and "Control+k" clash
+        let taken = self.registered.iter().find(|(_, bound)| {
+            bound.parse::<Shortcut>().map_or(bound.eq_ignore_ascii_case(keys), |b| b == shortcut)
+        });
+        if let Some((other, _)) = taken {
+            return Err(ShortcutError::Conflict {
+                keys: keys.to_string(),
+                action: other.clone(),
+            });
+        }
+        self.registered.insert(action.to_string(), keys.to_string());
+        self.custom.insert(action.to_string(), keys.to_string());
+        Ok(())
+    }
+
+    /// Removes a custom binding, returning its keys. Built-in actions are refused.
+    fn remove_custom(&mut self, action: &str) -> Result<String, ShortcutError> {
+        let keys = self.custom.remove(action).ok_or_else(|| ShortcutError::NotFound {
+            action: action.to_string(),
+        })?;
+        self.registered.remove(action);
+        Ok(keys)
+    }
+
+    /// Persists the custom shortcuts
+    fn save(&self) -> Result<(), ShortcutError> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+        let saved = CustomShortcuts {
+            custom: self.custom.clone(),
+        };
+        store::save(path, &saved).map_err(|e| ShortcutError::Failed {
+            message: format!("Failed to save shortcuts: {}", e),
+        })
+    }
+
     #[allow(dead_code)]
     pub fn unregister_all(&self) {
         // Cleanup
     }
 }
 
+/// Moves the OS registration of the built-in actions backed by real global shortcuts.
+/// `new` is `None` when the action is being unbound.
+fn rebind_global(app: &AppHandle, action: &str, old: Option<&str>, new: Option<&str>) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    if action != crate::panic_button::ACTION
+        && action != crate::search::ACTION
+        && action != crate::debug::ACTION
+        && !crate::history::is_action(action)
+    {
+        return Ok(());
+    }
+    if let Some(old) = old {
+        let _ = app.global_shortcut().unregister(old);
+    }
+    let Some(new) = new else {
+        return Ok(());
+    };
+    if action == crate::panic_button::ACTION {
+        crate::panic_button::register_shortcut(app, new)
+    } else {
+        // Search, history and devtools are only held while the window is focused
+        // (see the `on_focus_changed` hooks in each module)
+        let focused = app
+            .get_webview_window("main")
+            .and_then(|w| w.is_focused().ok())
+            .unwrap_or(false);
+        if !focused {
+            Ok(())
+        } else if action == crate::search::ACTION {
+            crate::search::register_shortcut(app, new)
+        } else if action == crate::debug::ACTION {
+            crate::debug::register_shortcut(app, new)
+        } else {
+            crate::history::register_shortcut(app, action, new)
+        }
+    }
+}
+
+/// Registers a custom shortcut with the OS; pressing it emits `global-shortcut-pressed`
+/// with the action name
+fn register_custom_global(app: &AppHandle, action: &str, keys: &str) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    let action = action.to_string();
+    app.global_shortcut()
+        .on_shortcut(keys, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                let _ = app.emit("global-shortcut-pressed", action.clone());
+            }
+        })
+        .map_err(|e| format!("Failed to register shortcut {}: {}", keys, e))
+}
+
+/// Checks a full set of bindings before it replaces the current one: every
+/// accelerator must parse and no two actions may share one
+pub fn validate_bindings(bindings: &HashMap<String, String>) -> Result<(), ShortcutError> {
+    let mut check = ShortcutManager::new();
+    let mut sorted: Vec<_> = bindings.iter().collect();
+    sorted.sort();
+    for (action, keys) in sorted {
+        check.add_custom(action, keys)?;
+    }
+    Ok(())
+}
+
+/// Releases every accelerator `manager` holds with the OS
+fn release_all(app: &AppHandle, manager: &ShortcutManager) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    for (action, keys) in &manager.registered {
+        if manager.custom.contains_key(action) {
+            let _ = app.global_shortcut().unregister(keys.as_str());
+        } else if let Err(e) = rebind_global(app, action, Some(keys), None) {
+            log::warn!("[shortcuts] {}", e);
+        }
+    }
+}
+
+/// Registers every accelerator `manager` holds with the OS. Returns the first failure.
+fn grab_all(app: &AppHandle, manager: &ShortcutManager) -> Result<(), String> {
+    let mut result = Ok(());
+    for (action, keys) in &manager.registered {
+        let registered = if manager.custom.contains_key(action) {
+            register_custom_global(app, action, keys)
+        } else {
+            rebind_global(app, action, None, Some(keys))
+        };
+        if let Err(e) = registered {
+            log::warn!("[shortcuts] {}", e);
+            result = result.and(Err(e));
+        }
+    }
+    result
+}
+
+/// Replaces every binding (e.g. from an imported settings file): the old
+/// accelerators are released, the new ones registered with the OS and the custom
+/// ones saved, then `shortcuts-changed` is emitted with the new list. If the OS
+/// refuses a new accelerator the previous bindings are put back.
+pub fn replace_bindings(app: &AppHandle, bindings: HashMap<String, String>) -> Result<(), String> {
+    validate_bindings(&bindings).map_err(|e| e.to_string())?;
+    let state = app.state::<Mutex<ShortcutManager>>();
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    let previous = manager.bindings();
+
+    release_all(app, &manager);
+    manager.set_bindings(bindings);
+    if let Err(e) = grab_all(app, &manager) {
+        release_all(app, &manager);
+        manager.set_bindings(previous);
+        let _ = grab_all(app, &manager);
+        return Err(e);
+    }
+    manager.save().map_err(|e| e.to_string())?;
+    app.emit("shortcuts-changed", manager.list()).map_err(|e| e.to_string())
+}
+
+/// Registers the saved custom shortcuts at startup
+pub fn register_custom(app: &AppHandle, manager: &ShortcutManager) {
+    for (action, keys) in manager.custom_bindings() {
+        if let Err(e) = register_custom_global(app, &action, &keys) {
+            log::warn!("[shortcuts] {}", e);
+        }
+    }
+}
+
 #[tauri::command]
+#[specta::specta]
 pub fn register_shortcuts(app: AppHandle) -> Result<(), String> {
     ShortcutManager::register_all(&app)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn update_shortcut(
     app: AppHandle,
     action: String,
@@ -42,22 +351,82 @@ pub fn update_shortcut(
 ) -> Result<(), String> {
     let state = app.state::<Mutex<ShortcutManager>>();
     let mut manager = state.lock().map_err(|e| e.to_string())?;
+    let old = manager.registered.get(&action).cloned();
+    if manager.custom.contains_key(&action) {
+        use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+        if let Some(old) = &old {
+            let _ = app.global_shortcut().unregister(old.as_str());
+        }
+        register_custom_global(&app, &action, &keys)?;
+        manager.custom.insert(action.clone(), keys.clone());
+        manager.registered.insert(action, keys);
+        return manager.save().map_err(|e| e.to_string());
+    }
+    rebind_global(&app, &action, old.as_deref(), Some(&keys))?;
     manager.registered.insert(action, keys);
     Ok(())
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn unregister_shortcut(
     app: AppHandle,
     action: String,
 ) -> Result<(), String> {
     let state = app.state::<Mutex<ShortcutManager>>();
     let mut manager = state.lock().map_err(|e| e.to_string())?;
-    manager.registered.remove(&action);
+    if let Some(keys) = manager.registered.remove(&action) {
+        rebind_global(&app, &action, Some(&keys), None)?;
+    }
     Ok(())
 }
 
+/// Tauri command to define a new global shortcut. Pressing `keys` emits
+/// `global-shortcut-pressed` with `action`. The binding is persisted.
 #[tauri::command]
+#[specta::specta]
+pub fn register_custom_shortcut(
+    app: AppHandle,
+    action: String,
+    keys: String,
+) -> Result<(), ShortcutError> {
+    let state = app.state::<Mutex<ShortcutManager>>();
+    let mut manager = state.lock().map_err(|e| ShortcutError::Failed { message: e.to_string() })?;
+    manager.add_custom(&action, &keys)?;
+    if let Err(message) = register_custom_global(&app, &action, &keys) {
+        let _ = manager.remove_custom(&action);
+        return Err(ShortcutError::Failed { message });
+    }
+    manager.save()?;
+    log::info!("[shortcuts] registered {} for {}", keys, action);
+    Ok(())
+}
+
+/// Tauri command to remove a shortcut added with `register_custom_shortcut`
+#[tauri::command]
+#[specta::specta]
+pub fn unregister_custom_shortcut(app: AppHandle, action: String) -> Result<(), ShortcutError> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let state = app.state::<Mutex<ShortcutManager>>();
+    let mut manager = state.lock().map_err(|e| ShortcutError::Failed { message: e.to_string() })?;
+    let keys = manager.remove_custom(&action)?;
+    let _ = app.global_shortcut().unregister(keys.as_str());
+    manager.save()
+}
+
+/// Tauri command to list every shortcut binding, built-in and custom
+#[tauri::command]
+#[specta::specta]
+pub fn list_shortcuts(app: AppHandle) -> Result<Vec<ShortcutBinding>, String> {
+    let state = app.state::<Mutex<ShortcutManager>>();
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.list())
+}
+
+#[tauri::command]
+#[specta::specta]
 pub fn init_shortcuts(app: AppHandle) -> Result<(), String> {
     ShortcutManager::register_all(&app)
 }
@@ -73,6 +442,31 @@ mod tests {
         assert!(manager.registered.is_empty());
     }
 
+    #[test]
+    fn test_shortcut_manager_defaults() {
+        let manager = ShortcutManager::with_defaults();
+        assert_eq!(
+            manager.bindings().get(crate::panic_button::ACTION).map(String::as_str),
+            Some(crate::panic_button::DEFAULT_SHORTCUT)
+        );
+        assert_eq!(
+            manager.bindings().get(crate::search::ACTION).map(String::as_str),
+            Some(crate::search::DEFAULT_SHORTCUT)
+        );
+        assert_eq!(
+            manager.bindings().get(crate::history::ACTION_BACK).map(String::as_str),
+            Some("Alt+Left")
+        );
+        assert_eq!(
+            manager.bindings().get(crate::history::ACTION_FORWARD).map(String::as_str),
+            Some("Alt+Right")
+        );
+        assert_eq!(
+            manager.bindings().get(crate::debug::ACTION).map(String::as_str),
+            Some("F12")
+        );
+    }
+
     #[test]
     fn test_shortcut_manager_register() {
         let mut manager = ShortcutManager::new();
@@ -104,4 +498,92 @@ mod tests {
         let json = serde_json::to_string(&manager.registered).unwrap();
         assert!(json.contains("action"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_custom_shortcut_register_and_unregister() {
+        let mut manager = ShortcutManager::with_defaults();
+        manager.add_custom("toggle-sidebar", "CommandOrControl+Shift+B").unwrap();
+        let binding = manager.list().into_iter().find(|b| b.action == "toggle-sidebar").unwrap();
+        assert!(binding.custom);
+        assert!(!manager.list().iter().find(|b| b.action == crate::debug::ACTION).unwrap().custom);
+
+        assert_eq!(manager.remove_custom("toggle-sidebar").unwrap(), "CommandOrControl+Shift+B");
+        assert!(!manager.bindings().contains_key("toggle-sidebar"));
+        // Built-ins can't be removed as custom shortcuts
+        assert!(matches!(
+            manager.remove_custom(crate::debug::ACTION),
+            Err(ShortcutError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_custom_shortcut_conflicts() {
+        let mut manager = ShortcutManager::with_defaults();
+        manager.add_custom("mark-read", "Ctrl+Shift+M").unwrap();
+
+        assert_eq!(
+            manager.add_custom("mark-read", "Ctrl+Shift+J"),
+            Err(ShortcutError::DuplicateAction { action: "mark-read".to_string() })
+        );
+        assert_eq!(
+            manager.add_custom(crate::debug::ACTION, "Ctrl+Shift+J"),
+            Err(ShortcutError::DuplicateAction { action: crate::debug::ACTION.to_string() })
+        );
+        // Same accelerator spelled differently
+        assert_eq!(
+            manager.add_custom("other", "Control+Shift+m"),
+            Err(ShortcutError::Conflict {
+                keys: "Control+Shift+m".to_string(),
+                action: "mark-read".to_string()
+            })
+        );
+        assert!(matches!(
+            manager.add_custom("bad", "Ctrl+Nope"),
+            Err(ShortcutError::InvalidKeys { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_bindings() {
+        let mut bindings = ShortcutManager::with_defaults().bindings();
+        bindings.insert("archive".to_string(), "Alt+Shift+A".to_string());
+        assert!(validate_bindings(&bindings).is_ok());
+
+        bindings.insert("other".to_string(), "alt+shift+a".to_string());
+        assert!(matches!(validate_bindings(&bindings), Err(ShortcutError::Conflict { .. })));
+
+        bindings.remove("other");
+        bindings.insert("bad".to_string(), "Ctrl+Nope".to_string());
+        assert!(matches!(validate_bindings(&bindings), Err(ShortcutError::InvalidKeys { .. })));
+    }
+
+    #[test]
+    fn test_set_bindings_splits_custom() {
+        let mut manager = ShortcutManager::with_defaults();
+        manager.add_custom("mark-read", "Ctrl+Shift+M").unwrap();
+
+        let mut bindings = ShortcutManager::with_defaults().bindings();
+        bindings.insert(crate::debug::ACTION.to_string(), "F11".to_string());
+        bindings.insert("archive".to_string(), "Alt+Shift+A".to_string());
+        manager.set_bindings(bindings);
+
+        assert_eq!(manager.bindings().get(crate::debug::ACTION).map(String::as_str), Some("F11"));
+        let custom = manager.custom_bindings();
+        assert_eq!(custom.len(), 1);
+        assert_eq!(custom.get("archive").map(String::as_str), Some("Alt+Shift+A"));
+    }
+
+    #[test]
+    fn test_custom_shortcuts_persist() {
+        let tmp = std::env::temp_dir().join("test-custom-shortcuts");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let mut manager = ShortcutManager::load(&tmp);
+        manager.add_custom("archive", "Alt+Shift+A").unwrap();
+        manager.save().unwrap();
+
+        let reloaded = ShortcutManager::load(&tmp);
+        assert_eq!(reloaded.custom_bindings().get("archive").map(String::as_str), Some("Alt+Shift+A"));
+        assert!(reloaded.bindings().contains_key(crate::debug::ACTION));
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
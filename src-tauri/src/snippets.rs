@@ -0,0 +1,321 @@
+//! Text snippets for the platforms' message composers.
+//!
+//! A snippet maps a trigger (e.g. `;brb`) to an expansion ("be right back").
+//! Typing the trigger as a whole word in the conversation composer and then a
+//! space or line break replaces it with the expansion. Triggers inside a longer
+//! word, and any other input on the page, are left alone. The script is
+//! re-injected on every page load through the injection registry.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::platform_manager::PlatformManager;
+use crate::store;
+
+/// Longest accepted trigger, in characters
+const MAX_TRIGGER_LEN: usize = 32;
+
+/// Longest accepted expansion, in characters
+const MAX_EXPANSION_LEN: usize = 2000;
+
+/// A trigger and the text it expands to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Snippet {
+    pub trigger: String,
+    pub expansion: String,
+}
+
+/// Manages the snippets and their persistence
+pub struct SnippetManager {
+    /// Trigger → expansion
+    snippets: std::sync::Mutex<BTreeMap<String, String>>,
+    store_path: PathBuf,
+}
+
+impl SnippetManager {
+    /// Creates a new SnippetManager, loading any saved snippets
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("snippets.json");
+        let snippets = store::load_or_default(&store_path);
+        Self {
+            snippets: std::sync::Mutex::new(snippets),
+            store_path,
+        }
+    }
+
+    /// The snippets, ordered by trigger
+    pub fn list(&self) -> Result<Vec<Snippet>, String> {
+        Ok(to_list(&*self.snippets.lock().map_err(|e| e.to_string())?))
+    }
+
+    /// Adds a snippet, replacing one with the same trigger
+    pub fn add(&self, trigger: &str, expansion: &str) -> Result<Vec<Snippet>, String> {
+        validate(trigger, expansion)?;
+        self.update(|snippets| {
+            snippets.insert(trigger.to_string(), expansion.to_string());
+        })
+    }
+
+    /// Removes a snippet
+    pub fn remove(&self, trigger: &str) -> Result<Vec<Snippet>, String> {
+        self.update(|snippets| {
+            snippets.remove(trigger);
+        })
+    }
+
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, String>)) -> Result<Vec<Snippet>, String> {
+        let mut snippets = self.snippets.lock().map_err(|e| e.to_string())?;
+        change(&mut snippets);
+        store::save(&self.store_path, &*snippets)
+            .map_err(|e| format!("Failed to save snippets: {}", e))?;
+        Ok(to_list(&snippets))
+    }
+}
+
+fn to_list(snippets: &BTreeMap<String, String>) -> Vec<Snippet> {
+    snippets
+        .iter()
+        .map(|(trigger, expansion)| Snippet {
+            trigger: trigger.clone(),
+            expansion: expansion.clone(),
+        })
+        .collect()
+}
+
+/// A trigger is one word (no whitespace) so it can be matched at word boundaries
+fn validate(trigger: &str, expansion: &str) -> Result<(), String> {
+    if trigger.is_empty() || trigger.chars().any(char::is_whitespace) {
+        return Err("A snippet trigger must be a single word without spaces".to_string());
+    }
+    if trigger.chars().count() > MAX_TRIGGER_LEN {
+        return Err(format!("A snippet trigger can be at most {} characters", MAX_TRIGGER_LEN));
+    }
+    if expansion.trim().is_empty() {
+        return Err("A snippet needs text to expand to".to_string());
+    }
+    if expansion.chars().count() > MAX_EXPANSION_LEN {
+        return Err(format!("A snippet can expand to at most {} characters", MAX_EXPANSION_LEN));
+    }
+    Ok(())
+}
+
+/// Finds the snippet the text before the caret just completed: a whole-word trigger
+/// followed by a single whitespace character. Returns the byte offset the trigger
+/// starts at and its expansion. The injected handler applies the same rule.
+pub fn find_expansion<'a>(before_caret: &str, snippets: &'a BTreeMap<String, String>) -> Option<(usize, &'a str)> {
+    let without_space = before_caret.strip_suffix(|c: char| c.is_whitespace())?;
+    let start = without_space
+        .rfind(char::is_whitespace)
+        .map(|i| i + without_space[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(0);
+    let word = &without_space[start..];
+    snippets.get(word).map(|expansion| (start, expansion.as_str()))
+}
+
+/// Builds the script that expands snippets in the composer. Without snippets it
+/// only removes a previously installed handler.
+pub fn snippets_script(snippets: &BTreeMap<String, String>, platform: Option<&crate::platform_manager::Platform>) -> String {
+    if snippets.is_empty() {
+        return r#"
+        (function() {
+            if (window.__MD_SNIPPETS__) window.__MD_SNIPPETS__.disable();
+        })();
+        "#
+        .to_string();
+    }
+
+    let (composer, _) = crate::composer::composer_selectors(platform);
+    let composer = serde_json::to_string(composer).unwrap_or_default();
+    let snippets = serde_json::to_string(snippets).unwrap_or_else(|_| "{}".to_string());
+
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_SNIPPETS__) window.__MD_SNIPPETS__.disable();
+
+            const COMPOSER = {composer};
+            const SNIPPETS = {snippets};
+            // Set while inserting an expansion, whose own input event mustn't expand again
+            let expanding = false;
+
+            // Same rule as `find_expansion`: a whole word, then one whitespace character
+            function findSnippet(before) {{
+                const m = before.match(/(^|\s)(\S+)(\s)$/);
+                if (!m || !Object.prototype.hasOwnProperty.call(SNIPPETS, m[2])) return null;
+                return {{ trigger: m[2], space: m[3], expansion: SNIPPETS[m[2]] }};
+            }}
+
+            function expandTextarea(input) {{
+                const caret = input.selectionStart;
+                if (caret !== input.selectionEnd) return;
+                const found = findSnippet(input.value.slice(0, caret));
+                if (!found) return;
+                const start = caret - found.trigger.length - found.space.length;
+                input.setRangeText(found.expansion + found.space, start, caret, 'end');
+                input.dispatchEvent(new Event('input', {{ bubbles: true }}));
+            }}
+
+            function expandEditable() {{
+                const selection = window.getSelection();
+                if (!selection || !selection.isCollapsed || !selection.rangeCount) return;
+                const node = selection.anchorNode;
+                if (!node || node.nodeType !== Node.TEXT_NODE) return;
+                const caret = selection.anchorOffset;
+                const found = findSnippet(node.textContent.slice(0, caret));
+                if (!found) return;
+                const range = document.createRange();
+                range.setStart(node, caret - found.trigger.length - found.space.length);
+                range.setEnd(node, caret);
+                selection.removeAllRanges();
+                selection.addRange(range);
+                // Through the editor's own input handling, so the platform sees the text
+                document.execCommand('insertText', false, found.expansion + found.space);
+            }}
+
+            function onInput(event) {{
+                if (expanding || event.isComposing || !event.target.closest) return;
+                if (event.inputType && event.inputType !== 'insertText' && event.inputType !== 'insertParagraph' && event.inputType !== 'insertLineBreak') return;
+                const composer = event.target.closest(COMPOSER);
+                if (!composer) return;
+                expanding = true;
+                try {{
+                    if (composer.value !== undefined) {{
+                        expandTextarea(composer);
+                    }} else {{
+                        expandEditable();
+                    }}
+                }} finally {{
+                    expanding = false;
+                }}
+            }}
+
+            document.addEventListener('input', onInput, true);
+
+            window.__MD_SNIPPETS__ = {{
+                disable: function() {{
+                    document.removeEventListener('input', onInput, true);
+                    delete window.__MD_SNIPPETS__;
+                }},
+            }};
+        }})();
+        "#,
+        composer = composer,
+        snippets = snippets,
+    )
+}
+
+/// The snippets script for the current snippets and platform
+fn current_script(app: &AppHandle, manager: &SnippetManager) -> Result<String, String> {
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    let snippets = manager.snippets.lock().map_err(|e| e.to_string())?;
+    Ok(snippets_script(&snippets, platform.as_ref()))
+}
+
+/// Script re-injected after every page load; `None` without snippets.
+pub fn page_load_script(app: &AppHandle) -> Option<String> {
+    let manager = app.try_state::<SnippetManager>()?;
+    if manager.list().ok()?.is_empty() {
+        return None;
+    }
+    current_script(app, &manager).ok()
+}
+
+fn apply(app: &AppHandle, manager: &SnippetManager) -> Result<(), String> {
+    crate::injection::apply(app, crate::injection::SNIPPETS, &current_script(app, manager)?)
+}
+
+/// Tauri command to add (or replace) a snippet: typing `trigger` as a word in the
+/// composer, then a space, expands it. Returns the snippets.
+#[tauri::command]
+#[specta::specta]
+pub fn add_snippet(
+    trigger: String,
+    expansion: String,
+    app: AppHandle,
+    manager: tauri::State<'_, SnippetManager>,
+) -> Result<Vec<Snippet>, String> {
+    let snippets = manager.add(trigger.trim(), &expansion)?;
+    apply(&app, &manager)?;
+    log::info!("[snippets] added {}", trigger.trim());
+    Ok(snippets)
+}
+
+/// Tauri command to remove a snippet. Returns the snippets.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_snippet(
+    trigger: String,
+    app: AppHandle,
+    manager: tauri::State<'_, SnippetManager>,
+) -> Result<Vec<Snippet>, String> {
+    let snippets = manager.remove(trigger.trim())?;
+    apply(&app, &manager)?;
+    Ok(snippets)
+}
+
+/// Tauri command to list the snippets, ordered by trigger
+#[tauri::command]
+#[specta::specta]
+pub fn list_snippets(manager: tauri::State<'_, SnippetManager>) -> Result<Vec<Snippet>, String> {
+    manager.list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippets() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (";brb".to_string(), "be right back".to_string()),
+            (";addr".to_string(), "221B Baker Street".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_expansion_matching() {
+        let snippets = snippets();
+        assert_eq!(find_expansion(";brb ", &snippets), Some((0, "be right back")));
+        assert_eq!(find_expansion("ok ;brb ", &snippets), Some((3, "be right back")));
+        assert_eq!(find_expansion("line\n;addr\n", &snippets), Some((5, "221B Baker Street")));
+        assert_eq!(find_expansion("café ;brb ", &snippets), Some(("café ".len(), "be right back")));
+
+        // Not followed by whitespace yet
+        assert_eq!(find_expansion(";brb", &snippets), None);
+        // Partial and longer triggers
+        assert_eq!(find_expansion(";br ", &snippets), None);
+        assert_eq!(find_expansion(";brbx ", &snippets), None);
+        // Inside a word
+        assert_eq!(find_expansion("x;brb ", &snippets), None);
+        // No match at all
+        assert_eq!(find_expansion("hello there ", &snippets), None);
+        assert_eq!(find_expansion(" ", &snippets), None);
+        assert_eq!(find_expansion("", &snippets), None);
+    }
+
+    #[test]
+    fn test_snippets_persist_and_validate() {
+        let tmp = std::env::temp_dir().join("test-snippets");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = SnippetManager::new(&tmp);
+        manager.add(";brb", "be right back").unwrap();
+        manager.add(";ty", "thank you").unwrap();
+        let saved = SnippetManager::new(&tmp).list().unwrap();
+        assert_eq!(saved.iter().map(|s| s.trigger.as_str()).collect::<Vec<_>>(), [";brb", ";ty"]);
+        assert_eq!(manager.remove(";ty").unwrap().len(), 1);
+
+        assert!(manager.add("two words", "x").is_err());
+        assert!(manager.add("", "x").is_err());
+        assert!(manager.add(";empty", "  ").is_err());
+        let _ = std::fs::remove_dir_all(&tmp);
+
+        let script = snippets_script(&snippets(), Some(&crate::platform_manager::Platform::X));
+        assert!(script.contains("dmComposerTextInput"));
+        assert!(script.contains("be right back"));
+        assert!(snippets_script(&BTreeMap::new(), None).contains("disable()"));
+    }
+}
@@ -2,10 +2,10 @@
 //! Handles spellcheck state, WebView communication, and text validation.
 
 use tauri::AppHandle;
-use tauri::{Emitter, Manager};
+use tauri::Emitter;
 use serde::Serialize;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use anyhow::{Context, Result};
 // use hunspell::Hunspell; // Disabled due to compilation issues
 
@@ -28,7 +28,7 @@ pub struct SpellcheckManager {
 impl SpellcheckManager {
     /// Create a new SpellcheckManager.
     pub fn new(app: &AppHandle) -> Result<Self> {
-        let dictionaries_dir = app.path().app_data_dir()
+        let dictionaries_dir = crate::store::data_dir(app)
             .context("Failed to resolve app data directory")?
             .join("dictionaries");
         
@@ -45,19 +45,11 @@ impl SpellcheckManager {
         })
     }
     
-    /// Create a disabled SpellcheckManager (for graceful degradation).
-    pub fn disabled() -> Self {
-        // For disabled spellcheck, we use a minimal struct without a valid AppHandle
-        // This allows the application to continue even if spellcheck initialization fails
+    /// Create a disabled SpellcheckManager without a dictionaries directory
+    /// (for graceful degradation).
+    pub fn disabled(app: &AppHandle) -> Self {
         Self {
-            app: Arc::new(
-                // Use a workaround: create a minimal AppHandle via the runtime
-                tauri::async_runtime::block_on(async {
-                    // This is a workaround - in production this would be handled differently
-                    // For now, we'll just use a placeholder that won't be used since spellcheck is disabled
-                    panic!("Spellcheck disabled - AppHandle not available for disabled spellcheck")
-                })
-            ),
+            app: Arc::new(app.clone()),
             enabled: false,
             language: "en-US".to_string(),
             dictionaries_dir: PathBuf::new(),
@@ -87,6 +79,9 @@ impl SpellcheckManager {
     
     /// Set the spellcheck language.
     pub fn set_language(&mut self, lang: &str) -> Result<()> {
+        if !Self::get_available_languages().iter().any(|l| l == lang) {
+            anyhow::bail!("Unsupported spellcheck language: {}", lang);
+        }
         self.language = lang.to_string();
         if self.enabled {
             self.initialize()?;
@@ -138,53 +133,52 @@ impl SpellcheckManager {
 
 /// Tauri command: Enable spellcheck.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn enable_spellcheck(_state: tauri::State<SpellcheckManager>) -> Result<(), String> {
-    // Disabled due to hunspell issues
-    Ok(())
+#[specta::specta]
+pub fn enable_spellcheck(state: tauri::State<Mutex<SpellcheckManager>>) -> Result<(), String> {
+    state.lock().map_err(|e| e.to_string())?.enable().map_err(|e| e.to_string())
 }
 
 /// Tauri command: Disable spellcheck.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn disable_spellcheck(_state: tauri::State<SpellcheckManager>) {
-    // Disabled due to hunspell issues
+#[specta::specta]
+pub fn disable_spellcheck(state: tauri::State<Mutex<SpellcheckManager>>) -> Result<(), String> {
+    state.lock().map_err(|e| e.to_string())?.disable();
+    Ok(())
 }
 
 /// Tauri command: Set spellcheck language.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn set_spellcheck_language(_state: tauri::State<SpellcheckManager>, _lang: String) -> Result<(), String> {
-    // Disabled due to hunspell issues
-    Ok(())
+#[specta::specta]
+pub fn set_spellcheck_language(state: tauri::State<Mutex<SpellcheckManager>>, lang: String) -> Result<(), String> {
+    state.lock().map_err(|e| e.to_string())?.set_language(&lang).map_err(|e| e.to_string())
 }
 
 /// Tauri command: Get available spellcheck languages.
 #[tauri::command]
-#[allow(dead_code)]
+#[specta::specta]
 pub fn get_available_languages() -> Vec<String> {
     SpellcheckManager::get_available_languages()
 }
 
 /// Tauri command: Check if a word is misspelled.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn is_misspelled(state: tauri::State<SpellcheckManager>, word: String) -> bool {
-    state.is_misspelled(&word)
+#[specta::specta]
+pub fn is_misspelled(state: tauri::State<Mutex<SpellcheckManager>>, word: String) -> Result<bool, String> {
+    Ok(state.lock().map_err(|e| e.to_string())?.is_misspelled(&word))
 }
 
 /// Tauri command: Get suggestions for a misspelled word.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn get_suggestions(state: tauri::State<SpellcheckManager>, word: String) -> Vec<String> {
-    state.get_suggestions(&word)
+#[specta::specta]
+pub fn get_suggestions(state: tauri::State<Mutex<SpellcheckManager>>, word: String) -> Result<Vec<String>, String> {
+    Ok(state.lock().map_err(|e| e.to_string())?.get_suggestions(&word))
 }
 
 /// Tauri command: Check a text for misspelled words.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn check_text(state: tauri::State<SpellcheckManager>, text: String) -> Vec<(usize, usize, String)> {
-    state.check_text(&text)
+#[specta::specta]
+pub fn check_text(state: tauri::State<Mutex<SpellcheckManager>>, text: String) -> Result<Vec<(usize, usize, String)>, String> {
+    Ok(state.lock().map_err(|e| e.to_string())?.check_text(&text))
 }
 
 // Unit tests
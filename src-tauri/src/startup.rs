@@ -0,0 +1,295 @@
+//! Launch-at-login and start-hidden preferences.
+//!
+//! "Launch at login" is delegated to the autostart plugin, which registers the app
+//! with the OS and passes `AUTOSTART_ARG` on those launches. Whether such a launch
+//! starts hidden in the tray is our own preference, persisted here and read in `setup`
+//! before the main window is built. `--hidden` starts hidden regardless.
+//!
+//! Otherwise the app comes back the way it was left: hidden in the tray if the
+//! window was hidden or minimized at quit (recorded in `window_state.json`), shown
+//! if it was showing. "Always start visible" turns that off.
+//!
+//! Only one instance runs at a time: a second launch hands its arguments to the
+//! running instance (`on_second_instance`), which raises its window, runs any
+//! `--action`, and the second process exits. Deep links passed to a second launch
+//! reach `crate::deep_link` through the single-instance plugin's deep-link support.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::store;
+
+/// Argument the autostart plugin passes when the OS launches the app at login
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+/// Argument that always starts the app hidden in the tray
+pub const HIDDEN_ARG: &str = "--hidden";
+
+/// Argument that starts the app in safe mode (see `safe_mode`)
+pub const SAFE_MODE_ARG: &str = "--safe-mode";
+
+/// Argument naming an action to run, as `--action=<name>` or `--action <name>`
+pub const ACTION_ARG: &str = "--action";
+
+/// Startup flags parsed from the command line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LaunchArgs {
+    /// `--hidden`: start minimized to the tray
+    pub hidden: bool,
+    /// `--autostart`: launched by the OS at login
+    pub autostarted: bool,
+    /// `--safe-mode`: load platforms without injections or custom CSS
+    pub safe_mode: bool,
+    /// `--action`: a tray/shortcut action to run, e.g. `new_message`
+    pub action: Option<String>,
+    /// A `scheme://` URL passed on the command line, e.g. a deep link
+    pub url: Option<String>,
+}
+
+impl LaunchArgs {
+    /// Parses the launch arguments, ignoring anything unrecognised
+    pub fn parse<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                HIDDEN_ARG => parsed.hidden = true,
+                AUTOSTART_ARG => parsed.autostarted = true,
+                SAFE_MODE_ARG => parsed.safe_mode = true,
+                ACTION_ARG => parsed.action = args.next().map(|a| a.as_ref().to_string()),
+                other => {
+                    if let Some(action) = other.strip_prefix(ACTION_ARG).and_then(|a| a.strip_prefix('=')) {
+                        parsed.action = Some(action.to_string());
+                    } else if other.contains("://") && parsed.url.is_none() {
+                        parsed.url = Some(other.to_string());
+                    }
+                }
+            }
+        }
+        parsed.action = parsed.action.filter(|a| !a.is_empty());
+        parsed
+    }
+}
+
+/// Persisted startup preferences
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct StartupConfig {
+    /// Start minimized to the tray when launched at login
+    pub start_hidden: bool,
+    /// Show the window on a normal launch even if it was hidden at quit
+    #[serde(default)]
+    pub always_start_visible: bool,
+}
+
+/// Whether a launch should start hidden in the tray: always with `--hidden`, for
+/// launches at login when start-hidden is on, and otherwise if the window wasn't
+/// visible at quit (unless "always start visible" is on). Never without a tray,
+/// which is the only way back to a hidden window.
+pub fn start_hidden(args: &LaunchArgs, config: &StartupConfig, was_visible: bool, tray_available: bool) -> bool {
+    tray_available
+        && (args.hidden
+            || (args.autostarted && config.start_hidden)
+            || (!was_visible && !config.always_start_visible))
+}
+
+/// Manages the startup preferences and their persistence
+pub struct StartupManager {
+    config: std::sync::Mutex<StartupConfig>,
+    store_path: PathBuf,
+}
+
+impl StartupManager {
+    /// Creates a new StartupManager, loading any saved preferences
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("startup.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: std::sync::Mutex::new(config),
+            store_path,
+        }
+    }
+
+    /// Gets the current preferences
+    pub fn get_config(&self) -> Result<StartupConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Updates and persists the start-hidden preference
+    pub fn set_start_hidden(&self, start_hidden: bool) -> Result<(), String> {
+        self.update(|config| config.start_hidden = start_hidden)
+    }
+
+    /// Updates and persists the always-start-visible preference
+    pub fn set_always_start_visible(&self, always_start_visible: bool) -> Result<(), String> {
+        self.update(|config| config.always_start_visible = always_start_visible)
+    }
+
+    fn update(&self, change: impl FnOnce(&mut StartupConfig)) -> Result<(), String> {
+        let config = {
+            let mut config = self.config.lock().map_err(|e| e.to_string())?;
+            change(&mut config);
+            config.clone()
+        };
+        store::save(&self.store_path, &config).map_err(|e| format!("Failed to save startup config: {}", e))
+    }
+
+    /// Whether this launch should start hidden in the tray (see `start_hidden`)
+    pub fn should_start_hidden(&self, args: &LaunchArgs, was_visible: bool, tray_available: bool) -> bool {
+        start_hidden(args, &self.get_config().unwrap_or_default(), was_visible, tray_available)
+    }
+}
+
+/// Shows, unminimizes and focuses the main window
+pub fn focus_main_window(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Single-instance callback: runs in the primary instance when the app is launched
+/// again. Raises the existing window and runs whatever the new launch asked for.
+pub fn on_second_instance(app: &AppHandle, argv: Vec<String>, _cwd: String) {
+    let args = LaunchArgs::parse(&argv);
+    log::info!("[startup] second launch forwarded: {:?}", args);
+
+    // `--hidden` on a relaunch (e.g. autostart racing a manual launch) shouldn't pop the window
+    if !args.hidden || args.action.is_some() || args.url.is_some() {
+        focus_main_window(app);
+    }
+    if let Some(action) = args.action {
+        let _ = app.emit("global-shortcut-trigger", action);
+    }
+    // A URL argument is a deep link; the deep-link plugin delivers it to `deep_link::handle`
+}
+
+/// Tauri command to enable or disable launching at login
+#[tauri::command]
+#[specta::specta]
+pub fn set_autostart(enabled: bool, app: AppHandle) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    }
+    .map_err(|e| e.to_string())?;
+    log::info!("[startup] launch at login {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// Tauri command to check whether the app launches at login
+#[tauri::command]
+#[specta::specta]
+pub fn is_autostart_enabled(app: AppHandle) -> Result<bool, String> {
+    app.autolaunch().is_enabled().map_err(|e| e.to_string())
+}
+
+/// Tauri command to set whether a launch at login starts hidden in the tray
+#[tauri::command]
+#[specta::specta]
+pub fn set_start_hidden(enabled: bool, manager: tauri::State<'_, StartupManager>) -> Result<(), String> {
+    manager.set_start_hidden(enabled)
+}
+
+/// Tauri command to set whether a normal launch always shows the window, instead of
+/// restoring whether it was hidden at quit
+#[tauri::command]
+#[specta::specta]
+pub fn set_always_start_visible(enabled: bool, manager: tauri::State<'_, StartupManager>) -> Result<(), String> {
+    manager.set_always_start_visible(enabled)
+}
+
+/// Tauri command to get the startup preferences
+#[tauri::command]
+#[specta::specta]
+pub fn get_startup_config(manager: tauri::State<'_, StartupManager>) -> Result<StartupConfig, String> {
+    manager.get_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_hidden_only_for_login_launches() {
+        let tmp = std::env::temp_dir().join("test-startup");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let at_login = LaunchArgs { autostarted: true, ..LaunchArgs::default() };
+        let manager = StartupManager::new(&tmp);
+        assert!(!manager.should_start_hidden(&at_login, true, true));
+
+        manager.set_start_hidden(true).unwrap();
+        let reloaded = StartupManager::new(&tmp);
+        assert!(reloaded.should_start_hidden(&at_login, true, true));
+        assert!(!reloaded.should_start_hidden(&LaunchArgs::default(), true, true));
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_parse_launch_args() {
+        let args = LaunchArgs::parse(["/usr/bin/messenger-desktop", "--verbose", "--hidden", "https://example.com"]);
+        assert!(args.hidden);
+        assert!(!args.autostarted);
+
+        let args = LaunchArgs::parse(["messenger-desktop", AUTOSTART_ARG]);
+        assert!(args.autostarted && !args.hidden);
+
+        let args = LaunchArgs::parse(["messenger-desktop", SAFE_MODE_ARG]);
+        assert!(args.safe_mode && !args.hidden);
+
+        // Only exact flags count
+        assert_eq!(LaunchArgs::parse(["app", "--hidden-files", "hidden"]), LaunchArgs::default());
+    }
+
+    #[test]
+    fn test_parse_forwarded_action_and_url() {
+        let args = LaunchArgs::parse(["app", "--action=new_message"]);
+        assert_eq!(args.action.as_deref(), Some("new_message"));
+
+        let args = LaunchArgs::parse(["app", "--action", "dnd", "messenger-desktop://platform/X"]);
+        assert_eq!(args.action.as_deref(), Some("dnd"));
+        assert_eq!(args.url.as_deref(), Some("messenger-desktop://platform/X"));
+
+        assert_eq!(LaunchArgs::parse(["app", "--action="]).action, None);
+        assert_eq!(LaunchArgs::parse(["app", "--action"]).action, None);
+    }
+
+    #[test]
+    fn test_hidden_flag_overrides_preference() {
+        let manager = StartupManager::new(&std::env::temp_dir().join("test-startup-missing"));
+        assert!(manager.should_start_hidden(&LaunchArgs::parse(["app", HIDDEN_ARG]), true, true));
+    }
+
+    #[test]
+    fn test_restores_last_visibility() {
+        let normal = LaunchArgs::default();
+        let config = StartupConfig::default();
+        assert!(!start_hidden(&normal, &config, true, true));
+        assert!(start_hidden(&normal, &config, false, true));
+
+        let always_visible = StartupConfig { always_start_visible: true, ..StartupConfig::default() };
+        assert!(!start_hidden(&normal, &always_visible, false, true));
+        // An explicit --hidden still wins
+        assert!(start_hidden(&LaunchArgs { hidden: true, ..normal.clone() }, &always_visible, true, true));
+
+        // Without a tray there'd be no way back to the window
+        assert!(!start_hidden(&normal, &config, false, false));
+        assert!(!start_hidden(&LaunchArgs { hidden: true, ..normal }, &config, true, false));
+
+        // No saved state yet: show the window
+        assert!(crate::window_manager::last_visible(&std::env::temp_dir().join("test-startup-visibility")));
+    }
+}
@@ -0,0 +1,221 @@
+//! JSON persistence shared by the managers.
+//!
+//! Each manager keeps its state in one file in the app data dir (`proxy.json`,
+//! `pins.json`, ...). `load_or_default` reads it back, falling back to the default
+//! when the file is missing or unreadable; an unreadable file is moved aside to
+//! `<name>.corrupt` (and logged) rather than silently overwritten by the next save.
+//! `save` writes to a temporary file and renames it over the old one, so a crash
+//! mid-write leaves the previous contents intact.
+//!
+//! The app data dir itself is picked once at startup by `resolve_data_dir`: when it
+//! can't be resolved or created (e.g. a locked-down home directory) the data goes
+//! to the temp dir instead, and `get_data_dir` tells the frontend so it can warn
+//! that nothing will survive a reboot. Code that needs the dir later goes through
+//! `data_dir` so it sees the same choice.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use specta::Type;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Directory under the temp dir used when the app data dir is unusable
+const FALLBACK_DIR_NAME: &str = "messenger-desktop";
+
+/// Where this run keeps its data
+#[derive(Serialize, Clone, Debug, Type)]
+pub struct DataDir {
+    pub path: PathBuf,
+    /// Why the app data dir couldn't be used; set when `path` is the temp fallback
+    pub fallback_reason: Option<String>,
+}
+
+/// Picks the data dir: `resolved` if it exists or can be created, otherwise a
+/// directory under `temp_dir`. Errors only when neither is usable.
+pub fn choose_data_dir(resolved: Result<PathBuf, String>, temp_dir: &Path) -> Result<DataDir, String> {
+    let reason = match resolved {
+        Ok(path) => match fs::create_dir_all(&path) {
+            Ok(()) => {
+                return Ok(DataDir {
+                    path,
+                    fallback_reason: None,
+                })
+            }
+            Err(e) => format!("{} could not be created: {}", path.display(), e),
+        },
+        Err(e) => format!("the app data directory could not be resolved: {}", e),
+    };
+
+    let fallback = temp_dir.join(FALLBACK_DIR_NAME);
+    fs::create_dir_all(&fallback).map_err(|e| {
+        format!("{}, and the fallback {} could not be created either: {}", reason, fallback.display(), e)
+    })?;
+    log::warn!("[store] {}; keeping data in {} for this run", reason, fallback.display());
+    Ok(DataDir {
+        path: fallback,
+        fallback_reason: Some(reason),
+    })
+}
+
+/// Resolves the data dir for this run (see `choose_data_dir`)
+pub fn resolve_data_dir(app: &AppHandle) -> Result<DataDir, String> {
+    choose_data_dir(app.path().app_data_dir().map_err(|e| e.to_string()), &std::env::temp_dir())
+}
+
+/// The data dir chosen at startup, or the app data dir if setup hasn't picked one yet
+pub fn data_dir(app: &AppHandle) -> tauri::Result<PathBuf> {
+    match app.try_state::<DataDir>() {
+        Some(dir) => Ok(dir.path.clone()),
+        None => app.path().app_data_dir(),
+    }
+}
+
+/// `path` with `suffix` appended to the file name (`x.json` -> `x.json.tmp`)
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Loads the value stored at `path`. Returns `None` if the file is missing or
+/// can't be parsed; a file that can't be parsed is moved to `<name>.corrupt`.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            log::warn!("[store] failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match serde_json::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            let corrupt = with_suffix(path, ".corrupt");
+            log::warn!(
+                "[store] {} is corrupt ({}), moved to {} and using defaults",
+                path.display(),
+                e,
+                corrupt.display()
+            );
+            let _ = fs::rename(path, corrupt);
+            None
+        }
+    }
+}
+
+/// Loads the value stored at `path`, or the default (see `load`)
+pub fn load_or_default<T: DeserializeOwned + Default>(path: &Path) -> T {
+    load(path).unwrap_or_default()
+}
+
+/// Atomically writes `value` to `path` as pretty JSON, creating the directory if needed
+pub fn save<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = with_suffix(path, ".tmp");
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)
+}
+
+/// Removes the file at `path`, if it exists
+pub fn remove(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Where this run keeps its data, and whether that is the temp-dir fallback
+#[tauri::command]
+#[specta::specta]
+pub fn get_data_dir(dir: tauri::State<'_, DataDir>) -> Result<DataDir, String> {
+    Ok(dir.inner().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        enabled: bool,
+        name: String,
+    }
+
+    #[test]
+    fn test_load_missing_is_default() {
+        let path = std::env::temp_dir().join("test-store-missing").join("config.json");
+        assert_eq!(load_or_default::<Config>(&path), Config::default());
+        assert!(load::<Config>(&path).is_none());
+    }
+
+    #[test]
+    fn test_save_then_load() {
+        let tmp = std::env::temp_dir().join("test-store-round-trip");
+        let path = tmp.join("nested").join("config.json");
+        let config = Config {
+            enabled: true,
+            name: "work".to_string(),
+        };
+        save(&path, &config).unwrap();
+        assert_eq!(load_or_default::<Config>(&path), config);
+
+        remove(&path).unwrap();
+        remove(&path).unwrap();
+        assert!(load::<Config>(&path).is_none());
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_atomic_overwrite_and_corruption() {
+        let tmp = std::env::temp_dir().join("test-store-atomic");
+        let path = tmp.join("config.json");
+        save(&path, &Config { enabled: true, name: "a".to_string() }).unwrap();
+        save(&path, &Config { enabled: false, name: "b".to_string() }).unwrap();
+        assert_eq!(load_or_default::<Config>(&path).name, "b");
+        assert!(!with_suffix(&path, ".tmp").exists());
+
+        // A torn or garbled file is set aside, not silently replaced
+        fs::write(&path, "{\"enabled\": tr").unwrap();
+        assert_eq!(load_or_default::<Config>(&path), Config::default());
+        assert!(!path.exists());
+        assert!(with_suffix(&path, ".corrupt").exists());
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_data_dir_falls_back_to_temp() {
+        let tmp = std::env::temp_dir().join("test-store-data-dir");
+        let _ = fs::remove_dir_all(&tmp);
+        fs::create_dir_all(&tmp).unwrap();
+
+        let dir = choose_data_dir(Ok(tmp.join("app")), &tmp).unwrap();
+        assert_eq!(dir.path, tmp.join("app"));
+        assert!(dir.fallback_reason.is_none());
+
+        // Resolution failed outright
+        let dir = choose_data_dir(Err("no home directory".to_string()), &tmp).unwrap();
+        assert_eq!(dir.path, tmp.join(FALLBACK_DIR_NAME));
+        assert!(dir.fallback_reason.unwrap().contains("no home directory"));
+
+        // Resolved, but can't be created (a file is in the way)
+        fs::write(tmp.join("blocked"), "").unwrap();
+        let dir = choose_data_dir(Ok(tmp.join("blocked").join("app")), &tmp).unwrap();
+        assert_eq!(dir.path, tmp.join(FALLBACK_DIR_NAME));
+        assert!(dir.fallback_reason.is_some());
+
+        // Neither is usable
+        assert!(choose_data_dir(Err("no home directory".to_string()), &tmp.join("blocked")).is_err());
+        let _ = fs::remove_dir_all(&tmp);
+    }
+}
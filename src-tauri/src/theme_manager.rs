@@ -1,5 +1,9 @@
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
+
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::store;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
@@ -11,22 +15,140 @@ pub enum Theme {
     Custom(String),
 }
 
+/// File the current theme is kept in, under the app data dir
+const THEME_FILE: &str = "theme.json";
+
+/// Id of the style element the theme is injected as
+const THEME_STYLE_ID: &str = "__md_theme__";
+
+/// File the accent color is kept in, under the app data dir
+const ACCENT_FILE: &str = "accent.json";
+
+/// Id of the style element the accent override is injected as
+const ACCENT_STYLE_ID: &str = "__md_accent__";
+
+/// Builds the script that puts `css` on the page, replacing the previous theme
+pub fn theme_script(css: &str) -> String {
+    crate::injection::style_script(THEME_STYLE_ID, css)
+}
+
+/// Validates a `#rgb` / `#rrggbb` color, returning it lowercased
+pub fn parse_hex_color(hex: &str) -> Result<String, String> {
+    let hex = hex.trim();
+    let digits = hex
+        .strip_prefix('#')
+        .filter(|d| matches!(d.len(), 3 | 6) && d.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("Invalid color '{}': expected #rgb or #rrggbb", hex))?;
+    Ok(format!("#{}", digits.to_ascii_lowercase()))
+}
+
+/// `r,g,b` of a color validated by `parse_hex_color`, for variables the platforms
+/// use inside `rgb(...)`
+fn rgb_triplet(hex: &str) -> String {
+    let digits = hex.trim_start_matches('#');
+    let channel = |i: usize| -> u8 {
+        if digits.len() == 3 {
+            let d = u8::from_str_radix(&digits[i..i + 1], 16).unwrap_or(0);
+            d * 17
+        } else {
+            u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).unwrap_or(0)
+        }
+    };
+    format!("{},{},{}", channel(0), channel(1), channel(2))
+}
+
+/// Where a platform's accent color lives
+struct AccentTargets {
+    /// CSS variables holding the accent as a color
+    vars: &'static [&'static str],
+    /// CSS variables holding it as an `r,g,b` triplet
+    rgb_vars: &'static [&'static str],
+    /// Links and icons drawn in the accent
+    color: &'static str,
+    /// Buttons and indicators filled with it
+    background: &'static str,
+}
+
+/// The accent targets of `platform`. Without an active platform, Messenger's are used.
+fn accent_targets(platform: Option<&Platform>) -> AccentTargets {
+    match platform {
+        Some(Platform::Instagram) => AccentTargets {
+            vars: &[],
+            rgb_vars: &["--ig-primary-button", "--ig-link", "--ig-focus-stroke"],
+            color: "a[href^=\"/explore/tags\"], [aria-label=\"Send\"] svg",
+            background: "[role=\"button\"][style*=\"primary-button\"]",
+        },
+        Some(Platform::X) => AccentTargets {
+            vars: &[],
+            rgb_vars: &[],
+            color: "a[href][role=\"link\"] span[style*=\"color: rgb(29, 155, 240)\"], [data-testid=\"dmComposerSendButton\"] svg",
+            background: "[role=\"tab\"][aria-selected=\"true\"] > div > div > div, [data-testid=\"messageEntry\"] [style*=\"background-color: rgb(29, 155, 240)\"]",
+        },
+        Some(Platform::Messenger) | Some(Platform::Facebook) | None => AccentTargets {
+            vars: &["--accent", "--primary-button-background", "--blue-link", "--primary-deemphasized-button-text"],
+            rgb_vars: &[],
+            color: "[aria-label=\"Send\"] svg, [aria-label=\"Press enter to send\"] svg, [aria-label=\"Like\"] svg",
+            background: "[aria-label=\"Send\"][role=\"button\"][style*=\"background\"]",
+        },
+    }
+}
+
+/// CSS recoloring `platform`'s accent with `hex` (validated by `parse_hex_color`)
+pub fn accent_css(hex: &str, platform: Option<&Platform>) -> String {
+    let targets = accent_targets(platform);
+    let rgb = rgb_triplet(hex);
+    let vars: String = targets
+        .vars
+        .iter()
+        .map(|v| format!("{}:{}!important;", v, hex))
+        .chain(targets.rgb_vars.iter().map(|v| format!("{}:{}!important;", v, rgb)))
+        .collect();
+
+    let mut css = String::new();
+    if !vars.is_empty() {
+        css.push_str(&format!(":root,.__fb-light-mode,.__fb-dark-mode{{{}}}", vars));
+    }
+    css.push_str(&format!("{}{{color:{hex}!important;fill:{hex}!important;}}", targets.color, hex = hex));
+    css.push_str(&format!("{}{{background-color:{}!important;}}", targets.background, hex));
+    css
+}
+
+/// Builds the script that puts the accent override on the page (or removes it)
+pub fn accent_script(css: &str) -> String {
+    crate::injection::style_script(ACCENT_STYLE_ID, css)
+}
+
 #[derive(Serialize, Clone)]
 struct ThemePayload {
     name: String,
     css: String,
 }
 
+/// Payload emitted with `accent-color-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct AccentColorPayload {
+    pub color: Option<String>,
+}
+
 pub struct ThemeManager {
     current: Theme,
+    /// Accent color (`#rrggbb` / `#rgb`) laid over the theme, if any
+    accent: Option<String>,
     app: AppHandle,
+    store_path: PathBuf,
+    accent_path: PathBuf,
 }
 
 impl ThemeManager {
-    pub fn new(app: &AppHandle) -> Self {
+    pub fn new(app: &AppHandle, app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join(THEME_FILE);
+        let accent_path = app_data_dir.join(ACCENT_FILE);
         Self {
-            current: Theme::Light,
+            current: store::load(&store_path).unwrap_or(Theme::Light),
+            accent: store::load::<String>(&accent_path).and_then(|hex| parse_hex_color(&hex).ok()),
             app: app.clone(),
+            store_path,
+            accent_path,
         }
     }
 
@@ -44,25 +166,94 @@ impl ThemeManager {
         };
 
         self.current = theme.clone();
+        store::save(&self.store_path, &self.current)?;
         let css = Self::get_css(&theme);
+        self.emit(name, css)
+    }
+
+    pub fn set_custom_css(&mut self, css: String) -> tauri::Result<()> {
+        self.current = Theme::Custom(css.clone());
+        store::save(&self.store_path, &self.current)?;
+        self.emit("custom", css)
+    }
 
+    /// Injects the theme into the page and emits it to the frontend. In safe mode
+    /// the theme is still recorded but no CSS is applied.
+    fn emit(&self, name: &str, css: String) -> tauri::Result<()> {
+        let css = if crate::safe_mode::is_active(&self.app) { String::new() } else { css };
+        // Re-injected on every navigation through the injection registry
+        if let Err(e) = crate::injection::apply(&self.app, crate::injection::THEME, &theme_script(&css)) {
+            log::warn!("[theme] failed to inject theme: {}", e);
+        }
         self.app.emit("set-theme", ThemePayload {
             name: name.to_string(),
             css,
-        })?;
+        })
+    }
 
-        Ok(())
+    /// Emits the current theme again (e.g. after leaving safe mode)
+    pub fn reapply(&self) -> tauri::Result<()> {
+        self.apply_accent();
+        self.emit(self.current_theme_name(), self.current_css())
     }
 
-    pub fn set_custom_css(&mut self, css: String) -> tauri::Result<()> {
-        self.current = Theme::Custom(css.clone());
-        self.app.emit("set-theme", ThemePayload {
-            name: "custom".to_string(),
-            css,
-        })?;
+    /// Recolors the platform's accent on top of the theme, and persists it
+    pub fn set_accent_color(&mut self, hex: &str) -> Result<String, String> {
+        let hex = parse_hex_color(hex)?;
+        store::save(&self.accent_path, &hex).map_err(|e| format!("Failed to save accent color: {}", e))?;
+        self.accent = Some(hex.clone());
+        self.apply_accent();
+        Ok(hex)
+    }
+
+    /// Goes back to the platform's own accent
+    pub fn clear_accent_color(&mut self) -> Result<(), String> {
+        store::remove(&self.accent_path).map_err(|e| format!("Failed to clear accent color: {}", e))?;
+        self.accent = None;
+        self.apply_accent();
         Ok(())
     }
 
+    /// The accent color, if one is set
+    pub fn accent_color(&self) -> Option<&str> {
+        self.accent.as_deref()
+    }
+
+    /// The accent override for the active platform; empty without an accent or in
+    /// safe mode
+    pub fn current_accent_css(&self) -> String {
+        match &self.accent {
+            Some(hex) if !crate::safe_mode::is_active(&self.app) => {
+                let platform = self
+                    .app
+                    .try_state::<PlatformManager>()
+                    .and_then(|m| m.get_current().ok().flatten());
+                accent_css(hex, platform.as_ref())
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Injects the accent override (re-injected on every navigation through the
+    /// injection registry) and tells the frontend
+    fn apply_accent(&self) {
+        let script = accent_script(&self.current_accent_css());
+        if let Err(e) = crate::injection::apply(&self.app, crate::injection::ACCENT, &script) {
+            log::warn!("[theme] failed to inject accent color: {}", e);
+        }
+        let _ = self.app.emit("accent-color-changed", AccentColorPayload { color: self.accent.clone() });
+    }
+
+    /// CSS of the current theme
+    pub fn current_css(&self) -> String {
+        Self::get_css(&self.current)
+    }
+
+    /// Restores the default (light) theme, dropping any custom CSS
+    pub fn reset(&mut self) -> tauri::Result<()> {
+        self.set_theme("light")
+    }
+
     pub fn get_css(theme: &Theme) -> String {
         match theme {
             Theme::Light => String::new(),
@@ -137,43 +328,95 @@ impl ThemeManager {
     pub fn current_theme(&self) -> &Theme {
         &self.current
     }
+
+    pub fn current_theme_name(&self) -> &'static str {
+        match &self.current {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::Darker => "darker",
+            Theme::OledBlack => "oled-black",
+            Theme::Custom(_) => "custom",
+        }
+    }
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn set_theme(
     state: tauri::State<std::sync::Mutex<ThemeManager>>,
     name: String,
-) -> tauri::Result<()> {
-    let mut manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    manager.set_theme(&name)
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.set_theme(&name).map_err(|e| e.to_string())
 }
 
+/// Resets the theme to the default (re-emits `set-theme`).
+/// The default is saved over `theme.json`.
 #[tauri::command]
+#[specta::specta]
+pub fn reset_theme(
+    state: tauri::State<std::sync::Mutex<ThemeManager>>,
+) -> Result<String, String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.reset().map_err(|e| e.to_string())?;
+    Ok(manager.current_theme_name().to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
 pub fn get_themes() -> Vec<String> {
     ThemeManager::get_themes()
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn set_custom_css(
     state: tauri::State<std::sync::Mutex<ThemeManager>>,
     css: String,
-) -> tauri::Result<()> {
-    let mut manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    manager.set_custom_css(css)
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.set_custom_css(css).map_err(|e| e.to_string())
 }
 
+/// Recolors the platform's accent (buttons, links) with `hex` (`#rgb` or `#rrggbb`)
+/// on top of the current theme. Returns the normalized color.
 #[tauri::command]
+#[specta::specta]
+pub fn set_accent_color(
+    state: tauri::State<std::sync::Mutex<ThemeManager>>,
+    hex: String,
+) -> Result<String, String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.set_accent_color(&hex)
+}
+
+/// Removes the accent color override
+#[tauri::command]
+#[specta::specta]
+pub fn clear_accent_color(
+    state: tauri::State<std::sync::Mutex<ThemeManager>>,
+) -> Result<(), String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.clear_accent_color()
+}
+
+/// The accent color override, if one is set
+#[tauri::command]
+#[specta::specta]
+pub fn get_accent_color(
+    state: tauri::State<std::sync::Mutex<ThemeManager>>,
+) -> Result<Option<String>, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.accent_color().map(str::to_string))
+}
+
+#[tauri::command]
+#[specta::specta]
 pub fn current_theme_name(
     state: tauri::State<std::sync::Mutex<ThemeManager>>,
-) -> tauri::Result<String> {
-    let manager = state.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    Ok(match manager.current_theme() {
-        Theme::Light => "light",
-        Theme::Dark => "dark",
-        Theme::Darker => "darker",
-        Theme::OledBlack => "oled-black",
-        Theme::Custom(_) => "custom",
-    }.to_string())
+) -> Result<String, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.current_theme_name().to_string())
 }
 
 // Unit tests
@@ -202,4 +445,28 @@ mod tests {
         let dark_css = ThemeManager::get_css(&Theme::Dark);
         assert!(dark_css.contains("background"));
     }
+
+    #[test]
+    fn test_accent_color_validation() {
+        assert_eq!(parse_hex_color("#1a2b3c").unwrap(), "#1a2b3c");
+        assert_eq!(parse_hex_color("#ABC").unwrap(), "#abc");
+        assert!(parse_hex_color("#xyz").is_err());
+        assert!(parse_hex_color("1a2b3c").is_err());
+        assert!(parse_hex_color("#1a2b3").is_err());
+        assert!(parse_hex_color("red").is_err());
+
+        assert_eq!(rgb_triplet("#abc"), "170,187,204");
+        assert_eq!(rgb_triplet("#1a2b3c"), "26,43,60");
+    }
+
+    #[test]
+    fn test_accent_css_per_platform() {
+        for platform in Platform::all() {
+            let css = accent_css("#ff0066", Some(&platform));
+            assert!(!css.is_empty());
+            assert!(css.contains("#ff0066!important"));
+        }
+        assert!(accent_css("#ff0066", Some(&Platform::Instagram)).contains("--ig-primary-button:255,0,102"));
+        assert!(accent_css("#ff0066", None).contains("--primary-button-background"));
+    }
 }
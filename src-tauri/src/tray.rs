@@ -1,11 +1,188 @@
 use tauri::{AppHandle, Manager, Emitter};
+use tauri::image::Image;
 use tauri::tray::{TrayIconBuilder, TrayIconEvent, TrayIconId};
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::store;
 
 const TRAY_ID: &str = "messenger-tray";
 
+/// Edge length, in pixels, tray icons are scaled to
+const TRAY_ICON_SIZE: u32 = 32;
+
+/// Colour of the unread badge
+const BADGE_COLOR: Rgba<u8> = Rgba([0xE4, 0x1E, 0x3F, 0xFF]);
+
+/// Minimum time between two tray badge updates
+const BADGE_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What to do with an incoming unread count
+#[derive(Debug, Clone, PartialEq)]
+enum BadgeAction {
+    /// Update the tray right away
+    ApplyNow,
+    /// Schedule a single flush of the latest count after the delay
+    Schedule(Duration),
+    /// A flush is already pending and will pick up this count
+    Coalesced,
+}
+
+/// Coalesces rapid unread-count updates so the tray updates at most once per interval.
+/// The most recent count is always the one applied.
+#[derive(Debug)]
+struct BadgeCoalescer {
+    interval: Duration,
+    latest: u32,
+    last_applied: Option<Instant>,
+    flush_scheduled: bool,
+}
+
+impl BadgeCoalescer {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            latest: 0,
+            last_applied: None,
+            flush_scheduled: false,
+        }
+    }
+
+    /// Records `count` as the latest value and decides whether to apply it now
+    fn offer(&mut self, count: u32, now: Instant) -> BadgeAction {
+        self.latest = count;
+        if self.flush_scheduled {
+            return BadgeAction::Coalesced;
+        }
+        match self.last_applied {
+            Some(last) if now.saturating_duration_since(last) < self.interval => {
+                self.flush_scheduled = true;
+                BadgeAction::Schedule(self.interval - now.saturating_duration_since(last))
+            }
+            _ => {
+                self.last_applied = Some(now);
+                BadgeAction::ApplyNow
+            }
+        }
+    }
+
+    /// Completes a scheduled flush, returning the count to apply
+    fn flush(&mut self, now: Instant) -> u32 {
+        self.flush_scheduled = false;
+        self.last_applied = Some(now);
+        self.latest
+    }
+}
+
+/// Persisted tray icon choice
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct TrayIconConfig {
+    /// Custom base icon; wins over the per-platform variant
+    pub custom_path: Option<String>,
+    /// Use the active platform's variant of the app icon
+    pub per_platform: bool,
+}
+
+/// Brand colour the per-platform variant is tinted with
+fn platform_color(platform: &Platform) -> [u8; 3] {
+    match platform {
+        Platform::Instagram => [0xC1, 0x35, 0x84],
+        Platform::Messenger => [0x00, 0x84, 0xFF],
+        Platform::Facebook => [0x18, 0x77, 0xF2],
+        Platform::X => [0x0F, 0x14, 0x19],
+    }
+}
+
+/// Loads an icon file and scales it to the tray size
+pub fn load_icon(path: &Path) -> Result<RgbaImage, String> {
+    let img = image::open(path).map_err(|e| format!("Failed to load tray icon {}: {}", path.display(), e))?;
+    Ok(fit(img.into_rgba8()))
+}
+
+/// Scales an icon to the tray size
+fn fit(img: RgbaImage) -> RgbaImage {
+    if img.dimensions() == (TRAY_ICON_SIZE, TRAY_ICON_SIZE) {
+        return img;
+    }
+    image::imageops::resize(&img, TRAY_ICON_SIZE, TRAY_ICON_SIZE, FilterType::Lanczos3)
+}
+
+/// Recolours an icon, keeping its shape (alpha) and shading (luminance)
+fn tint(img: &RgbaImage, color: [u8; 3]) -> RgbaImage {
+    let mut out = img.clone();
+    for pixel in out.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        let luma = (u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000;
+        // Mix the colour with the original luminance so details stay visible
+        let mix = |c: u8| ((u32::from(c) * 3 + luma) / 4) as u8;
+        *pixel = Rgba([mix(color[0]), mix(color[1]), mix(color[2]), a]);
+    }
+    out
+}
+
+/// Draws the unread badge (a dot in the top-right corner) over `img`
+fn overlay_badge(img: &RgbaImage) -> RgbaImage {
+    let mut out = img.clone();
+    let (w, h) = out.dimensions();
+    let radius = (w.min(h) as f32) * 0.22;
+    let (cx, cy) = (w as f32 - radius - 1.0, radius + 1.0);
+    for (x, y, pixel) in out.enumerate_pixels_mut() {
+        let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+        if dx * dx + dy * dy <= radius * radius {
+            *pixel = BADGE_COLOR;
+        }
+    }
+    out
+}
+
+/// Everything the tray icon is drawn from
+struct TrayIconState {
+    config: TrayIconConfig,
+    store_path: PathBuf,
+    /// The app icon at tray size
+    default: Option<RgbaImage>,
+    platform: Option<Platform>,
+    unread: u32,
+}
+
+impl TrayIconState {
+    /// The icon before the badge: custom, then per-platform, then the app icon
+    fn base(&self) -> Option<RgbaImage> {
+        if let Some(path) = &self.config.custom_path {
+            match load_icon(Path::new(path)) {
+                Ok(img) => return Some(img),
+                Err(e) => log::warn!("[tray] {}", e),
+            }
+        }
+        let default = self.default.as_ref()?;
+        match (&self.platform, self.config.per_platform) {
+            (Some(platform), true) => Some(tint(default, platform_color(platform))),
+            _ => Some(default.clone()),
+        }
+    }
+
+    /// The icon to show, badged when anything is unread
+    fn render(&self) -> Option<RgbaImage> {
+        let base = self.base()?;
+        Some(if self.unread > 0 { overlay_badge(&base) } else { base })
+    }
+
+    fn persist(&self) -> Result<(), String> {
+        store::save(&self.store_path, &self.config).map_err(|e| format!("Failed to save tray icon: {}", e))
+    }
+}
+
 pub struct TrayManager {
     app: AppHandle,
+    badge: Arc<Mutex<BadgeCoalescer>>,
+    icon: Arc<Mutex<TrayIconState>>,
 }
 
 impl TrayManager {
@@ -33,9 +210,86 @@ impl TrayManager {
 
         let _tray = builder.build(app)?;
 
-        Ok(Self {
+        let store_path = store::data_dir(app)?.join("tray_icon.json");
+        let config = store::load_or_default(&store_path);
+        let default = app
+            .default_window_icon()
+            .and_then(|icon| RgbaImage::from_raw(icon.width(), icon.height(), icon.rgba().to_vec()))
+            .map(fit);
+        let platform = app
+            .try_state::<PlatformManager>()
+            .and_then(|m| m.get_current().ok().flatten());
+
+        let manager = Self {
             app: app.clone(),
-        })
+            badge: Arc::new(Mutex::new(BadgeCoalescer::new(BADGE_UPDATE_INTERVAL))),
+            icon: Arc::new(Mutex::new(TrayIconState {
+                config,
+                store_path,
+                default,
+                platform,
+                unread: 0,
+            })),
+        };
+        Self::redraw(&manager.app, &manager.icon);
+        Ok(manager)
+    }
+
+    /// Redraws the tray icon from its current state
+    fn redraw(app: &AppHandle, icon: &Mutex<TrayIconState>) {
+        let Some(img) = icon.lock().ok().and_then(|state| state.render()) else {
+            return;
+        };
+        let (w, h) = img.dimensions();
+        if let Some(tray) = app.tray_by_id(&TrayIconId::new(TRAY_ID)) {
+            if let Err(e) = tray.set_icon(Some(Image::new_owned(img.into_raw(), w, h))) {
+                log::warn!("[tray] failed to set icon: {}", e);
+            }
+        }
+    }
+
+    /// Sets (or, with `None`, clears) a custom base icon. The file must load as an image.
+    pub fn set_custom_icon(&self, path: Option<String>) -> Result<(), String> {
+        if let Some(path) = &path {
+            load_icon(Path::new(path))?;
+        }
+        {
+            let mut state = self.icon.lock().map_err(|e| e.to_string())?;
+            state.config.custom_path = path;
+            state.persist()?;
+        }
+        Self::redraw(&self.app, &self.icon);
+        Ok(())
+    }
+
+    /// Turns the per-platform icon variant on or off
+    pub fn set_per_platform(&self, enabled: bool) -> Result<(), String> {
+        {
+            let mut state = self.icon.lock().map_err(|e| e.to_string())?;
+            state.config.per_platform = enabled;
+            state.persist()?;
+        }
+        Self::redraw(&self.app, &self.icon);
+        Ok(())
+    }
+
+    /// Gets the tray icon choice
+    pub fn icon_config(&self) -> Result<TrayIconConfig, String> {
+        Ok(self.icon.lock().map_err(|e| e.to_string())?.config.clone())
+    }
+
+    /// Follows the active platform (for the per-platform variant)
+    pub fn set_platform(&self, platform: Option<Platform>) {
+        let per_platform = match self.icon.lock() {
+            Ok(mut state) => {
+                state.platform = platform;
+                state.config.per_platform
+            }
+            Err(_) => return,
+        };
+        if per_platform {
+            Self::redraw(&self.app, &self.icon);
+        }
     }
 
     fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
@@ -63,19 +317,61 @@ impl TrayManager {
         )
     }
 
-    pub fn update_unread_count(&self, count: u32) {
+    /// Updates the unread count, coalescing bursts so the tray and the
+    /// `tray-badge-update` event fire at most once per `BADGE_UPDATE_INTERVAL`.
+    pub fn update_unread_count(&self, count: u32) -> Result<(), String> {
+        let action = self
+            .badge
+            .lock()
+            .map_err(|e| e.to_string())?
+            .offer(count, Instant::now());
+
+        match action {
+            BadgeAction::ApplyNow => Self::apply_unread_count(&self.app, &self.icon, count),
+            BadgeAction::Schedule(delay) => {
+                let app = self.app.clone();
+                let badge = self.badge.clone();
+                let icon = self.icon.clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    match badge.lock() {
+                        Ok(mut badge) => {
+                            let latest = badge.flush(Instant::now());
+                            drop(badge);
+                            Self::apply_unread_count(&app, &icon, latest);
+                        }
+                        Err(e) => log::warn!("[tray] failed to flush unread count: {}", e),
+                    }
+                });
+            }
+            BadgeAction::Coalesced => {}
+        }
+        Ok(())
+    }
+
+    fn apply_unread_count(app: &AppHandle, icon: &Mutex<TrayIconState>, count: u32) {
         let tooltip = if count > 0 {
             format!("Messenger ({})", count)
         } else {
             "Messenger".to_string()
         };
 
-        if let Some(tray) = self.app.tray_by_id(&TrayIconId::new(TRAY_ID)) {
+        if let Some(tray) = app.tray_by_id(&TrayIconId::new(TRAY_ID)) {
             let _ = tray.set_tooltip(Some(&tooltip));
         }
 
+        // Badge the icon only when the dot appears or disappears
+        let changed = icon.lock().is_ok_and(|mut state| {
+            let changed = (state.unread > 0) != (count > 0);
+            state.unread = count;
+            changed
+        });
+        if changed {
+            Self::redraw(app, icon);
+        }
+
         // Emit event for frontend to react
-        let _ = self.app.emit("tray-badge-update", count);
+        let _ = app.emit("tray-badge-update", count);
     }
 
     pub fn handle_event(app: &AppHandle, event: &TrayIconEvent) {
@@ -141,6 +437,17 @@ impl TrayManager {
                 }
             }
             "quit" => {
+                // Quitting from the tray skips CloseRequested; record whether the
+                // window was hidden so the next launch matches
+                if let Some(window) = app.get_webview_window("main") {
+                    let window_manager = app.state::<crate::window_manager::WindowManager>();
+                    let captured = tauri::async_runtime::block_on(
+                        crate::window_manager::capture_window(&window, &window_manager),
+                    );
+                    if let Err(e) = captured {
+                        log::warn!("[tray] failed to save window state: {}", e);
+                    }
+                }
                 app.exit(0);
             }
             _ => {}
@@ -154,31 +461,98 @@ impl TrayManager {
     }
 }
 
+/// Error of the tray commands when the tray failed to initialize at startup
+pub const TRAY_UNAVAILABLE: &str = "Tray unavailable: it failed to initialize at startup";
+
+/// Runs `f` on the tray manager, or fails with `TRAY_UNAVAILABLE` without one
+fn with_tray<M, T>(tray: Option<&Mutex<M>>, f: impl FnOnce(&M) -> Result<T, String>) -> Result<T, String> {
+    let tray = tray.ok_or_else(|| TRAY_UNAVAILABLE.to_string())?;
+    let manager = tray.lock().map_err(|e| e.to_string())?;
+    f(&manager)
+}
+
+/// Like `with_tray`, for updates only the tray would show: without a tray they
+/// have nowhere to go, so they are dropped instead of failing
+fn with_tray_or_skip<M>(tray: Option<&Mutex<M>>, f: impl FnOnce(&M) -> Result<(), String>) -> Result<(), String> {
+    match tray {
+        Some(_) => with_tray(tray, f),
+        None => Ok(()),
+    }
+}
+
+/// Shows `count` on the tray icon, if there is a tray
+pub fn set_unread(app: &AppHandle, count: u32) -> Result<(), String> {
+    with_tray_or_skip(app.try_state::<Mutex<TrayManager>>().as_deref(), |m| m.update_unread_count(count))
+}
+
 // Tauri commands for frontend invocation
 #[tauri::command]
-pub fn update_unread_count(
-    state: tauri::State<'_, std::sync::Mutex<TrayManager>>,
-    count: u32,
-) -> Result<(), String> {
-    let manager = state.lock().map_err(|e| e.to_string())?;
-    manager.update_unread_count(count);
-    Ok(())
+#[specta::specta]
+pub fn update_unread_count(app: AppHandle, count: u32) -> Result<(), String> {
+    // With the badge turned off the count is dropped, not shown
+    let count = match crate::unread::badge_mode(&app) {
+        crate::unread::BadgeMode::None => 0,
+        _ => count,
+    };
+    set_unread(&app, count)
 }
 
 #[tauri::command]
-pub fn set_tray_tooltip(
-    state: tauri::State<'_, std::sync::Mutex<TrayManager>>,
-    text: String,
-) -> Result<(), String> {
-    let manager = state.lock().map_err(|e| e.to_string())?;
-    manager.set_tooltip(&text);
-    Ok(())
+#[specta::specta]
+pub fn set_tray_tooltip(app: AppHandle, text: String) -> Result<(), String> {
+    with_tray_or_skip(app.try_state::<Mutex<TrayManager>>().as_deref(), |m| {
+        m.set_tooltip(&text);
+        Ok(())
+    })
+}
+
+/// Whether the tray came up, so the UI can hide controls that need it
+#[tauri::command]
+#[specta::specta]
+pub fn is_tray_available(app: AppHandle) -> bool {
+    app.try_state::<Mutex<TrayManager>>().is_some()
+}
+
+/// Follows a platform switch with the per-platform tray icon, if enabled
+pub fn on_platform_changed(app: &AppHandle, platform: &Platform) {
+    if let Some(state) = app.try_state::<Mutex<TrayManager>>() {
+        if let Ok(manager) = state.lock() {
+            manager.set_platform(Some(platform.clone()));
+        }
+    }
+}
+
+/// Sets a custom tray icon from an image file; `None` goes back to the app icon
+#[tauri::command]
+#[specta::specta]
+pub fn set_tray_icon(app: AppHandle, path: Option<String>) -> Result<(), String> {
+    with_tray(app.try_state::<Mutex<TrayManager>>().as_deref(), |m| {
+        m.set_custom_icon(path.filter(|p| !p.is_empty()))
+    })
+}
+
+/// Turns the per-platform tray icon on or off
+#[tauri::command]
+#[specta::specta]
+pub fn set_tray_icon_per_platform(app: AppHandle, enabled: bool) -> Result<(), String> {
+    with_tray(app.try_state::<Mutex<TrayManager>>().as_deref(), |m| m.set_per_platform(enabled))
 }
 
+/// Gets the tray icon choice
 #[tauri::command]
+#[specta::specta]
+pub fn get_tray_icon(app: AppHandle) -> Result<TrayIconConfig, String> {
+    with_tray(app.try_state::<Mutex<TrayManager>>().as_deref(), |m| m.icon_config())
+}
+
+#[tauri::command]
+#[specta::specta]
 pub fn init_tray(app: AppHandle) -> Result<(), String> {
     let manager = TrayManager::new(&app).map_err(|e| e.to_string())?;
     app.manage(std::sync::Mutex::new(manager));
+    if let Some(features) = app.try_state::<crate::diagnostics::Features>() {
+        features.ok("tray");
+    }
     Ok(())
 }
 
@@ -186,6 +560,7 @@ pub fn init_tray(app: AppHandle) -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_tray_manager_build_menu() {
@@ -201,6 +576,82 @@ mod tests {
         assert!(true);
     }
 
+    #[test]
+    fn test_rapid_unread_updates_are_coalesced() {
+        let mut badge = BadgeCoalescer::new(BADGE_UPDATE_INTERVAL);
+        let start = Instant::now();
+        let mut updates = 0;
+        for i in 1..=100u32 {
+            let now = start + Duration::from_millis(u64::from(i));
+            match badge.offer(i, now) {
+                BadgeAction::ApplyNow | BadgeAction::Schedule(_) => updates += 1,
+                BadgeAction::Coalesced => {}
+            }
+        }
+        // One immediate update plus one scheduled flush
+        assert_eq!(updates, 2);
+        // The flush applies the latest count, not a stale one
+        assert_eq!(badge.flush(start + BADGE_UPDATE_INTERVAL), 100);
+    }
+
+    #[test]
+    fn test_unread_update_after_interval_applies_immediately() {
+        let mut badge = BadgeCoalescer::new(BADGE_UPDATE_INTERVAL);
+        let start = Instant::now();
+        assert_eq!(badge.offer(1, start), BadgeAction::ApplyNow);
+        assert_eq!(
+            badge.offer(2, start + Duration::from_millis(100)),
+            BadgeAction::Schedule(Duration::from_millis(150))
+        );
+        assert_eq!(badge.flush(start + BADGE_UPDATE_INTERVAL), 2);
+        let later = start + BADGE_UPDATE_INTERVAL * 2;
+        assert_eq!(badge.offer(3, later), BadgeAction::ApplyNow);
+    }
+
+    #[test]
+    fn test_custom_icon_is_validated_and_scaled() {
+        let tmp = std::env::temp_dir().join("test-tray-icon");
+        fs::create_dir_all(&tmp).unwrap();
+        let path = tmp.join("icon.png");
+        RgbaImage::from_pixel(256, 256, Rgba([0, 200, 0, 255])).save(&path).unwrap();
+        assert_eq!(load_icon(&path).unwrap().dimensions(), (TRAY_ICON_SIZE, TRAY_ICON_SIZE));
+
+        let bogus = tmp.join("icon.txt");
+        fs::write(&bogus, "not an image").unwrap();
+        assert!(load_icon(&bogus).is_err());
+        assert!(load_icon(&tmp.join("missing.png")).is_err());
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_badge_overlays_any_base_icon() {
+        let base = RgbaImage::from_pixel(TRAY_ICON_SIZE, TRAY_ICON_SIZE, Rgba([0, 200, 0, 255]));
+        let badged = overlay_badge(&base);
+        assert_eq!(*badged.get_pixel(TRAY_ICON_SIZE - 6, 6), BADGE_COLOR);
+        assert_eq!(*badged.get_pixel(4, TRAY_ICON_SIZE - 4), Rgba([0, 200, 0, 255]));
+
+        // A platform variant keeps the icon's shape and still gets the badge
+        let transparent = Rgba([255, 255, 255, 0]);
+        let mut shaped = base.clone();
+        shaped.put_pixel(0, TRAY_ICON_SIZE - 1, transparent);
+        let tinted = tint(&shaped, platform_color(&Platform::Instagram));
+        assert_eq!(tinted.get_pixel(0, TRAY_ICON_SIZE - 1)[3], 0);
+        assert_ne!(*tinted.get_pixel(4, 20), Rgba([0, 200, 0, 255]));
+        assert_eq!(*overlay_badge(&tinted).get_pixel(TRAY_ICON_SIZE - 6, 6), BADGE_COLOR);
+    }
+
+    #[test]
+    fn test_commands_without_tray() {
+        let missing: Option<&Mutex<u32>> = None;
+        assert_eq!(with_tray(missing, |n| Ok(*n)), Err(TRAY_UNAVAILABLE.to_string()));
+        // Display-only updates are dropped, not failed, and never run
+        assert_eq!(with_tray_or_skip(missing, |_| Err("ran".to_string())), Ok(()));
+
+        let tray = Mutex::new(3u32);
+        assert_eq!(with_tray(Some(&tray), |n| Ok(*n)), Ok(3));
+        assert_eq!(with_tray_or_skip(Some(&tray), |_| Err("ran".to_string())), Err("ran".to_string()));
+    }
+
     #[test]
     fn test_tray_manager_set_tooltip() {
         // Just verify the function exists and compiles
@@ -0,0 +1,721 @@
+//! Native unread-count extraction.
+//!
+//! An injected script polls the active platform's page and reports what it finds to
+//! `report_unread_count`, which updates the tray directly. This keeps the badge
+//! working even if the frontend never calls `update_unread_count` itself. The
+//! platform's unread badge is read from the DOM when one is known, with the `(N)`
+//! prefix in `document.title` as the fallback.
+//!
+//! Counts are kept per account (the page always shows the active account); the
+//! tray shows their sum and `get_unread_by_account` the breakdown.
+//!
+//! What is counted follows the persisted badge mode (`set_badge_mode`): unread
+//! conversations (the platforms' own badges and title count), unread messages
+//! (summed from the unread rows of the conversation list), or nothing, which
+//! hides the badge but leaves notifications alone.
+//!
+//! A hidden webview may throttle the page's own timer, so a background task also
+//! asks the page for the count on a fixed interval (`set_background_unread_poll`)
+//! whether or not the window is visible. It stays idle while no platform is
+//! selected or the badge is off.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::platform_manager::{Platform, PlatformManager};
+use crate::store;
+
+/// How often the injected script reports the unread count
+const POLL_INTERVAL_MS: u32 = 5_000;
+
+/// Allowed background poll interval range, in seconds
+const MIN_BACKGROUND_POLL_SECS: u32 = 5;
+const MAX_BACKGROUND_POLL_SECS: u32 = 600;
+
+/// Runs the injected poller's report once; evaluated by the background poll
+const BACKGROUND_POLL_JS: &str = "window.__MD_UNREAD_REPORT__ && window.__MD_UNREAD_REPORT__();";
+
+/// How long extraction may come back empty before warning
+const STALE_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// What the unread badge counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum BadgeMode {
+    /// Conversations with unread messages
+    #[default]
+    Conversations,
+    /// Unread messages across all conversations
+    Messages,
+    /// No badge (notifications are unaffected)
+    None,
+}
+
+/// Persisted badge configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct BadgeConfig {
+    pub mode: BadgeMode,
+    /// Poll the page from the app so the badge stays current while hidden
+    #[serde(default = "default_background_poll")]
+    pub background_poll: bool,
+    /// Seconds between background polls
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u32,
+}
+
+fn default_background_poll() -> bool {
+    true
+}
+
+fn default_poll_interval_secs() -> u32 {
+    30
+}
+
+impl Default for BadgeConfig {
+    fn default() -> Self {
+        Self {
+            mode: BadgeMode::default(),
+            background_poll: default_background_poll(),
+            poll_interval_secs: default_poll_interval_secs(),
+        }
+    }
+}
+
+/// Payload emitted with `badge-mode-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct BadgeModeChangedPayload {
+    pub mode: BadgeMode,
+}
+
+/// Manages the badge configuration and its persistence
+pub struct BadgeManager {
+    config: Mutex<BadgeConfig>,
+    store_path: PathBuf,
+}
+
+impl BadgeManager {
+    /// Creates a new BadgeManager, loading any saved configuration
+    pub fn new(app_data_dir: &Path) -> Self {
+        let store_path = app_data_dir.join("badge.json");
+        let config = store::load_or_default(&store_path);
+        Self {
+            config: Mutex::new(config),
+            store_path,
+        }
+    }
+
+    /// Gets the current configuration
+    pub fn get_config(&self) -> Result<BadgeConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    /// Updates and persists the configuration
+    pub fn set(&self, config: BadgeConfig) -> Result<(), String> {
+        *self.config.lock().map_err(|e| e.to_string())? = config.clone();
+        store::save(&self.store_path, &config)
+            .map_err(|e| format!("Failed to save badge config: {}", e))
+    }
+
+    /// Updates and persists the background poll settings
+    pub fn set_background_poll(&self, enabled: bool, interval_secs: u32) -> Result<BadgeConfig, String> {
+        if !(MIN_BACKGROUND_POLL_SECS..=MAX_BACKGROUND_POLL_SECS).contains(&interval_secs) {
+            return Err(format!(
+                "Background poll interval must be between {} and {} seconds",
+                MIN_BACKGROUND_POLL_SECS, MAX_BACKGROUND_POLL_SECS
+            ));
+        }
+        let config = BadgeConfig {
+            background_poll: enabled,
+            poll_interval_secs: interval_secs,
+            ..self.get_config()?
+        };
+        self.set(config.clone())?;
+        Ok(config)
+    }
+}
+
+/// The badge mode in effect; conversations until the manager is set up
+pub fn badge_mode(app: &AppHandle) -> BadgeMode {
+    app.try_state::<BadgeManager>()
+        .and_then(|m| m.get_config().ok())
+        .map(|c| c.mode)
+        .unwrap_or_default()
+}
+
+/// How long the background poll sleeps between checks
+pub fn background_poll_interval(config: &BadgeConfig) -> Duration {
+    Duration::from_secs(u64::from(
+        config
+            .poll_interval_secs
+            .clamp(MIN_BACKGROUND_POLL_SECS, MAX_BACKGROUND_POLL_SECS),
+    ))
+}
+
+/// Decides whether the background poll should ask the page for its count now
+pub fn should_background_poll(config: &BadgeConfig, platform_selected: bool) -> bool {
+    config.background_poll && config.mode != BadgeMode::None && platform_selected
+}
+
+/// Payload emitted with `unread-extraction-failed`
+#[derive(Serialize, Clone, Debug)]
+pub struct UnreadExtractionFailedPayload {
+    pub platform: Option<String>,
+    pub seconds_without_count: u64,
+}
+
+/// What a single report means for the tray
+#[derive(Debug, Clone, PartialEq)]
+enum UnreadOutcome {
+    /// The count changed; update the tray
+    Update(u32),
+    /// Nothing to do
+    Unchanged,
+    /// Extraction has failed for `STALE_AFTER`; warn once
+    Warn(Duration),
+}
+
+/// Tracks reported counts so the tray only updates on change and failures are noticed
+#[derive(Debug)]
+struct UnreadTracker {
+    last_count: Option<u32>,
+    last_extracted: Instant,
+    warned: bool,
+}
+
+impl UnreadTracker {
+    fn new(now: Instant) -> Self {
+        Self {
+            last_count: None,
+            last_extracted: now,
+            warned: false,
+        }
+    }
+
+    fn record(&mut self, count: Option<u32>, now: Instant) -> UnreadOutcome {
+        match count {
+            Some(count) => {
+                self.last_extracted = now;
+                self.warned = false;
+                if self.last_count == Some(count) {
+                    UnreadOutcome::Unchanged
+                } else {
+                    self.last_count = Some(count);
+                    UnreadOutcome::Update(count)
+                }
+            }
+            None => {
+                let since = now.saturating_duration_since(self.last_extracted);
+                if !self.warned && since >= STALE_AFTER {
+                    self.warned = true;
+                    UnreadOutcome::Warn(since)
+                } else {
+                    UnreadOutcome::Unchanged
+                }
+            }
+        }
+    }
+}
+
+/// Key for the unread count when no accounts are set up
+const DEFAULT_ACCOUNT: &str = "default";
+
+/// Unread counts per account
+#[derive(Debug, Default)]
+struct AccountUnread {
+    counts: HashMap<String, u32>,
+}
+
+impl AccountUnread {
+    /// Sets one account's count and returns the total across accounts
+    fn set(&mut self, account: &str, count: u32) -> u32 {
+        self.counts.insert(account.to_string(), count);
+        self.total()
+    }
+
+    fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+}
+
+/// Watches the unread count reported by the injected script
+pub struct UnreadWatcher {
+    tracker: Mutex<UnreadTracker>,
+    /// Account the tracker's counts belong to
+    account: Mutex<String>,
+    by_account: Mutex<AccountUnread>,
+}
+
+impl UnreadWatcher {
+    pub fn new() -> Self {
+        Self {
+            tracker: Mutex::new(UnreadTracker::new(Instant::now())),
+            account: Mutex::new(DEFAULT_ACCOUNT.to_string()),
+            by_account: Mutex::new(AccountUnread::default()),
+        }
+    }
+
+    /// Records a report for `account`, starting afresh when the account changed
+    fn record(&self, account: &str, count: Option<u32>, now: Instant) -> Result<UnreadOutcome, String> {
+        let mut tracker = self.tracker.lock().map_err(|e| e.to_string())?;
+        let mut current = self.account.lock().map_err(|e| e.to_string())?;
+        if *current != account {
+            *current = account.to_string();
+            *tracker = UnreadTracker::new(now);
+        }
+        Ok(tracker.record(count, now))
+    }
+
+    /// Forgets all counts, e.g. when what is being counted changes
+    fn reset(&self) -> Result<(), String> {
+        *self.tracker.lock().map_err(|e| e.to_string())? = UnreadTracker::new(Instant::now());
+        *self.by_account.lock().map_err(|e| e.to_string())? = AccountUnread::default();
+        Ok(())
+    }
+
+    /// Unread counts keyed by account id
+    pub fn by_account(&self) -> Result<HashMap<String, u32>, String> {
+        Ok(self.by_account.lock().map_err(|e| e.to_string())?.counts.clone())
+    }
+}
+
+impl Default for UnreadWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How the page is read for a count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extraction {
+    /// Only the `(N)` prefix of the page title
+    Title,
+    /// The platform's own unread badge, whose text is the count
+    Badge(&'static str),
+    /// The conversation list: each `unread` row inside `list` adds the number in
+    /// its `count` element, or 1 when it shows none
+    Rows {
+        list: &'static str,
+        unread: &'static str,
+        count: &'static str,
+    },
+}
+
+/// How to read the count `mode` asks for on `platform`; `None` when there is no badge.
+/// The platforms' badges and title count unread conversations, so counting messages
+/// goes through the conversation list instead.
+pub fn extraction(mode: BadgeMode, platform: Option<&Platform>) -> Option<Extraction> {
+    let extraction = match (mode, platform) {
+        (BadgeMode::None, _) => return None,
+        // messenger.com keeps the title count accurate; no badge to read
+        (BadgeMode::Conversations, Some(Platform::Messenger) | None) => Extraction::Title,
+        (BadgeMode::Conversations, Some(Platform::Instagram)) => Extraction::Badge(
+            "a[href^=\"/direct/inbox\"] [aria-label*=\"unread\" i], a[href^=\"/direct/inbox\"] span[class] > span",
+        ),
+        (BadgeMode::Conversations, Some(Platform::Facebook)) => {
+            Extraction::Badge("a[href*=\"/messages/\"] [aria-label*=\"unread\" i]")
+        }
+        (BadgeMode::Conversations, Some(Platform::X)) => {
+            Extraction::Badge("[data-testid=\"AppTabBar_DirectMessage_Link\"] [aria-live=\"polite\"]")
+        }
+        (BadgeMode::Messages, Some(Platform::Messenger) | Some(Platform::Facebook)) => Extraction::Rows {
+            list: "[role=\"navigation\"] [role=\"grid\"]",
+            unread: "[role=\"row\"]:has([aria-label*=\"unread\" i])",
+            count: "[aria-label*=\"unread\" i]",
+        },
+        (BadgeMode::Messages, Some(Platform::Instagram)) => Extraction::Rows {
+            list: "[role=\"main\"] [role=\"list\"], [aria-label*=\"thread list\" i]",
+            unread: "[role=\"listitem\"]:has([aria-label*=\"unread\" i]), [role=\"button\"]:has([aria-label*=\"unread\" i])",
+            count: "[aria-label*=\"unread\" i]",
+        },
+        (BadgeMode::Messages, Some(Platform::X)) => Extraction::Rows {
+            list: "[data-testid=\"dm-inbox-panel\"], [aria-label*=\"Timeline: Messages\" i]",
+            unread: "[data-testid=\"conversation\"]:has([data-testid=\"unread\"], [aria-label*=\"unread\" i])",
+            count: "[data-testid=\"unread\"]",
+        },
+        (BadgeMode::Messages, None) => Extraction::Title,
+    };
+    Some(extraction)
+}
+
+/// Parses the `(N)` unread prefix platforms put in the page title
+pub fn parse_title_count(title: &str) -> Option<u32> {
+    let rest = title.trim_start().strip_prefix('(')?;
+    let (digits, _) = rest.split_once(')')?;
+    digits.trim().trim_end_matches('+').parse().ok()
+}
+
+/// Works out the unread count from a report: the DOM badge wins, then the title.
+/// A recognised platform title without a count means nothing is unread; anything
+/// else (login pages, blank titles) means extraction failed.
+pub fn extract_count(dom_count: Option<u32>, title: &str, platform: Option<&Platform>) -> Option<u32> {
+    dom_count.or_else(|| parse_title_count(title)).or_else(|| {
+        let name = platform.map(Platform::name)?;
+        title.contains(name).then_some(0)
+    })
+}
+
+/// Builds the polling script for `extraction`; with none it only stops the poller
+pub fn unread_script(extraction: Option<Extraction>) -> String {
+    let Some(extraction) = extraction else {
+        return r#"
+        (function() {
+            if (window.__MD_UNREAD_POLLER__) clearInterval(window.__MD_UNREAD_POLLER__);
+            delete window.__MD_UNREAD_POLLER__;
+            delete window.__MD_UNREAD_REPORT__;
+        })();
+        "#
+        .to_string();
+    };
+
+    let (badge, rows) = match extraction {
+        Extraction::Title => (None, None),
+        Extraction::Badge(selector) => (Some(selector), None),
+        Extraction::Rows { list, unread, count } => (None, Some([list, unread, count])),
+    };
+    let badge = serde_json::to_string(&badge).unwrap_or_else(|_| "null".to_string());
+    let rows = serde_json::to_string(&rows).unwrap_or_else(|_| "null".to_string());
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_UNREAD_POLLER__) clearInterval(window.__MD_UNREAD_POLLER__);
+            const BADGE = {badge};
+            const ROWS = {rows};
+
+            function number(el) {{
+                const n = parseInt((el.textContent || el.getAttribute('aria-label') || '').replace(/\D/g, ''), 10);
+                return isNaN(n) ? null : n;
+            }}
+
+            function domCount() {{
+                if (BADGE) {{
+                    const badge = document.querySelector(BADGE);
+                    return badge ? number(badge) : null;
+                }}
+                if (ROWS) {{
+                    // No conversation list on screen: fall back to the title
+                    const lists = document.querySelectorAll(ROWS[0]);
+                    if (!lists.length) return null;
+                    const rows = new Set();
+                    lists.forEach(function(list) {{
+                        list.querySelectorAll(ROWS[1]).forEach(function(row) {{ rows.add(row); }});
+                    }});
+                    let total = 0;
+                    rows.forEach(function(row) {{
+                        const count = row.querySelector(ROWS[2]);
+                        const n = count ? number(count) : null;
+                        total += n && n > 0 ? n : 1;
+                    }});
+                    return total;
+                }}
+                return null;
+            }}
+
+            function report() {{
+                const core = window.__TAURI__ && window.__TAURI__.core;
+                if (!core) return;
+                core.invoke('report_unread_count', {{ domCount: domCount(), title: document.title || '' }})
+                    .catch(function(e) {{ console.warn('[messenger-desktop] unread report failed:', e); }});
+            }}
+
+            report();
+            window.__MD_UNREAD_REPORT__ = report;
+            window.__MD_UNREAD_POLLER__ = setInterval(report, {interval});
+        }})();
+        "#,
+        badge = badge,
+        rows = rows,
+        interval = POLL_INTERVAL_MS,
+    )
+}
+
+/// Page-load hook: (re)installs the poller for the active platform and badge mode
+pub fn on_page_load(window: &WebviewWindow) {
+    let app = window.app_handle();
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    let script = unread_script(extraction(badge_mode(app), platform.as_ref()));
+    if let Err(e) = window.eval(&script) {
+        log::warn!("[unread] failed to install unread poller: {}", e);
+    }
+}
+
+/// Spawns the background poll, which keeps the badge current while the window is
+/// hidden and the page's own timer may be throttled.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let config = app
+                .try_state::<BadgeManager>()
+                .and_then(|m| m.get_config().ok())
+                .unwrap_or_default();
+            tokio::time::sleep(background_poll_interval(&config)).await;
+
+            // Settings may have changed while sleeping
+            let config = app
+                .try_state::<BadgeManager>()
+                .and_then(|m| m.get_config().ok())
+                .unwrap_or_default();
+            let platform_selected = app
+                .try_state::<PlatformManager>()
+                .and_then(|m| m.get_current().ok().flatten())
+                .is_some();
+            if !should_background_poll(&config, platform_selected) {
+                continue;
+            }
+            if let Some(window) = app.get_webview_window("main") {
+                if let Err(e) = window.eval(BACKGROUND_POLL_JS) {
+                    log::debug!("[unread] background poll failed: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Tauri command called by the injected poller with what it found in the page
+#[tauri::command]
+#[specta::specta]
+pub fn report_unread_count(
+    dom_count: Option<u32>,
+    title: String,
+    app: AppHandle,
+    watcher: tauri::State<'_, UnreadWatcher>,
+) -> Result<(), String> {
+    // A poller installed before the badge was turned off
+    if badge_mode(&app) == BadgeMode::None {
+        return Ok(());
+    }
+    let platform = app
+        .try_state::<PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    let count = extract_count(dom_count, &title, platform.as_ref());
+    let account = crate::accounts::notification_identity(&crate::accounts::managed_accounts(&app))
+        .map(|(id, _)| id)
+        .unwrap_or_else(|| DEFAULT_ACCOUNT.to_string());
+
+    let outcome = watcher.record(&account, count, Instant::now())?;
+
+    match outcome {
+        UnreadOutcome::Update(count) => {
+            let total = watcher
+                .by_account
+                .lock()
+                .map_err(|e| e.to_string())?
+                .set(&account, count);
+            crate::tray::set_unread(&app, total)
+        }
+        UnreadOutcome::Warn(since) => {
+            log::warn!(
+                "[unread] no unread count found for {}s (title: {:?})",
+                since.as_secs(),
+                title
+            );
+            app.emit(
+                "unread-extraction-failed",
+                UnreadExtractionFailedPayload {
+                    platform: platform.map(|p| p.name().to_string()),
+                    seconds_without_count: since.as_secs(),
+                },
+            )
+            .map_err(|e| e.to_string())
+        }
+        UnreadOutcome::Unchanged => Ok(()),
+    }
+}
+
+/// Tauri command to choose what the unread badge counts. `None` hides the badge;
+/// notifications are unaffected.
+#[tauri::command]
+#[specta::specta]
+pub fn set_badge_mode(
+    mode: BadgeMode,
+    app: AppHandle,
+    manager: tauri::State<'_, BadgeManager>,
+    watcher: tauri::State<'_, UnreadWatcher>,
+) -> Result<(), String> {
+    manager.set(BadgeConfig {
+        mode,
+        ..manager.get_config()?
+    })?;
+    // Counts taken the old way mean something else now
+    watcher.reset()?;
+    crate::tray::set_unread(&app, 0)?;
+    if let Some(window) = app.get_webview_window("main") {
+        on_page_load(&window);
+    }
+    log::info!("[unread] badge mode {:?}", mode);
+    app.emit("badge-mode-changed", BadgeModeChangedPayload { mode })
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command to get the badge configuration
+#[tauri::command]
+#[specta::specta]
+pub fn get_badge_config(manager: tauri::State<'_, BadgeManager>) -> Result<BadgeConfig, String> {
+    manager.get_config()
+}
+
+/// Tauri command to configure the background unread poll
+#[tauri::command]
+#[specta::specta]
+pub fn set_background_unread_poll(
+    enabled: bool,
+    interval_secs: u32,
+    manager: tauri::State<'_, BadgeManager>,
+) -> Result<BadgeConfig, String> {
+    let config = manager.set_background_poll(enabled, interval_secs)?;
+    log::info!(
+        "[unread] background poll {} every {}s",
+        if config.background_poll { "on" } else { "off" },
+        config.poll_interval_secs
+    );
+    Ok(config)
+}
+
+/// Tauri command to get the unread count of each account
+#[tauri::command]
+#[specta::specta]
+pub fn get_unread_by_account(watcher: tauri::State<'_, UnreadWatcher>) -> Result<HashMap<String, u32>, String> {
+    watcher.by_account()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_title_count() {
+        assert_eq!(parse_title_count("(3) Messenger"), Some(3));
+        assert_eq!(parse_title_count("(12) Instagram • Chats"), Some(12));
+        assert_eq!(parse_title_count("(99+) Facebook"), Some(99));
+        assert_eq!(parse_title_count("Messenger"), None);
+        assert_eq!(parse_title_count("(new) Messenger"), None);
+    }
+
+    #[test]
+    fn test_extract_count_fallbacks() {
+        let p = Platform::Messenger;
+        assert_eq!(extract_count(Some(4), "(3) Messenger", Some(&p)), Some(4));
+        assert_eq!(extract_count(None, "(3) Messenger", Some(&p)), Some(3));
+        assert_eq!(extract_count(None, "Messenger", Some(&p)), Some(0));
+        assert_eq!(extract_count(None, "Log in", Some(&p)), None);
+    }
+
+    #[test]
+    fn test_badge_mode_picks_extraction() {
+        assert_eq!(extraction(BadgeMode::None, Some(&Platform::X)), None);
+        assert_eq!(extraction(BadgeMode::Conversations, Some(&Platform::Messenger)), Some(Extraction::Title));
+        assert_eq!(extraction(BadgeMode::Conversations, None), Some(Extraction::Title));
+        assert!(matches!(
+            extraction(BadgeMode::Conversations, Some(&Platform::X)),
+            Some(Extraction::Badge(s)) if s.contains("AppTabBar_DirectMessage_Link")
+        ));
+        for platform in Platform::all() {
+            assert!(matches!(
+                extraction(BadgeMode::Messages, Some(&platform)),
+                Some(Extraction::Rows { .. })
+            ));
+        }
+
+        // No badge: the poller is stopped rather than installed
+        let script = unread_script(None);
+        assert!(script.contains("clearInterval"));
+        assert!(!script.contains("report_unread_count"));
+        assert!(unread_script(extraction(BadgeMode::Messages, Some(&Platform::X))).contains("data-testid=\\\"conversation\\\""));
+    }
+
+    #[test]
+    fn test_badge_config_persistence() {
+        let tmp = std::env::temp_dir().join("test-badge-mode");
+        std::fs::create_dir_all(&tmp).unwrap();
+        assert_eq!(BadgeManager::new(&tmp).get_config().unwrap().mode, BadgeMode::Conversations);
+        BadgeManager::new(&tmp)
+            .set(BadgeConfig {
+                mode: BadgeMode::None,
+                ..BadgeConfig::default()
+            })
+            .unwrap();
+        assert_eq!(BadgeManager::new(&tmp).get_config().unwrap().mode, BadgeMode::None);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_background_poll_gating() {
+        let config = BadgeConfig::default();
+        assert!(should_background_poll(&config, true));
+        assert!(!should_background_poll(&config, false));
+        let off = BadgeConfig {
+            background_poll: false,
+            ..BadgeConfig::default()
+        };
+        assert!(!should_background_poll(&off, true));
+        let no_badge = BadgeConfig {
+            mode: BadgeMode::None,
+            ..BadgeConfig::default()
+        };
+        assert!(!should_background_poll(&no_badge, true));
+
+        assert_eq!(background_poll_interval(&config), Duration::from_secs(30));
+        let too_fast = BadgeConfig {
+            poll_interval_secs: 1,
+            ..BadgeConfig::default()
+        };
+        assert_eq!(background_poll_interval(&too_fast), Duration::from_secs(5));
+
+        // Older files without the poll settings keep it on
+        let parsed: BadgeConfig = serde_json::from_str(r#"{"mode":"Messages"}"#).unwrap();
+        assert!(parsed.background_poll);
+        assert_eq!(parsed.poll_interval_secs, 30);
+
+        let tmp = std::env::temp_dir().join("test-badge-background-poll");
+        std::fs::create_dir_all(&tmp).unwrap();
+        let manager = BadgeManager::new(&tmp);
+        assert!(manager.set_background_poll(true, 1).is_err());
+        manager.set_background_poll(false, 60).unwrap();
+        let reloaded = BadgeManager::new(&tmp).get_config().unwrap();
+        assert!(!reloaded.background_poll);
+        assert_eq!(reloaded.poll_interval_secs, 60);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_tracker_updates_on_change_and_warns_once() {
+        let start = Instant::now();
+        let mut tracker = UnreadTracker::new(start);
+        assert_eq!(tracker.record(Some(2), start), UnreadOutcome::Update(2));
+        assert_eq!(tracker.record(Some(2), start), UnreadOutcome::Unchanged);
+
+        assert_eq!(tracker.record(None, start + Duration::from_secs(60)), UnreadOutcome::Unchanged);
+        let stale = start + STALE_AFTER;
+        assert_eq!(tracker.record(None, stale), UnreadOutcome::Warn(STALE_AFTER));
+        assert_eq!(tracker.record(None, stale + Duration::from_secs(60)), UnreadOutcome::Unchanged);
+
+        // A successful read re-arms the warning
+        assert_eq!(tracker.record(Some(0), stale), UnreadOutcome::Update(0));
+        assert_eq!(tracker.record(None, stale + STALE_AFTER), UnreadOutcome::Warn(STALE_AFTER));
+    }
+
+    #[test]
+    fn test_per_account_counts_are_summed() {
+        let mut unread = AccountUnread::default();
+        assert_eq!(unread.set("work", 3), 3);
+        assert_eq!(unread.set("home", 2), 5);
+        assert_eq!(unread.set("work", 0), 2);
+        assert_eq!(unread.counts.get("home"), Some(&2));
+    }
+
+    #[test]
+    fn test_account_switch_restarts_tracking() {
+        let watcher = UnreadWatcher::new();
+        let now = Instant::now();
+        assert_eq!(watcher.record("work", Some(2), now).unwrap(), UnreadOutcome::Update(2));
+        // Same count on another account is still news for that account
+        assert_eq!(watcher.record("home", Some(2), now).unwrap(), UnreadOutcome::Update(2));
+        assert_eq!(watcher.record("home", Some(2), now).unwrap(), UnreadOutcome::Unchanged);
+    }
+}
@@ -1,14 +1,26 @@
 //! Updater manager for Tauri app.
 //! Handles update checks, downloads, and installations.
+//!
+//! A check the user asked for retries transient network failures with
+//! exponential backoff; failures that retrying can't fix (bad signature, 404) end
+//! it at once. Scheduled checks try once, so a down server isn't hammered.
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 use serde::Serialize;
+use specta::Type;
 use tokio::sync::Mutex as TokioMutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use anyhow::Result;
 
+/// Attempts made by a retried update check
+const CHECK_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry; doubled for each one after
+const CHECK_BACKOFF: Duration = Duration::from_secs(1);
+
 /// Update information.
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Clone, Debug, Type)]
 pub struct UpdateInfo {
     pub version: String,
     pub body: String,
@@ -26,6 +38,63 @@ pub struct UpdateProgress {
     pub status: String,
 }
 
+/// Why an update check failed
+#[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+pub enum CheckError {
+    /// Transient (timeout, connection reset, 5xx); worth retrying
+    Network(String),
+    /// Retrying won't help (bad signature, 404, malformed manifest)
+    Fatal(String),
+}
+
+impl CheckError {
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, CheckError::Network(_))
+    }
+}
+
+impl std::fmt::Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckError::Network(reason) => write!(f, "network error: {}", reason),
+            CheckError::Fatal(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Payload emitted with `update-check-failed`
+#[derive(Serialize, Clone, Debug)]
+pub struct UpdateCheckFailedPayload {
+    pub reason: String,
+    pub retryable: bool,
+}
+
+/// Wait before retry number `retry` (starting at 1)
+fn backoff(base: Duration, retry: u32) -> Duration {
+    base * 2u32.saturating_pow(retry.saturating_sub(1))
+}
+
+/// Runs `check` up to `attempts` times, backing off between retryable failures
+async fn with_retry<T, F, Fut>(attempts: u32, base: Duration, mut check: F) -> Result<T, CheckError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, CheckError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match check().await {
+            Err(e) if e.is_retryable() && attempt < attempts => {
+                let delay = backoff(base, attempt);
+                log::warn!("[updater] update check failed ({}), retrying in {:?}", e, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Updater manager state.
 #[allow(dead_code)]
 pub struct UpdaterManager {
@@ -47,12 +116,21 @@ impl UpdaterManager {
         }
     }
 
-    /// Check for updates.
-    /// No update server configured — returns None until an endpoint is set up.
-    pub async fn check_update(&self) -> Result<Option<UpdateInfo>> {
+    /// Check for updates, retrying transient network failures when `retry` is set.
+    /// Scheduled checks pass `false` so they don't hammer a down server.
+    pub async fn check_update(&self, retry: bool) -> Result<Option<UpdateInfo>, CheckError> {
         let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)?.as_secs();
-        *self.last_check.lock().unwrap() = Some(current_time);
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| CheckError::Fatal(e.to_string()))?
+            .as_secs();
+        *self.last_check.lock().map_err(|e| CheckError::Fatal(e.to_string()))? = Some(current_time);
+        let attempts = if retry { CHECK_ATTEMPTS } else { 1 };
+        with_retry(attempts, CHECK_BACKOFF, || self.fetch_update()).await
+    }
+
+    /// The network call behind a check.
+    /// No update server configured — returns None until an endpoint is set up.
+    async fn fetch_update(&self) -> Result<Option<UpdateInfo>, CheckError> {
         Ok(None)
     }
 
@@ -71,8 +149,8 @@ impl UpdaterManager {
 
     /// Get the last update check time.
     #[allow(dead_code)]
-    pub fn get_last_check_time(&self) -> Option<u64> {
-        *self.last_check.lock().unwrap()
+    pub fn get_last_check_time(&self) -> Result<Option<u64>, String> {
+        Ok(*self.last_check.lock().map_err(|e| e.to_string())?)
     }
 
     /// Set the release channel.
@@ -97,42 +175,59 @@ impl UpdaterManager {
 
 /// Tauri command: Check for updates.
 #[tauri::command]
-pub async fn check_update(state: tauri::State<'_, TokioMutex<UpdaterManager>>) -> Result<Option<UpdateInfo>, String> {
-    state.lock().await.check_update().await.map_err(|e| e.to_string())
+#[specta::specta]
+pub async fn check_update(
+    app: AppHandle,
+    state: tauri::State<'_, TokioMutex<UpdaterManager>>,
+) -> Result<Option<UpdateInfo>, String> {
+    let result = state.lock().await.check_update(true).await;
+    result.map_err(|e| {
+        log::warn!("[updater] update check failed: {}", e);
+        let _ = app.emit(
+            "update-check-failed",
+            UpdateCheckFailedPayload {
+                reason: e.to_string(),
+                retryable: e.is_retryable(),
+            },
+        );
+        e.to_string()
+    })
 }
 
 /// Tauri command: Install an update.
 #[tauri::command]
+#[specta::specta]
 pub async fn install_update(state: tauri::State<'_, TokioMutex<UpdaterManager>>) -> Result<(), String> {
     state.lock().await.install_update().await.map_err(|e| e.to_string())
 }
 
 /// Tauri command: Get the current app version.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn get_current_version(state: tauri::State<'_, TokioMutex<UpdaterManager>>) -> String {
-    state.blocking_lock().get_current_version()
+#[specta::specta]
+pub async fn get_current_version(state: tauri::State<'_, TokioMutex<UpdaterManager>>) -> Result<String, String> {
+    Ok(state.lock().await.get_current_version())
 }
 
 /// Tauri command: Get the last update check time.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn get_last_check_time(state: tauri::State<'_, TokioMutex<UpdaterManager>>) -> Option<u64> {
-    state.blocking_lock().get_last_check_time()
+#[specta::specta]
+pub async fn get_last_check_time(state: tauri::State<'_, TokioMutex<UpdaterManager>>) -> Result<Option<u64>, String> {
+    state.lock().await.get_last_check_time()
 }
 
 /// Tauri command: Set the release channel.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn set_channel(state: tauri::State<'_, TokioMutex<UpdaterManager>>, channel: String) {
-    state.blocking_lock().set_channel(&channel);
+#[specta::specta]
+pub async fn set_channel(state: tauri::State<'_, TokioMutex<UpdaterManager>>, channel: String) -> Result<(), String> {
+    state.lock().await.set_channel(&channel);
+    Ok(())
 }
 
 /// Tauri command: Get the current release channel.
 #[tauri::command]
-#[allow(dead_code)]
-pub fn get_channel(state: tauri::State<'_, TokioMutex<UpdaterManager>>) -> String {
-    state.blocking_lock().get_channel()
+#[specta::specta]
+pub async fn get_channel(state: tauri::State<'_, TokioMutex<UpdaterManager>>) -> Result<String, String> {
+    Ok(state.lock().await.get_channel())
 }
 
 // Unit tests
@@ -177,6 +272,31 @@ mod tests {
         assert!(true);
     }
 
+    #[test]
+    fn test_check_retries_only_network_errors() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let run = |fail_first: u32, error: CheckError| {
+            let calls = AtomicU32::new(0);
+            let result = tauri::async_runtime::block_on(with_retry(3, Duration::from_millis(1), || {
+                let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                let error = error.clone();
+                async move { if call <= fail_first { Err(error) } else { Ok(call) } }
+            }));
+            (result, calls.load(Ordering::SeqCst))
+        };
+
+        let down = CheckError::Network("connection refused".into());
+        assert_eq!(run(u32::MAX, down.clone()), (Err(down.clone()), 3));
+        assert_eq!(run(1, down), (Ok(2), 2));
+        let missing = CheckError::Fatal("404 Not Found".into());
+        assert_eq!(run(u32::MAX, missing.clone()), (Err(missing), 1));
+
+        assert_eq!(backoff(CHECK_BACKOFF, 1), Duration::from_secs(1));
+        assert_eq!(backoff(CHECK_BACKOFF, 2), Duration::from_secs(2));
+        assert_eq!(backoff(CHECK_BACKOFF, 3), Duration::from_secs(4));
+    }
+
     #[test]
     fn test_updater_manager_default_channel() {
         // Test that default channel is "stable" when env var not set
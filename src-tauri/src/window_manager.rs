@@ -7,11 +7,16 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use crate::store;
+
 /// Window state for persistence
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct WindowState {
@@ -22,6 +27,557 @@ pub struct WindowState {
     pub maximized: bool,
     pub always_on_top: bool,
     pub focus_mode: bool,
+    #[serde(default)]
+    pub mini_mode: bool,
+    /// Size and position to restore when leaving mini mode
+    #[serde(default)]
+    pub pre_mini_bounds: Option<WindowBounds>,
+    /// Screen edge the window is docked to, if any
+    #[serde(default)]
+    pub docked: Option<DockEdge>,
+    /// Size and position to restore when undocking
+    #[serde(default)]
+    pub pre_dock_bounds: Option<WindowBounds>,
+    /// Monitor the window was last on
+    #[serde(default)]
+    pub monitor: Option<MonitorInfo>,
+    /// While always-on-top, also stay above full-screen apps (macOS)
+    #[serde(default)]
+    pub above_fullscreen: bool,
+    /// Whether the window was showing (not hidden to the tray or minimized) when
+    /// the app last quit
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    /// Native title bar and borders; off leaves a borderless window dragged by its
+    /// header (mini mode is always borderless)
+    #[serde(default = "default_decorations")]
+    pub decorations: bool,
+    /// Keep the window on the user's workspace (see `platform::set_sticky_workspace`)
+    #[serde(default)]
+    pub sticky_workspace: bool,
+    /// Virtual desktop the window is kept on (Windows)
+    #[serde(default)]
+    pub workspace: Option<String>,
+    /// Vibrancy / Mica / Acrylic behind the page
+    #[serde(default)]
+    pub window_effect: WindowEffect,
+    /// Where the window has been, oldest first (see `restore_previous_position`)
+    #[serde(default)]
+    pub position_history: Vec<PositionHistory>,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+fn default_decorations() -> bool {
+    true
+}
+
+/// Identifies a monitor by name and work-area geometry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub work_area: WindowBounds,
+}
+
+impl MonitorInfo {
+    /// Describes a connected monitor
+    pub fn from_monitor(monitor: &tauri::Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            work_area: work_area(monitor),
+        }
+    }
+}
+
+/// A connected monitor, as listed for the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct MonitorDetails {
+    pub name: Option<String>,
+    /// Full monitor geometry in physical pixels
+    pub bounds: WindowBounds,
+    /// Geometry excluding the taskbar / dock / menu bar
+    pub work_area: WindowBounds,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+impl MonitorDetails {
+    /// Describes a connected monitor; `primary` is the primary monitor, if known
+    pub fn from_monitor(monitor: &tauri::Monitor, primary: Option<&tauri::Monitor>) -> Self {
+        let position = monitor.position();
+        let size = monitor.size();
+        Self {
+            name: monitor.name().cloned(),
+            bounds: WindowBounds {
+                width: size.width as i32,
+                height: size.height as i32,
+                x: position.x,
+                y: position.y,
+            },
+            work_area: work_area(monitor),
+            scale_factor: monitor.scale_factor(),
+            primary: primary.is_some_and(|p| p.name() == monitor.name() && p.position() == position),
+        }
+    }
+}
+
+/// Finds the monitor called `name`
+fn find_monitor<'a>(monitors: &'a [MonitorInfo], name: &str) -> Result<&'a MonitorInfo, String> {
+    if monitors.is_empty() {
+        return Err("No monitors connected".to_string());
+    }
+    monitors
+        .iter()
+        .find(|m| m.name.as_deref() == Some(name))
+        .ok_or_else(|| format!("No monitor named {}", name))
+}
+
+/// Id of the style element focus mode is injected as
+const FOCUS_MODE_STYLE_ID: &str = "__md_focus_mode__";
+
+/// Hides the conversation list and navigation so only the open chat shows
+const FOCUS_MODE_CSS: &str = r#"
+    [role="navigation"], [data-testid="mwthreadlist"], [data-testid="DMDrawer"],
+    [aria-label="Thread list" i], [aria-label="Conversations" i] { display: none !important; }
+    [role="main"] { max-width: none !important; width: 100% !important; }
+"#;
+
+/// Builds the script that turns focus mode's CSS on or off in the page
+pub fn focus_mode_script(enabled: bool) -> String {
+    crate::injection::style_script(FOCUS_MODE_STYLE_ID, if enabled { FOCUS_MODE_CSS } else { "" })
+}
+
+/// Headers of a platform's page that can drag a borderless window
+fn drag_header_selector(platform: Option<&crate::platform_manager::Platform>) -> &'static str {
+    use crate::platform_manager::Platform;
+
+    match platform {
+        Some(Platform::Messenger) | Some(Platform::Facebook) => "[role=\"banner\"]",
+        Some(Platform::Instagram) => "header, [role=\"banner\"]",
+        Some(Platform::X) => "[data-testid=\"DmActivityContainer\"] [data-testid=\"DMConversationHeader\"], header[role=\"banner\"]",
+        None => "[role=\"banner\"]",
+    }
+}
+
+/// Builds the script that makes a borderless window draggable: a thin strip along
+/// the top edge (always there, whatever page is showing) plus the platform's header
+/// are marked as drag regions. With decorations on it removes them again.
+pub fn drag_region_script(decorations: bool, platform: Option<&crate::platform_manager::Platform>) -> String {
+    let header = serde_json::to_string(drag_header_selector(platform)).unwrap_or_default();
+    format!(
+        r#"
+        (function() {{
+            if (window.__MD_DRAG_REGION__) window.__MD_DRAG_REGION__.disable();
+            if ({decorations}) return;
+
+            const HEADER = {header};
+            const strip = document.createElement('div');
+            strip.setAttribute('data-tauri-drag-region', '');
+            strip.style.cssText = 'position:fixed;top:0;left:0;right:0;height:8px;z-index:2147483647;';
+            document.documentElement.appendChild(strip);
+
+            function mark() {{
+                document.querySelectorAll(HEADER).forEach(function(el) {{
+                    if (el.hasAttribute('data-tauri-drag-region')) return;
+                    el.setAttribute('data-tauri-drag-region', '');
+                    el.setAttribute('data-md-drag-region', '');
+                }});
+            }}
+            const observer = new MutationObserver(mark);
+            observer.observe(document.documentElement, {{ childList: true, subtree: true }});
+            mark();
+
+            window.__MD_DRAG_REGION__ = {{
+                disable: function() {{
+                    observer.disconnect();
+                    strip.remove();
+                    document.querySelectorAll('[data-md-drag-region]').forEach(function(el) {{
+                        el.removeAttribute('data-tauri-drag-region');
+                        el.removeAttribute('data-md-drag-region');
+                    }});
+                    delete window.__MD_DRAG_REGION__;
+                }},
+            }};
+        }})();
+        "#,
+        decorations = decorations,
+        header = header,
+    )
+}
+
+/// Zoom factors the webview is kept between
+const MIN_ZOOM_FACTOR: f64 = 0.3;
+const MAX_ZOOM_FACTOR: f64 = 3.0;
+
+/// Webview zoom factor for a zoom level (0.0 = 100%)
+pub fn zoom_factor(level: f64) -> f64 {
+    if level.is_finite() {
+        (level + 1.0).clamp(MIN_ZOOM_FACTOR, MAX_ZOOM_FACTOR)
+    } else {
+        1.0
+    }
+}
+
+/// Zoom levels file, keyed by platform name
+const ZOOM_FILE: &str = "zoom.json";
+
+/// Key used for zoom before any platform is selected
+const NO_PLATFORM: &str = "default";
+
+/// Per-platform zoom levels; only `levels` is persisted
+#[derive(Debug, Default)]
+struct ZoomLevels {
+    levels: HashMap<String, f64>,
+    current: Option<String>,
+}
+
+impl ZoomLevels {
+    fn key(&self) -> &str {
+        self.current.as_deref().unwrap_or(NO_PLATFORM)
+    }
+
+    /// The current platform's level
+    fn get(&self) -> f64 {
+        self.levels.get(self.key()).copied().unwrap_or(0.0)
+    }
+
+    /// Sets the current platform's level; 100% is stored as no entry
+    fn set(&mut self, level: f64) {
+        let key = self.key().to_string();
+        if level == 0.0 {
+            self.levels.remove(&key);
+        } else {
+            self.levels.insert(key, level);
+        }
+    }
+
+    /// Makes `platform` current. Returns its level.
+    fn switch_to(&mut self, platform: &str) -> f64 {
+        self.current = Some(platform.to_string());
+        self.get()
+    }
+}
+
+/// Sets the main window's webview zoom to `level`
+fn apply_zoom(app: &tauri::AppHandle, level: f64) -> Result<(), String> {
+    use tauri::Manager;
+
+    match app.get_webview_window("main") {
+        Some(window) => window.set_zoom(zoom_factor(level)).map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Page-load hook: restores the zoom level, which a full navigation can reset
+pub fn on_page_load(window: &tauri::WebviewWindow) {
+    use tauri::Manager;
+
+    let Some(manager) = window.app_handle().try_state::<WindowManager>() else {
+        return;
+    };
+    if let Err(e) = window.set_zoom(zoom_factor(manager.current_zoom())) {
+        warn!("Failed to restore zoom: {}", e);
+    }
+}
+
+/// How long the window must rest after moving before the move is recorded
+const MOVE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Move hook: records where the main window moved to once it has rested for
+/// `MOVE_DEBOUNCE`, on the async runtime rather than the event loop
+pub fn on_moved(app: &tauri::AppHandle, x: i32, y: i32) {
+    use tauri::Manager;
+
+    let Some(manager) = app.try_state::<WindowManager>() else {
+        return;
+    };
+    let generation = manager.moves.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(MOVE_DEBOUNCE).await;
+        let manager = app.state::<WindowManager>();
+        // Still moving: a later move records itself
+        if manager.moves.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Err(e) = manager.set_position(x, y).await {
+            warn!("Failed to record window position: {}", e);
+        }
+    });
+}
+
+/// Switches zoom to `platform`'s stored level and applies it, and retitles the window
+pub fn on_platform_changed(app: &tauri::AppHandle, platform: &crate::platform_manager::Platform) {
+    use tauri::Manager;
+
+    let Some(manager) = app.try_state::<WindowManager>() else {
+        return;
+    };
+    let level = manager.switch_zoom_platform(platform.name());
+    if let Err(e) = apply_zoom(app, level) {
+        warn!("Failed to apply zoom for {}: {}", platform.name(), e);
+    }
+    refresh_title(app);
+}
+
+/// The main window's title: the app name, then the platform and account when known,
+/// e.g. "Messenger Desktop - Instagram - Work". Every title goes through here.
+pub fn compose_title(app_name: &str, platform: Option<&crate::platform_manager::Platform>, account: Option<&str>) -> String {
+    let mut parts = vec![app_name];
+    parts.extend(platform.map(|p| p.name()));
+    parts.extend(account.map(str::trim).filter(|a| !a.is_empty()));
+    parts.join(" - ")
+}
+
+/// Title the main window has without an override
+pub fn default_title(app: &tauri::AppHandle) -> String {
+    use tauri::Manager;
+
+    let platform = app
+        .try_state::<crate::platform_manager::PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    let account = app.try_state::<WindowManager>().and_then(|m| m.title_account());
+    compose_title(&app.package_info().name, platform.as_ref(), account.as_deref())
+}
+
+/// Sets the main window's title: the override if one is set, otherwise the default
+pub fn refresh_title(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let title = app
+        .try_state::<WindowManager>()
+        .and_then(|m| m.title_override())
+        .unwrap_or_else(|| default_title(app));
+    if let Err(e) = window.set_title(&title) {
+        warn!("Failed to set window title: {}", e);
+    }
+}
+
+/// Names the active account in the window title (`None` leaves it out)
+pub fn set_title_account(app: &tauri::AppHandle, account: Option<String>) {
+    use tauri::Manager;
+
+    if let Some(manager) = app.try_state::<WindowManager>() {
+        if let Ok(mut title) = manager.title.lock() {
+            title.account = account;
+        }
+    }
+    refresh_title(app);
+}
+
+/// Fits saved window geometry onto the currently connected monitors.
+///
+/// The saved monitor is matched by name, then by position. On a match the window is
+/// clamped into that monitor's work area (it may have changed resolution); if the
+/// monitor is gone the window is centered on the primary monitor. Returns the bounds
+/// to use and the monitor they are on.
+pub fn fit_to_monitors(
+    state: &WindowState,
+    monitors: &[MonitorInfo],
+    primary: Option<&MonitorInfo>,
+) -> (WindowBounds, Option<MonitorInfo>) {
+    let saved = WindowBounds {
+        width: state.width,
+        height: state.height,
+        x: state.x,
+        y: state.y,
+    };
+
+    let matched = match &state.monitor {
+        Some(previous) => monitors
+            .iter()
+            .find(|m| m.name.is_some() && m.name == previous.name)
+            .or_else(|| {
+                monitors.iter().find(|m| {
+                    (m.work_area.x, m.work_area.y) == (previous.work_area.x, previous.work_area.y)
+                })
+            }),
+        None => monitors.iter().find(|m| contains(&m.work_area, saved.x, saved.y)),
+    };
+
+    if let Some(monitor) = matched {
+        return (clamp_into(&saved, &monitor.work_area), Some(monitor.clone()));
+    }
+    match primary.or_else(|| monitors.first()) {
+        Some(monitor) => (center_in(&saved, &monitor.work_area), Some(monitor.clone())),
+        None => (saved, None),
+    }
+}
+
+/// Shrinks `bounds` to fit `area` and moves it fully inside
+fn clamp_into(bounds: &WindowBounds, area: &WindowBounds) -> WindowBounds {
+    let width = bounds.width.min(area.width);
+    let height = bounds.height.min(area.height);
+    WindowBounds {
+        width,
+        height,
+        x: bounds.x.clamp(area.x, area.x + area.width - width),
+        y: bounds.y.clamp(area.y, area.y + area.height - height),
+    }
+}
+
+/// Shrinks `bounds` to fit `area` and centers it there
+fn center_in(bounds: &WindowBounds, area: &WindowBounds) -> WindowBounds {
+    let width = bounds.width.min(area.width);
+    let height = bounds.height.min(area.height);
+    WindowBounds {
+        width,
+        height,
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+    }
+}
+
+impl WindowState {
+    /// Moves and resizes the stored window geometry
+    fn set_bounds(&mut self, bounds: WindowBounds) {
+        self.width = bounds.width;
+        self.height = bounds.height;
+        self.x = bounds.x;
+        self.y = bounds.y;
+    }
+}
+
+/// Native material shown behind the page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowEffect {
+    #[default]
+    None,
+    /// macOS sidebar vibrancy
+    Sidebar,
+    /// macOS under-window vibrancy
+    UnderWindow,
+    /// Windows 11 Mica
+    Mica,
+    /// Windows 10/11 Acrylic
+    Acrylic,
+}
+
+impl WindowEffect {
+    /// Effects this OS can show, `None` included
+    pub fn supported() -> &'static [WindowEffect] {
+        if cfg!(target_os = "macos") {
+            &[WindowEffect::None, WindowEffect::Sidebar, WindowEffect::UnderWindow]
+        } else if cfg!(target_os = "windows") {
+            &[WindowEffect::None, WindowEffect::Mica, WindowEffect::Acrylic]
+        } else {
+            &[WindowEffect::None]
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WindowEffect::None => "none",
+            WindowEffect::Sidebar => "sidebar",
+            WindowEffect::UnderWindow => "under_window",
+            WindowEffect::Mica => "mica",
+            WindowEffect::Acrylic => "acrylic",
+        }
+    }
+
+    /// Parses an effect name, rejecting ones this OS can't show
+    pub fn parse(name: &str, supported: &[WindowEffect]) -> Result<Self, String> {
+        let names = || supported.iter().map(|e| e.name()).collect::<Vec<_>>().join(", ");
+        let effect = [
+            WindowEffect::None,
+            WindowEffect::Sidebar,
+            WindowEffect::UnderWindow,
+            WindowEffect::Mica,
+            WindowEffect::Acrylic,
+        ]
+        .into_iter()
+        .find(|e| e.name() == name.trim().to_lowercase())
+        .ok_or_else(|| format!("Unknown window effect '{}' (supported: {})", name, names()))?;
+        if !supported.contains(&effect) {
+            return Err(format!("Window effect '{}' is not supported on this platform (supported: {})", name, names()));
+        }
+        Ok(effect)
+    }
+}
+
+/// Id of the style element that lets the window effect show through the page
+const WINDOW_EFFECT_STYLE_ID: &str = "__md_window_effect__";
+
+/// Clears the page background so the effect shows through. `html body` outranks the
+/// themes' `body` rules, which also use `!important`.
+const WINDOW_EFFECT_CSS: &str = "html,html body{background:transparent!important;}";
+
+/// Builds the script that makes the page background transparent under an effect, or
+/// restores it
+pub fn window_effect_script(effect: WindowEffect) -> String {
+    let css = if effect == WindowEffect::None { "" } else { WINDOW_EFFECT_CSS };
+    crate::injection::style_script(WINDOW_EFFECT_STYLE_ID, css)
+}
+
+/// Screen edge a window can be docked against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum DockEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Bounds of a window docked to `edge`, taking half of the monitor's work area
+fn dock_bounds(edge: DockEdge, work_area: &WindowBounds) -> WindowBounds {
+    let half_width = work_area.width / 2;
+    let half_height = work_area.height / 2;
+    match edge {
+        DockEdge::Left => WindowBounds { width: half_width, ..*work_area },
+        DockEdge::Right => WindowBounds {
+            width: half_width,
+            x: work_area.x + work_area.width - half_width,
+            ..*work_area
+        },
+        DockEdge::Top => WindowBounds { height: half_height, ..*work_area },
+        DockEdge::Bottom => WindowBounds {
+            height: half_height,
+            y: work_area.y + work_area.height - half_height,
+            ..*work_area
+        },
+    }
+}
+
+/// Window (or monitor) size and position in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+pub struct WindowBounds {
+    pub width: i32,
+    pub height: i32,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Size of the compact mini-mode window
+const MINI_WIDTH: i32 = 380;
+const MINI_HEIGHT: i32 = 600;
+/// Gap between the mini window and the screen edges
+const MINI_MARGIN: i32 = 16;
+
+/// Payload emitted with `mini-mode-changed`
+#[derive(Serialize, Clone, Debug)]
+pub struct MiniModeChangedPayload {
+    pub enabled: bool,
+}
+
+/// CSS injected in mini mode: hide the open conversation and keep the thread list
+const MINI_MODE_CSS: &str = "[role=\"main\"]{display:none!important;}\
+[role=\"navigation\"]{width:100%!important;max-width:none!important;}";
+
+/// Mini-mode bounds pinned to the bottom-right corner of the monitor
+fn mini_bounds(monitor: &WindowBounds) -> WindowBounds {
+    WindowBounds {
+        width: MINI_WIDTH,
+        height: MINI_HEIGHT,
+        x: monitor.x + (monitor.width - MINI_WIDTH - MINI_MARGIN).max(0),
+        y: monitor.y + (monitor.height - MINI_HEIGHT - MINI_MARGIN).max(0),
+    }
 }
 
 impl Default for WindowState {
@@ -34,10 +590,102 @@ impl Default for WindowState {
             maximized: false,
             always_on_top: false,
             focus_mode: false,
+            mini_mode: false,
+            pre_mini_bounds: None,
+            docked: None,
+            pre_dock_bounds: None,
+            monitor: None,
+            above_fullscreen: false,
+            visible: true,
+            decorations: true,
+            sticky_workspace: false,
+            workspace: None,
+            window_effect: WindowEffect::None,
+            position_history: Vec::new(),
         }
     }
 }
 
+/// Smallest usable window (mini mode is allowed its own, narrower width)
+const MIN_WIDTH: i32 = 400;
+const MIN_HEIGHT: i32 = 300;
+
+/// Largest believable window dimension
+const MAX_DIMENSION: i32 = 16_384;
+
+/// Coordinates at or beyond this are bogus (Windows reports -32000 for minimized
+/// windows)
+const MAX_COORDINATE: i32 = 32_000;
+
+/// Reads a geometry field as an `i32`, if it is one
+fn geometry_field(value: &serde_json::Value, field: &str) -> Option<i32> {
+    value.get(field)?.as_i64().and_then(|n| i32::try_from(n).ok())
+}
+
+/// Parses `window_state.json`, repairing what would make the window unusable:
+/// non-numeric or overflowing geometry falls back to the defaults, sizes are
+/// clamped between the minimum and `MAX_DIMENSION`, and off-the-charts coordinates
+/// are replaced with the center sentinel. Geometry that is merely off every
+/// connected monitor is handled at restore by `fit_to_monitors`. Returns the state
+/// and a description of each repair.
+pub fn parse_window_state(contents: &str) -> (WindowState, Vec<String>) {
+    let mut repairs = Vec::new();
+    let defaults = WindowState::default();
+    let mut value: serde_json::Value = match serde_json::from_str(contents) {
+        Ok(value @ serde_json::Value::Object(_)) => value,
+        Ok(_) | Err(_) => {
+            repairs.push("unreadable window state, using defaults".to_string());
+            return (defaults, repairs);
+        }
+    };
+
+    let fields = [
+        ("width", defaults.width),
+        ("height", defaults.height),
+        ("x", defaults.x),
+        ("y", defaults.y),
+    ];
+    for (field, default) in fields {
+        if geometry_field(&value, field).is_none() {
+            repairs.push(format!("{} was not a valid number, using {}", field, default));
+            value[field] = default.into();
+        }
+    }
+    let mut state: WindowState = match serde_json::from_value(value) {
+        Ok(state) => state,
+        Err(e) => {
+            repairs.push(format!("invalid window state ({}), using defaults", e));
+            return (defaults, repairs);
+        }
+    };
+
+    let min_width = if state.mini_mode { MINI_WIDTH } else { MIN_WIDTH };
+    let width = state.width.clamp(min_width, MAX_DIMENSION);
+    let height = state.height.clamp(MIN_HEIGHT, MAX_DIMENSION);
+    if (width, height) != (state.width, state.height) {
+        repairs.push(format!("size {}x{} clamped to {}x{}", state.width, state.height, width, height));
+        state.width = width;
+        state.height = height;
+    }
+
+    let bogus = |c: i32| c != -1 && !(-MAX_COORDINATE + 1..MAX_COORDINATE).contains(&c);
+    if bogus(state.x) || bogus(state.y) {
+        repairs.push(format!("position ({}, {}) is off-screen, centering", state.x, state.y));
+        state.x = -1;
+        state.y = -1;
+    }
+
+    (state, repairs)
+}
+
+/// Whether the window was visible when the app last quit, read straight from
+/// `window_state.json` (setup needs it before the window is built)
+pub fn last_visible(app_data_dir: &Path) -> bool {
+    fs::read_to_string(app_data_dir.join("window_state.json"))
+        .map(|contents| parse_window_state(&contents).0.visible)
+        .unwrap_or(true)
+}
+
 /// Window manager state
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -45,22 +693,101 @@ pub struct WindowManagerState {
     pub window_state: WindowState,
     #[allow(dead_code)]
     pub default_window_state: WindowState,
-    pub zoom_level: f64,
-    #[allow(dead_code)]
-    pub saved_positions: Vec<PositionHistory>,
 }
 
 /// Position history for tracking window movements
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct PositionHistory {
+    /// When the window got there (ms since the epoch)
     pub timestamp: u64,
     pub x: i32,
     pub y: i32,
 }
 
+/// Positions kept in the history
+const MAX_POSITION_HISTORY: usize = 100;
+
+/// Moves closer together than this (ms) are one drag; only where it ends is kept
+const DRAG_SETTLE_MS: u64 = 1000;
+
+/// Adds a position to the history. A move that continues a drag updates the last
+/// entry instead, so a drag leaves one entry rather than one per pixel.
+fn record_position(history: &mut Vec<PositionHistory>, x: i32, y: i32, now: u64) {
+    match history.last_mut() {
+        Some(last) if now.saturating_sub(last.timestamp) < DRAG_SETTLE_MS => {
+            *last = PositionHistory { timestamp: now, x, y };
+        }
+        _ => history.push(PositionHistory { timestamp: now, x, y }),
+    }
+    let len = history.len();
+    if len > MAX_POSITION_HISTORY {
+        history.drain(0..(len - MAX_POSITION_HISTORY));
+    }
+}
+
+/// Finds the last position different from the current one (`x`, `y`), dropping the
+/// entries for the current position on the way. The returned entry stays in the
+/// history, as it becomes the current position.
+fn previous_position(history: &mut Vec<PositionHistory>, x: i32, y: i32) -> Option<PositionHistory> {
+    while history.last().is_some_and(|p| (p.x, p.y) == (x, y)) {
+        history.pop();
+    }
+    history.last().cloned()
+}
+
+/// File in the app data dir holding the autosave interval (seconds)
+const AUTOSAVE_FILE: &str = "window_autosave.json";
+
+/// Seconds between window-state autosaves unless configured
+const DEFAULT_AUTOSAVE_SECS: u64 = 30;
+
+/// Accepted autosave intervals (seconds)
+const MIN_AUTOSAVE_SECS: u64 = 5;
+const MAX_AUTOSAVE_SECS: u64 = 3600;
+
+/// The part of the window state the page-load scripts are built from
+#[derive(Debug, Clone, Copy)]
+pub struct PageLook {
+    pub focus_mode: bool,
+    pub decorations: bool,
+    pub window_effect: WindowEffect,
+}
+
+impl From<&WindowState> for PageLook {
+    fn from(state: &WindowState) -> Self {
+        Self {
+            focus_mode: state.focus_mode,
+            decorations: state.decorations,
+            window_effect: state.window_effect,
+        }
+    }
+}
+
+/// What the main window's title is made of besides the app name and platform
+#[derive(Debug, Default)]
+struct TitleState {
+    /// Set by `set_window_title` until `reset_window_title`
+    override_title: Option<String>,
+    account: Option<String>,
+}
+
 /// Window Manager - manages window behavior and state
 pub struct WindowManager {
     state: Arc<RwLock<WindowManagerState>>,
+    /// Set when the window moved or resized without being saved; cleared by every save
+    dirty: AtomicBool,
+    /// Seconds between autosaves (see `start_autosave`)
+    autosave_secs: AtomicU64,
+    /// Counts window moves, so `on_moved` records only the last of a drag
+    moves: AtomicU64,
+    /// Kept outside `state` so titles can be computed from sync callers
+    title: Mutex<TitleState>,
+    /// Zoom per platform (0.0 = 100%); kept outside `state` so it can be read from
+    /// sync page-load and platform-switch hooks
+    zoom: Mutex<ZoomLevels>,
+    /// Copy of what the page-load scripts read from `state`, for the same reason;
+    /// refreshed whenever those fields change
+    look: Mutex<PageLook>,
     app_data_dir: PathBuf,
 }
 
@@ -71,9 +798,20 @@ impl WindowManager {
             state: Arc::new(RwLock::new(WindowManagerState {
                 window_state: WindowState::default(),
                 default_window_state: WindowState::default(),
-                zoom_level: 0.0, // 0.0 = 100% zoom
-                saved_positions: Vec::new(),
             })),
+            zoom: Mutex::new(ZoomLevels {
+                levels: store::load_or_default(&app_data_dir.join(ZOOM_FILE)),
+                current: None,
+            }),
+            dirty: AtomicBool::new(false),
+            moves: AtomicU64::new(0),
+            look: Mutex::new(PageLook::from(&WindowState::default())),
+            title: Mutex::new(TitleState::default()),
+            autosave_secs: AtomicU64::new(
+                store::load::<u64>(&app_data_dir.join(AUTOSAVE_FILE))
+                    .filter(|secs| (MIN_AUTOSAVE_SECS..=MAX_AUTOSAVE_SECS).contains(secs))
+                    .unwrap_or(DEFAULT_AUTOSAVE_SECS),
+            ),
             app_data_dir,
         }
     }
@@ -87,15 +825,12 @@ impl WindowManager {
         if state_file.exists() {
             match fs::read_to_string(&state_file) {
                 Ok(contents) => {
-                    match serde_json::from_str(&contents) {
-                        Ok(state) => {
-                            info!("Window state loaded from file");
-                            return Ok(state);
-                        }
-                        Err(e) => {
-                            warn!("Failed to parse window state: {}", e);
-                        }
+                    let (state, repairs) = parse_window_state(&contents);
+                    for repair in &repairs {
+                        warn!("Repaired window state: {}", repair);
                     }
+                    info!("Window state loaded from file");
+                    return Ok(state);
                 }
                 Err(e) => {
                     warn!("Failed to read window state file: {}", e);
@@ -113,8 +848,8 @@ impl WindowManager {
 
         let state_file = self.app_data_dir.join("window_state.json");
 
-        let contents = serde_json::to_string_pretty(state)?;
-        fs::write(&state_file, contents)?;
+        store::save(&state_file, state)?;
+        self.dirty.store(false, Ordering::SeqCst);
 
         info!("Window state saved to file");
         Ok(())
@@ -138,19 +873,18 @@ impl WindowManager {
         *self.state.write().await = WindowManagerState {
             window_state: state.clone(),
             default_window_state: state.clone(),
-            zoom_level: 0.0,
-            saved_positions: Vec::new(),
         };
+        self.refresh_look(&state);
         
         Ok(state)
     }
 
     /// Update window state
-    #[allow(dead_code)]
     pub async fn update_window_state(&self, update: WindowState) -> Result<()> {
         debug!("Updating window state");
         
         let mut state = self.state.write().await;
+        self.refresh_look(&update);
         state.window_state = update;
         
         Ok(())
@@ -159,44 +893,118 @@ impl WindowManager {
     /// Toggle always-on-top mode
     pub async fn toggle_always_on_top(&self) -> Result<bool> {
         debug!("Toggling always-on-top");
-        
-        let mut state = self.state.write().await;
-        state.window_state.always_on_top = !state.window_state.always_on_top;
-        
-        info!("Always-on-top: {}", state.window_state.always_on_top);
-        Ok(state.window_state.always_on_top)
+
+        let enabled = !self.is_always_on_top().await;
+        self.set_always_on_top(enabled).await?;
+        Ok(enabled)
     }
 
     /// Set always-on-top mode
     pub async fn set_always_on_top(&self, enabled: bool) -> Result<()> {
         debug!("Setting always-on-top to: {}", enabled);
-        
-        let mut state = self.state.write().await;
-        state.window_state.always_on_top = enabled;
-        
+
+        let updated = {
+            let mut state = self.state.write().await;
+            state.window_state.always_on_top = enabled;
+            state.window_state.clone()
+        };
+        self.save_window_state(&updated).await?;
+
         info!("Always-on-top: {}", enabled);
         Ok(())
     }
 
+    /// Set whether an always-on-top window also stays above full-screen apps
+    pub async fn set_above_fullscreen(&self, enabled: bool) -> Result<WindowState> {
+        let updated = {
+            let mut state = self.state.write().await;
+            state.window_state.above_fullscreen = enabled;
+            state.window_state.clone()
+        };
+        self.save_window_state(&updated).await?;
+
+        info!("Above full-screen: {}", enabled);
+        Ok(updated)
+    }
+
+    /// Turn the native title bar and borders on or off
+    pub async fn set_decorations(&self, enabled: bool) -> Result<WindowState> {
+        let updated = {
+            let mut state = self.state.write().await;
+            state.window_state.decorations = enabled;
+            state.window_state.clone()
+        };
+        self.refresh_look(&updated);
+        self.save_window_state(&updated).await?;
+
+        info!("Window decorations: {}", enabled);
+        Ok(updated)
+    }
+
+    /// Keep the window on its workspace, remembering the Windows virtual desktop
+    pub async fn set_sticky_workspace(&self, enabled: bool, workspace: Option<String>) -> Result<WindowState> {
+        let updated = {
+            let mut state = self.state.write().await;
+            state.window_state.sticky_workspace = enabled;
+            state.window_state.workspace = workspace;
+            state.window_state.clone()
+        };
+        self.save_window_state(&updated).await?;
+
+        info!("Sticky workspace: {}", enabled);
+        Ok(updated)
+    }
+
+    /// Set the material shown behind the page
+    pub async fn set_window_effect(&self, effect: WindowEffect) -> Result<WindowState> {
+        let updated = {
+            let mut state = self.state.write().await;
+            state.window_state.window_effect = effect;
+            state.window_state.clone()
+        };
+        self.refresh_look(&updated);
+        self.save_window_state(&updated).await?;
+
+        info!("Window effect: {}", effect.name());
+        Ok(updated)
+    }
+
     /// Get always-on-top status
     pub async fn is_always_on_top(&self) -> bool {
         self.state.read().await.window_state.always_on_top
     }
 
-    /// Set zoom level
+    /// Current platform's zoom level
+    fn current_zoom(&self) -> f64 {
+        self.zoom.lock().map(|z| z.get()).unwrap_or(0.0)
+    }
+
+    /// Makes `platform` the one zoom commands apply to. Returns its level.
+    pub fn switch_zoom_platform(&self, platform: &str) -> f64 {
+        self.zoom.lock().map(|mut z| z.switch_to(platform)).unwrap_or(0.0)
+    }
+
+    /// Updates the zoom levels and persists them
+    fn update_zoom(&self, update: impl FnOnce(&mut ZoomLevels)) -> Result<()> {
+        let mut zoom = self.zoom.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        update(&mut zoom);
+        store::save(&self.app_data_dir.join(ZOOM_FILE), &zoom.levels)?;
+        Ok(())
+    }
+
+    /// Set the current platform's zoom level
     pub async fn set_zoom(&self, level: f64) -> Result<()> {
         debug!("Setting zoom level to: {}", level);
-        
-        let mut state = self.state.write().await;
-        state.zoom_level = level;
-        
+
+        self.update_zoom(|z| z.set(level))?;
+
         info!("Zoom level: {}%", (level + 1.0) * 100.0);
         Ok(())
     }
 
-    /// Get current zoom level
+    /// Get the current platform's zoom level
     pub async fn get_zoom(&self) -> f64 {
-        self.state.read().await.zoom_level
+        self.current_zoom()
     }
 
     /// Increase zoom level
@@ -215,18 +1023,26 @@ impl WindowManager {
         Ok(new_level)
     }
 
-    /// Reset zoom level
+    /// Reset the current platform's zoom level
     pub async fn reset_zoom(&self) -> Result<f64> {
         self.set_zoom(0.0).await?;
         Ok(0.0)
     }
 
+    /// Reset every platform's zoom level
+    pub async fn reset_all_zoom(&self) -> Result<()> {
+        self.update_zoom(|z| z.levels.clear())?;
+        info!("Zoom reset for all platforms");
+        Ok(())
+    }
+
     /// Toggle focus mode (hide sidebar, show only chat)
     pub async fn toggle_focus_mode(&self) -> Result<bool> {
         debug!("Toggling focus mode");
         
         let mut state = self.state.write().await;
         state.window_state.focus_mode = !state.window_state.focus_mode;
+        self.refresh_look(&state.window_state);
         
         info!("Focus mode: {}", state.window_state.focus_mode);
         Ok(state.window_state.focus_mode)
@@ -238,11 +1054,137 @@ impl WindowManager {
         
         let mut state = self.state.write().await;
         state.window_state.focus_mode = enabled;
+        self.refresh_look(&state.window_state);
         
         info!("Focus mode: {}", enabled);
         Ok(())
     }
 
+    /// Toggle mini mode.
+    ///
+    /// Entering saves `current` and shrinks to a compact window in the corner of
+    /// `monitor`; leaving restores the saved bounds. Always-on-top is left as is so a
+    /// pinned mini window stays pinned. The result is persisted.
+    pub async fn toggle_mini_mode(
+        &self,
+        current: WindowBounds,
+        monitor: Option<WindowBounds>,
+    ) -> Result<WindowState> {
+        debug!("Toggling mini mode");
+
+        let updated = {
+            let mut state = self.state.write().await;
+            let window = &mut state.window_state;
+            let target = if window.mini_mode {
+                window.mini_mode = false;
+                window.pre_mini_bounds.take().unwrap_or(WindowBounds {
+                    width: WindowState::default().width,
+                    height: WindowState::default().height,
+                    ..current
+                })
+            } else {
+                window.mini_mode = true;
+                window.pre_mini_bounds = Some(current);
+                mini_bounds(&monitor.unwrap_or(current))
+            };
+            window.set_bounds(target);
+            window.clone()
+        };
+        self.save_window_state(&updated).await?;
+
+        info!("Mini mode: {}", updated.mini_mode);
+        Ok(updated)
+    }
+
+    /// Dock the window to `edge` of `work_area`, saving `current` for undocking.
+    /// Re-docking an already docked window keeps the original pre-dock geometry.
+    pub async fn dock(
+        &self,
+        edge: DockEdge,
+        current: WindowBounds,
+        work_area: WindowBounds,
+    ) -> Result<WindowState> {
+        debug!("Docking window to {:?}", edge);
+
+        let updated = {
+            let mut state = self.state.write().await;
+            let window = &mut state.window_state;
+            if window.mini_mode {
+                return Err(anyhow::anyhow!("Leave mini mode before docking"));
+            }
+            if window.docked.is_none() {
+                window.pre_dock_bounds = Some(current);
+            }
+            window.docked = Some(edge);
+            window.maximized = false;
+            window.set_bounds(dock_bounds(edge, &work_area));
+            window.clone()
+        };
+        self.save_window_state(&updated).await?;
+
+        info!("Window docked: {:?}", edge);
+        Ok(updated)
+    }
+
+    /// Record the window's actual geometry and monitor, and persist it
+    pub async fn set_geometry(
+        &self,
+        bounds: WindowBounds,
+        monitor: Option<MonitorInfo>,
+        maximized: bool,
+    ) -> Result<WindowState> {
+        let updated = {
+            let mut state = self.state.write().await;
+            state.window_state.set_bounds(bounds);
+            state.window_state.monitor = monitor;
+            state.window_state.maximized = maximized;
+            state.window_state.clone()
+        };
+        self.save_window_state(&updated).await?;
+        Ok(updated)
+    }
+
+    /// Undock the window, restoring its pre-dock geometry
+    pub async fn undock(&self) -> Result<WindowState> {
+        debug!("Undocking window");
+
+        let updated = {
+            let mut state = self.state.write().await;
+            let window = &mut state.window_state;
+            if window.docked.take().is_none() {
+                return Err(anyhow::anyhow!("Window is not docked"));
+            }
+            let default = WindowState::default();
+            let target = window.pre_dock_bounds.take().unwrap_or(WindowBounds {
+                width: default.width,
+                height: default.height,
+                x: window.x,
+                y: window.y,
+            });
+            window.set_bounds(target);
+            window.clone()
+        };
+        self.save_window_state(&updated).await?;
+
+        info!("Window undocked");
+        Ok(updated)
+    }
+
+    /// Get mini mode status
+    pub async fn is_mini_mode(&self) -> bool {
+        self.state.read().await.window_state.mini_mode
+    }
+
+    /// Focus mode, decorations and window effect, readable without blocking from
+    /// sync hooks such as page load
+    pub fn page_look(&self) -> PageLook {
+        *self.look.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn refresh_look(&self, window_state: &WindowState) {
+        *self.look.lock().unwrap_or_else(|e| e.into_inner()) = PageLook::from(window_state);
+    }
+
     /// Get focus mode status
     pub async fn is_in_focus_mode(&self) -> bool {
         self.state.read().await.window_state.focus_mode
@@ -287,8 +1229,7 @@ impl WindowManager {
         Ok(!state.window_state.maximized) // Placeholder
     }
 
-    /// Set window position
-    #[allow(dead_code)]
+    /// Set window position (called as the window moves)
     pub async fn set_position(&self, x: i32, y: i32) -> Result<()> {
         debug!("Setting window position to: ({}, {})", x, y);
         
@@ -297,21 +1238,92 @@ impl WindowManager {
         state.window_state.y = y;
         
         // Track position history
-        state.saved_positions.push(PositionHistory {
-            timestamp: chrono::Utc::now().timestamp() as u64,
-            x,
-            y,
-        });
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        record_position(&mut state.window_state.position_history, x, y, now);
+        // Written by the next autosave rather than on every step of a drag
+        self.mark_dirty();
         
-        // Keep only last 100 positions
-        let len = state.saved_positions.len();
-        if len > 100 {
-            state.saved_positions.drain(0..(len - 100));
+        Ok(())
+    }
+
+    /// Title set by `set_window_title`, if any
+    pub fn title_override(&self) -> Option<String> {
+        self.title.lock().ok().and_then(|t| t.override_title.clone())
+    }
+
+    /// Account named in the window title
+    pub fn title_account(&self) -> Option<String> {
+        self.title.lock().ok().and_then(|t| t.account.clone())
+    }
+
+    /// Overrides the window title until cleared with `None`
+    pub fn set_title_override(&self, title: Option<String>) {
+        if let Ok(mut state) = self.title.lock() {
+            state.override_title = title;
         }
-        
+    }
+
+    /// Notes that the window changed without being saved (moved, resized)
+    pub fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::SeqCst);
+    }
+
+    /// Time between autosaves
+    pub fn autosave_interval(&self) -> Duration {
+        Duration::from_secs(self.autosave_secs.load(Ordering::SeqCst))
+    }
+
+    /// Sets the time between autosaves (5 s to an hour), persisted
+    pub fn set_autosave_interval(&self, secs: u64) -> Result<()> {
+        if !(MIN_AUTOSAVE_SECS..=MAX_AUTOSAVE_SECS).contains(&secs) {
+            return Err(anyhow::anyhow!(
+                "Autosave interval must be between {} and {} seconds",
+                MIN_AUTOSAVE_SECS,
+                MAX_AUTOSAVE_SECS
+            ));
+        }
+        store::save(&self.app_data_dir.join(AUTOSAVE_FILE), &secs)?;
+        self.autosave_secs.store(secs, Ordering::SeqCst);
+        info!("Window state autosave every {}s", secs);
         Ok(())
     }
 
+    /// Saves through `save` only if the window changed since the last save, so an
+    /// idle window isn't rewritten every tick. Returns whether it saved.
+    pub async fn autosave<F, Fut>(&self, save: F) -> std::result::Result<bool, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = std::result::Result<(), String>>,
+    {
+        if !self.dirty.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        save().await?;
+        Ok(true)
+    }
+
+    /// Steps back to the last position the window was at before the current one.
+    /// Returns `None` if there is nowhere to go back to.
+    pub async fn restore_previous_position(&self) -> Option<PositionHistory> {
+        let mut state = self.state.write().await;
+        let current = &mut state.window_state;
+        let previous = previous_position(&mut current.position_history, current.x, current.y)?;
+        current.x = previous.x;
+        current.y = previous.y;
+        info!("Restored previous position ({}, {})", previous.x, previous.y);
+        Some(previous)
+    }
+
+    /// Positions the window has been at, oldest first
+    pub async fn position_history(&self) -> Vec<PositionHistory> {
+        self.state.read().await.window_state.position_history.clone()
+    }
+
+    /// Forgets where the window has been
+    pub async fn clear_position_history(&self) {
+        self.state.write().await.window_state.position_history.clear();
+    }
+
     /// Set window size
     #[allow(dead_code)]
     pub async fn set_size(&self, width: i32, height: i32) -> Result<()> {
@@ -337,9 +1349,8 @@ impl WindowManager {
         *self.state.write().await = WindowManagerState {
             window_state: default.clone(),
             default_window_state: default.clone(),
-            zoom_level: 0.0,
-            saved_positions: Vec::new(),
         };
+        self.refresh_look(&default);
         
         info!("Window state reset to default");
         Ok(default)
@@ -390,27 +1401,110 @@ impl WindowManager {
     }
 }
 
-impl Default for WindowManager {
-    fn default() -> Self {
-        // Use standard app data directory
-        let app_data_dir = std::env::var("HOME")
-            .ok()
-            .map(PathBuf::from)
-            .unwrap_or_else(|| PathBuf::from("."));
+impl Default for WindowManager {
+    fn default() -> Self {
+        // Use standard app data directory
+        let app_data_dir = std::env::var("HOME")
+            .ok()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        Self::new(app_data_dir)
+    }
+}
+
+// Tauri commands
+
+/// Applies the stored always-on-top settings to the real window
+fn apply_always_on_top(window: &tauri::WebviewWindow, state: &WindowState) -> Result<(), String> {
+    crate::platform::set_always_on_top(window, state.always_on_top, state.above_fullscreen)?;
+    // On macOS above-full-screen shares the all-Spaces flag; leaving it mustn't unstick the window
+    if cfg!(target_os = "macos") && state.sticky_workspace {
+        apply_sticky_workspace(window, state)?;
+    }
+    Ok(())
+}
+
+/// Applies the stored sticky-workspace setting, returning the virtual desktop to
+/// remember (Windows)
+fn apply_sticky_workspace(window: &tauri::WebviewWindow, state: &WindowState) -> Result<Option<String>, String> {
+    crate::platform::set_sticky_workspace(window, state.sticky_workspace, state.workspace.as_deref())
+}
+
+/// Applies the stored window effect: the native material plus a transparent webview
+/// background so it isn't painted over. Linux has no effects; it only logs.
+fn apply_window_effect(window: &tauri::WebviewWindow, state: &WindowState) -> Result<(), String> {
+    use tauri::window::{Color, Effect, EffectsBuilder};
+
+    let effect = match state.window_effect {
+        WindowEffect::None => None,
+        WindowEffect::Sidebar => Some(Effect::Sidebar),
+        WindowEffect::UnderWindow => Some(Effect::UnderWindowBackground),
+        WindowEffect::Mica => Some(Effect::Mica),
+        WindowEffect::Acrylic => Some(Effect::Acrylic),
+    };
+    if cfg!(target_os = "linux") {
+        if effect.is_some() {
+            info!("Window effects aren't available on Linux; ignoring {}", state.window_effect.name());
+        }
+        return Ok(());
+    }
+    match effect {
+        Some(effect) => {
+            window
+                .set_effects(EffectsBuilder::new().effect(effect).build())
+                .map_err(|e| e.to_string())?;
+            window
+                .set_background_color(Some(Color(0, 0, 0, 0)))
+                .map_err(|e| e.to_string())
+        }
+        None => {
+            window
+                .set_effects(None::<tauri::utils::config::WindowEffectsConfig>)
+                .map_err(|e| e.to_string())?;
+            window.set_background_color(None).map_err(|e| e.to_string())
+        }
+    }
+}
 
-        Self::new(app_data_dir)
+/// Shows or hides the native title bar and borders per `state`. A borderless window
+/// stays resizable: Windows and macOS keep resizing from the edges, and on Linux tao
+/// adds its own resize borders to undecorated resizable windows.
+fn apply_decorations(window: &tauri::WebviewWindow, state: &WindowState) -> Result<(), String> {
+    let decorated = state.decorations && !state.mini_mode;
+    window.set_decorations(decorated).map_err(|e| e.to_string())?;
+    if !decorated {
+        window.set_resizable(true).map_err(|e| e.to_string())?;
     }
+    Ok(())
 }
 
-// Tauri commands
+/// Sets always-on-top on the window first and only then records it, so the stored
+/// flag never drifts from the window when the OS call fails
+async fn update_always_on_top(
+    window: &tauri::WebviewWindow,
+    window_manager: &WindowManager,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut state = window_manager.get_window_state().await;
+    state.always_on_top = enabled;
+    apply_always_on_top(window, &state)?;
+    window_manager
+        .set_always_on_top(enabled)
+        .await
+        .map_err(|e| e.to_string())
+}
 
 /// Toggle always-on-top mode
-#[tauri::command]
+#[tauri::command(async)]
 #[specta::specta]
 pub async fn toggle_always_on_top(
+    window: tauri::WebviewWindow,
     window_manager: tauri::State<'_, WindowManager>,
 ) -> Result<bool, String> {
-    window_manager.toggle_always_on_top().await.map_err(|e| e.to_string())
+    let enabled = !window_manager.is_always_on_top().await;
+    update_always_on_top(&window, &window_manager, enabled).await?;
+    Ok(enabled)
 }
 
 /// Set always-on-top mode
@@ -418,9 +1512,98 @@ pub async fn toggle_always_on_top(
 #[specta::specta]
 pub async fn set_always_on_top(
     enabled: bool,
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<(), String> {
+    update_always_on_top(&window, &window_manager, enabled).await
+}
+
+/// Set whether an always-on-top window also stays above full-screen apps (macOS only;
+/// elsewhere always-on-top already does)
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn set_above_fullscreen(
+    enabled: bool,
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<(), String> {
+    let state = window_manager
+        .set_above_fullscreen(enabled)
+        .await
+        .map_err(|e| e.to_string())?;
+    apply_always_on_top(&window, &state)
+}
+
+/// Keep the window on the user's workspace (persisted): on every workspace on Linux,
+/// on every Space on macOS, and on its current virtual desktop on Windows
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn set_sticky_workspace(
+    enabled: bool,
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<(), String> {
+    let mut state = window_manager.get_window_state().await;
+    state.sticky_workspace = enabled;
+    // Pin to the desktop the window is on now, not the one saved earlier
+    state.workspace = None;
+    let workspace = apply_sticky_workspace(&window, &state)?;
+    window_manager
+        .set_sticky_workspace(enabled, workspace)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Set the native material behind the page (persisted): `sidebar` or `under_window`
+/// vibrancy on macOS, `mica` or `acrylic` on Windows, `none` to turn it off. Errors on
+/// effects this OS doesn't have.
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn set_window_effect(
+    effect: String,
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<(), String> {
+    use tauri::Manager;
+
+    let effect = WindowEffect::parse(&effect, WindowEffect::supported())?;
+    let mut state = window_manager.get_window_state().await;
+    state.window_effect = effect;
+    apply_window_effect(&window, &state)?;
+    window_manager
+        .set_window_effect(effect)
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::injection::apply(window.app_handle(), crate::injection::WINDOW_EFFECT, &window_effect_script(effect))
+}
+
+/// Turn the window's native title bar and borders on or off (persisted). Without
+/// them the platform's header and a strip along the top edge drag the window.
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn set_decorations(
+    enabled: bool,
+    window: tauri::WebviewWindow,
     window_manager: tauri::State<'_, WindowManager>,
 ) -> Result<(), String> {
-    window_manager.set_always_on_top(enabled).await.map_err(|e| e.to_string())
+    use tauri::Manager;
+
+    let state = window_manager
+        .set_decorations(enabled)
+        .await
+        .map_err(|e| e.to_string())?;
+    apply_decorations(&window, &state)?;
+
+    let platform = window
+        .app_handle()
+        .try_state::<crate::platform_manager::PlatformManager>()
+        .and_then(|m| m.get_current().ok().flatten());
+    crate::injection::apply(
+        window.app_handle(),
+        crate::injection::DRAG_REGION,
+        &drag_region_script(enabled, platform.as_ref()),
+    )
 }
 
 /// Get always-on-top status
@@ -437,9 +1620,11 @@ pub async fn is_always_on_top(
 #[specta::specta]
 pub async fn set_zoom(
     level: f64,
+    app: tauri::AppHandle,
     window_manager: tauri::State<'_, WindowManager>,
 ) -> Result<(), String> {
-    window_manager.set_zoom(level).await.map_err(|e| e.to_string())
+    window_manager.set_zoom(level).await.map_err(|e| e.to_string())?;
+    apply_zoom(&app, level)
 }
 
 /// Get current zoom level
@@ -455,36 +1640,59 @@ pub async fn get_zoom(
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn zoom_in(
+    app: tauri::AppHandle,
     window_manager: tauri::State<'_, WindowManager>,
 ) -> Result<f64, String> {
-    window_manager.zoom_in().await.map_err(|e| e.to_string())
+    let level = window_manager.zoom_in().await.map_err(|e| e.to_string())?;
+    apply_zoom(&app, level)?;
+    Ok(level)
 }
 
 /// Zoom out
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn zoom_out(
+    app: tauri::AppHandle,
     window_manager: tauri::State<'_, WindowManager>,
 ) -> Result<f64, String> {
-    window_manager.zoom_out().await.map_err(|e| e.to_string())
+    let level = window_manager.zoom_out().await.map_err(|e| e.to_string())?;
+    apply_zoom(&app, level)?;
+    Ok(level)
 }
 
 /// Reset zoom
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn reset_zoom(
+    app: tauri::AppHandle,
     window_manager: tauri::State<'_, WindowManager>,
 ) -> Result<f64, String> {
-    window_manager.reset_zoom().await.map_err(|e| e.to_string())
+    let level = window_manager.reset_zoom().await.map_err(|e| e.to_string())?;
+    apply_zoom(&app, level)?;
+    Ok(level)
+}
+
+/// Reset zoom on every platform
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn reset_all_zoom(
+    app: tauri::AppHandle,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<(), String> {
+    window_manager.reset_all_zoom().await.map_err(|e| e.to_string())?;
+    apply_zoom(&app, 0.0)
 }
 
 /// Toggle focus mode
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn toggle_focus_mode(
+    app: tauri::AppHandle,
     window_manager: tauri::State<'_, WindowManager>,
 ) -> Result<bool, String> {
-    window_manager.toggle_focus_mode().await.map_err(|e| e.to_string())
+    let enabled = window_manager.toggle_focus_mode().await.map_err(|e| e.to_string())?;
+    crate::injection::apply(&app, crate::injection::FOCUS_MODE, &focus_mode_script(enabled))?;
+    Ok(enabled)
 }
 
 /// Set focus mode
@@ -492,9 +1700,11 @@ pub async fn toggle_focus_mode(
 #[specta::specta]
 pub async fn set_focus_mode(
     enabled: bool,
+    app: tauri::AppHandle,
     window_manager: tauri::State<'_, WindowManager>,
 ) -> Result<(), String> {
-    window_manager.set_focus_mode(enabled).await.map_err(|e| e.to_string())
+    window_manager.set_focus_mode(enabled).await.map_err(|e| e.to_string())?;
+    crate::injection::apply(&app, crate::injection::FOCUS_MODE, &focus_mode_script(enabled))
 }
 
 /// Get focus mode status
@@ -506,6 +1716,319 @@ pub async fn is_in_focus_mode(
     Ok(window_manager.is_in_focus_mode().await)
 }
 
+/// Toggle the compact mini window (thread list only, pinned to a screen corner)
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn toggle_mini_mode(
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<bool, String> {
+    use tauri::Emitter;
+
+    let current = window_bounds(&window)?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .map(|m| work_area(&m));
+
+    let state = window_manager
+        .toggle_mini_mode(current, monitor)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    apply_decorations(&window, &state)?;
+    apply_bounds(&window, &state)?;
+    apply_always_on_top(&window, &state)?;
+    window
+        .eval(&mini_mode_script(state.mini_mode))
+        .map_err(|e| e.to_string())?;
+
+    window
+        .emit("mini-mode-changed", MiniModeChangedPayload { enabled: state.mini_mode })
+        .map_err(|e| e.to_string())?;
+    Ok(state.mini_mode)
+}
+
+/// Get mini mode status
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn is_mini_mode(
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<bool, String> {
+    Ok(window_manager.is_mini_mode().await)
+}
+
+/// Dock the window to the left, right, top or bottom half of its monitor
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn dock_window(
+    edge: DockEdge,
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<WindowState, String> {
+    let current = window_bounds(&window)?;
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.primary_monitor().ok().flatten())
+        .ok_or_else(|| "No monitor available".to_string())?;
+
+    let state = window_manager
+        .dock(edge, current, work_area(&monitor))
+        .await
+        .map_err(|e| e.to_string())?;
+    window.unmaximize().map_err(|e| e.to_string())?;
+    apply_bounds(&window, &state)?;
+    Ok(state)
+}
+
+/// List the connected monitors; empty if none can be detected
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn list_monitors(window: tauri::WebviewWindow) -> Result<Vec<MonitorDetails>, String> {
+    let primary = window.primary_monitor().ok().flatten();
+    Ok(window
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|m| MonitorDetails::from_monitor(m, primary.as_ref()))
+        .collect())
+}
+
+/// Move the window to the named monitor, centered in its work area
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn move_to_monitor(
+    name: String,
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<WindowState, String> {
+    let monitors: Vec<MonitorInfo> = window
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(MonitorInfo::from_monitor)
+        .collect();
+    let monitor = find_monitor(&monitors, &name)?;
+    let bounds = center_in(&window_bounds(&window)?, &monitor.work_area);
+
+    let state = window_manager
+        .set_geometry(bounds, Some(monitor.clone()), false)
+        .await
+        .map_err(|e| e.to_string())?;
+    window.unmaximize().map_err(|e| e.to_string())?;
+    apply_bounds(&window, &state)?;
+    Ok(state)
+}
+
+/// Move the window back to where it was before its last move (e.g. after an
+/// accidental drag). Returns the position moved to, or `None` if there is none.
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn restore_previous_position(
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<Option<PositionHistory>, String> {
+    let Some(previous) = window_manager.restore_previous_position().await else {
+        return Ok(None);
+    };
+    window
+        .set_position(tauri::PhysicalPosition::new(previous.x, previous.y))
+        .map_err(|e| e.to_string())?;
+    Ok(Some(previous))
+}
+
+/// Positions the window has been at, oldest first
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn get_position_history(
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<Vec<PositionHistory>, String> {
+    Ok(window_manager.position_history().await)
+}
+
+/// Forget the window's position history
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn clear_position_history(
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<(), String> {
+    window_manager.clear_position_history().await;
+    Ok(())
+}
+
+/// Undock the window, restoring its previous size and position
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn undock_window(
+    window: tauri::WebviewWindow,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<WindowState, String> {
+    let state = window_manager.undock().await.map_err(|e| e.to_string())?;
+    apply_bounds(&window, &state)?;
+    Ok(state)
+}
+
+/// Restores the saved window geometry at startup.
+///
+/// The window goes back to the monitor it was on, clamped into it; if that monitor is
+/// gone it is centered on the primary monitor. A docked window is re-docked to the
+/// same edge of that monitor.
+pub async fn restore_window(app: &tauri::AppHandle) -> Result<(), String> {
+    use tauri::Manager;
+
+    let window_manager = app.state::<WindowManager>();
+    let saved = window_manager
+        .restore_window_state()
+        .await
+        .map_err(|e| e.to_string())?;
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    let monitors: Vec<MonitorInfo> = window
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(MonitorInfo::from_monitor)
+        .collect();
+    let primary = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|m| MonitorInfo::from_monitor(&m));
+    let (bounds, monitor) = fit_to_monitors(&saved, &monitors, primary.as_ref());
+
+    let work_area = monitor.as_ref().map(|m| m.work_area);
+    let state = match (saved.docked, work_area) {
+        (Some(edge), Some(work_area)) => window_manager.dock(edge, bounds, work_area).await,
+        _ => window_manager.set_geometry(bounds, monitor, saved.maximized).await,
+    }
+    .map_err(|e| e.to_string())?;
+
+    apply_bounds(&window, &state)?;
+    apply_always_on_top(&window, &state)?;
+    apply_decorations(&window, &state)?;
+    if let Err(e) = apply_window_effect(&window, &state) {
+        warn!("Failed to restore window effect: {}", e);
+    }
+    if state.sticky_workspace {
+        // A virtual desktop removed since last time isn't worth failing the restore over
+        if let Err(e) = apply_sticky_workspace(&window, &state) {
+            warn!("Failed to restore sticky workspace: {}", e);
+        }
+    }
+    // Maximizing would show a window that started hidden in the tray
+    if state.maximized && window.is_visible().unwrap_or(true) {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Records the window's current geometry, monitor and visibility so the next launch
+/// can restore them
+pub async fn capture_window(
+    window: &tauri::WebviewWindow,
+    window_manager: &WindowManager,
+) -> Result<(), String> {
+    let bounds = window_bounds(window)?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .map(|m| MonitorInfo::from_monitor(&m));
+    let maximized = window.is_maximized().map_err(|e| e.to_string())?;
+    let visible = window.is_visible().unwrap_or(true) && !window.is_minimized().unwrap_or(false);
+    window_manager.state.write().await.window_state.visible = visible;
+    window_manager
+        .set_geometry(bounds, monitor, maximized)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Saves the main window's state every autosave interval when it moved or resized
+/// since the last save, so a crash or power loss doesn't lose the session's geometry
+pub fn start_autosave(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let Some(window_manager) = app.try_state::<WindowManager>() else {
+                return;
+            };
+            tokio::time::sleep(window_manager.autosave_interval()).await;
+
+            let Some(window) = app.get_webview_window("main") else {
+                continue;
+            };
+            if let Err(e) = window_manager.autosave(|| capture_window(&window, &window_manager)).await {
+                warn!("Failed to autosave window state: {}", e);
+            }
+        }
+    });
+}
+
+/// Current outer bounds of a window
+fn window_bounds(window: &tauri::WebviewWindow) -> Result<WindowBounds, String> {
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    Ok(WindowBounds {
+        width: size.width as i32,
+        height: size.height as i32,
+        x: position.x,
+        y: position.y,
+    })
+}
+
+/// Work area of a monitor (excludes the taskbar / dock / menu bar)
+fn work_area(monitor: &tauri::Monitor) -> WindowBounds {
+    let area = monitor.work_area();
+    WindowBounds {
+        width: area.size.width as i32,
+        height: area.size.height as i32,
+        x: area.position.x,
+        y: area.position.y,
+    }
+}
+
+/// Returns `true` if the point lies within `bounds`
+fn contains(bounds: &WindowBounds, x: i32, y: i32) -> bool {
+    x >= bounds.x && x < bounds.x + bounds.width && y >= bounds.y && y < bounds.y + bounds.height
+}
+
+/// Moves and resizes a window to the geometry stored in `state`
+fn apply_bounds(window: &tauri::WebviewWindow, state: &WindowState) -> Result<(), String> {
+    use tauri::{PhysicalPosition, PhysicalSize};
+
+    window
+        .set_size(PhysicalSize::new(state.width as u32, state.height as u32))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(state.x, state.y))
+        .map_err(|e| e.to_string())
+}
+
+/// Builds the script that adds or removes the mini-mode stylesheet
+fn mini_mode_script(enabled: bool) -> String {
+    format!(
+        r#"
+        (() => {{
+            const id = 'messenger-desktop-mini-mode';
+            const existing = document.getElementById(id);
+            if (existing) existing.remove();
+            if (!{}) return;
+            const style = document.createElement('style');
+            style.id = id;
+            style.textContent = '{}';
+            document.head.appendChild(style);
+        }})();
+        "#,
+        enabled, MINI_MODE_CSS
+    )
+}
+
 /// Save current window state
 #[tauri::command(async)]
 #[specta::specta]
@@ -515,6 +2038,48 @@ pub async fn save_window_state(
     window_manager.save_current_state().await.map_err(|e| e.to_string())
 }
 
+/// Override the main window's title until `reset_window_title`; platform and
+/// account switches leave it alone meanwhile
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn set_window_title(
+    title: String,
+    app: tauri::AppHandle,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<(), String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Window title cannot be empty".to_string());
+    }
+    window_manager.set_title_override(Some(title.to_string()));
+    refresh_title(&app);
+    Ok(())
+}
+
+/// Drop the title override, going back to the app name, platform and account
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn reset_window_title(
+    app: tauri::AppHandle,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<String, String> {
+    window_manager.set_title_override(None);
+    refresh_title(&app);
+    Ok(default_title(&app))
+}
+
+/// Set how often (seconds, 5 to 3600) the window state is autosaved when it changed
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn set_autosave_interval(
+    seconds: u32,
+    window_manager: tauri::State<'_, WindowManager>,
+) -> Result<(), String> {
+    window_manager
+        .set_autosave_interval(seconds as u64)
+        .map_err(|e| e.to_string())
+}
+
 /// Restore window state
 #[tauri::command(async)]
 #[specta::specta]
@@ -615,6 +2180,72 @@ pub async fn toggle_fullscreen(
     window_manager.toggle_fullscreen().await.map_err(|e| e.to_string())
 }
 
+/// An open window, as listed by `list_windows`
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct WindowInfo {
+    pub label: String,
+    pub title: String,
+    /// The platform the window's page is on, if any
+    pub platform: Option<String>,
+    pub visible: bool,
+}
+
+/// Describes a window; the platform is taken from the host of the page it shows
+fn window_info(label: &str, title: &str, host: Option<&str>, visible: bool) -> WindowInfo {
+    WindowInfo {
+        label: label.to_string(),
+        title: title.to_string(),
+        platform: host
+            .and_then(crate::platform_manager::Platform::from_host)
+            .map(|p| p.name().to_string()),
+        visible,
+    }
+}
+
+/// Orders windows for a switcher: the main window first, then by label
+fn sort_windows(mut windows: Vec<WindowInfo>) -> Vec<WindowInfo> {
+    windows.sort_by(|a, b| (a.label != "main", &a.label).cmp(&(b.label != "main", &b.label)));
+    windows
+}
+
+/// List the open windows (main, popped-out conversations, incognito sessions)
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn list_windows(app: tauri::AppHandle) -> Result<Vec<WindowInfo>, String> {
+    use tauri::Manager;
+    let windows = app
+        .webview_windows()
+        .into_iter()
+        .map(|(label, window)| {
+            let host = window.url().ok().and_then(|url| url.host_str().map(str::to_string));
+            window_info(
+                &label,
+                &window.title().unwrap_or_default(),
+                host.as_deref(),
+                window.is_visible().unwrap_or(false),
+            )
+        })
+        .collect();
+    Ok(sort_windows(windows))
+}
+
+/// Show and focus a window by label. Fails if it has been closed since it was listed.
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn focus_window(label: String, app: tauri::AppHandle) -> Result<(), String> {
+    use tauri::Manager;
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window {} is no longer open", label))?;
+    if window.is_minimized().unwrap_or(false) {
+        window.unminimize().map_err(|e| e.to_string())?;
+    }
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    debug!("Focused window {}", label);
+    Ok(())
+}
+
 // Unit tests
 #[cfg(test)]
 mod tests {
@@ -642,6 +2273,29 @@ mod tests {
         assert_eq!(deserialized.x, 100);
     }
 
+    #[test]
+    fn test_previous_position_skips_duplicates() {
+        let mut history = Vec::new();
+        record_position(&mut history, 0, 0, 0);
+        record_position(&mut history, 50, 50, 10_000);
+        record_position(&mut history, 50, 50, 20_000);
+        // A drag: only where it ends is kept
+        record_position(&mut history, 60, 60, 30_000);
+        record_position(&mut history, 300, 300, 30_100);
+        assert_eq!(history.len(), 4);
+
+        let previous = previous_position(&mut history, 300, 300).unwrap();
+        assert_eq!((previous.x, previous.y), (50, 50));
+        let previous = previous_position(&mut history, 50, 50).unwrap();
+        assert_eq!((previous.x, previous.y), (0, 0));
+        assert!(previous_position(&mut history, 0, 0).is_none());
+
+        for i in 0..150 {
+            record_position(&mut history, i, i, i as u64 * DRAG_SETTLE_MS);
+        }
+        assert_eq!(history.len(), MAX_POSITION_HISTORY);
+    }
+
     #[test]
     fn test_window_manager_new() {
         let manager = WindowManager::new(PathBuf::from("/tmp"));
@@ -659,8 +2313,451 @@ mod tests {
             maximized: false,
             always_on_top: true,
             focus_mode: false,
+            mini_mode: false,
+            pre_mini_bounds: None,
+            docked: None,
+            pre_dock_bounds: None,
+            monitor: None,
+            above_fullscreen: false,
+            visible: true,
+            decorations: true,
+            sticky_workspace: false,
+            workspace: None,
+            window_effect: WindowEffect::None,
+            position_history: Vec::new(),
         };
         let cloned = state.clone();
         assert_eq!(cloned.width, 1280);
     }
+
+    #[test]
+    fn test_mini_mode_round_trip() {
+        let tmp = std::env::temp_dir().join("test-mini-mode");
+        fs::create_dir_all(&tmp).unwrap();
+        let manager = WindowManager::new(tmp.clone());
+        let current = WindowBounds { width: 1200, height: 800, x: 100, y: 50 };
+        let monitor = WindowBounds { width: 1920, height: 1080, x: 0, y: 0 };
+
+        tauri::async_runtime::block_on(async {
+            let mini = manager.toggle_mini_mode(current, Some(monitor)).await.unwrap();
+            assert!(mini.mini_mode);
+            assert_eq!((mini.width, mini.height), (MINI_WIDTH, MINI_HEIGHT));
+            assert_eq!((mini.x, mini.y), (1920 - MINI_WIDTH - MINI_MARGIN, 1080 - MINI_HEIGHT - MINI_MARGIN));
+
+            // Mini mode is persisted
+            assert!(manager.load_window_state().await.unwrap().mini_mode);
+
+            let restored = manager.toggle_mini_mode(current, Some(monitor)).await.unwrap();
+            assert!(!restored.mini_mode);
+            assert_eq!((restored.width, restored.height, restored.x, restored.y), (1200, 800, 100, 50));
+            assert!(restored.pre_mini_bounds.is_none());
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_mini_mode_keeps_always_on_top() {
+        let tmp = std::env::temp_dir().join("test-mini-mode-on-top");
+        fs::create_dir_all(&tmp).unwrap();
+        let manager = WindowManager::new(tmp.clone());
+        let current = WindowBounds { width: 1200, height: 800, x: 0, y: 0 };
+        tauri::async_runtime::block_on(async {
+            manager.set_always_on_top(true).await.unwrap();
+            let mini = manager.toggle_mini_mode(current, None).await.unwrap();
+            assert!(mini.always_on_top);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_dock_bounds() {
+        // Work area of a 1920x1080 screen with a 40px taskbar at the bottom
+        let area = WindowBounds { width: 1920, height: 1040, x: 0, y: 0 };
+        assert_eq!(dock_bounds(DockEdge::Left, &area), WindowBounds { width: 960, height: 1040, x: 0, y: 0 });
+        assert_eq!(dock_bounds(DockEdge::Right, &area), WindowBounds { width: 960, height: 1040, x: 960, y: 0 });
+        assert_eq!(dock_bounds(DockEdge::Top, &area), WindowBounds { width: 1920, height: 520, x: 0, y: 0 });
+        assert_eq!(dock_bounds(DockEdge::Bottom, &area), WindowBounds { width: 1920, height: 520, x: 0, y: 520 });
+
+        // Secondary monitor to the right, with a menu bar at the top
+        let second = WindowBounds { width: 1280, height: 775, x: 1920, y: 25 };
+        assert_eq!(dock_bounds(DockEdge::Right, &second), WindowBounds { width: 640, height: 775, x: 2560, y: 25 });
+    }
+
+    #[test]
+    fn test_dock_and_undock() {
+        let tmp = std::env::temp_dir().join("test-dock-window");
+        fs::create_dir_all(&tmp).unwrap();
+        let manager = WindowManager::new(tmp.clone());
+        let current = WindowBounds { width: 1200, height: 800, x: 100, y: 50 };
+        let area = WindowBounds { width: 1920, height: 1040, x: 0, y: 0 };
+
+        tauri::async_runtime::block_on(async {
+            manager.dock(DockEdge::Right, current, area).await.unwrap();
+            // Re-docking to another edge keeps the original geometry for undocking
+            let docked = manager.dock(DockEdge::Left, dock_bounds(DockEdge::Right, &area), area).await.unwrap();
+            assert_eq!(docked.docked, Some(DockEdge::Left));
+            assert_eq!(docked.pre_dock_bounds, Some(current));
+
+            // Dock state is persisted
+            assert_eq!(manager.load_window_state().await.unwrap().docked, Some(DockEdge::Left));
+
+            let undocked = manager.undock().await.unwrap();
+            assert!(undocked.docked.is_none());
+            assert_eq!((undocked.width, undocked.height, undocked.x, undocked.y), (1200, 800, 100, 50));
+            assert!(manager.undock().await.is_err());
+
+            manager.toggle_mini_mode(current, Some(area)).await.unwrap();
+            assert!(manager.dock(DockEdge::Left, current, area).await.is_err());
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    fn monitor(name: &str, width: i32, height: i32, x: i32) -> MonitorInfo {
+        MonitorInfo {
+            name: Some(name.to_string()),
+            work_area: WindowBounds { width, height, x, y: 0 },
+        }
+    }
+
+    fn saved_on(monitor: &MonitorInfo, x: i32, y: i32) -> WindowState {
+        WindowState {
+            width: 1200,
+            height: 800,
+            x,
+            y,
+            monitor: Some(monitor.clone()),
+            above_fullscreen: false,
+            visible: true,
+            ..WindowState::default()
+        }
+    }
+
+    #[test]
+    fn test_restore_on_same_monitor() {
+        let primary = monitor("DELL", 1920, 1080, 0);
+        let external = monitor("LG", 2560, 1440, 1920);
+        let monitors = vec![primary.clone(), external.clone()];
+
+        let (bounds, on) = fit_to_monitors(&saved_on(&external, 2200, 100), &monitors, Some(&primary));
+        assert_eq!(bounds, WindowBounds { width: 1200, height: 800, x: 2200, y: 100 });
+        assert_eq!(on, Some(external));
+    }
+
+    #[test]
+    fn test_restore_clamps_after_resolution_change() {
+        let primary = monitor("DELL", 1920, 1080, 0);
+        let before = monitor("LG", 2560, 1440, 1920);
+        // Same monitor, now running at a lower resolution
+        let after = monitor("LG", 1280, 720, 1920);
+
+        let (bounds, on) = fit_to_monitors(&saved_on(&before, 3000, 500), &[primary.clone(), after.clone()], Some(&primary));
+        assert_eq!(bounds, WindowBounds { width: 1200, height: 720, x: 2000, y: 0 });
+        assert_eq!(on, Some(after));
+    }
+
+    #[test]
+    fn test_restore_falls_back_to_primary_centered() {
+        let primary = monitor("DELL", 1920, 1080, 0);
+        let gone = monitor("LG", 2560, 1440, 1920);
+
+        let (bounds, on) = fit_to_monitors(&saved_on(&gone, 2200, 100), &[primary.clone()], Some(&primary));
+        assert_eq!(bounds, WindowBounds { width: 1200, height: 800, x: 360, y: 140 });
+        assert_eq!(on, Some(primary));
+
+        // Nothing to validate against: keep the saved geometry
+        let (bounds, on) = fit_to_monitors(&saved_on(&gone, 2200, 100), &[], None);
+        assert_eq!((bounds.x, bounds.y), (2200, 100));
+        assert!(on.is_none());
+    }
+
+    #[test]
+    fn test_zoom_factor() {
+        assert_eq!(zoom_factor(0.0), 1.0);
+        assert!((zoom_factor(0.1) - 1.1).abs() < 1e-9);
+        assert_eq!(zoom_factor(-5.0), MIN_ZOOM_FACTOR);
+        assert_eq!(zoom_factor(f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn test_focus_mode_script_toggles_one_style() {
+        assert!(focus_mode_script(true).contains("display: none"));
+        assert!(focus_mode_script(false).contains("style.remove()"));
+        assert!(!focus_mode_script(false).contains("display: none"));
+    }
+
+    #[test]
+    fn test_zoom_is_per_platform() {
+        let tmp = std::env::temp_dir().join("test-zoom-per-platform");
+        fs::create_dir_all(&tmp).unwrap();
+        let manager = WindowManager::new(tmp.clone());
+
+        tauri::async_runtime::block_on(async {
+            manager.switch_zoom_platform("Instagram");
+            manager.set_zoom(-0.1).await.unwrap();
+            assert_eq!(manager.switch_zoom_platform("Messenger"), 0.0);
+            manager.set_zoom(0.1).await.unwrap();
+            assert_eq!(manager.get_zoom().await, 0.1);
+
+            // Switching back applies the stored level
+            assert_eq!(manager.switch_zoom_platform("Instagram"), -0.1);
+            manager.reset_zoom().await.unwrap();
+            assert_eq!(manager.get_zoom().await, 0.0);
+
+            // Persisted across restarts
+            let reloaded = WindowManager::new(tmp.clone());
+            assert_eq!(reloaded.switch_zoom_platform("Messenger"), 0.1);
+            assert_eq!(reloaded.switch_zoom_platform("Instagram"), 0.0);
+
+            manager.reset_all_zoom().await.unwrap();
+            assert_eq!(manager.switch_zoom_platform("Messenger"), 0.0);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_window_state_repair() {
+        // Valid state passes untouched
+        let valid = serde_json::to_string(&WindowState::default()).unwrap();
+        let (state, repairs) = parse_window_state(&valid);
+        assert!(repairs.is_empty());
+        assert_eq!((state.width, state.height, state.x, state.y), (1200, 800, -1, -1));
+
+        // Zero size and the Windows minimized position
+        let (state, repairs) = parse_window_state(
+            r#"{"width":0,"height":-5,"x":-32000,"y":-32000,"maximized":false,"always_on_top":true,"focus_mode":false}"#,
+        );
+        assert_eq!((state.width, state.height), (MIN_WIDTH, MIN_HEIGHT));
+        assert_eq!((state.x, state.y), (-1, -1));
+        assert!(state.always_on_top);
+        assert_eq!(repairs.len(), 2);
+
+        // Overflowing / non-numeric geometry falls back to the defaults
+        let (state, repairs) = parse_window_state(
+            r#"{"width":1e20,"height":"tall","x":99999999999,"y":10,"maximized":false,"always_on_top":false,"focus_mode":false}"#,
+        );
+        assert_eq!((state.width, state.height, state.x, state.y), (1200, 800, -1, -1));
+        assert_eq!(repairs.len(), 3);
+
+        // Huge sizes are capped; mini mode keeps its narrower width
+        let (state, _) = parse_window_state(
+            r#"{"width":380,"height":100000,"x":10,"y":10,"maximized":false,"always_on_top":false,"focus_mode":false,"mini_mode":true}"#,
+        );
+        assert_eq!((state.width, state.height, state.x, state.y), (MINI_WIDTH, MAX_DIMENSION, 10, 10));
+
+        // Garbage
+        let (state, repairs) = parse_window_state("[1, 2, 3]");
+        assert_eq!(state.width, 1200);
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(parse_window_state(r#"{"width":800}"#).0.width, 1200);
+    }
+
+    #[test]
+    fn test_find_monitor() {
+        assert_eq!(find_monitor(&[], "DELL"), Err("No monitors connected".to_string()));
+
+        let monitors = [monitor("DELL", 1920, 1080, 0), monitor("LG", 2560, 1440, 1920)];
+        let lg = find_monitor(&monitors, "LG").unwrap();
+        assert_eq!(lg.work_area.x, 1920);
+        assert!(find_monitor(&monitors, "Samsung").is_err());
+
+        // Moving onto it centers the window in its work area
+        let moved = center_in(&WindowBounds { width: 1200, height: 800, x: 10, y: 10 }, &lg.work_area);
+        assert_eq!(moved, WindowBounds { width: 1200, height: 800, x: 1920 + 680, y: 320 });
+    }
+
+    #[test]
+    fn test_always_on_top_persists_for_restore() {
+        let tmp = std::env::temp_dir().join("test-always-on-top");
+        fs::create_dir_all(&tmp).unwrap();
+        let manager = WindowManager::new(tmp.clone());
+
+        tauri::async_runtime::block_on(async {
+            // Repeated toggles track the flag exactly
+            for expected in [true, false, true] {
+                assert_eq!(manager.toggle_always_on_top().await.unwrap(), expected);
+                assert_eq!(manager.is_always_on_top().await, expected);
+            }
+            manager.set_above_fullscreen(true).await.unwrap();
+
+            // A restart restores both, for `restore_window` to apply to the window
+            let restored = WindowManager::new(tmp.clone()).restore_window_state().await.unwrap();
+            assert!(restored.always_on_top);
+            assert!(restored.above_fullscreen);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_decorations_persist_and_drag_region() {
+        let tmp = std::env::temp_dir().join("test-decorations");
+        fs::create_dir_all(&tmp).unwrap();
+        let manager = WindowManager::new(tmp.clone());
+
+        tauri::async_runtime::block_on(async {
+            assert!(manager.page_look().decorations);
+            manager.set_decorations(false).await.unwrap();
+            assert!(!manager.page_look().decorations);
+            let restored = WindowManager::new(tmp.clone()).restore_window_state().await.unwrap();
+            assert!(!restored.decorations);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+
+        // Old state files without the field keep their title bar
+        let state: WindowState = serde_json::from_str(
+            r#"{"width":800,"height":600,"x":0,"y":0,"maximized":false,"always_on_top":false,"focus_mode":false}"#,
+        )
+        .unwrap();
+        assert!(state.decorations);
+
+        let x = drag_region_script(false, Some(&crate::platform_manager::Platform::X));
+        assert!(x.contains("data-tauri-drag-region"));
+        assert!(x.contains("DMConversationHeader"));
+        assert!(drag_region_script(true, None).contains("if (true) return;"));
+    }
+
+    #[test]
+    fn test_page_look_follows_window_state() {
+        let tmp = std::env::temp_dir().join("test-page-look");
+        let _ = fs::remove_dir_all(&tmp);
+        let manager = WindowManager::new(tmp.clone());
+
+        tauri::async_runtime::block_on(async {
+            assert!(!manager.page_look().focus_mode);
+            assert!(manager.toggle_focus_mode().await.unwrap());
+            assert!(manager.page_look().focus_mode);
+
+            manager.set_window_effect(WindowEffect::Acrylic).await.unwrap();
+            assert_eq!(manager.page_look().window_effect, WindowEffect::Acrylic);
+
+            manager.reset_to_default().await.unwrap();
+            let look = manager.page_look();
+            assert!(!look.focus_mode && look.decorations);
+            assert_eq!(look.window_effect, WindowEffect::None);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_window_effect_validation() {
+        let mac = [WindowEffect::None, WindowEffect::Sidebar, WindowEffect::UnderWindow];
+        assert_eq!(WindowEffect::parse("under_window", &mac), Ok(WindowEffect::UnderWindow));
+        assert_eq!(WindowEffect::parse(" None ", &mac), Ok(WindowEffect::None));
+        let err = WindowEffect::parse("mica", &mac).unwrap_err();
+        assert!(err.contains("not supported") && err.contains("sidebar, under_window"));
+        assert!(WindowEffect::parse("glass", &mac).unwrap_err().contains("Unknown"));
+        assert_eq!(WindowEffect::parse("none", &[WindowEffect::None]), Ok(WindowEffect::None));
+        assert!(WindowEffect::supported().contains(&WindowEffect::None));
+
+        assert!(window_effect_script(WindowEffect::Mica).contains("transparent"));
+        assert!(!window_effect_script(WindowEffect::None).contains("transparent"));
+
+        let tmp = std::env::temp_dir().join("test-window-effect");
+        fs::create_dir_all(&tmp).unwrap();
+        tauri::async_runtime::block_on(async {
+            WindowManager::new(tmp.clone()).set_window_effect(WindowEffect::Acrylic).await.unwrap();
+            let restored = WindowManager::new(tmp.clone()).restore_window_state().await.unwrap();
+            assert_eq!(restored.window_effect, WindowEffect::Acrylic);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_autosave_skips_unchanged_state() {
+        let tmp = std::env::temp_dir().join("test-window-autosave");
+        fs::create_dir_all(&tmp).unwrap();
+        let manager = WindowManager::new(tmp.clone());
+        let writes = std::sync::atomic::AtomicU32::new(0);
+        let (manager_ref, writes_ref) = (&manager, &writes);
+        let save = || async move {
+            writes_ref.fetch_add(1, Ordering::SeqCst);
+            manager_ref.save_current_state().await.map_err(|e| e.to_string())
+        };
+
+        tauri::async_runtime::block_on(async {
+            // Nothing changed yet
+            assert!(!manager.autosave(save).await.unwrap());
+
+            manager.set_position(40, 50).await.unwrap();
+            assert!(manager.autosave(save).await.unwrap());
+            // Saved: the next ticks have nothing to write
+            assert!(!manager.autosave(save).await.unwrap());
+            assert!(!manager.autosave(save).await.unwrap());
+            assert_eq!(writes.load(Ordering::SeqCst), 1);
+
+            // Any other save clears the flag too
+            manager.mark_dirty();
+            manager.save_current_state().await.unwrap();
+            assert!(!manager.autosave(save).await.unwrap());
+        });
+
+        assert_eq!(manager.autosave_interval(), Duration::from_secs(DEFAULT_AUTOSAVE_SECS));
+        assert!(manager.set_autosave_interval(1).is_err());
+        manager.set_autosave_interval(120).unwrap();
+        assert_eq!(WindowManager::new(tmp.clone()).autosave_interval(), Duration::from_secs(120));
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_compose_title() {
+        use crate::platform_manager::Platform;
+
+        assert_eq!(compose_title("Messenger Desktop", None, None), "Messenger Desktop");
+        assert_eq!(
+            compose_title("Messenger Desktop", Some(&Platform::Instagram), None),
+            "Messenger Desktop - Instagram"
+        );
+        assert_eq!(
+            compose_title("Messenger Desktop", Some(&Platform::Messenger), Some("Work")),
+            "Messenger Desktop - Messenger - Work"
+        );
+        // An account without a platform yet, and a blank account name
+        assert_eq!(compose_title("Messenger Desktop", None, Some("Work")), "Messenger Desktop - Work");
+        assert_eq!(compose_title("Messenger Desktop", Some(&Platform::X), Some("  ")), "Messenger Desktop - X");
+
+        let manager = WindowManager::new(std::env::temp_dir());
+        assert_eq!(manager.title_override(), None);
+        manager.set_title_override(Some("Focus".to_string()));
+        assert_eq!(manager.title_override().as_deref(), Some("Focus"));
+        manager.set_title_override(None);
+        assert_eq!(manager.title_override(), None);
+    }
+
+    #[test]
+    fn test_sticky_workspace_persists() {
+        let tmp = std::env::temp_dir().join("test-sticky-workspace");
+        fs::create_dir_all(&tmp).unwrap();
+        let manager = WindowManager::new(tmp.clone());
+
+        tauri::async_runtime::block_on(async {
+            manager
+                .set_sticky_workspace(true, Some("00000000000000000000000000000001".to_string()))
+                .await
+                .unwrap();
+            let restored = WindowManager::new(tmp.clone()).restore_window_state().await.unwrap();
+            assert!(restored.sticky_workspace);
+            assert_eq!(restored.workspace.as_deref(), Some("00000000000000000000000000000001"));
+
+            manager.set_sticky_workspace(false, None).await.unwrap();
+            let restored = WindowManager::new(tmp.clone()).restore_window_state().await.unwrap();
+            assert!(!restored.sticky_workspace);
+            assert_eq!(restored.workspace, None);
+        });
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_list_windows_reflects_open_windows() {
+        let windows = sort_windows(vec![
+            window_info("incognito-1a2b", "X (Incognito)", Some("x.com"), true),
+            window_info("conversation-messenger-42", "Ana - Messenger", Some("www.messenger.com"), false),
+            window_info("main", "Social Hub", Some("localhost"), true),
+        ]);
+        let labels: Vec<&str> = windows.iter().map(|w| w.label.as_str()).collect();
+        assert_eq!(labels, ["main", "conversation-messenger-42", "incognito-1a2b"]);
+        assert_eq!(windows[0].platform, None);
+        assert_eq!(windows[1].platform.as_deref(), Some("Messenger"));
+        assert!(!windows[1].visible);
+        assert_eq!(windows[2].platform.as_deref(), Some("X"));
+        assert_eq!(windows[2].title, "X (Incognito)");
+    }
 }
@@ -0,0 +1,3416 @@
+// @ts-nocheck
+// Generated by `npm run bindings` - do not edit by hand.
+
+/** user-defined commands **/
+
+
+export const commands = {
+/**
+ * Show a notification using the notification plugin
+ */
+async showNotification(title: string, body: string, iconUrl: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("show_notification", { title, body, iconUrl }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set Do Not Disturb mode
+ */
+async setDnd(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_dnd", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Toggle Do Not Disturb mode
+ */
+async toggleDnd() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_dnd") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get Do Not Disturb status
+ */
+async isDndEnabled() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_dnd_enabled") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set notification sound path
+ */
+async setNotificationSound(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_notification_sound", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get notification settings
+ */
+async getNotificationSettings() : Promise<Result<NotificationSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_notification_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable/disable notifications
+ */
+async setNotificationEnabled(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_notification_enabled", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset notification settings to their defaults (emits `notification-settings-changed`)
+ */
+async resetNotificationSettings() : Promise<Result<NotificationSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_notification_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable/disable notification sound
+ */
+async setNotificationSoundEnabled(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_notification_sound_enabled", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set notification sound to default
+ */
+async useDefaultNotificationSound() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("use_default_notification_sound") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Handle notification from JavaScript frontend
+ */
+async handleNotification(title: string, options: JsonValue | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("handle_notification", { title, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable Do Not Disturb for the given number of minutes. Returns the expiry (Unix ms).
+ */
+async setDndUntil(minutes: number) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_dnd_until", { minutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to turn DND (and sounds off) automatically during calls
+ */
+async setDndDuringCalls(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_dnd_during_calls", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the call configuration
+ */
+async getCallConfig() : Promise<Result<CallConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_call_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command called by the injected probe when the call state changes
+ */
+async reportCallState(state: CallState) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_call_state", { state }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the kind of call in progress on the current platform
+ */
+async getCallState() : Promise<Result<CallKind, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_call_state") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the seconds left on a timed Do Not Disturb (0 if none)
+ */
+async getDndRemaining() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_dnd_remaining") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get what the current or last Do Not Disturb period held back, by conversation
+ */
+async getDndDigest() : Promise<Result<DndDigest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_dnd_digest") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable/disable the summary notification shown when Do Not Disturb ends
+ */
+async setDigestOnDndEnd(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_digest_on_dnd_end", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set how many notifications may be shown per second, and the burst allowed
+ */
+async setNotificationRateLimit(perSecond: number, burst: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_notification_rate_limit", { perSecond, burst }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set whether a conversation's toasts collapse into one group in the Windows
+ * Action Center, and the app identity (AUMID) they are shown under
+ */
+async setToastGrouping(enabled: boolean, appId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_toast_grouping", { enabled, appId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable/disable holding notifications back while the system's Do Not Disturb
+ * (Focus Assist on Windows, Focus on macOS) is on
+ */
+async setRespectSystemDnd(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_respect_system_dnd", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the notification toast actions (built-ins first)
+ */
+async listNotificationActions() : Promise<Result<NotificationAction[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_notification_actions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Register a custom notification toast action
+ */
+async registerNotificationAction(id: string, title: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("register_notification_action", { id, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a custom notification toast action
+ */
+async unregisterNotificationAction(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unregister_notification_action", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Called by the platform glue when a toast button is clicked. Applies built-in
+ * actions and emits `notification-action`.
+ */
+async invokeNotificationAction(actionId: string, conversationId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("invoke_notification_action", { actionId, conversationId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Called with the activation arguments of a Windows toast button
+ * (`action=<id>&conversation=<id>`); see `invoke_notification_action`
+ */
+async handleToastActivation(arguments: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("handle_toast_activation", { arguments }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes a still-shown notification from the notification center. Returns false
+ * if it was no longer tracked.
+ */
+async dismissNotification(id: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("dismiss_notification", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clears all of the app's notifications from the notification center
+ */
+async dismissAllNotifications() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("dismiss_all_notifications") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Called by `THREAD_READ_HOOK_JS` when a conversation is open in the focused
+ * window: its notifications have been read, so they leave the notification center
+ */
+async reportConversationRead(conversationId: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_conversation_read", { conversationId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add a notification filter by sender or body keyword
+ */
+async addNotificationFilter(field: FilterField, action: FilterAction, pattern: string, regex: boolean) : Promise<Result<NotificationFilter, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_notification_filter", { field, action, pattern, regex }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a notification filter
+ */
+async removeNotificationFilter(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_notification_filter", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List notification filters
+ */
+async listNotificationFilters() : Promise<Result<NotificationFilter[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_notification_filters") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get notification history (including suppressed notifications), oldest first
+ */
+async getNotificationHistory() : Promise<Result<NotificationRecord[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_notification_history") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Mute a conversation for the given number of minutes (0 = until unmuted)
+ */
+async muteConversation(conversationId: string, minutes: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mute_conversation", { conversationId, minutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Unmute a conversation
+ */
+async unmuteConversation(conversationId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unmute_conversation", { conversationId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Let a conversation through DND and mutes for the given number of minutes
+ * (0 = until cleared)
+ */
+async prioritizeConversation(conversationId: string, minutes: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("prioritize_conversation", { conversationId, minutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a conversation's priority
+ */
+async clearPriority(conversationId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_priority", { conversationId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List prioritized conversations with their expiry (Unix ms, `None` = until cleared)
+ */
+async listPrioritized() : Promise<Result<Partial<{ [key in string]: number | null }>, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_prioritized") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Send a test notification through the OS display path and play the sound.
+ *
+ * Unlike `show_notification` this ignores DND, mutes and filters so the test always
+ * shows, and it is not recorded in history. Fails if the OS refuses the notification.
+ */
+async sendTestNotification() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_test_notification") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Play the message-sent sound (called by the injected composer hook)
+ */
+async playSentSound() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("play_sent_sound") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable/disable the message-sent sound
+ */
+async setSentSoundEnabled(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sent_sound_enabled", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the message-sent sound (`None` for the system default)
+ */
+async setSentSound(path: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sent_sound", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the notification preview mode for a platform, or the default with no platform
+ */
+async setPreviewMode(mode: PreviewMode, platform: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_preview_mode", { mode, platform }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a platform's preview mode override
+ */
+async clearPreviewMode(platform: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_preview_mode", { platform }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Give a platform its own DND schedule (HH:MM, overnight allowed); platforms
+ * without one use the global schedule
+ */
+async setPlatformDndSchedule(platform: string, start: string, end: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_platform_dnd_schedule", { platform, start, end }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a platform's DND schedule, so the global one applies again
+ */
+async clearPlatformDndSchedule(platform: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_platform_dnd_schedule", { platform }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Toggle always-on-top mode
+ */
+async toggleAlwaysOnTop() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_always_on_top") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set always-on-top mode
+ */
+async setAlwaysOnTop(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_always_on_top", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get always-on-top status
+ */
+async isAlwaysOnTop() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_always_on_top") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set whether an always-on-top window also stays above full-screen apps (macOS only;
+ * elsewhere always-on-top already does)
+ */
+async setAboveFullscreen(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_above_fullscreen", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Keep the window on the user's workspace (persisted): on every workspace on Linux,
+ * on every Space on macOS, and on its current virtual desktop on Windows
+ */
+async setStickyWorkspace(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_sticky_workspace", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the native material behind the page (persisted): `sidebar` or `under_window`
+ * vibrancy on macOS, `mica` or `acrylic` on Windows, `none` to turn it off. Errors on
+ * effects this OS doesn't have.
+ */
+async setWindowEffect(effect: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_window_effect", { effect }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set how often (seconds, 5 to 3600) the window state is autosaved when it changed
+ */
+async setAutosaveInterval(seconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_autosave_interval", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Override the main window's title until `reset_window_title`; platform and
+ * account switches leave it alone meanwhile
+ */
+async setWindowTitle(title: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_window_title", { title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Drop the title override, going back to the app name, platform and account
+ */
+async resetWindowTitle() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_window_title") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turn the window's native title bar and borders on or off (persisted). Without
+ * them the platform's header and a strip along the top edge drag the window.
+ */
+async setDecorations(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_decorations", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set window zoom level
+ */
+async setZoom(level: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_zoom", { level }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get current zoom level
+ */
+async getZoom() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_zoom") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Zoom in
+ */
+async zoomIn() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("zoom_in") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Zoom out
+ */
+async zoomOut() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("zoom_out") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset zoom
+ */
+async resetZoom() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_zoom") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset zoom on every platform
+ */
+async resetAllZoom() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_all_zoom") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get zoom formatted string
+ */
+async getZoomFormatted() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_zoom_formatted") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get zoom percentage for display
+ */
+async getZoomPercentage() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_zoom_percentage") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Toggle focus mode
+ */
+async toggleFocusMode() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_focus_mode") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set focus mode
+ */
+async setFocusMode(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_focus_mode", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get focus mode status
+ */
+async isInFocusMode() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_in_focus_mode") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Toggle the compact mini window (thread list only, pinned to a screen corner)
+ */
+async toggleMiniMode() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_mini_mode") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get mini mode status
+ */
+async isMiniMode() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_mini_mode") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Dock the window to the left, right, top or bottom half of its monitor
+ */
+async dockWindow(edge: DockEdge) : Promise<Result<WindowState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("dock_window", { edge }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Undock the window, restoring its previous size and position
+ */
+async undockWindow() : Promise<Result<WindowState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undock_window") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the connected monitors; empty if none can be detected
+ */
+async listMonitors() : Promise<Result<MonitorDetails[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_monitors") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move the window to the named monitor, centered in its work area
+ */
+async moveToMonitor(name: string) : Promise<Result<WindowState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_to_monitor", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move the window back to where it was before its last move (e.g. after an
+ * accidental drag). Returns the position moved to, or `None` if there is none.
+ */
+async restorePreviousPosition() : Promise<Result<PositionHistory | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_previous_position") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Positions the window has been at, oldest first
+ */
+async getPositionHistory() : Promise<Result<PositionHistory[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_position_history") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Forget the window's position history
+ */
+async clearPositionHistory() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_position_history") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get current window state
+ */
+async getWindowState() : Promise<Result<WindowState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_window_state") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save current window state
+ */
+async saveWindowState() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_window_state") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restore window state
+ */
+async restoreWindowState() : Promise<Result<WindowState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_window_state") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset to default window state
+ */
+async resetWindowState() : Promise<Result<WindowState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_window_state") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Toggle fullscreen
+ */
+async toggleFullscreen() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_fullscreen") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Toggle maximize window
+ */
+async toggleMaximize() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_maximize") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set maximize state
+ */
+async setMaximized(maximized: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_maximized", { maximized }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get maximize state
+ */
+async isMaximized() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_maximized") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Minimize to tray
+ */
+async minimizeToTray() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("minimize_to_tray") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restore from tray
+ */
+async restoreFromTray() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_from_tray") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the open windows (main, popped-out conversations, incognito sessions)
+ */
+async listWindows() : Promise<Result<WindowInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_windows") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Show and focus a window by label. Fails if it has been closed since it was listed.
+ */
+async focusWindow(label: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("focus_window", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async initTray() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("init_tray") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateUnreadCount(count: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_unread_count", { count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command called by the injected poller with what it found in the page
+ */
+async reportUnreadCount(domCount: number | null, title: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_unread_count", { domCount, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the unread count of each account
+ */
+async getUnreadByAccount() : Promise<Result<Partial<{ [key in string]: number }>, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_unread_by_account") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to configure the background unread poll
+ */
+async setBackgroundUnreadPoll(enabled: boolean, intervalSecs: number) : Promise<Result<BadgeConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_background_unread_poll", { enabled, intervalSecs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to choose what the unread badge counts. `None` hides the badge;
+ * notifications are unaffected.
+ */
+async setBadgeMode(mode: BadgeMode) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_badge_mode", { mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the badge configuration
+ */
+async getBadgeConfig() : Promise<Result<BadgeConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_badge_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setTrayTooltip(text: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tray_tooltip", { text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets a custom tray icon from an image file; `None` goes back to the app icon
+ */
+async setTrayIcon(path: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tray_icon", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turns the per-platform tray icon on or off
+ */
+async setTrayIconPerPlatform(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tray_icon_per_platform", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets the tray icon choice
+ */
+async getTrayIcon() : Promise<Result<TrayIconConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_tray_icon") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether the tray came up, so the UI can hide controls that need it
+ */
+async isTrayAvailable() : Promise<boolean> {
+    return await TAURI_INVOKE("is_tray_available");
+},
+/**
+ * Tauri command called by the injected probe with what it found
+ */
+async reportConnectionStatus(online: boolean, loadError: boolean, loggedIn: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_connection_status", { online, loadError, loggedIn }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the active platform's connection status. Also re-runs the
+ * probe, so a change shows up as `connection-status-changed` shortly after.
+ * `None` until the first probe has reported.
+ */
+async getConnectionStatus() : Promise<Result<ConnectionStatus | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_connection_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to turn auto-reload on reconnect on or off
+ */
+async setAutoReconnect(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_auto_reconnect", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the network configuration
+ */
+async getAutoReconnect() : Promise<Result<NetworkConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_auto_reconnect") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to save every image and video of the open conversation to
+ * `dest_dir`. Emits `media-export-progress` after each file; files that fail are
+ * listed in the returned manifest instead of aborting the export.
+ */
+async exportConversationMedia(conversationId: string, destDir: string) : Promise<Result<MediaManifest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_conversation_media", { conversationId, destDir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command called by the injected collector with the conversation's media
+ * URLs; `None` if the conversation isn't open
+ */
+async reportConversationMedia(requestId: string, urls: string[] | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_conversation_media", { requestId, urls }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command called by the injected probe with what it read
+ */
+async reportIdentity(name: string | null, handle: string | null, avatarUrl: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_identity", { name, handle, avatarUrl }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get who is logged in to `platform`: `None` if logged out or
+ * not opened yet. For the active platform it also re-reads the page, so a change
+ * shows up as `identity-changed` shortly after.
+ */
+async getLoggedInIdentity(platform: string) : Promise<Result<Identity | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_logged_in_identity", { platform }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command called by the injected hook with the page's history state
+ */
+async reportHistoryState(canGoBack: boolean, canGoForward: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_history_state", { canGoBack, canGoForward }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to go back within the platform
+ */
+async webviewBack() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("webview_back") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to go forward within the platform
+ */
+async webviewForward() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("webview_forward") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to check whether back is available
+ */
+async canGoBack() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("can_go_back") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to check whether forward is available
+ */
+async canGoForward() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("can_go_forward") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async initShortcuts() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("init_shortcuts") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async registerShortcuts() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("register_shortcuts") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateShortcut(action: string, keys: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_shortcut", { action, keys }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unregisterShortcut(action: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unregister_shortcut", { action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to define a new global shortcut. Pressing `keys` emits
+ * `global-shortcut-pressed` with `action`. The binding is persisted.
+ */
+async registerCustomShortcut(action: string, keys: string) : Promise<Result<null, ShortcutError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("register_custom_shortcut", { action, keys }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to remove a shortcut added with `register_custom_shortcut`
+ */
+async unregisterCustomShortcut(action: string) : Promise<Result<null, ShortcutError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unregister_custom_shortcut", { action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to list every shortcut binding, built-in and custom
+ */
+async listShortcuts() : Promise<Result<ShortcutBinding[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_shortcuts") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to hide the app, wipe all sessions and the clipboard (and media
+ * when `wipe_media` is set), then quit. Does not prompt.
+ */
+async panicWipe(wipeMedia: boolean) : Promise<null> {
+    return await TAURI_INVOKE("panic_wipe", { wipeMedia });
+},
+/**
+ * Tauri command to focus the platform's search box (or request the overlay)
+ */
+async focusSearch() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("focus_search") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setTheme(name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_theme", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getThemes() : Promise<string[]> {
+    return await TAURI_INVOKE("get_themes");
+},
+async setCustomCss(css: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_custom_css", { css }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async currentThemeName() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("current_theme_name") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resets the theme to the default (re-emits `set-theme`).
+ * The default is saved over `theme.json`.
+ */
+async resetTheme() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_theme") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Recolors the platform's accent (buttons, links) with `hex` (`#rgb` or `#rrggbb`)
+ * on top of the current theme. Returns the normalized color.
+ */
+async setAccentColor(hex: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_accent_color", { hex }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes the accent color override
+ */
+async clearAccentColor() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_accent_color") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The accent color override, if one is set
+ */
+async getAccentColor() : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_accent_color") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to choose how tightly messages are packed
+ */
+async setMessageDensity(mode: MessageDensity) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_message_density", { mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the message density
+ */
+async getMessageDensity() : Promise<Result<MessageDensity, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_message_density") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setPrivacy(blockTyping: boolean, blockReadReceipts: boolean, hideLastActive: boolean, blockLinkPreviews: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_privacy", { blockTyping, blockReadReceipts, hideLastActive, blockLinkPreviews }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getPrivacy() : Promise<Result<PrivacyConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_privacy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setBlockTyping(value: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_block_typing", { value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setBlockReadReceipts(value: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_block_read_receipts", { value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Blocks or allows read receipts in one conversation (keyed by the id in its URL).
+ * Returns whether receipts end up blocked there.
+ */
+async setConversationReadReceipts(conversationId: string, blocked: boolean) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_conversation_read_receipts", { conversationId, blocked }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listBlockedReceiptConversations() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_blocked_receipt_conversations") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearBlockedReceiptConversations() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_blocked_receipt_conversations") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setHideLastActive(value: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_hide_last_active", { value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setBlockLinkPreviews(value: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_block_link_previews", { value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resets the privacy guard to its defaults (re-emits `update-privacy`).
+ * The defaults are saved over `privacy.json`.
+ */
+async resetPrivacyConfig() : Promise<Result<PrivacyConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_privacy_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Check for updates.
+ */
+async checkUpdate() : Promise<Result<UpdateInfo | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_update") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Install an update.
+ */
+async installUpdate() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_update") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Get the current app version.
+ */
+async getCurrentVersion() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_current_version") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Get the last update check time.
+ */
+async getLastCheckTime() : Promise<Result<number | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_last_check_time") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Set the release channel.
+ */
+async setChannel(channel: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_channel", { channel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Get the current release channel.
+ */
+async getChannel() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_channel") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: List all accounts.
+ */
+async listAccounts() : Promise<Account[]> {
+    return await TAURI_INVOKE("list_accounts");
+},
+/**
+ * Tauri command: Add an account.
+ */
+async addAccount(name: string) : Promise<Result<Account, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_account", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Remove an account.
+ */
+async removeAccount(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_account", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Switch to an account, restoring the platform last used with it.
+ */
+async switchAccount(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("switch_account", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Set profile picture for an account.
+ */
+async setProfilePicture(id: string, path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_profile_picture", { id, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Set session token for an account.
+ */
+async setSessionToken(id: string, token: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_token", { id, token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Get session token for an account.
+ */
+async getSessionToken(id: string) : Promise<string | null> {
+    return await TAURI_INVOKE("get_session_token", { id });
+},
+/**
+ * Tauri command: Update last sync time for an account.
+ */
+async updateLastSync(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_last_sync", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Get media permissions.
+ */
+async getMediaPermissions() : Promise<Result<MediaPermissions, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_media_permissions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Grant media permission.
+ */
+async grantMediaPermission(permissionType: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("grant_media_permission", { permissionType }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Save a media file.
+ */
+async saveMediaFile(name: string, data: number[]) : Promise<Result<MediaFile, MediaSaveError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_media_file", { name, data }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Get a media file by ID.
+ */
+async getMediaFileCommand(id: string) : Promise<Result<MediaFile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_media_file_command", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Generate a preview for a media file.
+ */
+async generatePreviewCommand(id: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_preview_command", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Delete a media file by ID.
+ */
+async deleteMediaFileCommand(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_media_file_command", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Enable spellcheck.
+ */
+async enableSpellcheck() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enable_spellcheck") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Disable spellcheck.
+ */
+async disableSpellcheck() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("disable_spellcheck") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Set spellcheck language.
+ */
+async setSpellcheckLanguage(lang: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_spellcheck_language", { lang }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Get available spellcheck languages.
+ */
+async getAvailableLanguages() : Promise<string[]> {
+    return await TAURI_INVOKE("get_available_languages");
+},
+/**
+ * Tauri command: Check if a word is misspelled.
+ */
+async isMisspelled(word: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_misspelled", { word }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Get suggestions for a misspelled word.
+ */
+async getSuggestions(word: string) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_suggestions", { word }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Check a text for misspelled words.
+ */
+async checkText(text: string) : Promise<Result<([number, number, string])[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_text", { text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command: Handle file drop.
+ */
+async handleFileDrop(paths: string[]) : Promise<FileDropPayload> {
+    return await TAURI_INVOKE("handle_file_drop", { paths });
+},
+/**
+ * Tauri command: Validate dropped files.
+ */
+async validateFiles(paths: string[]) : Promise<FileDropInfo[]> {
+    return await TAURI_INVOKE("validate_files", { paths });
+},
+/**
+ * Tauri command to select a platform by name
+ */
+async selectPlatform(platformName: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("select_platform", { platformName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the currently selected platform
+ */
+async getCurrentPlatform() : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_current_platform") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the last used platform from storage
+ */
+async getLastPlatform() : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_last_platform") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to list the platforms in switcher order. Hidden platforms are
+ * left out unless `include_hidden` is set; each entry carries a `visible` flag.
+ */
+async listPlatforms(includeHidden: boolean | null) : Promise<Result<JsonValue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_platforms", { includeHidden }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to save the order of platforms in the switcher
+ */
+async setPlatformOrder(order: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_platform_order", { order }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to show or hide a platform in the switcher
+ */
+async setPlatformVisibility(name: string, visible: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_platform_visibility", { name, visible }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clears the session for a specific platform.
+ *
+ * # Arguments
+ *
+ * * `platform` - The platform name.
+ * * `engine` - The Tauri state containing the `PrivacyEngine` instance.
+ *
+ * # Returns
+ *
+ * `Ok(())` on success, or an error message on failure.
+ */
+async clearPlatformSession(platform: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_platform_session", { platform }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clears the cache of the webview profile while keeping the platforms logged in.
+ * The platforms share the profile and the webview doesn't keep its cache per site,
+ * so this is profile-wide: it frees the cache of every platform at once.
+ *
+ * # Arguments
+ *
+ * * `app` - The app handle, used to emit `webview-cache-cleared`.
+ * * `engine` - The Tauri state containing the `PrivacyEngine` instance.
+ *
+ * # Returns
+ *
+ * The number of bytes freed, or an error message on failure.
+ */
+async clearWebviewCache() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_webview_cache") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns how much disk the platforms' shared webview profile uses: per platform,
+ * shared between them, and how much of it is cache (see `clear_webview_cache` to
+ * free it). The profile is walked off the main thread, as a long-used one can hold
+ * many files.
+ *
+ * # Arguments
+ *
+ * * `engine` - The Tauri state containing the `PrivacyEngine` instance.
+ *
+ * # Returns
+ *
+ * The bytes used per platform, shared, by the cache and in total, or an error
+ * message on failure.
+ */
+async getSessionUsage() : Promise<Result<SessionUsage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_usage") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Logs out of a platform by removing the webview's cookies for its domains
+ * (unlike `clear_webview_cache`, which keeps the login). The platforms share the
+ * webview profile, but their cookies are scoped by domain, so the other platforms
+ * stay logged in. If it is the active platform, the page's storage is cleared too
+ * and it is sent to the login page. Emits `logged-out`.
+ *
+ * The cookie store is read off the main thread, as WebView2 deadlocks otherwise.
+ *
+ * # Arguments
+ *
+ * * `platform` - The platform name.
+ * * `app` - The app handle, used to emit `logged-out`.
+ * * `manager` - The Tauri state containing the `PlatformManager` instance.
+ *
+ * # Returns
+ *
+ * `Ok(())` on success, or an error message on failure.
+ */
+async logoutPlatform(platform: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("logout_platform", { platform }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clears all sessions.
+ *
+ * # Arguments
+ *
+ * * `engine` - The Tauri state containing the `PrivacyEngine` instance.
+ *
+ * # Returns
+ *
+ * `Ok(())` on success, or an error message on failure.
+ */
+async clearAllSessions() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_all_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the Content Security Policy (CSP) for a given platform.
+ *
+ * # Arguments
+ *
+ * * `platform` - The platform name.
+ *
+ * # Returns
+ *
+ * The CSP string for the platform.
+ */
+async getCspForPlatform(platform: string) : Promise<string> {
+    return await TAURI_INVOKE("get_csp_for_platform", { platform });
+},
+/**
+ * Tauri command to back up the platforms' sessions to a zip archive.
+ * The archive contains auth data and must be kept private.
+ */
+async exportSession(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_session", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to restore the platforms' sessions from a zip archive. Applies
+ * after a restart (`restart_app`).
+ */
+async importSession(path: string, force: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_session", { path, force }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to set the PIN and idle timeout (minutes, 0 = manual only).
+ * Passing no PIN turns the lock off.
+ */
+async setAppLock(pin: string | null, idleMinutes: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_app_lock", { pin, idleMinutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to lock the app immediately
+ */
+async lockAppNow() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lock_app_now") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to unlock the app. Returns `false` for a wrong PIN.
+ */
+async unlockApp(pin: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_app", { pin }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command called by the injected activity hook
+ */
+async reportActivity() : Promise<null> {
+    return await TAURI_INVOKE("report_activity");
+},
+/**
+ * Tauri command to get the lock state
+ */
+async getAppLockStatus() : Promise<Result<LockStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_app_lock_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to pin a conversation with an optional note
+ */
+async addPin(platform: string, conversationId: string, note: string | null) : Promise<Result<Pin, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_pin", { platform, conversationId, note }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to list pins, oldest first
+ */
+async listPins() : Promise<Result<Pin[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_pins") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to remove a pin
+ */
+async removePin(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_pin", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to open a pinned conversation (emits `navigate-conversation`)
+ */
+async openPin(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_pin", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to schedule a reminder to send `text` at `at_timestamp` (Unix ms)
+ */
+async scheduleReminder(conversationId: string, text: string, atTimestamp: number) : Promise<Result<Reminder, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("schedule_reminder", { conversationId, text, atTimestamp }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to list pending reminders, soonest first
+ */
+async listReminders() : Promise<Result<Reminder[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_reminders") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to cancel a pending reminder
+ */
+async cancelReminder(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_reminder", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to open a platform in a new incognito window. Returns the session id.
+ * Async: a sync command runs on the main thread, and building a window there
+ * deadlocks on Windows (WebView2).
+ */
+async startIncognitoSession(platform: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_incognito_session", { platform }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to close an incognito session and delete its data
+ */
+async endIncognitoSession(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("end_incognito_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to list the open incognito session ids
+ */
+async listIncognitoSessions() : Promise<string[]> {
+    return await TAURI_INVOKE("list_incognito_sessions");
+},
+/**
+ * Tauri command to open links to `domain` (and its subdomains) in an in-app popup.
+ * Returns the updated allowlist.
+ */
+async addInappDomain(domain: string) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_inapp_domain", { domain }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to send links to `domain` back to the system browser. Returns the
+ * updated allowlist.
+ */
+async removeInappDomain(domain: string) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_inapp_domain", { domain }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to list the domains whose links open in-app
+ */
+async listInappDomains() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_inapp_domains") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to open a conversation in its own window (or focus it if it is
+ * already open). `title` is the conversation / sender name for the window title.
+ * Returns the window label. Async: a sync command runs on the main thread, and
+ * building a window there deadlocks on Windows (WebView2).
+ */
+async openConversationWindow(platform: string, conversationId: string, title: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_conversation_window", { platform, conversationId, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to close a popped-out conversation window
+ */
+async closeConversationWindow(label: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("close_conversation_window", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to list the popped-out conversations
+ */
+async listConversationWindows() : Promise<ConversationWindow[]> {
+    return await TAURI_INVOKE("list_conversation_windows");
+},
+/**
+ * Tauri command to configure the session keepalive
+ */
+async setSessionKeepalive(enabled: boolean, minutes: number) : Promise<Result<KeepaliveConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_keepalive", { enabled, minutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the session keepalive configuration
+ */
+async getSessionKeepalive() : Promise<Result<KeepaliveConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_keepalive") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to turn the bandwidth saver on or off
+ */
+async setBandwidthSaver(enabled: boolean, skipTrackers: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_bandwidth_saver", { enabled, skipTrackers }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the bandwidth saver configuration
+ */
+async getBandwidthSaver() : Promise<Result<BandwidthSaverConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_bandwidth_saver") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to set whether media may autoplay: `allow`, `muted_only` (muted
+ * first) or `block` (only when the user starts it)
+ */
+async setAutoplayPolicy(policy: AutoplayPolicy) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_autoplay_policy", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the autoplay policy
+ */
+async getAutoplayPolicy() : Promise<Result<AutoplayPolicy, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_autoplay_policy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to configure image resizing. `enabled` resizes without asking.
+ */
+async setAutoResizeImages(enabled: boolean, maxDimension: number, quality: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_auto_resize_images", { enabled, maxDimension, quality }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the image-resize configuration
+ */
+async getImageResizeConfig() : Promise<Result<ImageResizeConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_image_resize_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command used by the page hook: resizes an image with the current settings.
+ * Returns `None` if the original should be sent as-is.
+ */
+async resizeImage(data: number[]) : Promise<Result<ResizedImage | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resize_image", { data }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to choose whether Enter sends (Shift+Enter for a new line) or
+ * inserts a new line (Shift/Ctrl/Cmd+Enter to send)
+ */
+async setSendOnEnter(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_send_on_enter", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the composer configuration
+ */
+async getComposerConfig() : Promise<Result<ComposerConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_composer_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to add (or replace) a snippet: typing `trigger` as a word in the
+ * composer, then a space, expands it. Returns the snippets.
+ */
+async addSnippet(trigger: string, expansion: string) : Promise<Result<Snippet[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_snippet", { trigger, expansion }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to remove a snippet. Returns the snippets.
+ */
+async removeSnippet(trigger: string) : Promise<Result<Snippet[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_snippet", { trigger }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to list the snippets, ordered by trigger
+ */
+async listSnippets() : Promise<Result<Snippet[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_snippets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to enable or disable launching at login
+ */
+async setAutostart(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_autostart", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to check whether the app launches at login
+ */
+async isAutostartEnabled() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_autostart_enabled") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to set whether a launch at login starts hidden in the tray
+ */
+async setStartHidden(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_start_hidden", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to set whether a normal launch always shows the window, instead of
+ * restoring whether it was hidden at quit
+ */
+async setAlwaysStartVisible(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_always_start_visible", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the startup preferences
+ */
+async getStartupConfig() : Promise<Result<StartupConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_startup_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to route the webview through a proxy (applies after restart)
+ */
+async setProxy(url: string) : Promise<Result<ProxyConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_proxy", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to remove the proxy (applies after restart)
+ */
+async clearProxy() : Promise<Result<ProxyConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_proxy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the proxy configuration
+ */
+async getProxy() : Promise<Result<ProxyConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_proxy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to turn hardware acceleration on or off (applies after restart)
+ */
+async setHardwareAcceleration(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_hardware_acceleration", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the GPU configuration
+ */
+async getHardwareAcceleration() : Promise<Result<GpuConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_hardware_acceleration") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get all settings as one consistent snapshot
+ */
+async getAllSettings() : Promise<Result<AppSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_all_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Apply a full settings snapshot
+ */
+async applySettings(settings: AppSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export all settings to a versioned JSON file.
+ *
+ * Accounts (which carry session tokens) are only written when `include_accounts` is set.
+ */
+async exportSettings(path: string, includeAccounts: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_settings", { path, includeAccounts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import settings from a file written by `export_settings`.
+ *
+ * Accounts in the file are ignored unless `include_accounts` is set. Everything is
+ * parsed and validated before anything is applied; if applying fails part way the
+ * settings and shortcuts from before are put back.
+ */
+async importSettings(path: string, includeAccounts: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_settings", { path, includeAccounts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Read text from the clipboard.
+ */
+async readClipboardText() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_clipboard_text") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write text to the clipboard.
+ */
+async writeClipboardText(text: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_clipboard_text", { text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Print the current page.
+ */
+async printPage() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("print_page") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restart the app, e.g. after a setting that only applies at startup changed.
+ * Emits `before-restart` and saves the window state first. `restart` exits
+ * without closing the window, so the quit cleanup (which clears sessions) doesn't
+ * run and sessions are kept.
+ */
+async restartApp() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restart_app") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Open the webview DevTools inspector on the given window.
+ */
+async openDevtools() : Promise<null> {
+    return await TAURI_INVOKE("open_devtools");
+},
+/**
+ * Close the webview DevTools inspector on the given window.
+ */
+async closeDevtools() : Promise<null> {
+    return await TAURI_INVOKE("close_devtools");
+},
+/**
+ * Toggle DevTools — opens if closed, closes if open.
+ */
+async toggleDevtools() : Promise<null> {
+    return await TAURI_INVOKE("toggle_devtools");
+},
+/**
+ * Returns `true` if DevTools are currently open on the given window.
+ */
+async isDevtoolsOpen() : Promise<boolean> {
+    return await TAURI_INVOKE("is_devtools_open");
+},
+/**
+ * Tauri command to get build and runtime diagnostics
+ */
+async getDiagnostics() : Promise<Result<Diagnostics, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_diagnostics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get the current log level
+ */
+async getLogLevel() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_log_level") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to change the log level at runtime (trace, debug, info, warn, error)
+ */
+async setLogLevel(level: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_log_level", { level }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to reveal the log directory in the file manager. Returns the log
+ * file's path.
+ */
+async openLogFile() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_log_file") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to write a diagnostics zip (logs + settings, no accounts or
+ * credentials) to `path` for attaching to a bug report
+ */
+async collectDiagnostics(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("collect_diagnostics", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to turn safe mode on for the rest of this run
+ */
+async enterSafeMode() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enter_safe_mode") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to leave safe mode, restoring injections and the theme
+ */
+async exitSafeMode() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("exit_safe_mode") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to check whether safe mode is on (e.g. to show the banner after
+ * a `--safe-mode` launch)
+ */
+async isSafeMode() : Promise<boolean> {
+    return await TAURI_INVOKE("is_safe_mode");
+},
+/**
+ * Tauri command to list the injections and whether each is enabled
+ */
+async listInjections() : Promise<Result<InjectionInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_injections") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to enable or disable an injection. Enabling injects it right away;
+ * disabling reloads the page without it.
+ */
+async setInjectionEnabled(name: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_injection_enabled", { name, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Where this run keeps its data, and whether that is the temp-dir fallback
+ */
+async getDataDir() : Promise<Result<DataDir, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_data_dir") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * Account information.
+ */
+export type Account = { id: string; name: string; data_dir: string; is_active: boolean; profile_picture: string | null; last_sync: string | null; session_token: string | null }
+/**
+ * Snapshot of all user-facing settings
+ */
+export type AppSettings = { notifications: NotificationSettings; privacy: PrivacyConfig; window: WindowState; zoom_level: number; theme: string; custom_css: string | null; message_density: MessageDensity; platform: string | null; update_channel: string }
+/**
+ * Whether media may start playing without the user asking
+ */
+export type AutoplayPolicy = /**
+ * The page decides, as in a browser
+ */
+"allow" | /**
+ * Autoplaying media is muted first
+ */
+"muted_only" | /**
+ * Media only plays when the user starts it
+ */
+"block"
+/**
+ * Persisted badge configuration
+ */
+export type BadgeConfig = { mode: BadgeMode; /**
+ * Poll the page from the app so the badge stays current while hidden
+ */
+background_poll: boolean; /**
+ * Seconds between background polls
+ */
+poll_interval_secs: number }
+/**
+ * What the unread badge counts
+ */
+export type BadgeMode = /**
+ * Conversations with unread messages
+ */
+"Conversations" | /**
+ * Unread messages across all conversations
+ */
+"Messages" | /**
+ * No badge (notifications are unaffected)
+ */
+"None"
+/**
+ * Persisted bandwidth-saver configuration
+ */
+export type BandwidthSaverConfig = { enabled: boolean; /**
+ * Also refuse tracker / ad requests while enabled
+ */
+skip_trackers: boolean }
+/**
+ * Persisted call configuration
+ */
+export type CallConfig = { /**
+ * Turn DND on and pause sounds while in a call
+ */
+dnd_during_calls: boolean }
+/**
+ * The kind of call in progress
+ */
+export type CallKind = "None" | "Voice" | "Video" | "ScreenShare"
+/**
+ * What the probe sees of a call. Fields the probe leaves out read as `false`.
+ */
+export type CallState = { /**
+ * The platform's call controls are on screen
+ */
+call_ui: boolean; /**
+ * The call controls include a camera toggle or video
+ */
+call_video: boolean; camera: boolean; microphone: boolean; screen_sharing: boolean }
+/**
+ * Persisted composer configuration
+ */
+export type ComposerConfig = { /**
+ * Enter sends (Shift+Enter for a new line); off swaps the two
+ */
+send_on_enter: boolean }
+/**
+ * What the probe found
+ */
+export type ConnectionStatus = /**
+ * The platform is loaded and logged in
+ */
+"connected" | /**
+ * The platform is loaded but showing its login page
+ */
+"logged_out" | /**
+ * No network, or the platform can't be reached
+ */
+"offline" | /**
+ * The webview is showing an error page
+ */
+"load_error"
+/**
+ * A popped-out conversation
+ */
+export type ConversationWindow = { label: string; platform: string; conversation_id: string; title: string }
+/**
+ * Where this run keeps its data
+ */
+export type DataDir = { path: string; /**
+ * Why the app data dir couldn't be used; set when `path` is the temp fallback
+ */
+fallback_reason: string | null }
+/**
+ * Environment details for bug triage
+ */
+export type Diagnostics = { app_version: string; tauri_version: string; /**
+ * WebView2 / WebKitGTK / WKWebView version, if it could be read
+ */
+webview_version: string | null; os: string; arch: string; platform: string | null; hardware_acceleration: boolean; data_dir: string; features: FeatureStatus[] }
+/**
+ * Notifications held back by one DND period from one conversation
+ */
+export type DigestGroup = { conversation_id: string | null; /**
+ * Conversation (or sender) name, from the notification title
+ */
+name: string; count: number }
+/**
+ * What the last (or current) DND period held back, busiest conversation first
+ */
+export type DndDigest = { total: number; groups: DigestGroup[] }
+/**
+ * Do Not Disturb schedule
+ */
+export type DNDSchedule = { start_time: string; end_time: string }
+/**
+ * Screen edge a window can be docked against
+ */
+export type DockEdge = "left" | "right" | "top" | "bottom"
+/**
+ * A media file that couldn't be exported
+ */
+export type FailedMedia = { url: string; error: string }
+/**
+ * How an optional feature came up
+ */
+export type FeatureState = "Ok" | /**
+ * Running without it, or with less of it
+ */
+"Degraded"
+/**
+ * An optional feature's startup outcome
+ */
+export type FeatureStatus = { name: string; state: FeatureState; /**
+ * Why it is degraded
+ */
+detail: string | null }
+/**
+ * File drop information.
+ */
+export type FileDropInfo = { name: string; path: string; size: number; mime_type: string; is_image: boolean; is_video: boolean; is_audio: boolean; is_document: boolean }
+/**
+ * File drop event payload.
+ */
+export type FileDropPayload = { files: FileDropInfo[]; status: string; error: string | null }
+/**
+ * What happens to a notification that matches a filter
+ */
+export type FilterAction = /**
+ * Always show, even if a deny filter also matches
+ */
+"allow" | /**
+ * Suppress (the notification is still recorded in history)
+ */
+"deny"
+/**
+ * Which part of a notification a filter matches against
+ */
+export type FilterField = "sender" | "body"
+/**
+ * Persisted GPU configuration
+ */
+export type GpuConfig = { hardware_acceleration: boolean }
+/**
+ * The account logged in to a platform
+ */
+export type Identity = { /**
+ * Display name, or the handle where the page only shows that
+ */
+name: string; /**
+ * Username, without a leading `@`
+ */
+handle: string | null; avatar_url: string | null }
+/**
+ * Persisted image-resize configuration
+ */
+export type ImageResizeConfig = { /**
+ * Resize without asking
+ */
+enabled: boolean; /**
+ * Longest side after resizing, in pixels
+ */
+max_dimension: number; /**
+ * JPEG quality (1-100)
+ */
+quality: number }
+/**
+ * An injection as reported to the frontend
+ */
+export type InjectionInfo = { name: string; phase: Phase; enabled: boolean }
+/**
+ * Persisted keepalive configuration
+ */
+export type KeepaliveConfig = { enabled: boolean; interval_minutes: number }
+/**
+ * Lock state reported to the frontend
+ */
+export type LockStatus = { enabled: boolean; idle_minutes: number; locked: boolean }
+/**
+ * Media file metadata.
+ */
+export type MediaFile = { id: string; name: string; path: string; size: number; mime_type: string; is_image: boolean; is_video: boolean; is_audio: boolean }
+/**
+ * What an export wrote, and what it couldn't
+ */
+export type MediaManifest = { conversation_id: string; dest_dir: string; saved: SavedMedia[]; failed: FailedMedia[] }
+/**
+ * Media permissions state.
+ */
+export type MediaPermissions = { camera: boolean; microphone: boolean }
+/**
+ * Why a media file couldn't be saved, for the frontend to advise the user
+ */
+export type MediaSaveError = /**
+ * The disk (or the user's quota) is full
+ */
+({ kind: "disk_full" } & { message: string }) | /**
+ * The media directory isn't writable, or the file is locked (e.g. by antivirus)
+ */
+({ kind: "permission_denied" } & { message: string }) | /**
+ * The path is longer than the file system allows
+ */
+({ kind: "path_too_long" } & { message: string }) | /**
+ * Any other IO error
+ */
+({ kind: "failed" } & { message: string })
+/**
+ * How tightly messages are packed
+ */
+export type MessageDensity = /**
+ * The platform's own spacing
+ */
+"Comfortable" | "Cozy" | "Compact"
+/**
+ * A connected monitor, as listed for the frontend
+ */
+export type MonitorDetails = { name: string | null; /**
+ * Full monitor geometry in physical pixels
+ */
+bounds: WindowBounds; /**
+ * Geometry excluding the taskbar / dock / menu bar
+ */
+work_area: WindowBounds; scale_factor: number; primary: boolean }
+/**
+ * Identifies a monitor by name and work-area geometry
+ */
+export type MonitorInfo = { name: string | null; work_area: WindowBounds }
+/**
+ * Persisted network configuration
+ */
+export type NetworkConfig = { /**
+ * Reload the platform when the network comes back
+ */
+auto_reconnect: boolean }
+/**
+ * A button shown on a notification toast
+ */
+export type NotificationAction = { id: string; title: string }
+/**
+ * Sender / keyword filter applied before a notification is shown
+ */
+export type NotificationFilter = { id: string; field: FilterField; action: FilterAction; pattern: string; /**
+ * Treat `pattern` as a (case-insensitive) regex instead of a substring
+ */
+regex: boolean }
+/**
+ * A notification as recorded in history
+ */
+export type NotificationRecord = { id: string; title: string; body: string; sender_name: string | null; conversation_id: string | null; /**
+ * Unix timestamp (ms) the notification was received
+ */
+timestamp: number; /**
+ * `true` if DND, a filter, a mute or the rate limit kept it from being shown
+ */
+suppressed: boolean; account_id: string | null; account_name: string | null }
+/**
+ * Platform-specific notification settings
+ */
+export type NotificationSettings = { enabled: boolean; sound_enabled: boolean; sound_path: string | null; do_not_disturb: boolean; dnd_schedule: DNDSchedule | null; show_preview: boolean; quick_reply_enabled: boolean; filters: NotificationFilter[]; /**
+ * Muted conversation ids mapped to the mute expiry (Unix ms), `None` = indefinite
+ */
+muted_conversations: Partial<{ [key in string]: number | null }>; /**
+ * Conversations that break through DND and mutes, mapped to the priority
+ * expiry (Unix ms), `None` = until cleared
+ */
+prioritized_conversations: Partial<{ [key in string]: number | null }>; /**
+ * Play a sound when one of the user's own messages is sent
+ */
+sent_sound_enabled: boolean; /**
+ * Custom sent sound; `None` uses the system default
+ */
+sent_sound_path: string | null; /**
+ * What a notification reveals, unless overridden for its platform
+ */
+preview_mode: PreviewMode; /**
+ * Per-platform preview mode overrides, keyed by platform name
+ */
+platform_preview_modes: Partial<{ [key in string]: PreviewMode }>; /**
+ * Per-platform DND schedules, keyed by platform name; others use `dnd_schedule`
+ */
+platform_dnd_schedules: Partial<{ [key in string]: DNDSchedule }>; /**
+ * Bounce the dock / flash the taskbar on a mention while unfocused
+ */
+bounce_on_mention: boolean; /**
+ * Raise the window and open the conversation when a notification is clicked
+ */
+focus_on_click: boolean; /**
+ * When DND ends, show one summary of what it held back instead of nothing
+ */
+digest_on_dnd_end: boolean; /**
+ * How many notifications may be shown per second (see `RateLimit`)
+ */
+rate_limit: RateLimit; /**
+ * How toasts are grouped in the Windows Action Center
+ */
+toast_grouping: ToastGrouping; /**
+ * Hold notifications back while the system's own Do Not Disturb (Focus
+ * Assist, Focus) is on
+ */
+respect_system_dnd: boolean }
+/**
+ * When an injection runs
+ */
+export type Phase = /**
+ * After every page load, and when its module applies a change
+ */
+"on_load" | /**
+ * Only when its module applies it
+ */
+"on_demand"
+/**
+ * A pinned conversation
+ */
+export type Pin = { id: string; platform: string; conversation_id: string; note: string | null; /**
+ * Unix timestamp (ms) the pin was created
+ */
+created_at: number }
+/**
+ * Position history for tracking window movements
+ */
+export type PositionHistory = { /**
+ * When the window got there (ms since the epoch)
+ */
+timestamp: number; x: number; y: number }
+/**
+ * What a notification reveals about the message
+ */
+export type PreviewMode = /**
+ * Sender and message
+ */
+"full" | /**
+ * Sender only
+ */
+"sender_only" | /**
+ * Message only
+ */
+"body_only" | /**
+ * Neither: a generic "New message."
+ */
+"hidden"
+export type PrivacyConfig = { block_typing: boolean; block_read_receipts: boolean; hide_last_active: boolean; block_link_previews: boolean; /**
+ * Conversations whose read receipts are blocked even with `block_read_receipts` off
+ */
+blocked_receipt_conversations: string[] }
+/**
+ * Persisted proxy configuration
+ */
+export type ProxyConfig = { url: string | null }
+/**
+ * Flood protection for notifications reported by the page: a token bucket that
+ * refills `per_second` tokens a second up to `burst`. Notifications beyond it are
+ * recorded but not shown; the most recent of them is shown once a token frees up.
+ */
+export type RateLimit = { per_second: number; burst: number }
+/**
+ * A pending reminder
+ */
+export type Reminder = { id: string; conversation_id: string; /**
+ * Draft message to pre-fill
+ */
+text: string; /**
+ * Unix timestamp (ms) the reminder is due
+ */
+at: number }
+/**
+ * A recompressed image handed back to the page
+ */
+export type ResizedImage = { data: number[]; mime_type: string; width: number; height: number }
+/**
+ * A downloaded media file
+ */
+export type SavedMedia = { url: string; /**
+ * File name within the export directory
+ */
+file_name: string; bytes: number }
+/**
+ * Disk used by the webview profile the platforms share
+ */
+export type SessionUsage = { /**
+ * Bytes of site storage (IndexedDB, Local Storage) each platform keeps, by name.
+ * Every platform is listed, at 0 if it stores nothing.
+ */
+platforms: Partial<{ [key in string]: number }>; /**
+ * Bytes no single platform owns: the HTTP cache, the cookie store and the
+ * webview's own files
+ */
+shared: number; /**
+ * Bytes `clear_webview_cache` can free (part of `shared`)
+ */
+cache: number; /**
+ * Bytes used in all
+ */
+total: number }
+/**
+ * One binding as listed by `list_shortcuts`
+ */
+export type ShortcutBinding = { action: string; keys: string; /**
+ * Defined by the frontend with `register_custom_shortcut`
+ */
+custom: boolean }
+/**
+ * Why a custom shortcut was refused
+ */
+export type ShortcutError = /**
+ * `keys` is not a valid accelerator
+ */
+({ kind: "invalid_keys" } & { keys: string; reason: string }) | /**
+ * An action with this name is already bound
+ */
+({ kind: "duplicate_action" } & { action: string }) | /**
+ * `keys` is already bound to `action`
+ */
+({ kind: "conflict" } & { keys: string; action: string }) | /**
+ * No custom shortcut has this action name
+ */
+({ kind: "not_found" } & { action: string }) | /**
+ * Registering with the OS or saving failed
+ */
+({ kind: "failed" } & { message: string })
+/**
+ * A trigger and the text it expands to
+ */
+export type Snippet = { trigger: string; expansion: string }
+/**
+ * Persisted startup preferences
+ */
+export type StartupConfig = { /**
+ * Start minimized to the tray when launched at login
+ */
+start_hidden: boolean; /**
+ * Show the window on a normal launch even if it was hidden at quit
+ */
+always_start_visible: boolean }
+/**
+ * How toasts are grouped in the Windows Action Center. Windows groups toasts by
+ * the app identity (AUMID) they are shown under, and collapses toasts sharing a
+ * group within it; other platforms ignore this.
+ */
+export type ToastGrouping = { /**
+ * Collapse each conversation's toasts into one group
+ */
+enabled: boolean; /**
+ * App User Model ID the toasts are shown under; `None` uses the bundle
+ * identifier, which the installer gives the Start menu shortcut
+ */
+app_id: string | null }
+/**
+ * Persisted tray icon choice
+ */
+export type TrayIconConfig = { /**
+ * Custom base icon; wins over the per-platform variant
+ */
+custom_path: string | null; /**
+ * Use the active platform's variant of the app icon
+ */
+per_platform: boolean }
+/**
+ * Update information.
+ */
+export type UpdateInfo = { version: string; body: string; date: string | null; url: string | null }
+/**
+ * Window (or monitor) size and position in physical pixels
+ */
+export type WindowBounds = { width: number; height: number; x: number; y: number }
+/**
+ * Native material shown behind the page
+ */
+export type WindowEffect = "none" | /**
+ * macOS sidebar vibrancy
+ */
+"sidebar" | /**
+ * macOS under-window vibrancy
+ */
+"under_window" | /**
+ * Windows 11 Mica
+ */
+"mica" | /**
+ * Windows 10/11 Acrylic
+ */
+"acrylic"
+/**
+ * An open window, as listed by `list_windows`
+ */
+export type WindowInfo = { label: string; title: string; /**
+ * The platform the window's page is on, if any
+ */
+platform: string | null; visible: boolean }
+/**
+ * Window state for persistence
+ */
+export type WindowState = { width: number; height: number; x: number; y: number; maximized: boolean; always_on_top: boolean; focus_mode: boolean; mini_mode: boolean; /**
+ * Size and position to restore when leaving mini mode
+ */
+pre_mini_bounds: WindowBounds | null; /**
+ * Screen edge the window is docked to, if any
+ */
+docked: DockEdge | null; /**
+ * Size and position to restore when undocking
+ */
+pre_dock_bounds: WindowBounds | null; /**
+ * Monitor the window was last on
+ */
+monitor: MonitorInfo | null; /**
+ * While always-on-top, also stay above full-screen apps (macOS)
+ */
+above_fullscreen: boolean; /**
+ * Whether the window was showing (not hidden to the tray or minimized) when
+ * the app last quit
+ */
+visible: boolean; /**
+ * Native title bar and borders; off leaves a borderless window dragged by its
+ * header (mini mode is always borderless)
+ */
+decorations: boolean; /**
+ * Keep the window on the user's workspace (see `platform::set_sticky_workspace`)
+ */
+sticky_workspace: boolean; /**
+ * Virtual desktop the window is kept on (Windows)
+ */
+workspace: string | null; /**
+ * Vibrancy / Mica / Acrylic behind the page
+ */
+window_effect: WindowEffect; /**
+ * Where the window has been, oldest first (see `restore_previous_position`)
+ */
+position_history: PositionHistory[] }
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
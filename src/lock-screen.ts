@@ -0,0 +1,28 @@
+import { commands } from './bindings';
+
+export async function unlock(pin: string): Promise<void> {
+  const error = document.getElementById('error-msg');
+  // The backend closes this window once the PIN is right
+  const result = await commands.unlockApp(pin);
+  if (result.status === 'error') {
+    // Too many wrong PINs: the backend says how long to wait
+    if (error) error.textContent = result.error;
+  } else if (!result.data && error) {
+    error.textContent = 'Wrong PIN';
+  }
+}
+
+document.addEventListener('DOMContentLoaded', () => {
+  const form = document.getElementById('unlock') as HTMLFormElement;
+  const input = document.getElementById('pin') as HTMLInputElement;
+  const button = form.querySelector('button') as HTMLButtonElement;
+  form.addEventListener('submit', async event => {
+    event.preventDefault();
+    if (!input.value) return;
+    button.disabled = true;
+    await unlock(input.value);
+    input.value = '';
+    button.disabled = false;
+    input.focus();
+  });
+});
@@ -1,26 +1,24 @@
-import { invoke } from '@tauri-apps/api/core';
+import { commands } from './bindings';
 
 export async function selectPlatform(name: string): Promise<void> {
-  try {
-    await invoke('select_platform', { platformName: name });
+  const result = await commands.selectPlatform(name);
+  if (result.status === 'ok') {
     document.getElementById('selector')?.remove();
-  } catch (err) {
+  } else {
     const el = document.getElementById('error-msg');
     if (el) {
-      el.textContent = `Failed to load ${name}: ${err}`;
+      el.textContent = `Failed to load ${name}: ${result.error}`;
       el.style.display = 'block';
     }
   }
 }
 
 export async function showLastPlatform(): Promise<void> {
-  try {
-    const last: string | null = await invoke('get_last_platform');
-    if (last) {
-      const el = document.getElementById('last-platform');
-      if (el) el.textContent = `Last used: ${last}`;
-    }
-  } catch (_) {}
+  const result = await commands.getLastPlatform();
+  if (result.status === 'ok' && result.data) {
+    const el = document.getElementById('last-platform');
+    if (el) el.textContent = `Last used: ${result.data}`;
+  }
 }
 
 document.addEventListener('DOMContentLoaded', () => {
@@ -1,83 +1,101 @@
-type Settings = {
-    "auto-start": boolean;
-    "minimize-to-tray": boolean;
-    language: string;
-    "notifications-enabled": boolean;
-    "dnd-enabled": boolean;
-    "dnd-from": string;
-    "dnd-to": string;
-    theme: string;
-    "text-size": number;
-    "custom-css": string;
-    "block-typing": boolean;
-    "block-receipts": boolean;
-    "hide-active": boolean;
-    "block-previews": boolean;
-    shortcuts: Record<string, string>;
-};
+import { commands, type AppSettings, type Result } from '../bindings';
+
+// The snapshot the page was filled from. Settings without a command of their own
+// (the DND schedule) are changed here and applied whole with `apply_settings`.
+let settings: AppSettings;
+
+// Returns the command's data, or shows its error and throws
+function unwrap<T>(result: Result<T, unknown>): T {
+    if (result.status === 'error') {
+        const message = typeof result.error === 'string' ? result.error : JSON.stringify(result.error);
+        alert(message);
+        throw new Error(message);
+    }
+    return result.data;
+}
+
+function input(id: string): HTMLInputElement {
+    return document.getElementById(id) as HTMLInputElement;
+}
+
+function renderShortcuts(bindings: { action: string; keys: string }[]) {
+    const body = document.getElementById('shortcut-rows')!;
+    body.replaceChildren();
+    for (const { action, keys } of bindings) {
+        const row = document.createElement('tr');
+        const name = document.createElement('td');
+        name.textContent = action;
+        const binding = document.createElement('td');
+        binding.textContent = keys;
+        const edit = document.createElement('button');
+        edit.className = 'edit-button';
+        edit.textContent = 'Edit';
+        edit.addEventListener('click', async () => {
+            const newKeybinding = prompt(`Enter new keybinding for ${action}:`, keys);
+            if (newKeybinding) {
+                unwrap(await commands.updateShortcut(action, newKeybinding));
+                binding.textContent = newKeybinding;
+            }
+        });
+        const cell = document.createElement('td');
+        cell.append(edit);
+        row.append(name, binding, cell);
+        body.append(row);
+    }
+}
 
 // Initialize settings on load
 window.addEventListener('DOMContentLoaded', async () => {
     // Load all settings from backend
-    const settings: Settings = await window.__TAURI__.invoke('get_all_settings');
-    
+    settings = unwrap(await commands.getAllSettings());
+
     // Populate form fields
-    (document.getElementById('auto-start') as HTMLInputElement).checked = settings["auto-start"];
-    (document.getElementById('minimize-to-tray') as HTMLInputElement).checked = settings["minimize-to-tray"];
-    (document.getElementById('language') as HTMLSelectElement).value = settings.language;
-    (document.getElementById('notifications-enabled') as HTMLInputElement).checked = settings["notifications-enabled"];
-    (document.getElementById('dnd-enabled') as HTMLInputElement).checked = settings["dnd-enabled"];
-    (document.getElementById('dnd-from') as HTMLInputElement).value = settings["dnd-from"];
-    (document.getElementById('dnd-to') as HTMLInputElement).value = settings["dnd-to"];
-    
+    input('auto-start').checked = unwrap(await commands.isAutostartEnabled());
+    input('notifications-enabled').checked = settings.notifications.enabled;
+    input('dnd-enabled').checked = settings.notifications.do_not_disturb;
+    input('dnd-from').value = settings.notifications.dnd_schedule?.start_time ?? '';
+    input('dnd-to').value = settings.notifications.dnd_schedule?.end_time ?? '';
+
     // Set theme radio
     const themeRadios = document.getElementsByName('theme') as NodeListOf<HTMLInputElement>;
     themeRadios.forEach(radio => {
         radio.checked = radio.value === settings.theme;
     });
-    
-    // Set text size slider
-    (document.getElementById('text-size') as HTMLInputElement).value = settings["text-size"].toString();
+
+    // Set text size slider (zoom 0 is 100%)
+    const textSize = Math.round((settings.zoom_level + 1) * 100);
+    input('text-size').value = textSize.toString();
     const textSizeValue = document.getElementById('text-size-value');
-    if (textSizeValue) textSizeValue.textContent = `${settings["text-size"]}%`;
-    
+    if (textSizeValue) textSizeValue.textContent = `${textSize}%`;
+
     // Set custom CSS if theme is custom
     if (settings.theme === 'custom') {
         document.getElementById('custom-css-row')?.classList.add('active');
         const customCss = document.getElementById('custom-css') as HTMLTextAreaElement | null;
-        if (customCss) customCss.value = settings["custom-css"];
+        if (customCss) customCss.value = settings.custom_css ?? '';
     }
-    
+
     // Set privacy toggles
-    (document.getElementById('block-typing') as HTMLInputElement).checked = settings["block-typing"];
-    (document.getElementById('block-receipts') as HTMLInputElement).checked = settings["block-receipts"];
-    (document.getElementById('hide-active') as HTMLInputElement).checked = settings["hide-active"];
-    (document.getElementById('block-previews') as HTMLInputElement).checked = settings["block-previews"];
-    
+    input('block-typing').checked = settings.privacy.block_typing;
+    input('block-receipts').checked = settings.privacy.block_read_receipts;
+    input('hide-active').checked = settings.privacy.hide_last_active;
+    input('block-previews').checked = settings.privacy.block_link_previews;
+
     // Set shortcuts
-    const shortcuts = settings.shortcuts;
-    const setShortcutText = (id: string, value: string) => {
-        const el = document.getElementById(id);
-        if (el) el.textContent = value;
-    };
-    setShortcutText('shortcut-toggle-window', shortcuts["toggle-window"]);
-    setShortcutText('shortcut-new-message', shortcuts["new-message"]);
-    setShortcutText('shortcut-mute', shortcuts.mute);
-    setShortcutText('shortcut-dnd', shortcuts.dnd);
-    setShortcutText('shortcut-fullscreen', shortcuts.fullscreen);
-    
+    renderShortcuts(unwrap(await commands.listShortcuts()));
+
     // Set version
-    const version = await window.__TAURI__.invoke('get_current_version');
+    const version = unwrap(await commands.getCurrentVersion());
     const versionEl = document.getElementById('version');
     if (versionEl) versionEl.textContent = version;
-    
+
     // Setup sidebar navigation
     const sidebarItems = document.querySelectorAll('.sidebar-item');
     sidebarItems.forEach(item => {
         item.addEventListener('click', () => {
             sidebarItems.forEach(i => i.classList.remove('active'));
             item.classList.add('active');
-            
+
             const sectionId = item.getAttribute('data-section');
             document.querySelectorAll('.section').forEach(section => {
                 section.classList.remove('active');
@@ -85,38 +103,41 @@ window.addEventListener('DOMContentLoaded', async () => {
             document.getElementById(sectionId!)!.classList.add('active');
         });
     });
-    
-    // Auto-save on change for toggles
-    document.querySelectorAll('input[type="checkbox"]').forEach(toggle => {
-        toggle.addEventListener('change', async () => {
-            const key = toggle.id;
-            const value = (toggle as HTMLInputElement).checked;
-            await window.__TAURI__.invoke('save_setting', { key, value });
-        });
+
+    // General toggles
+    input('auto-start').addEventListener('change', async () => {
+        unwrap(await commands.setAutostart(input('auto-start').checked));
+    });
+    input('notifications-enabled').addEventListener('change', async () => {
+        unwrap(await commands.setNotificationEnabled(input('notifications-enabled').checked));
+    });
+    input('dnd-enabled').addEventListener('change', async () => {
+        unwrap(await commands.setDnd(input('dnd-enabled').checked));
     });
-    
-    // Auto-save for language select
+
+    // Spellcheck language select
     document.getElementById('language')!.addEventListener('change', async (e) => {
-        const key = 'language';
         const value = (e.target as HTMLSelectElement).value;
-        await window.__TAURI__.invoke('save_setting', { key, value });
+        unwrap(await commands.setSpellcheckLanguage(value));
     });
-    
-    // Auto-save for DND time inputs
-    document.querySelectorAll('#dnd-from, #dnd-to').forEach(input => {
-        input.addEventListener('change', async (e) => {
-            const key = (e.target as HTMLInputElement).id;
-            const value = (e.target as HTMLInputElement).value;
-            await window.__TAURI__.invoke('save_setting', { key, value });
+
+    // DND schedule: applied once both ends are set
+    document.querySelectorAll('#dnd-from, #dnd-to').forEach(field => {
+        field.addEventListener('change', async () => {
+            const start_time = input('dnd-from').value;
+            const end_time = input('dnd-to').value;
+            settings = unwrap(await commands.getAllSettings());
+            settings.notifications.dnd_schedule = start_time && end_time ? { start_time, end_time } : null;
+            unwrap(await commands.applySettings(settings));
         });
     });
-    
+
     // Theme radio change handler
     document.querySelectorAll('input[name="theme"]').forEach(radio => {
         radio.addEventListener('change', async (e) => {
             const theme = (e.target as HTMLInputElement).value;
-            await window.__TAURI__.invoke('set_theme', { theme_name: theme });
-            
+            unwrap(await commands.setTheme(theme));
+
             if (theme === 'custom') {
                 document.getElementById('custom-css-row')!.classList.add('active');
             } else {
@@ -124,76 +145,62 @@ window.addEventListener('DOMContentLoaded', async () => {
             }
         });
     });
-    
+
     // Text size slider handler
     document.getElementById('text-size')!.addEventListener('input', (e) => {
         const value = (e.target as HTMLInputElement).value;
         document.getElementById('text-size-value')!.textContent = `${value}%`;
     });
-    
+
     document.getElementById('text-size')!.addEventListener('change', async (e) => {
         const value = parseInt((e.target as HTMLInputElement).value);
-        await window.__TAURI__.invoke('set_zoom', { level: value / 100 });
+        unwrap(await commands.setZoom(value / 100 - 1));
     });
-    
+
     // Custom CSS handler
     document.getElementById('custom-css')!.addEventListener('change', async (e) => {
         const value = (e.target as HTMLTextAreaElement).value;
-        await window.__TAURI__.invoke('save_setting', { key: 'custom-css', value });
+        unwrap(await commands.setCustomCss(value));
     });
-    
+
     // Privacy toggles handler
     document.querySelectorAll('#block-typing, #block-receipts, #hide-active, #block-previews').forEach(toggle => {
         toggle.addEventListener('change', async () => {
-            const block_typing = (document.getElementById('block-typing') as HTMLInputElement).checked;
-            const block_read_receipts = (document.getElementById('block-receipts') as HTMLInputElement).checked;
-            const hide_last_active = (document.getElementById('hide-active') as HTMLInputElement).checked;
-            const block_link_previews = (document.getElementById('block-previews') as HTMLInputElement).checked;
-            
-            await window.__TAURI__.invoke('set_privacy', {
-                block_typing,
-                block_read_receipts,
-                hide_last_active,
-                block_link_previews
-            });
+            unwrap(await commands.setPrivacy(
+                input('block-typing').checked,
+                input('block-receipts').checked,
+                input('hide-active').checked,
+                input('block-previews').checked,
+            ));
         });
     });
-    
-    // Shortcut edit buttons
-    document.querySelectorAll('.edit-button').forEach(button => {
-        button.addEventListener('click', async (e) => {
-            const action = (e.target as HTMLButtonElement).getAttribute('data-action');
-            const newKeybinding = prompt(`Enter new keybinding for ${action}:`, '');
-            
-            if (newKeybinding) {
-                await window.__TAURI__.invoke('update_shortcut', { action, keys: newKeybinding });
-                // Update UI
-                document.getElementById(`shortcut-${action}`)!.textContent = newKeybinding;
-            }
-        });
-    });
-    
+
     // Clear cache button
     document.getElementById('clear-cache')!.addEventListener('click', async () => {
         const confirmClear = confirm('Are you sure you want to clear cookies and cache? This cannot be undone.');
         if (confirmClear) {
-            await window.__TAURI__.invoke('clear_webview_data', { data_type: 'all' });
+            unwrap(await commands.clearAllSessions());
             alert('Cookies and cache cleared successfully.');
         }
     });
-    
+
     // Reset settings button
     document.getElementById('reset-settings')!.addEventListener('click', async () => {
         const confirmReset = confirm('Are you sure you want to reset all settings to default? This cannot be undone.');
         if (confirmReset) {
-            await window.__TAURI__.invoke('reset_settings');
+            unwrap(await commands.resetNotificationSettings());
+            unwrap(await commands.resetPrivacyConfig());
+            unwrap(await commands.resetTheme());
+            unwrap(await commands.resetAllZoom());
             alert('Settings reset successfully. The app will reload.');
             window.location.reload();
         }
     });
-    
+
     // Choose notification sound button
     document.getElementById('choose-sound')!.addEventListener('click', async () => {
-        await window.__TAURI__.invoke('choose_notification_sound');
+        const path = prompt('Path to a sound file (leave empty for the system sound):', '');
+        if (path === null) return;
+        unwrap(await (path ? commands.setNotificationSound(path) : commands.useDefaultNotificationSound()));
     });
-});
\ No newline at end of file
+});
@@ -8,7 +8,8 @@ export default defineConfig({
     emptyOutDir: true,
     rollupOptions: {
       input: {
-        main: './src/index.html'
+        main: './src/index.html',
+        lock: './src/lock.html'
       }
     }
   },